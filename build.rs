@@ -1,3 +1,4 @@
 fn main() {
     tonic_build::compile_protos("proto/transaction.proto").unwrap();
+    tonic_build::compile_protos("proto/shredstream.proto").unwrap();
 }
\ No newline at end of file
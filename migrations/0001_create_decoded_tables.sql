@@ -0,0 +1,50 @@
+-- Decoded output of the Postgres sink, rows are keyed by signature so that corrected
+-- re-emissions of a transaction replace the first ones
+
+CREATE TABLE IF NOT EXISTS transactions (
+    signature TEXT PRIMARY KEY,
+    slot BIGINT NOT NULL,
+    timestamp_micros BIGINT NOT NULL,
+    payload JSONB NOT NULL
+);
+CREATE INDEX IF NOT EXISTS transactions_slot_idx ON transactions (slot);
+
+CREATE TABLE IF NOT EXISTS instructions (
+    signature TEXT NOT NULL,
+    instruction_index INTEGER NOT NULL,
+    slot BIGINT NOT NULL,
+    program_id TEXT,
+    protocol TEXT,
+    instruction_name TEXT,
+    parsed_data JSONB,
+    PRIMARY KEY (signature, instruction_index)
+);
+CREATE INDEX IF NOT EXISTS instructions_protocol_idx ON instructions (protocol, instruction_name, slot);
+
+CREATE TABLE IF NOT EXISTS swaps (
+    signature TEXT NOT NULL,
+    instruction_index INTEGER NOT NULL,
+    slot BIGINT NOT NULL,
+    protocol TEXT NOT NULL,
+    pool TEXT NOT NULL,
+    input_mint TEXT,
+    output_mint TEXT,
+    amount_in NUMERIC(20, 0) NOT NULL,
+    min_amount_out NUMERIC(20, 0) NOT NULL,
+    exact_in BOOLEAN NOT NULL,
+    "user" TEXT,
+    PRIMARY KEY (signature, instruction_index)
+);
+CREATE INDEX IF NOT EXISTS swaps_input_mint_idx ON swaps (input_mint, slot);
+CREATE INDEX IF NOT EXISTS swaps_output_mint_idx ON swaps (output_mint, slot);
+
+CREATE TABLE IF NOT EXISTS token_launches (
+    mint TEXT PRIMARY KEY,
+    platform TEXT NOT NULL,
+    name TEXT,
+    symbol TEXT,
+    uri TEXT,
+    creator TEXT,
+    slot BIGINT NOT NULL,
+    signature TEXT NOT NULL
+);
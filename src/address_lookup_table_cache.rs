@@ -1,4 +1,5 @@
 use dashmap::DashMap;
+use json::object;
 use once_cell::sync::Lazy;
 use solana_client::rpc_client::RpcClient;
 use solana_sdk::{
@@ -10,9 +11,13 @@ use solana_sdk::{
     message::VersionedMessage,
 };
 use std::sync::Arc;
+use tokio::task::JoinHandle;
 use tokio::time::{Duration, Instant};
 use tracing::{debug, error, info, warn};
 
+use crate::address_lookup_table_decoding::ADDRESS_LOOKUP_TABLE_PROGRAM_ID;
+use crate::operational_events::{publish_operational_event, Severity, RPC_FAILURE_EVENT};
+
 /// Cache entry for a lookup table
 #[derive(Clone, Debug)]
 pub struct LookupTableCacheEntry {
@@ -70,6 +75,12 @@ impl AddressLookupTableCache {
             }
             Err(e) => {
                 error!("Failed to fetch lookup table {}: {}", lookup_table_pubkey, e);
+                publish_operational_event(
+                    RPC_FAILURE_EVENT,
+                    Severity::Warning,
+                    format!("Failed to fetch lookup table {}: {}", lookup_table_pubkey, e),
+                    object! { "call" => "getAccountInfo", "lookupTable" => lookup_table_pubkey.to_string() },
+                );
                 
                 // If we have a stale cache entry, use it as fallback
                 if let Some(entry) = self.cache.get(lookup_table_pubkey) {
@@ -111,6 +122,12 @@ impl AddressLookupTableCache {
             }
             Err(e) => {
                 error!("Failed to force refresh lookup table {}: {}", lookup_table_pubkey, e);
+                publish_operational_event(
+                    RPC_FAILURE_EVENT,
+                    Severity::Warning,
+                    format!("Failed to force refresh lookup table {}: {}", lookup_table_pubkey, e),
+                    object! { "call" => "getAccountInfo", "lookupTable" => lookup_table_pubkey.to_string() },
+                );
                 Err(e)
             }
         }
@@ -226,6 +243,33 @@ impl AddressLookupTableCache {
         resolved_keys
     }
 
+    /// Resolve lookup table addresses from the cache only, without any RPC call.
+    /// Stale entries are used as-is, tables that were never fetched yield placeholders.
+    /// Returns the keys and whether every lookup could be resolved.
+    pub fn resolve_address_lookups_cached(
+        &self,
+        base_account_keys: &[Pubkey],
+        lookups: &[MessageAddressTableLookup],
+    ) -> (Vec<Pubkey>, bool) {
+        let mut resolved_keys = base_account_keys.to_vec();
+        let mut complete = true;
+
+        for lookup in lookups {
+            let entry = self.cache.get(&lookup.account_key).filter(|entry| entry.is_valid);
+            for &index in lookup.readonly_indexes.iter().chain(lookup.writable_indexes.iter()) {
+                match entry.as_ref().and_then(|entry| entry.addresses.get(index as usize)) {
+                    Some(address) => resolved_keys.push(*address),
+                    None => {
+                        complete = false;
+                        resolved_keys.push(Pubkey::default()); // placeholder
+                    }
+                }
+            }
+        }
+
+        (resolved_keys, complete)
+    }
+
     /// Get cache statistics
     pub fn get_cache_stats(&self) -> (usize, usize) {
         let total_entries = self.cache.len();
@@ -283,8 +327,7 @@ impl AddressLookupTableCache {
 
         if let Some(program_pubkey) = account_keys.get(instruction.program_id_index as usize) {
             // Check if this is the Address Lookup Table program
-            let address_lookup_table_program_id = "AddressLookupTab1e1111111111111111111111111";
-            if program_pubkey.to_string() == address_lookup_table_program_id {
+            if program_pubkey == &ADDRESS_LOOKUP_TABLE_PROGRAM_ID {
                 // Check the instruction discriminant for ExtendLookupTable (discriminant 2)
                 if instruction.data.len() >= 4 {
                     let discriminant = u32::from_le_bytes([
@@ -376,4 +419,79 @@ pub fn process_transaction_for_lookup_table_extensions(transaction: &VersionedTr
     if let Some(cache) = get_lookup_table_cache() {
         cache.process_transaction_for_extensions(transaction);
     }
-}
\ No newline at end of file
+}
+
+/// Deadline for resolving the lookup tables of one transaction, 0 disables it
+static ALT_RESOLVE_DEADLINE_MS: Lazy<u64> = Lazy::new(|| {
+    std::env::var("ALT_RESOLVE_DEADLINE_MS")
+        .unwrap_or_else(|_| "0".to_string())
+        .parse()
+        .unwrap_or(0)
+});
+
+pub fn alt_resolve_deadline() -> Option<Duration> {
+    match *ALT_RESOLVE_DEADLINE_MS {
+        0 => None,
+        ms => Some(Duration::from_millis(ms)),
+    }
+}
+
+/// Outcome of a resolution bounded by the deadline
+pub enum DeadlineResolution {
+    /// Every lookup resolved in time
+    Complete(Vec<Pubkey>),
+    /// Deadline hit: cached addresses with placeholders, and the full resolution still running
+    Partial {
+        account_keys: Vec<Pubkey>,
+        pending: JoinHandle<Vec<Pubkey>>,
+    },
+}
+
+/// Resolve account keys, giving up on the RPC after `deadline` and falling back to
+/// whatever the cache holds. The full resolution keeps running in the background so
+/// that the cache is warm for the next transaction and a correction can be emitted.
+pub async fn resolve_transaction_account_keys_with_deadline(
+    base_account_keys: &[Pubkey],
+    lookups: Option<&[MessageAddressTableLookup]>,
+    deadline: Duration,
+) -> DeadlineResolution {
+    let (cache, lookups) = match (get_lookup_table_cache(), lookups) {
+        (Some(cache), Some(lookups)) if !lookups.is_empty() => (cache, lookups),
+        _ => return DeadlineResolution::Complete(resolve_transaction_account_keys(base_account_keys, lookups).await),
+    };
+
+    let base_account_keys_owned = base_account_keys.to_vec();
+    let lookups_owned = lookups.to_vec();
+    let mut pending = tokio::spawn(async move {
+        cache.resolve_address_lookups(&base_account_keys_owned, &lookups_owned).await
+    });
+
+    match tokio::time::timeout(deadline, &mut pending).await {
+        Ok(Ok(account_keys)) => DeadlineResolution::Complete(account_keys),
+        Ok(Err(e)) => {
+            error!("Lookup table resolution task failed: {}", e);
+            let (account_keys, _) = cache.resolve_address_lookups_cached(base_account_keys, lookups);
+            DeadlineResolution::Complete(account_keys)
+        }
+        Err(_) => {
+            let (account_keys, complete) = cache.resolve_address_lookups_cached(base_account_keys, lookups);
+            if complete {
+                // Tables were only being refreshed, the cached content is usable as-is
+                return DeadlineResolution::Complete(account_keys);
+            }
+            debug!("Lookup table resolution exceeded {:?}, emitting partial account keys", deadline);
+            DeadlineResolution::Partial { account_keys, pending }
+        }
+    }
+}
+
+/// Opt-in corrected follow-up for transactions emitted with partial account keys
+static PARTIAL_FOLLOWUP_ENABLED: Lazy<bool> = Lazy::new(|| {
+    std::env::var("PARTIAL_FOLLOWUP_ENABLED")
+        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+});
+
+pub fn is_partial_followup_enabled() -> bool {
+    *PARTIAL_FOLLOWUP_ENABLED
+}
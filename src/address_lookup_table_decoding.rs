@@ -0,0 +1,126 @@
+use json::{object, JsonValue};
+use once_cell::sync::Lazy;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use crate::byte_parser::*;
+use crate::decode_anomalies::check_parse_residue;
+use crate::utils::create_standardized_instruction;
+
+pub const ADDRESS_LOOKUP_TABLE_PROGRAM_ID: Pubkey = pubkey!("AddressLookupTab1e1111111111111111111111111");
+
+pub const LOOKUP_TABLE_CREATE_ACCOUNTS: &[&str] = &["lookupTable", "authority", "payer", "systemProgram"];
+pub const LOOKUP_TABLE_AUTHORITY_ACCOUNTS: &[&str] = &["lookupTable", "authority"];
+pub const LOOKUP_TABLE_EXTEND_ACCOUNTS: &[&str] = &["lookupTable", "authority", "payer", "systemProgram"];
+pub const LOOKUP_TABLE_CLOSE_ACCOUNTS: &[&str] = &["lookupTable", "authority", "recipient"];
+
+/// The lookup table program is a native program, instructions are bincode with a u32 variant index
+pub const LOOKUP_TABLE_CREATE_DISCRIMINATOR: [u8; 4] = [0, 0, 0, 0];
+pub const LOOKUP_TABLE_FREEZE_DISCRIMINATOR: [u8; 4] = [1, 0, 0, 0];
+pub const LOOKUP_TABLE_EXTEND_DISCRIMINATOR: [u8; 4] = [2, 0, 0, 0];
+pub const LOOKUP_TABLE_DEACTIVATE_DISCRIMINATOR: [u8; 4] = [3, 0, 0, 0];
+pub const LOOKUP_TABLE_CLOSE_DISCRIMINATOR: [u8; 4] = [4, 0, 0, 0];
+
+/// A table holds at most 256 addresses, a longer extension is rejected by the program
+const LOOKUP_TABLE_MAX_ADDRESSES: u64 = 256;
+
+/// Opt-in flag, lookup table instructions are not decoded when disabled
+static LOOKUP_TABLE_DECODING_ENABLED: Lazy<bool> = Lazy::new(|| {
+    std::env::var("LOOKUP_TABLE_DECODING_ENABLED")
+        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+});
+
+pub fn is_lookup_table_decoding_enabled() -> bool {
+    *LOOKUP_TABLE_DECODING_ENABLED
+}
+
+/// Unlike token movements, table changes are emitted on their own: consumers replaying
+/// table state need every extension, and extensions are usually sent alone
+pub fn is_lookup_table_program(program_id: &Pubkey) -> bool {
+    is_lookup_table_decoding_enabled() && program_id == &ADDRESS_LOOKUP_TABLE_PROGRAM_ID
+}
+
+pub enum LookupTableInstructionType {
+    CreateLookupTable,
+    FreezeLookupTable,
+    ExtendLookupTable,
+    DeactivateLookupTable,
+    CloseLookupTable,
+}
+
+impl LookupTableInstructionType {
+    pub fn name(&self) -> &'static str {
+        match self {
+            LookupTableInstructionType::CreateLookupTable => "CreateLookupTable",
+            LookupTableInstructionType::FreezeLookupTable => "FreezeLookupTable",
+            LookupTableInstructionType::ExtendLookupTable => "ExtendLookupTable",
+            LookupTableInstructionType::DeactivateLookupTable => "DeactivateLookupTable",
+            LookupTableInstructionType::CloseLookupTable => "CloseLookupTable",
+        }
+    }
+}
+
+pub fn get_lookup_table_instruction_type(data: &[u8]) -> Option<LookupTableInstructionType> {
+    match data.get(0..4) {
+        Some(d) if d == LOOKUP_TABLE_CREATE_DISCRIMINATOR => Some(LookupTableInstructionType::CreateLookupTable),
+        Some(d) if d == LOOKUP_TABLE_FREEZE_DISCRIMINATOR => Some(LookupTableInstructionType::FreezeLookupTable),
+        Some(d) if d == LOOKUP_TABLE_EXTEND_DISCRIMINATOR => Some(LookupTableInstructionType::ExtendLookupTable),
+        Some(d) if d == LOOKUP_TABLE_DEACTIVATE_DISCRIMINATOR => Some(LookupTableInstructionType::DeactivateLookupTable),
+        Some(d) if d == LOOKUP_TABLE_CLOSE_DISCRIMINATOR => Some(LookupTableInstructionType::CloseLookupTable),
+        _ => None,
+    }
+}
+
+/// CreateLookupTable (lookup_table=0, authority=1, payer=2, system_program=3) carries the slot the
+/// table address is derived from, ExtendLookupTable (lookup_table=0, authority=1, payer=2,
+/// system_program=3) the appended addresses as a bincode vector with a u64 length.
+/// Freeze, Deactivate (lookup_table=0, authority=1) and Close (+ recipient=2) have no args.
+pub fn deserialize_lookup_table_instruction(
+    instr_type: &LookupTableInstructionType,
+    data: &[u8],
+    accounts_indices: &[u8],
+    account_keys: &[Pubkey],
+    is_signer: &[bool],
+    is_writable: &[bool],
+) -> Result<JsonValue, String> {
+    let instruction_name = instr_type.name();
+    let mut offset = 4;
+
+    let mut parsed_data = match instr_type {
+        LookupTableInstructionType::CreateLookupTable => {
+            let recent_slot = parse_u64(data, &mut offset)?;
+            let bump_seed = parse_u8(data, &mut offset)?;
+            object! {
+                "recent_slot" => recent_slot,
+                "bump_seed" => bump_seed,
+            }
+        },
+        LookupTableInstructionType::ExtendLookupTable => {
+            let len = parse_u64(data, &mut offset)?;
+            if len > LOOKUP_TABLE_MAX_ADDRESSES {
+                return Err(format!("ExtendLookupTable with {} addresses, at most {} fit in a table", len, LOOKUP_TABLE_MAX_ADDRESSES));
+            }
+            let new_addresses = (0..len)
+                .map(|_| parse_pubkey(data, &mut offset).map(|address| address.to_string()))
+                .collect::<Result<Vec<String>, String>>()?;
+            object! {
+                "new_addresses" => new_addresses,
+            }
+        },
+        _ => object! {},
+    };
+
+    check_parse_residue(&format!("AddressLookupTable {}", instruction_name), remaining(data, offset), &mut parsed_data);
+
+    Ok(create_standardized_instruction(
+        &ADDRESS_LOOKUP_TABLE_PROGRAM_ID,
+        instruction_name,
+        "AddressLookupTable",
+        data,
+        accounts_indices,
+        account_keys,
+        is_signer,
+        is_writable,
+        parsed_data
+    ))
+}
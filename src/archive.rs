@@ -0,0 +1,388 @@
+//! Compact indexed archive of decoded output (`.ssda`).
+//!
+//! Layout:
+//! - header: `SSDA` magic and a version byte
+//! - records: `u32` length, then `u64` slot, `u64` timestamp and the binary `EncodedValue` payload
+//! - footer: bincode `ArchiveIndex` (record offsets by slot and by signature)
+//! - trailer: `u64` offset of the footer and the `SSDX` magic
+//!
+//! All integers are little endian. A file whose writer did not finish has no footer,
+//! the reader then rebuilds the index with a sequential scan.
+
+use json::JsonValue;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+use crate::encoder::{decode_binary, EncodedPayload, OutputEncoding};
+use crate::output_message::OutputMessage;
+
+pub const ARCHIVE_EXTENSION: &str = "ssda";
+
+const HEADER_MAGIC: &[u8; 4] = b"SSDA";
+const TRAILER_MAGIC: &[u8; 4] = b"SSDX";
+const ARCHIVE_VERSION: u8 = 1;
+const HEADER_SIZE: u64 = 5;
+const TRAILER_SIZE: u64 = 12;
+/// Slot and timestamp in front of every payload
+const RECORD_HEADER_SIZE: usize = 16;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ArchiveError {
+    #[error("archive I/O failed: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("corrupted archive: {0}")]
+    Corrupted(String),
+}
+
+/// Footer of a finished archive
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ArchiveIndex {
+    /// Record offsets per slot, in file order
+    slots: BTreeMap<u64, Vec<u64>>,
+    /// (signature, record offset) sorted by signature
+    signatures: Vec<(String, u64)>,
+}
+
+impl ArchiveIndex {
+    fn add(&mut self, slot: u64, offset: u64, payload: &JsonValue) {
+        self.slots.entry(slot).or_default().push(offset);
+        for_each_signature(payload, |signature| self.signatures.push((signature.to_string(), offset)));
+    }
+
+    fn seal(&mut self) {
+        self.signatures.sort_unstable();
+    }
+}
+
+/// Visits the first signature of every transaction of a transaction or batch payload
+fn for_each_signature(payload: &JsonValue, mut visit: impl FnMut(&str)) {
+    if payload["transactions"].is_array() {
+        for transaction in payload["transactions"].members() {
+            if let Some(signature) = transaction["signatures"][0].as_str() {
+                visit(signature);
+            }
+        }
+    } else if let Some(signature) = payload["signatures"][0].as_str() {
+        visit(signature);
+    }
+}
+
+/// One archived output message
+#[derive(Debug, Clone)]
+pub struct ArchiveRecord {
+    pub slot: u64,
+    pub timestamp: u64,
+    pub payload: JsonValue,
+}
+
+/// Appends output messages to an archive, the index is written by `finish`
+pub struct ArchiveWriter {
+    writer: BufWriter<File>,
+    offset: u64,
+    index: ArchiveIndex,
+}
+
+impl ArchiveWriter {
+    pub fn create(path: &Path) -> Result<Self, ArchiveError> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(HEADER_MAGIC)?;
+        writer.write_all(&[ARCHIVE_VERSION])?;
+        Ok(Self {
+            writer,
+            offset: HEADER_SIZE,
+            index: ArchiveIndex::default(),
+        })
+    }
+
+    pub fn append(&mut self, message: &OutputMessage) -> Result<(), ArchiveError> {
+        let EncodedPayload::Binary(payload) = message.encoded(OutputEncoding::Binary) else {
+            unreachable!("binary encoding always yields bytes");
+        };
+        let length = u32::try_from(RECORD_HEADER_SIZE + payload.len())
+            .map_err(|_| ArchiveError::Corrupted(format!("record of {} bytes is too large", payload.len())))?;
+
+        self.writer.write_all(&length.to_le_bytes())?;
+        self.writer.write_all(&message.slot.to_le_bytes())?;
+        self.writer.write_all(&message.timestamp.to_le_bytes())?;
+        self.writer.write_all(payload)?;
+
+        self.index.add(message.slot, self.offset, &message.payload);
+        self.offset += 4 + length as u64;
+        Ok(())
+    }
+
+    /// Writes the footer index and trailer, the archive is complete afterwards
+    pub fn finish(mut self) -> Result<(), ArchiveError> {
+        self.index.seal();
+        let footer = bincode::serialize(&self.index).map_err(|e| ArchiveError::Corrupted(e.to_string()))?;
+        self.writer.write_all(&footer)?;
+        self.writer.write_all(&self.offset.to_le_bytes())?;
+        self.writer.write_all(TRAILER_MAGIC)?;
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Random access to an archive through its index
+pub struct ArchiveReader {
+    path: PathBuf,
+    file: BufReader<File>,
+    index: ArchiveIndex,
+}
+
+impl ArchiveReader {
+    pub fn open(path: &Path) -> Result<Self, ArchiveError> {
+        let mut file = BufReader::new(File::open(path)?);
+        let mut header = [0u8; HEADER_SIZE as usize];
+        file.read_exact(&mut header)?;
+        if &header[..4] != HEADER_MAGIC {
+            return Err(ArchiveError::Corrupted(format!("{} is not an archive", path.display())));
+        }
+        if header[4] != ARCHIVE_VERSION {
+            return Err(ArchiveError::Corrupted(format!("unsupported archive version {}", header[4])));
+        }
+
+        let mut reader = Self {
+            path: path.to_path_buf(),
+            file,
+            index: ArchiveIndex::default(),
+        };
+        reader.index = match reader.read_footer()? {
+            Some(index) => index,
+            None => {
+                warn!("Archive {} has no index, the writer did not finish, rebuilding it", path.display());
+                reader.rebuild_index()?
+            }
+        };
+        Ok(reader)
+    }
+
+    fn read_footer(&mut self) -> Result<Option<ArchiveIndex>, ArchiveError> {
+        let file_size = self.file.seek(SeekFrom::End(0))?;
+        if file_size < HEADER_SIZE + TRAILER_SIZE {
+            return Ok(None);
+        }
+        self.file.seek(SeekFrom::End(-(TRAILER_SIZE as i64)))?;
+        let mut trailer = [0u8; TRAILER_SIZE as usize];
+        self.file.read_exact(&mut trailer)?;
+        if &trailer[8..] != TRAILER_MAGIC {
+            return Ok(None);
+        }
+
+        let footer_offset = u64::from_le_bytes(trailer[..8].try_into().expect("8 bytes"));
+        let footer_end = file_size - TRAILER_SIZE;
+        if footer_offset < HEADER_SIZE || footer_offset > footer_end {
+            return Err(ArchiveError::Corrupted(format!("footer offset {} out of bounds", footer_offset)));
+        }
+        self.file.seek(SeekFrom::Start(footer_offset))?;
+        let mut footer = vec![0u8; (footer_end - footer_offset) as usize];
+        self.file.read_exact(&mut footer)?;
+        bincode::deserialize(&footer)
+            .map(Some)
+            .map_err(|e| ArchiveError::Corrupted(format!("invalid footer: {}", e)))
+    }
+
+    /// Scans an unfinished archive, a truncated last record is ignored
+    fn rebuild_index(&mut self) -> Result<ArchiveIndex, ArchiveError> {
+        let mut index = ArchiveIndex::default();
+        let mut offset = HEADER_SIZE;
+        self.file.seek(SeekFrom::Start(offset))?;
+        loop {
+            match self.read_next() {
+                Ok(Some((record, size))) => {
+                    index.add(record.slot, offset, &record.payload);
+                    offset += size;
+                }
+                Ok(None) => break,
+                Err(ArchiveError::Io(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                    warn!("Archive {} ends with a truncated record at offset {}", self.path.display(), offset);
+                    break;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        index.seal();
+        Ok(index)
+    }
+
+    /// Reads the record at the current position with its size on disk, None at the end of the records
+    fn read_next(&mut self) -> Result<Option<(ArchiveRecord, u64)>, ArchiveError> {
+        let mut length = [0u8; 4];
+        match self.file.read_exact(&mut length) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e.into()),
+        }
+        let length = u32::from_le_bytes(length) as usize;
+        if length < RECORD_HEADER_SIZE {
+            return Err(ArchiveError::Corrupted(format!("record length {} is too short", length)));
+        }
+        let mut record = vec![0u8; length];
+        self.file.read_exact(&mut record)?;
+
+        let slot = u64::from_le_bytes(record[0..8].try_into().expect("8 bytes"));
+        let timestamp = u64::from_le_bytes(record[8..16].try_into().expect("8 bytes"));
+        let payload = decode_binary(&record[RECORD_HEADER_SIZE..]).map_err(ArchiveError::Corrupted)?;
+        Ok(Some((ArchiveRecord { slot, timestamp, payload }, 4 + length as u64)))
+    }
+
+    fn read_at(&mut self, offset: u64) -> Result<ArchiveRecord, ArchiveError> {
+        self.file.seek(SeekFrom::Start(offset))?;
+        self.read_next()?
+            .map(|(record, _)| record)
+            .ok_or_else(|| ArchiveError::Corrupted(format!("no record at offset {}", offset)))
+    }
+
+    /// First and last archived slot, None for an empty archive
+    pub fn slot_range(&self) -> Option<(u64, u64)> {
+        let first = *self.index.slots.keys().next()?;
+        let last = *self.index.slots.keys().next_back()?;
+        Some((first, last))
+    }
+
+    pub fn record_count(&self) -> usize {
+        self.index.slots.values().map(Vec::len).sum()
+    }
+
+    /// Records of the slots in `from..=to`, in slot order then file order
+    pub fn read_slots(&mut self, from: u64, to: u64) -> Result<Vec<ArchiveRecord>, ArchiveError> {
+        if from > to {
+            return Ok(Vec::new());
+        }
+        let offsets: Vec<u64> = self.index.slots.range(from..=to).flat_map(|(_, offsets)| offsets.iter().copied()).collect();
+        offsets.into_iter().map(|offset| self.read_at(offset)).collect()
+    }
+
+    /// Every record in file order
+    pub fn read_all(&mut self) -> Result<Vec<ArchiveRecord>, ArchiveError> {
+        let mut offsets: Vec<u64> = self.index.slots.values().flatten().copied().collect();
+        offsets.sort_unstable();
+        offsets.into_iter().map(|offset| self.read_at(offset)).collect()
+    }
+
+    /// The record holding the transaction with this signature, a whole batch for batched output
+    pub fn find_signature(&mut self, signature: &str) -> Result<Option<ArchiveRecord>, ArchiveError> {
+        let Ok(position) = self
+            .index
+            .signatures
+            .binary_search_by(|(indexed, _)| indexed.as_str().cmp(signature))
+        else {
+            return Ok(None);
+        };
+        let offset = self.index.signatures[position].1;
+        self.read_at(offset).map(Some)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(slot: u64, signature: &str) -> OutputMessage {
+        let payload = json::object! {
+            "slot" => slot,
+            "signatures" => json::array![signature],
+            "instructions" => json::array![],
+        };
+        OutputMessage::new(payload, slot * 400, Default::default())
+    }
+
+    fn archive_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("archive_{}_{}.{}", std::process::id(), name, ARCHIVE_EXTENSION))
+    }
+
+    /// Two records in slot 10, one each in slots 11 and 13
+    fn write_archive(path: &Path, finish: bool) {
+        let mut writer = ArchiveWriter::create(path).unwrap();
+        for (slot, signature) in [(10, "sig-a"), (10, "sig-b"), (11, "sig-c"), (13, "sig-d")] {
+            writer.append(&message(slot, signature)).unwrap();
+        }
+        if finish {
+            writer.finish().unwrap();
+        }
+    }
+
+    fn slots(records: &[ArchiveRecord]) -> Vec<u64> {
+        records.iter().map(|record| record.slot).collect()
+    }
+
+    #[test]
+    fn finished_archive_round_trips() {
+        let path = archive_path("finished");
+        write_archive(&path, true);
+
+        let mut reader = ArchiveReader::open(&path).unwrap();
+        assert_eq!(reader.record_count(), 4);
+        assert_eq!(reader.slot_range(), Some((10, 13)));
+
+        let all = reader.read_all().unwrap();
+        assert_eq!(slots(&all), vec![10, 10, 11, 13]);
+        assert_eq!(all[1].payload["signatures"][0], "sig-b");
+        assert_eq!(all[3].timestamp, 13 * 400);
+
+        assert_eq!(slots(&reader.read_slots(10, 10).unwrap()), vec![10, 10]);
+        assert_eq!(slots(&reader.read_slots(11, 12).unwrap()), vec![11]);
+        assert!(reader.read_slots(12, 12).unwrap().is_empty());
+        assert!(reader.read_slots(13, 10).unwrap().is_empty());
+
+        let found = reader.find_signature("sig-c").unwrap().unwrap();
+        assert_eq!(found.slot, 11);
+        assert_eq!(found.payload["signatures"][0], "sig-c");
+        assert!(reader.find_signature("sig-z").unwrap().is_none());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn unfinished_archive_rebuilds_its_index() {
+        let path = archive_path("unfinished");
+        write_archive(&path, false);
+
+        let mut reader = ArchiveReader::open(&path).unwrap();
+        assert_eq!(reader.record_count(), 4);
+        assert_eq!(reader.slot_range(), Some((10, 13)));
+        assert_eq!(reader.find_signature("sig-d").unwrap().unwrap().slot, 13);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn truncated_last_record_is_ignored() {
+        let path = archive_path("truncated");
+        write_archive(&path, false);
+        let size = std::fs::metadata(&path).unwrap().len();
+        File::options().write(true).open(&path).unwrap().set_len(size - 3).unwrap();
+
+        let mut reader = ArchiveReader::open(&path).unwrap();
+        assert_eq!(reader.record_count(), 3);
+        assert_eq!(slots(&reader.read_all().unwrap()), vec![10, 10, 11]);
+        assert!(reader.find_signature("sig-d").unwrap().is_none());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn empty_archive_has_no_slots() {
+        let path = archive_path("empty");
+        ArchiveWriter::create(&path).unwrap().finish().unwrap();
+
+        let mut reader = ArchiveReader::open(&path).unwrap();
+        assert_eq!(reader.record_count(), 0);
+        assert_eq!(reader.slot_range(), None);
+        assert!(reader.read_all().unwrap().is_empty());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn foreign_file_is_rejected() {
+        let path = archive_path("foreign");
+        std::fs::write(&path, b"not an archive at all").unwrap();
+        assert!(matches!(ArchiveReader::open(&path), Err(ArchiveError::Corrupted(_))));
+
+        std::fs::write(&path, b"SS").unwrap();
+        assert!(matches!(ArchiveReader::open(&path), Err(ArchiveError::Io(_))));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,170 @@
+use json::JsonValue;
+use rustc_hash::{FxHashMap as HashMap, FxHashSet as HashSet};
+use std::sync::Arc;
+use tonic::service::Interceptor;
+use tonic::{Request, Status};
+use tracing::info;
+
+use crate::output_message::OutputMessage;
+
+/// Stream restrictions of an API token, applied on top of the subscriber's own filters.
+/// Empty sets do not restrict.
+#[derive(Debug, Default)]
+pub struct TokenGrant {
+    pub name: String,
+    pub mints: HashSet<String>,
+    pub programs: HashSet<String>,
+    pub accounts: HashSet<String>,
+    pub instructions: HashSet<String>,
+}
+
+/// Restrictions a stream is able to apply to what it sends
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GrantScope {
+    pub mints: bool,
+    pub programs: bool,
+    pub accounts: bool,
+    pub instructions: bool,
+}
+
+impl GrantScope {
+    /// Items that cannot be related to any mint, program or account
+    pub const NONE: GrantScope = GrantScope { mints: false, programs: false, accounts: false, instructions: false };
+    /// Items that expose the accounts they are about
+    pub const ACCOUNTS: GrantScope = GrantScope { mints: true, programs: false, accounts: true, instructions: false };
+    /// Items that expose their accounts and the programs they invoke
+    pub const ACCOUNTS_AND_PROGRAMS: GrantScope = GrantScope { mints: true, programs: true, accounts: true, instructions: false };
+    /// Decoded transactions, every restriction applies
+    pub const ALL: GrantScope = GrantScope { mints: true, programs: true, accounts: true, instructions: true };
+}
+
+impl TokenGrant {
+    pub fn allows(&self, message: &OutputMessage) -> bool {
+        (self.mints.is_empty() || message.touches_any(&self.mints))
+            && (self.programs.is_empty() || message.runs_any(&self.programs))
+            && (self.accounts.is_empty() || message.touches_any(&self.accounts))
+            && (self.instructions.is_empty() || message.has_instruction(&self.instructions))
+    }
+
+    /// `allows` for items known only by their accounts and invoked programs, the stream
+    /// must have checked with `check_scope` that the grant has no other restriction
+    pub fn allows_keys(&self, accounts: &HashSet<String>, programs: &HashSet<String>) -> bool {
+        (self.mints.is_empty() || self.mints.iter().any(|mint| accounts.contains(mint)))
+            && (self.programs.is_empty() || self.programs.iter().any(|program| programs.contains(program)))
+            && (self.accounts.is_empty() || self.accounts.iter().any(|account| accounts.contains(account)))
+    }
+
+    pub fn is_restricted(&self) -> bool {
+        !self.mints.is_empty() || !self.programs.is_empty() || !self.accounts.is_empty() || !self.instructions.is_empty()
+    }
+
+    /// Denies a stream that cannot apply every restriction of the grant, rather than letting
+    /// a restricted token see more than it was granted
+    pub fn check_scope(&self, stream: &str, scope: GrantScope) -> Result<(), Status> {
+        let unscoped: Vec<&str> = [
+            ("mints", &self.mints, scope.mints),
+            ("programs", &self.programs, scope.programs),
+            ("accounts", &self.accounts, scope.accounts),
+            ("instructions", &self.instructions, scope.instructions),
+        ]
+        .into_iter()
+        .filter(|(_, restriction, applies)| !restriction.is_empty() && !applies)
+        .map(|(name, _, _)| name)
+        .collect();
+        if unscoped.is_empty() {
+            return Ok(());
+        }
+        Err(Status::permission_denied(format!(
+            "API token '{}' is restricted to {} which {} cannot apply",
+            self.name,
+            unscoped.join(", "),
+            stream
+        )))
+    }
+
+    fn from_json(entry: &JsonValue) -> Result<(String, Self), String> {
+        let token = entry["token"].as_str().filter(|token| !token.is_empty()).ok_or("entry without a token")?;
+        let name = entry["name"].as_str().unwrap_or("unnamed").to_string();
+        let set = |field: &str| -> HashSet<String> {
+            entry[field].members().filter_map(|value| value.as_str()).map(|value| value.trim().to_string()).collect()
+        };
+        let grant = TokenGrant {
+            name,
+            mints: set("mints"),
+            programs: set("programs"),
+            accounts: set("accounts"),
+            instructions: set("instructions"),
+        };
+        Ok((token.to_string(), grant))
+    }
+}
+
+/// Rejects requests without a configured token, given as `authorization: Bearer <token>`
+/// or `x-api-key: <token>`, and attaches the token's grant to accepted requests
+#[derive(Clone)]
+pub struct ApiTokenInterceptor {
+    tokens: Arc<HashMap<String, Arc<TokenGrant>>>,
+}
+
+fn request_token(request: &Request<()>) -> Option<&str> {
+    let metadata = request.metadata();
+    if let Some(authorization) = metadata.get("authorization").and_then(|value| value.to_str().ok()) {
+        return authorization.strip_prefix("Bearer ").map(str::trim);
+    }
+    metadata.get("x-api-key").and_then(|value| value.to_str().ok()).map(str::trim)
+}
+
+impl Interceptor for ApiTokenInterceptor {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        let token = request_token(&request).ok_or_else(|| Status::unauthenticated("Missing API token"))?;
+        let grant = self.tokens.get(token).cloned().ok_or_else(|| Status::unauthenticated("Invalid API token"))?;
+        request.extensions_mut().insert(grant);
+        Ok(request)
+    }
+}
+
+/// Grant of the token that authenticated the request, None when authentication is disabled
+pub fn request_grant<T>(request: &Request<T>) -> Option<Arc<TokenGrant>> {
+    request.extensions().get::<Arc<TokenGrant>>().cloned()
+}
+
+/// Grant of the request when it restricts what `stream` sends, after checking that the stream
+/// can apply all its restrictions. None when authentication is disabled or the token is unrestricted.
+pub fn stream_grant<T>(request: &Request<T>, stream: &str, scope: GrantScope) -> Result<Option<Arc<TokenGrant>>, Status> {
+    let Some(grant) = request_grant(request).filter(|grant| grant.is_restricted()) else {
+        return Ok(None);
+    };
+    grant.check_scope(stream, scope)?;
+    Ok(Some(grant))
+}
+
+/// Tokens come from API_TOKENS, a comma-separated list of unrestricted tokens, and from
+/// API_TOKENS_FILE, a JSON array of `{ token, name, mints, programs, accounts, instructions }`.
+/// Authentication is disabled when neither is set.
+pub fn api_token_interceptor_from_env() -> Result<Option<ApiTokenInterceptor>, String> {
+    let mut tokens: HashMap<String, Arc<TokenGrant>> = HashMap::default();
+
+    if let Ok(configured) = std::env::var("API_TOKENS") {
+        for token in configured.split(',').map(str::trim).filter(|token| !token.is_empty()) {
+            tokens.insert(token.to_string(), Arc::new(TokenGrant { name: "unrestricted".to_string(), ..Default::default() }));
+        }
+    }
+
+    if let Some(path) = std::env::var("API_TOKENS_FILE").ok().filter(|path| !path.trim().is_empty()) {
+        let content = std::fs::read_to_string(&path).map_err(|e| format!("Failed to read API_TOKENS_FILE '{}': {}", path, e))?;
+        let entries = json::parse(&content).map_err(|e| format!("Invalid API_TOKENS_FILE '{}': {}", path, e))?;
+        if !entries.is_array() {
+            return Err(format!("API_TOKENS_FILE '{}' must contain a JSON array", path));
+        }
+        for entry in entries.members() {
+            let (token, grant) = TokenGrant::from_json(entry).map_err(|e| format!("Invalid API_TOKENS_FILE '{}': {}", path, e))?;
+            tokens.insert(token, Arc::new(grant));
+        }
+    }
+
+    if tokens.is_empty() {
+        return Ok(None);
+    }
+    info!("gRPC subscribers must present one of {} API tokens", tokens.len());
+    Ok(Some(ApiTokenInterceptor { tokens: Arc::new(tokens) }))
+}
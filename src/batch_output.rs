@@ -0,0 +1,76 @@
+use json::{object, JsonValue};
+use once_cell::sync::Lazy;
+use rustc_hash::FxHashMap as HashMap;
+
+/// When enabled, every decoded transaction of a FEC payload is sent as a single
+/// batch message whose instructions reference a shared account-key dictionary
+static OUTPUT_BATCH_MODE: Lazy<bool> = Lazy::new(|| {
+    std::env::var("OUTPUT_BATCH_MODE")
+        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+});
+
+pub fn is_batch_output_enabled() -> bool {
+    *OUTPUT_BATCH_MODE
+}
+
+/// Deduplicated list of base58 pubkeys, referenced by position from the batch
+#[derive(Debug, Default)]
+pub struct AccountKeyDictionary {
+    keys: Vec<String>,
+    positions: HashMap<String, usize>,
+}
+
+impl AccountKeyDictionary {
+    pub fn intern(&mut self, pubkey: &str) -> usize {
+        if let Some(&position) = self.positions.get(pubkey) {
+            return position;
+        }
+        let position = self.keys.len();
+        self.keys.push(pubkey.to_string());
+        self.positions.insert(pubkey.to_string(), position);
+        position
+    }
+
+    pub fn into_keys(self) -> Vec<String> {
+        self.keys
+    }
+}
+
+// Replaces the inline pubkeys of a standardized transaction with dictionary
+// references, accounts that could not be resolved keep their "unknown" marker
+fn compact_transaction(transaction: &mut JsonValue, dictionary: &mut AccountKeyDictionary) {
+    for instruction in transaction["message"]["instructions"].members_mut() {
+        if let Some(program_id) = instruction["program_id"].as_str().map(str::to_string) {
+            instruction.remove("program_id");
+            instruction["programIdKey"] = dictionary.intern(&program_id).into();
+        }
+
+        for account in instruction["accounts"].members_mut() {
+            let Some(pubkey) = account["pubkey"].as_str().map(str::to_string) else { continue };
+            if pubkey == "unknown" {
+                continue;
+            }
+            account.remove("pubkey");
+            account["key"] = dictionary.intern(&pubkey).into();
+        }
+    }
+}
+
+/// Builds the batch message for one payload:
+/// `{ slot, accountKeys: [..], transactions: [..] }`
+pub fn build_transaction_batch(slot: u64, transactions: Vec<JsonValue>) -> JsonValue {
+    let mut dictionary = AccountKeyDictionary::default();
+    let mut compacted = Vec::with_capacity(transactions.len());
+
+    for mut transaction in transactions {
+        compact_transaction(&mut transaction, &mut dictionary);
+        compacted.push(transaction);
+    }
+
+    object! {
+        "slot" => slot,
+        "accountKeys" => dictionary.into_keys(),
+        "transactions" => compacted,
+    }
+}
@@ -0,0 +1,100 @@
+use std::io;
+use std::os::fd::AsRawFd;
+use tokio::io::Interest;
+use tokio::net::UdpSocket;
+use tokio::sync::mpsc;
+use tracing::{error, warn};
+
+/// Largest shred packet, as in the per-packet receive loop
+pub const PACKET_SIZE: usize = 1232;
+
+/// Packets per recvmmsg call from UDP_RECV_BATCH, 1 falls back to one recv_from per packet
+pub fn udp_recv_batch() -> usize {
+    std::env::var("UDP_RECV_BATCH")
+        .unwrap_or_else(|_| "64".to_string())
+        .parse()
+        .ok()
+        .filter(|batch| *batch > 0)
+        .unwrap_or(64)
+}
+
+/// Reads up to `buffers.len()` datagrams in one syscall, returns the size of each.
+/// Truncated datagrams are reported with a size above PACKET_SIZE.
+fn recv_batch(socket: &UdpSocket, buffers: &mut [[u8; PACKET_SIZE]]) -> io::Result<Vec<usize>> {
+    let mut iovecs: Vec<libc::iovec> = buffers
+        .iter_mut()
+        .map(|buffer| libc::iovec { iov_base: buffer.as_mut_ptr().cast(), iov_len: PACKET_SIZE })
+        .collect();
+    let mut headers: Vec<libc::mmsghdr> = iovecs
+        .iter_mut()
+        .map(|iovec| {
+            // SAFETY: mmsghdr is plain data, all-zero is a valid empty header
+            let mut header: libc::mmsghdr = unsafe { std::mem::zeroed() };
+            header.msg_hdr.msg_iov = iovec;
+            header.msg_hdr.msg_iovlen = 1;
+            header
+        })
+        .collect();
+
+    // SAFETY: every header points to one live iovec covering one live buffer of PACKET_SIZE bytes
+    let received = unsafe {
+        libc::recvmmsg(
+            socket.as_raw_fd(),
+            headers.as_mut_ptr(),
+            headers.len() as libc::c_uint,
+            libc::MSG_DONTWAIT as _,
+            std::ptr::null_mut(),
+        )
+    };
+    if received < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(headers[..received as usize]
+        .iter()
+        .map(|header| {
+            if header.msg_hdr.msg_flags & libc::MSG_TRUNC != 0 {
+                PACKET_SIZE + 1
+            } else {
+                header.msg_len as usize
+            }
+        })
+        .collect())
+}
+
+/// Drains the socket `batch` packets per syscall into a channel of packet batches, so that
+/// the kernel queue is emptied at syscall speed while shreds are collected on the other side
+pub fn spawn_batch_receiver(socket: UdpSocket, batch: usize) -> mpsc::Receiver<Vec<Box<[u8]>>> {
+    let (packets_tx, packets_rx) = mpsc::channel::<Vec<Box<[u8]>>>(1024);
+    tokio::spawn(async move {
+        let mut buffers = vec![[0u8; PACKET_SIZE]; batch];
+        loop {
+            if let Err(e) = socket.readable().await {
+                error!("Error waiting for shred packets: {:?}", e);
+                continue;
+            }
+            let sizes = match socket.try_io(Interest::READABLE, || recv_batch(&socket, &mut buffers)) {
+                Ok(sizes) => sizes,
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => continue,
+                Err(e) => {
+                    error!("Error receiving shreds: {:?}", e);
+                    continue;
+                }
+            };
+            let packets: Vec<Box<[u8]>> = sizes
+                .iter()
+                .zip(&buffers)
+                .filter_map(|(&size, buffer)| {
+                    if size > PACKET_SIZE {
+                        warn!("Received data size {} exceeds buffer length {}", size, PACKET_SIZE);
+                        return None;
+                    }
+                    Some(Box::from(&buffer[..size]))
+                })
+                .collect();
+            if packets_tx.send(packets).await.is_err() {
+                break;
+            }
+        }
+    });
+    packets_rx
+}
@@ -0,0 +1,280 @@
+//! Replays archived decoder output over the same gRPC interface as the live decoder,
+//! so backtests can run the exact client code used against the real-time stream.
+//!
+//! Usage: shredstream-reader [--listen ADDR] [--realtime] [--rate MSG_PER_SEC] [--slots FROM-TO] [--loop] FILE...
+//!
+//! Supported archives:
+//! - NDJSON (`.ndjson`, `.jsonl`, `.json`): one decoded transaction per line, or one
+//!   `{"transaction_json": "...", "timestamp": <micros>}` record per line
+//! - Protobuf (`.pb`, `.bin`): length-delimited `TransactionResponse` messages, with either
+//!   encoding of the transaction (JSON text or binary)
+//! - Indexed archive (`.ssda`): written by the archive sink, `--slots` only reads the
+//!   requested slots through the index instead of the whole file
+//! - Parquet (`.parquet`): written by the parquet sink, needs a build with the `parquet`
+//!   feature. Transactions come back with their signature and decoded instructions only.
+//!
+//! NDJSON and protobuf archives can be gzipped (`.ndjson.gz`, ...), as the file sink does
+//! with its closed segments.
+
+use flate2::read::MultiGzDecoder;
+use prost::Message;
+use shredstream_decoder::archive::{ArchiveReader, ARCHIVE_EXTENSION};
+use shredstream_decoder::grpc_server::serve_grpc;
+use shredstream_decoder::grpc_server::transaction::TransactionResponse;
+use shredstream_decoder::encoder::decode_binary;
+use shredstream_decoder::mint_index::index_transaction_accounts;
+use shredstream_decoder::output_message::{OutputMessage, OutputSender};
+use shredstream_decoder::resume_buffer::publish_output;
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tracing::{error, info, warn};
+
+struct ReaderOptions {
+    listen: String,
+    realtime: bool,
+    rate: Option<f64>,
+    repeat: bool,
+    slots: Option<(u64, u64)>,
+    files: Vec<PathBuf>,
+}
+
+fn parse_args() -> Result<ReaderOptions, String> {
+    let mut options = ReaderOptions {
+        listen: std::env::var("GRPC_SERVER_ENDPOINT").unwrap_or_else(|_| "0.0.0.0:50051".to_string()),
+        realtime: false,
+        rate: None,
+        repeat: false,
+        slots: None,
+        files: Vec::new(),
+    };
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--listen" => options.listen = args.next().ok_or("--listen requires an address")?,
+            "--realtime" => options.realtime = true,
+            "--loop" => options.repeat = true,
+            "--rate" => {
+                let rate = args.next().ok_or("--rate requires a value")?;
+                options.rate = Some(rate.parse().map_err(|_| format!("Invalid --rate value: {}", rate))?);
+            }
+            "--slots" => {
+                let range = args.next().ok_or("--slots requires a FROM-TO range")?;
+                options.slots = Some(parse_slot_range(&range).ok_or_else(|| format!("Invalid --slots range: {}", range))?);
+            }
+            _ if arg.starts_with("--") => return Err(format!("Unknown option: {}", arg)),
+            _ => options.files.push(PathBuf::from(arg)),
+        }
+    }
+
+    if options.files.is_empty() {
+        return Err("No archive file given".to_string());
+    }
+    Ok(options)
+}
+
+fn parse_slot_range(range: &str) -> Option<(u64, u64)> {
+    let (from, to) = range.split_once('-')?;
+    let (from, to) = (from.trim().parse().ok()?, to.trim().parse().ok()?);
+    (from <= to).then_some((from, to))
+}
+
+enum ArchiveFormat {
+    Json,
+    Protobuf,
+    Indexed,
+    Parquet,
+}
+
+/// Format of an archive and whether it is gzipped, `out.1700000000000.ndjson.gz` is gzipped NDJSON
+fn archive_format(path: &Path) -> Result<(ArchiveFormat, bool), String> {
+    let gzipped = path.extension().and_then(|ext| ext.to_str()) == Some("gz");
+    let inner = if gzipped { Path::new(path.file_stem().unwrap_or_default()) } else { path };
+    let format = match inner.extension().and_then(|ext| ext.to_str()).unwrap_or_default() {
+        "ndjson" | "jsonl" | "json" => ArchiveFormat::Json,
+        "pb" | "bin" => ArchiveFormat::Protobuf,
+        ARCHIVE_EXTENSION => ArchiveFormat::Indexed,
+        "parquet" => ArchiveFormat::Parquet,
+        other => return Err(format!("Unsupported archive format '{}' for {}", other, path.display())),
+    };
+    // Indexed and Parquet files are read with random access, they are never gzipped as a whole
+    if gzipped && matches!(format, ArchiveFormat::Indexed | ArchiveFormat::Parquet) {
+        return Err(format!("Gzipped {} archives are not supported, decompress it first", path.display()));
+    }
+    Ok((format, gzipped))
+}
+
+fn open_archive(path: &Path, gzipped: bool) -> Result<Box<dyn Read>, String> {
+    let file = std::fs::File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    Ok(if gzipped { Box::new(MultiGzDecoder::new(file)) } else { Box::new(file) })
+}
+
+fn to_message(payload: json::JsonValue, timestamp: u64) -> OutputMessage {
+    let accounts = index_transaction_accounts(&payload);
+    OutputMessage::new(payload, timestamp, accounts)
+}
+
+fn read_json_archive(path: &Path, gzipped: bool) -> Result<Vec<OutputMessage>, String> {
+    let file = open_archive(path, gzipped)?;
+    let mut messages = Vec::new();
+
+    for (line_number, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record = match json::parse(&line) {
+            Ok(record) => record,
+            Err(e) => {
+                warn!("Skipping invalid JSON at {}:{}: {}", path.display(), line_number + 1, e);
+                continue;
+            }
+        };
+
+        let timestamp = record["timestamp"].as_u64().unwrap_or(0);
+        if let Some(transaction_json) = record["transaction_json"].as_str() {
+            match json::parse(transaction_json) {
+                Ok(payload) => messages.push(to_message(payload, timestamp)),
+                Err(e) => warn!("Skipping invalid transaction_json at {}:{}: {}", path.display(), line_number + 1, e),
+            }
+        } else {
+            messages.push(to_message(record, timestamp));
+        }
+    }
+
+    Ok(messages)
+}
+
+fn read_protobuf_archive(path: &Path, gzipped: bool) -> Result<Vec<OutputMessage>, String> {
+    let mut bytes = Vec::new();
+    open_archive(path, gzipped)?
+        .read_to_end(&mut bytes)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let mut buf = bytes.as_slice();
+    let mut messages = Vec::new();
+
+    while !buf.is_empty() {
+        let response = TransactionResponse::decode_length_delimited(&mut buf)
+            .map_err(|e| format!("Corrupted protobuf archive {}: {}", path.display(), e))?;
+        let payload = if response.transaction_binary.is_empty() {
+            json::parse(&response.transaction_json).map_err(|e| e.to_string())
+        } else {
+            decode_binary(&response.transaction_binary)
+        };
+        match payload {
+            Ok(payload) => messages.push(to_message(payload, response.timestamp)),
+            Err(e) => warn!("Skipping undecodable record in {}: {}", path.display(), e),
+        }
+    }
+
+    Ok(messages)
+}
+
+fn read_indexed_archive(path: &Path, slots: Option<(u64, u64)>) -> Result<Vec<OutputMessage>, String> {
+    let mut reader = ArchiveReader::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let records = match slots {
+        Some((from, to)) => reader.read_slots(from, to),
+        None => reader.read_all(),
+    }
+    .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    Ok(records.into_iter().map(|record| to_message(record.payload, record.timestamp)).collect())
+}
+
+#[cfg(feature = "parquet")]
+fn read_parquet_archive(path: &Path) -> Result<Vec<OutputMessage>, String> {
+    let records = shredstream_decoder::parquet_archive::read_parquet_segment(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    Ok(records.into_iter().map(|record| to_message(record.payload, record.timestamp)).collect())
+}
+
+#[cfg(not(feature = "parquet"))]
+fn read_parquet_archive(path: &Path) -> Result<Vec<OutputMessage>, String> {
+    Err(format!("Reading {} needs a build with the parquet feature", path.display()))
+}
+
+async fn replay(messages: &[Arc<OutputMessage>], options: &ReaderOptions, tx: &OutputSender) {
+    let pacing = options.rate.filter(|rate| *rate > 0.0).map(|rate| Duration::from_secs_f64(1.0 / rate));
+    let mut previous_timestamp: Option<u64> = None;
+
+    for message in messages {
+        if options.realtime {
+            if let Some(previous) = previous_timestamp {
+                let gap = message.timestamp.saturating_sub(previous);
+                if gap > 0 {
+                    tokio::time::sleep(Duration::from_micros(gap)).await;
+                }
+            }
+            previous_timestamp = Some(message.timestamp);
+        } else if let Some(pacing) = pacing {
+            tokio::time::sleep(pacing).await;
+        }
+
+        // Republished with fresh sequence numbers so resume tokens work against the replay too
+        if publish_output(tx, OutputMessage::clone(message)).is_err() {
+            warn!("All subscribers disconnected, replay continues");
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    tracing_subscriber::fmt::init();
+    dotenv::dotenv().ok();
+
+    let options = match parse_args() {
+        Ok(options) => options,
+        Err(e) => {
+            error!("{}", e);
+            eprintln!("Usage: shredstream-reader [--listen ADDR] [--realtime] [--rate MSG_PER_SEC] [--slots FROM-TO] [--loop] FILE...");
+            std::process::exit(2);
+        }
+    };
+
+    let mut messages = Vec::new();
+    for path in &options.files {
+        let (format, gzipped) = archive_format(path)?;
+        let mut loaded = match format {
+            ArchiveFormat::Json => read_json_archive(path, gzipped)?,
+            ArchiveFormat::Protobuf => read_protobuf_archive(path, gzipped)?,
+            ArchiveFormat::Indexed => read_indexed_archive(path, options.slots)?,
+            ArchiveFormat::Parquet => read_parquet_archive(path)?,
+        };
+        // Formats without an index are filtered after loading
+        if let Some((from, to)) = options.slots {
+            loaded.retain(|message| (from..=to).contains(&message.slot));
+        }
+        info!("Loaded {} messages from {}", loaded.len(), path.display());
+        messages.extend(loaded.into_iter().map(Arc::new));
+    }
+
+    let (tx, _) = broadcast::channel::<Arc<OutputMessage>>(1000);
+    let addr: std::net::SocketAddr = options.listen.parse().expect("Invalid gRPC address format");
+    let server_tx = tx.clone();
+    tokio::spawn(async move {
+        if let Err(e) = serve_grpc(addr, server_tx).await {
+            error!("gRPC server failed: {:?}", e);
+        }
+    });
+    info!("Serving {} archived messages on {}", messages.len(), addr);
+
+    loop {
+        // Replay only starts once a client is listening, nothing would be delivered otherwise
+        while tx.receiver_count() == 0 {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+
+        replay(&messages, &options, &tx).await;
+        info!("Replay finished");
+
+        if !options.repeat {
+            break;
+        }
+    }
+
+    // Let subscribers drain the channel before the server goes away
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    Ok(())
+}
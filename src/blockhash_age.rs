@@ -0,0 +1,98 @@
+use dashmap::DashMap;
+use json::{object, JsonValue};
+use once_cell::sync::Lazy;
+use solana_entry::entry::Entry;
+use solana_sdk::hash::Hash;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Slots a blockhash stays usable, the validator's MAX_PROCESSING_AGE
+pub const MAX_BLOCKHASH_AGE_SLOTS: u64 = 150;
+
+/// Opt-in flag, tick hashes are neither recorded nor looked up when disabled
+static BLOCKHASH_AGE_ENABLED: Lazy<bool> = Lazy::new(|| {
+    std::env::var("BLOCKHASH_AGE_ENABLED")
+        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+});
+
+/// Age from which a blockhash is reported as near expiry
+static BLOCKHASH_NEAR_EXPIRY_SLOTS: Lazy<u64> = Lazy::new(|| {
+    std::env::var("BLOCKHASH_NEAR_EXPIRY_SLOTS")
+        .unwrap_or_else(|_| "120".to_string())
+        .parse()
+        .unwrap_or(120)
+});
+
+/// Slots of tick hashes kept behind the highest one, a bit more than a blockhash lives
+const RETAINED_SLOTS: u64 = MAX_BLOCKHASH_AGE_SLOTS * 2;
+
+/// Tick hash → slot it was produced in, the last tick of a slot is the blockhash of that slot
+static RECENT_TICK_HASHES: Lazy<DashMap<Hash, u64>> = Lazy::new(DashMap::new);
+static HIGHEST_TICK_SLOT: AtomicU64 = AtomicU64::new(0);
+static FIRST_TICK_SLOT: AtomicU64 = AtomicU64::new(u64::MAX);
+
+pub fn is_blockhash_age_enabled() -> bool {
+    *BLOCKHASH_AGE_ENABLED
+}
+
+/// Records the tick hashes of decoded entries, every blockhash is one of them
+pub fn record_tick_hashes(slot: u64, entries: &[Entry]) {
+    for entry in entries.iter().filter(|entry| entry.transactions.is_empty()) {
+        RECENT_TICK_HASHES.insert(entry.hash, slot);
+    }
+    FIRST_TICK_SLOT.fetch_min(slot, Ordering::Relaxed);
+
+    // Prune once per new highest slot instead of on every FEC block
+    let previous_highest = HIGHEST_TICK_SLOT.fetch_max(slot, Ordering::Relaxed);
+    if slot > previous_highest {
+        let oldest_kept = slot.saturating_sub(RETAINED_SLOTS);
+        RECENT_TICK_HASHES.retain(|_, tick_slot| *tick_slot >= oldest_kept);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockhashAge {
+    Fresh(u64),
+    NearExpiry(u64),
+    Expired(u64),
+    /// Not among the tick hashes seen, lost shreds or a forged blockhash
+    Unknown,
+    /// The decoder has not seen a full blockhash lifetime of ticks yet
+    Warmup,
+}
+
+/// Age of a transaction's blockhash relative to the slot the transaction was seen in
+pub fn blockhash_age(slot: u64, recent_blockhash: &Hash) -> BlockhashAge {
+    let Some(blockhash_slot) = RECENT_TICK_HASHES.get(recent_blockhash).map(|entry| *entry) else {
+        let first = FIRST_TICK_SLOT.load(Ordering::Relaxed);
+        if first == u64::MAX || slot < first.saturating_add(MAX_BLOCKHASH_AGE_SLOTS) {
+            return BlockhashAge::Warmup;
+        }
+        return BlockhashAge::Unknown;
+    };
+    let age = slot.saturating_sub(blockhash_slot);
+    if age > MAX_BLOCKHASH_AGE_SLOTS {
+        BlockhashAge::Expired(age)
+    } else if age >= *BLOCKHASH_NEAR_EXPIRY_SLOTS {
+        BlockhashAge::NearExpiry(age)
+    } else {
+        BlockhashAge::Fresh(age)
+    }
+}
+
+impl BlockhashAge {
+    pub fn to_json(&self) -> JsonValue {
+        let (status, age) = match self {
+            BlockhashAge::Fresh(age) => ("fresh", Some(*age)),
+            BlockhashAge::NearExpiry(age) => ("near_expiry", Some(*age)),
+            BlockhashAge::Expired(age) => ("expired", Some(*age)),
+            BlockhashAge::Unknown => ("unknown", None),
+            BlockhashAge::Warmup => ("warmup", None),
+        };
+        object! {
+            "status" => status,
+            "ageSlots" => age,
+            "maxAgeSlots" => MAX_BLOCKHASH_AGE_SLOTS,
+        }
+    }
+}
@@ -2,6 +2,7 @@ use borsh::{BorshDeserialize, BorshSerialize};
 use json::{object, JsonValue};
 use solana_sdk::pubkey::Pubkey;
 use solana_sdk::pubkey;
+use crate::decode_anomalies::check_parse_residue;
 use crate::utils::create_standardized_instruction;
 
 pub const BOOP_CREATE_TOKEN_INSTRUCTION_DISCRIMINATOR: [u8; 8] = [84, 52, 204, 228, 24, 140, 234, 75];
@@ -55,13 +56,15 @@ pub fn deserialize_boop_create_token_instruction(data: &[u8], accounts_indices:
         }
     };
 
-    let parsed_data = object! {
+    let mut parsed_data = object! {
         "salt" => args.salt.to_string(),
         "name" => args.name,
         "symbol" => args.symbol,
         "uri" => args.uri,
     };
     
+    check_parse_residue("Boop CreateToken", remaining_data_ref, &mut parsed_data);
+
     Ok(create_standardized_instruction(
         &BOOP_PROGRAM_ID,
         "CreateToken",
@@ -90,11 +93,13 @@ pub fn deserialize_boop_deploy_bonding_curve_instruction(data: &[u8], accounts_i
         }
     };
 
-    let parsed_data = object! {
+    let mut parsed_data = object! {
         "creator" => bs58::encode(args.creator.to_bytes()).into_string(),
         "salt" => args.salt.to_string(),
     };
     
+    check_parse_residue("Boop DeployBondingCurve", remaining_data_ref, &mut parsed_data);
+
     Ok(create_standardized_instruction(
         &BOOP_PROGRAM_ID,
         "DeployBondingCurve",
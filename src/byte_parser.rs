@@ -0,0 +1,91 @@
+use solana_sdk::pubkey::Pubkey;
+
+// Little-endian readers over instruction data. Each helper reads at `offset`,
+// advances it past the consumed bytes and fails instead of panicking on short input.
+
+fn take<'a>(data: &'a [u8], offset: &mut usize, len: usize, what: &str) -> Result<&'a [u8], String> {
+    let end = offset
+        .checked_add(len)
+        .ok_or_else(|| format!("Offset overflow while reading {}", what))?;
+    let bytes = data.get(*offset..end).ok_or_else(|| {
+        format!("Data too short for {}: need {} bytes at offset {}, have {}", what, len, offset, data.len())
+    })?;
+    *offset = end;
+    Ok(bytes)
+}
+
+pub fn parse_fixed_bytes<const N: usize>(data: &[u8], offset: &mut usize) -> Result<[u8; N], String> {
+    let bytes = take(data, offset, N, "fixed bytes")?;
+    let mut array = [0u8; N];
+    array.copy_from_slice(bytes);
+    Ok(array)
+}
+
+pub fn parse_u8(data: &[u8], offset: &mut usize) -> Result<u8, String> {
+    Ok(take(data, offset, 1, "u8")?[0])
+}
+
+pub fn parse_bool(data: &[u8], offset: &mut usize) -> Result<bool, String> {
+    match parse_u8(data, offset)? {
+        0 => Ok(false),
+        1 => Ok(true),
+        other => Err(format!("Invalid bool value {} at offset {}", other, *offset - 1)),
+    }
+}
+
+pub fn parse_u16(data: &[u8], offset: &mut usize) -> Result<u16, String> {
+    Ok(u16::from_le_bytes(parse_fixed_bytes(data, offset)?))
+}
+
+pub fn parse_u32(data: &[u8], offset: &mut usize) -> Result<u32, String> {
+    Ok(u32::from_le_bytes(parse_fixed_bytes(data, offset)?))
+}
+
+pub fn parse_i32(data: &[u8], offset: &mut usize) -> Result<i32, String> {
+    Ok(i32::from_le_bytes(parse_fixed_bytes(data, offset)?))
+}
+
+pub fn parse_u64(data: &[u8], offset: &mut usize) -> Result<u64, String> {
+    Ok(u64::from_le_bytes(parse_fixed_bytes(data, offset)?))
+}
+
+pub fn parse_i64(data: &[u8], offset: &mut usize) -> Result<i64, String> {
+    Ok(i64::from_le_bytes(parse_fixed_bytes(data, offset)?))
+}
+
+pub fn parse_u128(data: &[u8], offset: &mut usize) -> Result<u128, String> {
+    Ok(u128::from_le_bytes(parse_fixed_bytes(data, offset)?))
+}
+
+pub fn parse_i128(data: &[u8], offset: &mut usize) -> Result<i128, String> {
+    Ok(i128::from_le_bytes(parse_fixed_bytes(data, offset)?))
+}
+
+pub fn parse_f64(data: &[u8], offset: &mut usize) -> Result<f64, String> {
+    Ok(f64::from_le_bytes(parse_fixed_bytes(data, offset)?))
+}
+
+pub fn parse_pubkey(data: &[u8], offset: &mut usize) -> Result<Pubkey, String> {
+    Ok(Pubkey::new_from_array(parse_fixed_bytes(data, offset)?))
+}
+
+/// Borsh Option<Pubkey>: a 0/1 tag followed by the key when present
+pub fn parse_option_pubkey(data: &[u8], offset: &mut usize) -> Result<Option<Pubkey>, String> {
+    match parse_u8(data, offset)? {
+        0 => Ok(None),
+        1 => Ok(Some(parse_pubkey(data, offset)?)),
+        tag => Err(format!("Invalid Option<Pubkey> tag {} at offset {}", tag, *offset - 1)),
+    }
+}
+
+/// Borsh string: u32 length prefix followed by UTF-8 bytes
+pub fn parse_string(data: &[u8], offset: &mut usize) -> Result<String, String> {
+    let len = parse_u32(data, offset)? as usize;
+    let bytes = take(data, offset, len, "string")?;
+    String::from_utf8(bytes.to_vec()).map_err(|e| format!("Invalid UTF-8 string: {}", e))
+}
+
+/// Bytes not consumed by the parser, for residue checks
+pub fn remaining<'a>(data: &'a [u8], offset: usize) -> &'a [u8] {
+    data.get(offset..).unwrap_or_default()
+}
@@ -0,0 +1,282 @@
+use futures_util::StreamExt;
+use once_cell::sync::Lazy;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::{info, warn};
+
+use crate::encoder::decode_binary;
+use crate::grpc_server::transaction::transaction_service_client::TransactionServiceClient;
+use crate::grpc_server::transaction::StreamTransactionsRequest;
+use crate::operational_events::{publish_operational_event, Severity};
+
+pub const CANARY_DEGRADED_EVENT: &str = "canary_degraded";
+pub const CANARY_RECOVERED_EVENT: &str = "canary_recovered";
+
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// Built-in subscriber of our own gRPC stream, an end-to-end check of broadcast and serialization
+#[derive(Debug, Clone)]
+pub struct CanaryConfig {
+    pub endpoint: String,
+    pub mints: Vec<String>,
+    pub encoding: String,
+    pub report_interval: Duration,
+    pub max_gap_rate: f64,
+    pub max_p99_latency: Duration,
+}
+
+impl CanaryConfig {
+    /// Build the configuration from env, returns None unless CANARY_ENABLED is set.
+    /// The canary connects to the local gRPC server unless CANARY_GRPC_ENDPOINT is given.
+    pub fn from_env(grpc_address: &str) -> Option<Self> {
+        let enabled = std::env::var("CANARY_ENABLED")
+            .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+            .unwrap_or(false);
+        if !enabled {
+            return None;
+        }
+        let endpoint = std::env::var("CANARY_GRPC_ENDPOINT")
+            .ok()
+            .filter(|v| !v.trim().is_empty())
+            .unwrap_or_else(|| {
+                let port = grpc_address.rsplit(':').next().unwrap_or("50051");
+                format!("http://127.0.0.1:{}", port)
+            });
+        let mints = std::env::var("CANARY_MINTS")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|mint| !mint.is_empty())
+            .map(String::from)
+            .collect();
+        let report_interval_secs = std::env::var("CANARY_REPORT_INTERVAL_SECS")
+            .unwrap_or_else(|_| "60".to_string())
+            .parse()
+            .ok()
+            .filter(|secs| *secs > 0)
+            .unwrap_or(60);
+        let max_gap_rate = std::env::var("CANARY_MAX_GAP_RATE")
+            .unwrap_or_else(|_| "0.01".to_string())
+            .parse()
+            .unwrap_or(0.01);
+        let max_p99_latency_ms = std::env::var("CANARY_MAX_P99_LATENCY_MS")
+            .unwrap_or_else(|_| "500".to_string())
+            .parse()
+            .unwrap_or(500);
+
+        Some(Self {
+            endpoint,
+            mints,
+            encoding: std::env::var("CANARY_ENCODING").unwrap_or_default(),
+            report_interval: Duration::from_secs(report_interval_secs),
+            max_gap_rate,
+            max_p99_latency: Duration::from_millis(max_p99_latency_ms),
+        })
+    }
+}
+
+/// Canary measurements over the last completed report interval, with totals since startup
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CanaryReport {
+    pub connected: bool,
+    pub messages_total: u64,
+    pub missed_total: u64,
+    pub decode_failures_total: u64,
+    pub reconnects_total: u64,
+    pub window_messages: u64,
+    pub window_missed: u64,
+    pub gap_rate: f64,
+    pub latency_p50_us: u64,
+    pub latency_p99_us: u64,
+    pub latency_max_us: u64,
+    /// Microseconds since the epoch at the end of the window, 0 before the first report
+    pub window_end: u64,
+}
+
+static CONNECTED: AtomicBool = AtomicBool::new(false);
+static MESSAGES_TOTAL: AtomicU64 = AtomicU64::new(0);
+static MISSED_TOTAL: AtomicU64 = AtomicU64::new(0);
+static DECODE_FAILURES_TOTAL: AtomicU64 = AtomicU64::new(0);
+static RECONNECTS_TOTAL: AtomicU64 = AtomicU64::new(0);
+
+/// Delivery latencies of the current window, in microseconds
+static WINDOW_LATENCIES: Lazy<Mutex<Vec<u64>>> = Lazy::new(|| Mutex::new(Vec::new()));
+static WINDOW_MISSED: AtomicU64 = AtomicU64::new(0);
+static LAST_REPORT: Lazy<Mutex<Option<CanaryReport>>> = Lazy::new(|| Mutex::new(None));
+
+fn now_micros() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_micros() as u64
+}
+
+/// Last report of the canary, None when it is disabled or no window completed yet
+pub fn canary_report() -> Option<CanaryReport> {
+    *LAST_REPORT.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn percentile(sorted: &[u64], percentile: f64) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = ((sorted.len() - 1) as f64 * percentile).round() as usize;
+    sorted[rank.min(sorted.len() - 1)]
+}
+
+fn close_window() -> CanaryReport {
+    let mut latencies = std::mem::take(&mut *WINDOW_LATENCIES.lock().unwrap_or_else(|poisoned| poisoned.into_inner()));
+    let window_missed = WINDOW_MISSED.swap(0, Ordering::Relaxed);
+    latencies.sort_unstable();
+
+    let window_messages = latencies.len() as u64;
+    let expected = window_messages + window_missed;
+    let report = CanaryReport {
+        connected: CONNECTED.load(Ordering::Relaxed),
+        messages_total: MESSAGES_TOTAL.load(Ordering::Relaxed),
+        missed_total: MISSED_TOTAL.load(Ordering::Relaxed),
+        decode_failures_total: DECODE_FAILURES_TOTAL.load(Ordering::Relaxed),
+        reconnects_total: RECONNECTS_TOTAL.load(Ordering::Relaxed),
+        window_messages,
+        window_missed,
+        gap_rate: if expected == 0 { 0.0 } else { window_missed as f64 / expected as f64 },
+        latency_p50_us: percentile(&latencies, 0.50),
+        latency_p99_us: percentile(&latencies, 0.99),
+        latency_max_us: latencies.last().copied().unwrap_or(0),
+        window_end: now_micros(),
+    };
+    *LAST_REPORT.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(report);
+    report
+}
+
+/// Checks the payload deserializes the way a client would read it
+fn payload_decodes(transaction_json: &str, transaction_binary: &[u8]) -> bool {
+    if !transaction_binary.is_empty() {
+        decode_binary(transaction_binary).is_ok()
+    } else {
+        json::parse(transaction_json).is_ok()
+    }
+}
+
+async fn subscribe_once(config: &CanaryConfig) -> Result<(), String> {
+    let mut client = TransactionServiceClient::connect(config.endpoint.clone())
+        .await
+        .map_err(|e| format!("failed to connect to {}: {}", config.endpoint, e))?;
+    let request = StreamTransactionsRequest {
+        mints: config.mints.clone(),
+        encoding: config.encoding.clone(),
+        ..Default::default()
+    };
+    let mut stream = client
+        .stream_transactions(request)
+        .await
+        .map_err(|e| format!("subscription rejected: {}", e))?
+        .into_inner();
+
+    CONNECTED.store(true, Ordering::Relaxed);
+    info!("Canary subscribed to {}", config.endpoint);
+    let mut last_sequence = 0u64;
+
+    while let Some(response) = stream.next().await {
+        let response = response.map_err(|e| format!("stream failed: {}", e))?;
+
+        let latency = now_micros().saturating_sub(response.timestamp);
+        WINDOW_LATENCIES
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .push(latency);
+        MESSAGES_TOTAL.fetch_add(1, Ordering::Relaxed);
+
+        // stream_sequence starts at 1 for every subscription
+        let missed = response.stream_sequence.saturating_sub(last_sequence + 1);
+        if missed > 0 {
+            WINDOW_MISSED.fetch_add(missed, Ordering::Relaxed);
+            MISSED_TOTAL.fetch_add(missed, Ordering::Relaxed);
+        }
+        last_sequence = last_sequence.max(response.stream_sequence);
+
+        if !payload_decodes(&response.transaction_json, &response.transaction_binary) {
+            DECODE_FAILURES_TOTAL.fetch_add(1, Ordering::Relaxed);
+            warn!("Canary received a payload that does not decode (stream_sequence={})", response.stream_sequence);
+        }
+    }
+    Err("stream closed by the server".to_string())
+}
+
+/// Keeps the canary subscription alive, reconnecting after failures
+async fn run_canary_subscriber(config: CanaryConfig) {
+    loop {
+        if let Err(e) = subscribe_once(&config).await {
+            warn!("Canary: {}, reconnecting in {:?}", e, RECONNECT_DELAY);
+        }
+        CONNECTED.store(false, Ordering::Relaxed);
+        RECONNECTS_TOTAL.fetch_add(1, Ordering::Relaxed);
+        tokio::time::sleep(RECONNECT_DELAY).await;
+    }
+}
+
+/// Runs the canary subscriber and closes a measurement window every report interval.
+/// A degraded window (gap rate, p99 latency, undecodable payloads or no connection) is published
+/// on the operational events stream once, along with the recovery.
+pub async fn run_canary(config: CanaryConfig) {
+    info!(
+        "Canary enabled against {} (mints={:?}, report every {:?})",
+        config.endpoint, config.mints, config.report_interval
+    );
+    tokio::spawn(run_canary_subscriber(config.clone()));
+
+    let mut ticker = tokio::time::interval(config.report_interval);
+    ticker.tick().await;
+    let mut degraded = false;
+    let mut decode_failures_seen = 0u64;
+
+    loop {
+        ticker.tick().await;
+        let report = close_window();
+        info!(
+            "Canary: connected={} messages={} missed={} gap_rate={:.4} latency p50={}us p99={}us max={}us decode_failures={} reconnects={}",
+            report.connected,
+            report.window_messages,
+            report.window_missed,
+            report.gap_rate,
+            report.latency_p50_us,
+            report.latency_p99_us,
+            report.latency_max_us,
+            report.decode_failures_total,
+            report.reconnects_total
+        );
+
+        let mut reasons = Vec::new();
+        if !report.connected {
+            reasons.push("disconnected");
+        }
+        if report.gap_rate > config.max_gap_rate {
+            reasons.push("gap_rate");
+        }
+        if report.latency_p99_us > config.max_p99_latency.as_micros() as u64 {
+            reasons.push("latency");
+        }
+        if report.decode_failures_total > decode_failures_seen {
+            reasons.push("decode_failures");
+        }
+        decode_failures_seen = report.decode_failures_total;
+
+        let details = json::object! {
+            "reasons" => reasons.clone(),
+            "messages" => report.window_messages,
+            "missed" => report.window_missed,
+            "gapRate" => report.gap_rate,
+            "latencyP99Us" => report.latency_p99_us,
+        };
+        if !reasons.is_empty() && !degraded {
+            degraded = true;
+            warn!("Canary degraded: {}", reasons.join(", "));
+            publish_operational_event(CANARY_DEGRADED_EVENT, Severity::Error, format!("Canary degraded: {}", reasons.join(", ")), details);
+        } else if reasons.is_empty() && degraded {
+            degraded = false;
+            info!("Canary recovered");
+            publish_operational_event(CANARY_RECOVERED_EVENT, Severity::Info, "Canary recovered", details);
+        }
+    }
+}
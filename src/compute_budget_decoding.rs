@@ -0,0 +1,60 @@
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+
+pub const COMPUTE_BUDGET_PROGRAM_ID: Pubkey = pubkey!("ComputeBudget111111111111111111111111111111");
+
+const SET_COMPUTE_UNIT_LIMIT: u8 = 2;
+const SET_COMPUTE_UNIT_PRICE: u8 = 3;
+
+/// Limit the runtime applies per instruction when the transaction does not set one
+const DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT: u64 = 200_000;
+const MAX_COMPUTE_UNIT_LIMIT: u64 = 1_400_000;
+const MICRO_LAMPORTS_PER_LAMPORT: u128 = 1_000_000;
+
+pub fn is_compute_budget_program(program_id: &Pubkey) -> bool {
+    program_id == &COMPUTE_BUDGET_PROGRAM_ID
+}
+
+/// Compute budget requested by the ComputeBudget instructions of a transaction.
+/// Like memos these are attached to the transaction and are not output instructions.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ComputeBudget {
+    pub unit_limit: Option<u32>,
+    /// Micro-lamports per compute unit
+    pub unit_price: Option<u64>,
+}
+
+impl ComputeBudget {
+    /// Applies one ComputeBudget instruction. The runtime rejects transactions repeating one,
+    /// the last is kept here. Heap frame and data size requests carry no fee.
+    pub fn apply(&mut self, data: &[u8]) {
+        match data.split_first() {
+            Some((&SET_COMPUTE_UNIT_LIMIT, args)) => {
+                if let Some(bytes) = args.get(0..4) {
+                    self.unit_limit = Some(u32::from_le_bytes(bytes.try_into().expect("4 bytes")));
+                }
+            }
+            Some((&SET_COMPUTE_UNIT_PRICE, args)) => {
+                if let Some(bytes) = args.get(0..8) {
+                    self.unit_price = Some(u64::from_le_bytes(bytes.try_into().expect("8 bytes")));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Effective compute unit limit, the runtime default when none was requested
+    pub fn cu_limit(&self, non_compute_budget_instructions: usize) -> u64 {
+        let limit = match self.unit_limit {
+            Some(limit) => limit as u64,
+            None => DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT.saturating_mul(non_compute_budget_instructions as u64),
+        };
+        limit.min(MAX_COMPUTE_UNIT_LIMIT)
+    }
+
+    /// Prioritization fee the transaction pays, rounded up like the runtime does
+    pub fn priority_fee_lamports(&self, non_compute_budget_instructions: usize) -> u64 {
+        let micro_lamports = self.unit_price.unwrap_or(0) as u128 * self.cu_limit(non_compute_budget_instructions) as u128;
+        micro_lamports.div_ceil(MICRO_LAMPORTS_PER_LAMPORT) as u64
+    }
+}
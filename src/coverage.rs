@@ -0,0 +1,142 @@
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+use solana_sdk::instruction::CompiledInstruction;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::BTreeMap;
+use tracing::warn;
+
+use crate::decode_anomalies::report_account_count_mismatch;
+use crate::decoder_registry::{find_decoder_by_program, find_layout_variant, find_registered_instruction};
+
+/// When enabled, a configured account minimum that no observed instruction meets anymore
+/// is relaxed to the learned minimum, with a warning, instead of dropping every instruction
+static ACCOUNT_MIN_AUTO_RELAX: Lazy<bool> = Lazy::new(|| {
+    std::env::var("ACCOUNT_MIN_AUTO_RELAX")
+        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+});
+
+/// Observations needed before the learned distribution is trusted for relaxing
+static ACCOUNT_MIN_RELAX_SAMPLES: Lazy<u64> = Lazy::new(|| {
+    std::env::var("ACCOUNT_MIN_RELAX_SAMPLES")
+        .unwrap_or_else(|_| "100".to_string())
+        .parse()
+        .unwrap_or(100)
+});
+
+pub fn is_account_min_auto_relax_enabled() -> bool {
+    *ACCOUNT_MIN_AUTO_RELAX
+}
+
+/// Learned account-count distribution of one registered instruction
+#[derive(Debug, Clone, Default)]
+struct AccountCountStats {
+    samples: u64,
+    min: usize,
+    max: usize,
+    histogram: BTreeMap<usize, u64>,
+    configured_minimum: Option<usize>,
+    relaxed: bool,
+}
+
+static ACCOUNT_COUNTS: Lazy<DashMap<(Pubkey, &'static str), AccountCountStats>> = Lazy::new(DashMap::new);
+
+fn registered_instruction(program_key: &Pubkey, data: &[u8]) -> Option<(&'static str, &'static str)> {
+    find_registered_instruction(program_key, data).map(|(decoder, instruction)| (decoder.protocol, instruction.name))
+}
+
+/// Records the account count of every instruction a registered decoder recognizes
+pub fn observe_instruction_accounts(program_key: &Pubkey, instr: &CompiledInstruction) {
+    let Some((_, name)) = registered_instruction(program_key, &instr.data) else { return };
+    let count = instr.accounts.len();
+
+    let mut stats = ACCOUNT_COUNTS.entry((*program_key, name)).or_default();
+    if stats.samples == 0 || count < stats.min {
+        stats.min = count;
+    }
+    stats.max = stats.max.max(count);
+    stats.samples += 1;
+    *stats.histogram.entry(count).or_insert(0) += 1;
+}
+
+/// Checks an instruction against the account minimum of its decoder. Reports the mismatch
+/// and rejects it, unless auto-relaxing is enabled and the learned distribution shows the
+/// protocol no longer sends that many accounts. Slots pinned to a historical layout use its minimum.
+pub fn accept_account_count(
+    context: &str,
+    required: usize,
+    slot: u64,
+    program_key: &Pubkey,
+    instr: &CompiledInstruction,
+    account_keys: &[Pubkey],
+) -> bool {
+    let required = find_layout_variant(program_key, &instr.data, slot)
+        .and_then(|variant| variant.min_accounts)
+        .unwrap_or(required);
+    let count = instr.accounts.len();
+    let key = registered_instruction(program_key, &instr.data).map(|(_, name)| (*program_key, name));
+
+    if let Some(mut stats) = key.and_then(|key| ACCOUNT_COUNTS.get_mut(&key)) {
+        stats.configured_minimum = Some(required);
+        if count < required
+            && is_account_min_auto_relax_enabled()
+            && stats.samples >= *ACCOUNT_MIN_RELAX_SAMPLES
+            && stats.max < required
+            && count >= stats.min
+        {
+            if !stats.relaxed {
+                stats.relaxed = true;
+                warn!(
+                    "{}: no instruction out of {} met the minimum of {} accounts (observed {}..={}), relaxing the minimum to {}",
+                    context, stats.samples, required, stats.min, stats.max, stats.min
+                );
+            }
+            return true;
+        }
+    }
+
+    if count < required {
+        report_account_count_mismatch(context, required, instr, account_keys);
+        return false;
+    }
+    true
+}
+
+/// Account-count coverage of one registered instruction, as exposed by the coverage report
+#[derive(Debug, Clone)]
+pub struct AccountCountCoverage {
+    pub protocol: &'static str,
+    pub program_id: Pubkey,
+    pub instruction: &'static str,
+    pub samples: u64,
+    pub min_accounts: usize,
+    pub max_accounts: usize,
+    /// Minimum enforced by the decoder, known once an instruction went through the check
+    pub configured_minimum: Option<usize>,
+    pub relaxed: bool,
+    /// (account count, occurrences), ordered by account count
+    pub distribution: Vec<(usize, u64)>,
+}
+
+pub fn account_count_coverage() -> Vec<AccountCountCoverage> {
+    let mut coverage: Vec<AccountCountCoverage> = ACCOUNT_COUNTS
+        .iter()
+        .map(|entry| {
+            let (program_id, instruction) = *entry.key();
+            let stats = entry.value();
+            AccountCountCoverage {
+                protocol: find_decoder_by_program(&program_id).map(|decoder| decoder.protocol).unwrap_or("Unknown"),
+                program_id,
+                instruction,
+                samples: stats.samples,
+                min_accounts: stats.min,
+                max_accounts: stats.max,
+                configured_minimum: stats.configured_minimum,
+                relaxed: stats.relaxed,
+                distribution: stats.histogram.iter().map(|(count, occurrences)| (*count, *occurrences)).collect(),
+            }
+        })
+        .collect();
+    coverage.sort_by(|a, b| (a.protocol, a.instruction).cmp(&(b.protocol, b.instruction)));
+    coverage
+}
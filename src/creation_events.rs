@@ -0,0 +1,161 @@
+use json::{object, JsonValue};
+use rustc_hash::FxHashSet as HashSet;
+
+use crate::post_processing::PostProcessorEvent;
+
+pub const TOKEN_LAUNCH_EVENT: &str = "token_launch";
+pub const POOL_CREATED_EVENT: &str = "pool_created";
+
+fn account_at(instruction: &JsonValue, position: usize) -> Option<&str> {
+    instruction["accounts"][position]["pubkey"]
+        .as_str()
+        .filter(|pubkey| *pubkey != "unknown")
+}
+
+/// Where a launch instruction keeps the mint, the creator and the token metadata
+struct LaunchLayout<'a> {
+    mint: Option<&'a str>,
+    creator: Option<&'a str>,
+    metadata: &'a JsonValue,
+}
+
+fn launch_layout<'a>(instruction: &'a JsonValue, protocol: &str, instruction_name: &str) -> Option<LaunchLayout<'a>> {
+    let parsed = &instruction["parsed_data"];
+    match (protocol, instruction_name) {
+        // Accounts: mint=0, the creator is an arg
+        ("Pumpfun", "Create") => Some(LaunchLayout { mint: account_at(instruction, 0), creator: parsed["creator"].as_str(), metadata: parsed }),
+        // Accounts: sender=0, mint=3
+        ("Moonit", "TokenMint") => Some(LaunchLayout { mint: account_at(instruction, 3), creator: account_at(instruction, 0), metadata: parsed }),
+        // Accounts: mint=2, payer=3
+        ("Boop", "CreateToken") => Some(LaunchLayout { mint: account_at(instruction, 2), creator: account_at(instruction, 3), metadata: parsed }),
+        // The decoder already names the accounts, the metadata is nested in the mint params
+        ("RaydiumLaunchLab", "Initialize") => Some(LaunchLayout {
+            mint: parsed["baseMint"].as_str(),
+            creator: parsed["creator"].as_str(),
+            metadata: &parsed["baseMintParam"],
+        }),
+        // Accounts: creator=2, base_mint=3
+        ("MeteoraVCurve", "InitializeVirtualPoolWithSplToken" | "InitializeVirtualPoolWithToken2022") => {
+            Some(LaunchLayout { mint: account_at(instruction, 3), creator: account_at(instruction, 2), metadata: parsed })
+        }
+        _ => None,
+    }
+}
+
+/// One token_launch event per launch instruction of the transaction, in the same shape for every launchpad
+pub fn token_launch_events(slot: u64, json_transaction: &JsonValue) -> Vec<PostProcessorEvent> {
+    let signature = json_transaction["signatures"][0].as_str().unwrap_or_default();
+
+    json_transaction["message"]["instructions"]
+        .members()
+        .filter_map(|instruction| {
+            let (Some(protocol), Some(name)) = (instruction["protocol"].as_str(), instruction["instruction_name"].as_str()) else {
+                return None;
+            };
+            let layout = launch_layout(instruction, protocol, name)?;
+            let mint = layout.mint?;
+
+            let event = object! {
+                "event" => TOKEN_LAUNCH_EVENT,
+                "platform" => protocol,
+                "mint" => mint,
+                "name" => layout.metadata["name"].as_str(),
+                "symbol" => layout.metadata["symbol"].as_str(),
+                "uri" => layout.metadata["uri"].as_str(),
+                "creator" => layout.creator,
+                "slot" => slot,
+                "signature" => signature,
+            };
+
+            let mut accounts = HashSet::default();
+            accounts.insert(mint.to_string());
+            accounts.extend(layout.creator.map(str::to_string));
+            Some(PostProcessorEvent { event_type: TOKEN_LAUNCH_EVENT, json: event, accounts })
+        })
+        .collect()
+}
+
+/// Where a pool creation keeps the pool and its mints, and the args holding the initial amounts
+struct PoolLayout {
+    pool: usize,
+    mint_a: usize,
+    mint_b: usize,
+    amounts: Option<(&'static str, &'static str)>,
+}
+
+fn pool_layout(instruction: &JsonValue, protocol: &str, instruction_name: &str) -> Option<PoolLayout> {
+    let layout = |pool, mint_a, mint_b, amounts| Some(PoolLayout { pool, mint_a, mint_b, amounts });
+    match (protocol, instruction_name) {
+        // Accounts: amm=4, coin_mint=8, pc_mint=9
+        ("Raydium", "Initialize2") => layout(4, 8, 9, Some(("init_coin_amount", "init_pc_amount"))),
+        // Accounts: pool_state=3, token_0_mint=4, token_1_mint=5
+        ("RaydiumCPMM", "Initialize") => layout(3, 4, 5, Some(("init_amount_0", "init_amount_1"))),
+        // Concentrated pools are created empty, liquidity comes with the first position
+        ("RaydiumCLMM", "CreatePool") => layout(2, 3, 4, None),
+        ("Orca", "InitializePool") => layout(4, 1, 2, None),
+        ("Orca", "InitializePoolV2") => layout(6, 1, 2, None),
+        // Accounts: pool=0, base_mint=3, quote_mint=4
+        ("PumpAMM", "CreatePool") => layout(0, 3, 4, Some(("baseAmountIn", "quoteAmountIn"))),
+        // Accounts: pool=0, token_a_mint=2, token_b_mint=3
+        ("MeteoraDyn", "InitializePermissionlessPool") => layout(0, 2, 3, Some(("token_a_amount", "token_b_amount"))),
+        // The three variants differ in layout, the decoder records the positions. DAMM v2
+        // pools are seeded with liquidity rather than token amounts.
+        ("MeteoraAmmV2", "CreatePool") => {
+            let parsed = &instruction["parsed_data"];
+            layout(
+                parsed["pool_index"].as_usize()?,
+                parsed["mint_a_index"].as_usize()?,
+                parsed["mint_b_index"].as_usize()?,
+                None,
+            )
+        }
+        _ => None,
+    }
+}
+
+/// One pool_created event per pool creation of the transaction, in the same shape for every AMM.
+/// Initial amounts are null where the instruction does not deposit any.
+pub fn pool_created_events(slot: u64, json_transaction: &JsonValue) -> Vec<PostProcessorEvent> {
+    let signature = json_transaction["signatures"][0].as_str().unwrap_or_default();
+
+    json_transaction["message"]["instructions"]
+        .members()
+        .filter_map(|instruction| {
+            let (Some(protocol), Some(name)) = (instruction["protocol"].as_str(), instruction["instruction_name"].as_str()) else {
+                return None;
+            };
+            let layout = pool_layout(instruction, protocol, name)?;
+            let (Some(pool), Some(mint_a), Some(mint_b)) = (
+                account_at(instruction, layout.pool),
+                account_at(instruction, layout.mint_a),
+                account_at(instruction, layout.mint_b),
+            ) else {
+                return None;
+            };
+
+            let parsed = &instruction["parsed_data"];
+            let (amount_a, amount_b) = match layout.amounts {
+                Some((field_a, field_b)) => (parsed[field_a].as_str(), parsed[field_b].as_str()),
+                None => (None, None),
+            };
+
+            let event = object! {
+                "event" => POOL_CREATED_EVENT,
+                "platform" => protocol,
+                "pool" => pool,
+                "mint_a" => mint_a,
+                "mint_b" => mint_b,
+                "amount_a" => amount_a,
+                "amount_b" => amount_b,
+                "slot" => slot,
+                "signature" => signature,
+            };
+
+            let mut accounts = HashSet::default();
+            accounts.insert(pool.to_string());
+            accounts.insert(mint_a.to_string());
+            accounts.insert(mint_b.to_string());
+            Some(PostProcessorEvent { event_type: POOL_CREATED_EVENT, json: event, accounts })
+        })
+        .collect()
+}
@@ -0,0 +1,111 @@
+use json::JsonValue;
+use once_cell::sync::Lazy;
+use solana_sdk::instruction::CompiledInstruction;
+use solana_sdk::pubkey::Pubkey;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tracing::{debug, error, warn};
+
+/// Strict/dev mode: decode anomalies are escalated to errors with a payload dump.
+/// Production mode stays lenient and only logs them at a low level.
+static DECODER_STRICT_MODE: Lazy<bool> = Lazy::new(|| {
+    std::env::var("DECODER_STRICT_MODE")
+        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+});
+
+static UNKNOWN_DISCRIMINATORS: AtomicU64 = AtomicU64::new(0);
+static ACCOUNT_COUNT_MISMATCHES: AtomicU64 = AtomicU64::new(0);
+static PARSE_RESIDUES: AtomicU64 = AtomicU64::new(0);
+
+pub fn is_strict_mode() -> bool {
+    *DECODER_STRICT_MODE
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AnomalyCounts {
+    pub unknown_discriminators: u64,
+    pub account_count_mismatches: u64,
+    pub parse_residues: u64,
+}
+
+pub fn anomaly_counts() -> AnomalyCounts {
+    AnomalyCounts {
+        unknown_discriminators: UNKNOWN_DISCRIMINATORS.load(Ordering::Relaxed),
+        account_count_mismatches: ACCOUNT_COUNT_MISMATCHES.load(Ordering::Relaxed),
+        parse_residues: PARSE_RESIDUES.load(Ordering::Relaxed),
+    }
+}
+
+fn dump_accounts(instr: &CompiledInstruction, account_keys: &[Pubkey]) -> String {
+    instr
+        .accounts
+        .iter()
+        .map(|&index| {
+            account_keys
+                .get(index as usize)
+                .map(|key| key.to_string())
+                .unwrap_or_else(|| format!("#{}", index))
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// An instruction of a tracked program whose discriminator no decoder recognizes
+pub fn report_unknown_discriminator(protocol: &str, program_key: &Pubkey, instr: &CompiledInstruction, account_keys: &[Pubkey]) {
+    UNKNOWN_DISCRIMINATORS.fetch_add(1, Ordering::Relaxed);
+    if is_strict_mode() {
+        error!(
+            "[strict] {}: unknown discriminator {} for program {} (data={}, accounts=[{}])",
+            protocol,
+            hex::encode(instr.data.get(0..8).unwrap_or(&instr.data)),
+            program_key,
+            hex::encode(&instr.data),
+            dump_accounts(instr, account_keys)
+        );
+    } else {
+        debug!("{}: unknown discriminator {}", protocol, hex::encode(instr.data.get(0..8).unwrap_or(&instr.data)));
+    }
+}
+
+/// An instruction with fewer accounts than its decoder requires
+pub fn report_account_count_mismatch(context: &str, required: usize, instr: &CompiledInstruction, account_keys: &[Pubkey]) {
+    ACCOUNT_COUNT_MISMATCHES.fetch_add(1, Ordering::Relaxed);
+    if is_strict_mode() {
+        error!(
+            "[strict] {}: {} accounts, a minimum of {} is needed (data={}, accounts=[{}])",
+            context,
+            instr.accounts.len(),
+            required,
+            hex::encode(&instr.data),
+            dump_accounts(instr, account_keys)
+        );
+    } else {
+        warn!("{}: The instruction does not contain a minimum of {} accounts needed.", context, required);
+    }
+}
+
+/// Bytes left over after a decoder consumed every field it knows about
+pub fn report_parse_residue(context: &str, residue_len: usize, data: &[u8]) {
+    PARSE_RESIDUES.fetch_add(1, Ordering::Relaxed);
+    if is_strict_mode() {
+        error!(
+            "[strict] {}: {} unparsed trailing bytes (data={})",
+            context,
+            residue_len,
+            hex::encode(data)
+        );
+    } else {
+        debug!("{}: {} unparsed trailing bytes", context, residue_len);
+    }
+}
+
+/// Checks the bytes left after a decoder consumed its known fields. An all-zero tail is
+/// treated as padding, anything else usually means the program appended new parameters:
+/// it is reported and its length added to the parsed data as `parse_residue_len`.
+pub fn check_parse_residue(context: &str, residue: &[u8], parsed_data: &mut JsonValue) {
+    if residue.iter().all(|byte| *byte == 0) {
+        return;
+    }
+    report_parse_residue(context, residue.len(), residue);
+    parsed_data["parse_residue_len"] = residue.len().into();
+}
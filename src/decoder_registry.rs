@@ -0,0 +1,522 @@
+use json::JsonValue;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::BTreeMap;
+use tracing::{error, info, warn};
+
+use crate::address_lookup_table_decoding::*;
+use crate::boop_decoding::*;
+use crate::drift_decoding::*;
+use crate::kamino_lend_decoding::*;
+use crate::meteora_amm_v2_decoding::*;
+use crate::meteora_dlmm_decoding::*;
+use crate::meteora_vcurve_decoding::*;
+use crate::metaplex_metadata_decoding::*;
+use crate::meteoradyn_decoding::*;
+use crate::moonit_decoding::*;
+use crate::orca_decoding::*;
+use crate::pumpamm_decoding::*;
+use crate::pumpfun_decoding::*;
+use crate::raydium_cpmm_decoding::{
+    RAYDIUM_CPMM_PROGRAM, RAYDIUM_CPMM_INITIALIZE_ACCOUNTS, RAYDIUM_CPMM_SWAP_ACCOUNTS, RAYDIUM_CPMM_DEPOSIT_ACCOUNTS,
+    RAYDIUM_CPMM_WITHDRAW_ACCOUNTS, INITIALIZE_DISCRIMINATOR as RAYDIUM_CPMM_INITIALIZE_DISCRIMINATOR,
+    SWAP_BASE_INPUT_DISCRIMINATOR as RAYDIUM_CPMM_SWAP_BASE_INPUT_DISCRIMINATOR,
+    SWAP_BASE_OUTPUT_DISCRIMINATOR as RAYDIUM_CPMM_SWAP_BASE_OUTPUT_DISCRIMINATOR,
+    DEPOSIT_DISCRIMINATOR as RAYDIUM_CPMM_DEPOSIT_DISCRIMINATOR, WITHDRAW_DISCRIMINATOR as RAYDIUM_CPMM_WITHDRAW_DISCRIMINATOR,
+};
+use crate::raydium_clmm_decoding::*;
+use crate::raydium_decoding::{RAYDIUM_INITIALIZE2_ACCOUNTS, RAYDIUM_LP_PROGRAM};
+use crate::raydium_launchlab_decoding::*;
+use crate::spl_token_decoding::*;
+use crate::token_2022_decoding::*;
+use crate::idl_decoding::find_idl_program;
+
+/// Raydium AMM v4 is not an Anchor program, instructions are selected by their first byte
+const RAYDIUM_INITIALIZE2_DISCRIMINATOR: [u8; 1] = [1];
+const RAYDIUM_SWAP_BASE_IN_DISCRIMINATOR: [u8; 1] = [9];
+const RAYDIUM_SWAP_BASE_OUT_DISCRIMINATOR: [u8; 1] = [11];
+
+/// An instruction recognized by a decoder, identified by its leading data bytes
+#[derive(Debug, Clone, Copy)]
+pub struct RegisteredInstruction {
+    pub name: &'static str,
+    pub discriminator: &'static [u8],
+    /// Names of the accounts in order, empty when the layout is not fixed
+    pub accounts: &'static [&'static str],
+}
+
+impl RegisteredInstruction {
+    const fn with_accounts(self, accounts: &'static [&'static str]) -> Self {
+        RegisteredInstruction { accounts, ..self }
+    }
+}
+
+/// A program handled by the processing loop, the protocol name matches the
+/// `protocol` field of the standardized instructions it produces
+#[derive(Debug, Clone, Copy)]
+pub struct RegisteredDecoder {
+    pub protocol: &'static str,
+    pub program_id: Pubkey,
+    pub instructions: &'static [RegisteredInstruction],
+}
+
+const fn ix(name: &'static str, discriminator: &'static [u8]) -> RegisteredInstruction {
+    RegisteredInstruction { name, discriminator, accounts: &[] }
+}
+
+pub static REGISTERED_DECODERS: &[RegisteredDecoder] = &[
+    RegisteredDecoder {
+        protocol: "Pumpfun",
+        program_id: PUMPFUN_PROGRAM_ID,
+        instructions: &[
+            ix("Create", &PUMP_CREATE_INSTRUCTION_DISCRIMINATOR).with_accounts(PUMP_CREATE_ACCOUNTS),
+            ix("Buy", &PUMP_BUY_INSTRUCTION_DISCRIMINATOR).with_accounts(PUMP_BUY_ACCOUNTS),
+        ],
+    },
+    RegisteredDecoder {
+        protocol: "Raydium",
+        program_id: RAYDIUM_LP_PROGRAM,
+        instructions: &[
+            ix("Initialize2", &RAYDIUM_INITIALIZE2_DISCRIMINATOR).with_accounts(RAYDIUM_INITIALIZE2_ACCOUNTS),
+            ix("SwapBaseIn", &RAYDIUM_SWAP_BASE_IN_DISCRIMINATOR),
+            ix("SwapBaseOut", &RAYDIUM_SWAP_BASE_OUT_DISCRIMINATOR),
+        ],
+    },
+    RegisteredDecoder {
+        protocol: "Moonit",
+        program_id: MOONIT_PROGRAM_ID,
+        instructions: &[
+            ix("TokenMint", &MOONIT_TOKEN_MINT_INSTRUCTION_DISCRIMINATOR).with_accounts(MOONIT_TOKEN_MINT_ACCOUNTS),
+            ix("Buy", &MOONIT_BUY_INSTRUCTION_DISCRIMINATOR).with_accounts(MOONIT_TRADE_ACCOUNTS),
+            ix("Sell", &MOONIT_SELL_INSTRUCTION_DISCRIMINATOR).with_accounts(MOONIT_TRADE_ACCOUNTS),
+        ],
+    },
+    RegisteredDecoder {
+        protocol: "RaydiumLaunchLab",
+        program_id: RAYDIUM_LAUNCHLAB_PROGRAM_ID,
+        instructions: &[
+            ix("Initialize", &RAYDIUM_LAUNCHLAB_INITIALIZE_INSTRUCTION_DISCRIMINATOR).with_accounts(RAYDIUM_LAUNCHLAB_INITIALIZE_ACCOUNTS),
+            ix("BuyExactIn", &RAYDIUM_LAUNCHLAB_BUY_EXACT_IN_INSTRUCTION_DISCRIMINATOR).with_accounts(RAYDIUM_LAUNCHLAB_TRADE_ACCOUNTS),
+            ix("BuyExactOut", &RAYDIUM_LAUNCHLAB_BUY_EXACT_OUT_INSTRUCTION_DISCRIMINATOR).with_accounts(RAYDIUM_LAUNCHLAB_TRADE_ACCOUNTS),
+            ix("SellExactIn", &RAYDIUM_LAUNCHLAB_SELL_EXACT_IN_INSTRUCTION_DISCRIMINATOR).with_accounts(RAYDIUM_LAUNCHLAB_TRADE_ACCOUNTS),
+            ix("SellExactOut", &RAYDIUM_LAUNCHLAB_SELL_EXACT_OUT_INSTRUCTION_DISCRIMINATOR).with_accounts(RAYDIUM_LAUNCHLAB_TRADE_ACCOUNTS),
+            ix("MigrateToAmm", &RAYDIUM_LAUNCHLAB_MIGRATE_TO_AMM_INSTRUCTION_DISCRIMINATOR),
+            ix("MigrateToCpswap", &RAYDIUM_LAUNCHLAB_MIGRATE_TO_CPSWAP_INSTRUCTION_DISCRIMINATOR),
+        ],
+    },
+    RegisteredDecoder {
+        protocol: "Boop",
+        program_id: BOOP_PROGRAM_ID,
+        instructions: &[
+            ix("CreateToken", &BOOP_CREATE_TOKEN_INSTRUCTION_DISCRIMINATOR),
+            ix("DeployBondingCurve", &BOOP_DEPLOY_BONDING_CURVE_INSTRUCTION_DISCRIMINATOR),
+        ],
+    },
+    RegisteredDecoder {
+        protocol: "PumpAMM",
+        program_id: PUMPAMM_PROGRAM_ID,
+        instructions: &[
+            ix("Buy", &PUMPAMM_BUY_INSTRUCTION_DISCRIMINATOR).with_accounts(PUMPAMM_TRADE_ACCOUNTS),
+            ix("Sell", &PUMPAMM_SELL_INSTRUCTION_DISCRIMINATOR).with_accounts(PUMPAMM_TRADE_ACCOUNTS),
+            ix("CreatePool", &PUMPAMM_CREATE_POOL_DISCRIMINATOR).with_accounts(PUMPAMM_CREATE_POOL_ACCOUNTS),
+            ix("Deposit", &PUMPAMM_DEPOSIT_DISCRIMINATOR).with_accounts(PUMPAMM_LIQUIDITY_ACCOUNTS),
+            ix("Withdraw", &PUMPAMM_WITHDRAW_DISCRIMINATOR).with_accounts(PUMPAMM_LIQUIDITY_ACCOUNTS),
+            ix("CollectCoinCreatorFee", &PUMPAMM_COLLECT_COIN_CREATOR_FEE_DISCRIMINATOR).with_accounts(PUMPAMM_COLLECT_COIN_CREATOR_FEE_ACCOUNTS),
+        ],
+    },
+    RegisteredDecoder {
+        protocol: "RaydiumCPMM",
+        program_id: RAYDIUM_CPMM_PROGRAM,
+        instructions: &[
+            ix("Initialize", &RAYDIUM_CPMM_INITIALIZE_DISCRIMINATOR).with_accounts(RAYDIUM_CPMM_INITIALIZE_ACCOUNTS),
+            ix("SwapBaseInput", &RAYDIUM_CPMM_SWAP_BASE_INPUT_DISCRIMINATOR).with_accounts(RAYDIUM_CPMM_SWAP_ACCOUNTS),
+            ix("SwapBaseOutput", &RAYDIUM_CPMM_SWAP_BASE_OUTPUT_DISCRIMINATOR).with_accounts(RAYDIUM_CPMM_SWAP_ACCOUNTS),
+            ix("Deposit", &RAYDIUM_CPMM_DEPOSIT_DISCRIMINATOR).with_accounts(RAYDIUM_CPMM_DEPOSIT_ACCOUNTS),
+            ix("Withdraw", &RAYDIUM_CPMM_WITHDRAW_DISCRIMINATOR).with_accounts(RAYDIUM_CPMM_WITHDRAW_ACCOUNTS),
+        ],
+    },
+    RegisteredDecoder {
+        protocol: "RaydiumCLMM",
+        program_id: RAYDIUM_CLMM_PROGRAM,
+        instructions: &[
+            ix("CreatePool", &RAYDIUM_CLMM_CREATE_POOL_DISCRIMINATOR).with_accounts(RAYDIUM_CLMM_CREATE_POOL_ACCOUNTS),
+            ix("OpenPosition", &RAYDIUM_CLMM_OPEN_POSITION_DISCRIMINATOR).with_accounts(RAYDIUM_CLMM_OPEN_POSITION_ACCOUNTS),
+            ix("OpenPositionV2", &RAYDIUM_CLMM_OPEN_POSITION_V2_DISCRIMINATOR).with_accounts(RAYDIUM_CLMM_OPEN_POSITION_V2_ACCOUNTS),
+            ix("OpenPositionWithToken22Nft", &RAYDIUM_CLMM_OPEN_POSITION_WITH_TOKEN22_NFT_DISCRIMINATOR)
+                .with_accounts(RAYDIUM_CLMM_OPEN_POSITION_WITH_TOKEN22_NFT_ACCOUNTS),
+            ix("IncreaseLiquidity", &RAYDIUM_CLMM_INCREASE_LIQUIDITY_DISCRIMINATOR).with_accounts(RAYDIUM_CLMM_INCREASE_LIQUIDITY_ACCOUNTS),
+            ix("IncreaseLiquidityV2", &RAYDIUM_CLMM_INCREASE_LIQUIDITY_V2_DISCRIMINATOR).with_accounts(RAYDIUM_CLMM_INCREASE_LIQUIDITY_V2_ACCOUNTS),
+            ix("DecreaseLiquidity", &RAYDIUM_CLMM_DECREASE_LIQUIDITY_DISCRIMINATOR).with_accounts(RAYDIUM_CLMM_DECREASE_LIQUIDITY_ACCOUNTS),
+            ix("DecreaseLiquidityV2", &RAYDIUM_CLMM_DECREASE_LIQUIDITY_V2_DISCRIMINATOR).with_accounts(RAYDIUM_CLMM_DECREASE_LIQUIDITY_V2_ACCOUNTS),
+            ix("Swap", &RAYDIUM_CLMM_SWAP_DISCRIMINATOR).with_accounts(RAYDIUM_CLMM_SWAP_ACCOUNTS),
+            ix("SwapV2", &RAYDIUM_CLMM_SWAP_V2_DISCRIMINATOR).with_accounts(RAYDIUM_CLMM_SWAP_V2_ACCOUNTS),
+        ],
+    },
+    RegisteredDecoder {
+        protocol: "MeteoraVCurve",
+        program_id: METEORA_VCURVE_PROGRAM_ID,
+        instructions: &[
+            ix("InitializeVirtualPoolWithSplToken", &INITIALIZE_VIRTUAL_POOL_WITH_SPL_TOKEN_DISCRIMINATOR).with_accounts(METEORA_VCURVE_INITIALIZE_SPL_ACCOUNTS),
+            ix("InitializeVirtualPoolWithToken2022", &INITIALIZE_VIRTUAL_POOL_WITH_TOKEN2022_DISCRIMINATOR).with_accounts(METEORA_VCURVE_INITIALIZE_TOKEN2022_ACCOUNTS),
+            ix("Swap", &METEORA_VCURVE_SWAP_DISCRIMINATOR).with_accounts(METEORA_VCURVE_SWAP_ACCOUNTS),
+            ix("MigrationDammV2", &MIGRATION_DAMM_V2_DISCRIMINATOR),
+            ix("MigrateMeteoraDamm", &MIGRATE_METEORA_DAMM_DISCRIMINATOR),
+        ],
+    },
+    RegisteredDecoder {
+        protocol: "MeteoraDyn",
+        program_id: METEORADYN_PROGRAM_ID,
+        instructions: &[ix("InitializePermissionlessPool", &INIT_PERMISSIONLESS_POOL_IX_DISCRIMINATOR)],
+    },
+    RegisteredDecoder {
+        protocol: "MeteoraAmmV2",
+        program_id: METEORA_AMM_V2_PROGRAM_ID,
+        instructions: &[
+            ix("CreatePool1", &CREATE_POOL_1_DISCRIMINATOR).with_accounts(METEORA_AMM_V2_CREATE_POOL1_ACCOUNTS),
+            ix("CreatePool2", &CREATE_POOL_2_DISCRIMINATOR).with_accounts(METEORA_AMM_V2_CREATE_POOL2_ACCOUNTS),
+            ix("CreatePool3", &CREATE_POOL_3_DISCRIMINATOR).with_accounts(METEORA_AMM_V2_CREATE_POOL3_ACCOUNTS),
+            ix("Swap", &SWAP_DISCRIMINATOR).with_accounts(METEORA_AMM_V2_SWAP_ACCOUNTS),
+            ix("AddLiquidity", &ADD_LIQUIDITY_DISCRIMINATOR),
+            ix("RemoveLiquidity1", &REMOVE_LIQUIDITY_1_DISCRIMINATOR),
+            ix("RemoveLiquidity2", &REMOVE_LIQUIDITY_2_DISCRIMINATOR),
+        ],
+    },
+    RegisteredDecoder {
+        protocol: "MeteoraDLMM",
+        program_id: METEORA_DLMM_PROGRAM_ID,
+        instructions: &[
+            ix("AddLiquidityByStrategy", &DLMM_ADD_LIQUIDITY_BY_STRATEGY_DISCRIMINATOR).with_accounts(DLMM_ADD_LIQUIDITY_BY_STRATEGY_ACCOUNTS),
+            ix("AddLiquidityByStrategy2", &DLMM_ADD_LIQUIDITY_BY_STRATEGY2_DISCRIMINATOR).with_accounts(DLMM_ADD_LIQUIDITY_BY_STRATEGY2_ACCOUNTS),
+            ix("AddLiquidityByStrategyOneSide", &DLMM_ADD_LIQUIDITY_BY_STRATEGY_ONE_SIDE_DISCRIMINATOR).with_accounts(DLMM_ADD_LIQUIDITY_ONE_SIDE_ACCOUNTS),
+            ix("AddLiquidityOneSide", &DLMM_ADD_LIQUIDITY_ONE_SIDE_DISCRIMINATOR).with_accounts(DLMM_ADD_LIQUIDITY_ONE_SIDE_ACCOUNTS),
+            ix("Swap", &DLMM_SWAP_DISCRIMINATOR).with_accounts(DLMM_SWAP_ACCOUNTS),
+            ix("Swap2", &DLMM_SWAP2_DISCRIMINATOR).with_accounts(DLMM_SWAP2_ACCOUNTS),
+            ix("SwapExactOut", &DLMM_SWAP_EXACT_OUT_DISCRIMINATOR).with_accounts(DLMM_SWAP_ACCOUNTS),
+            ix("RemoveLiquidity", &DLMM_REMOVE_LIQUIDITY_DISCRIMINATOR).with_accounts(DLMM_REMOVE_LIQUIDITY_ACCOUNTS),
+        ],
+    },
+    RegisteredDecoder {
+        protocol: "Orca",
+        program_id: ORCA_WHIRLPOOL_PROGRAM_ID,
+        instructions: &[
+            ix("Swap", &ORCA_SWAP_INSTRUCTION_DISCRIMINATOR).with_accounts(ORCA_SWAP_ACCOUNTS),
+            ix("SwapV2", &ORCA_SWAP_V2_INSTRUCTION_DISCRIMINATOR).with_accounts(ORCA_SWAP_V2_ACCOUNTS),
+            ix("IncreaseLiquidity", &ORCA_INCREASE_LIQUIDITY_INSTRUCTION_DISCRIMINATOR).with_accounts(ORCA_LIQUIDITY_ACCOUNTS),
+            ix("DecreaseLiquidity", &ORCA_DECREASE_LIQUIDITY_INSTRUCTION_DISCRIMINATOR).with_accounts(ORCA_LIQUIDITY_ACCOUNTS),
+            ix("InitializePool", &ORCA_INITIALIZE_POOL_INSTRUCTION_DISCRIMINATOR).with_accounts(ORCA_INITIALIZE_POOL_ACCOUNTS),
+            ix("OpenPosition", &ORCA_OPEN_POSITION_INSTRUCTION_DISCRIMINATOR).with_accounts(ORCA_OPEN_POSITION_ACCOUNTS),
+            ix("ClosePosition", &ORCA_CLOSE_POSITION_INSTRUCTION_DISCRIMINATOR).with_accounts(ORCA_CLOSE_POSITION_ACCOUNTS),
+            ix("TwoHopSwap", &ORCA_TWO_HOP_SWAP_INSTRUCTION_DISCRIMINATOR).with_accounts(ORCA_TWO_HOP_SWAP_ACCOUNTS),
+            ix("OpenBundledPosition", &ORCA_OPEN_BUNDLED_POSITION_INSTRUCTION_DISCRIMINATOR).with_accounts(ORCA_OPEN_BUNDLED_POSITION_ACCOUNTS),
+            ix("CloseBundledPosition", &ORCA_CLOSE_BUNDLED_POSITION_INSTRUCTION_DISCRIMINATOR).with_accounts(ORCA_CLOSE_BUNDLED_POSITION_ACCOUNTS),
+            ix("OpenPositionWithTokenExtensions", &ORCA_OPEN_POSITION_WITH_TOKEN_EXTENSIONS_INSTRUCTION_DISCRIMINATOR).with_accounts(ORCA_OPEN_POSITION_WITH_TOKEN_EXTENSIONS_ACCOUNTS),
+            ix("ClosePositionWithTokenExtensions", &ORCA_CLOSE_POSITION_WITH_TOKEN_EXTENSIONS_INSTRUCTION_DISCRIMINATOR).with_accounts(ORCA_CLOSE_POSITION_WITH_TOKEN_EXTENSIONS_ACCOUNTS),
+            ix("InitializePoolV2", &ORCA_INITIALIZE_POOL_V2_INSTRUCTION_DISCRIMINATOR).with_accounts(ORCA_INITIALIZE_POOL_V2_ACCOUNTS),
+            ix("IncreaseLiquidityV2", &ORCA_INCREASE_LIQUIDITY_V2_INSTRUCTION_DISCRIMINATOR).with_accounts(ORCA_LIQUIDITY_V2_ACCOUNTS),
+            ix("DecreaseLiquidityV2", &ORCA_DECREASE_LIQUIDITY_V2_INSTRUCTION_DISCRIMINATOR).with_accounts(ORCA_LIQUIDITY_V2_ACCOUNTS),
+            ix("TwoHopSwapV2", &ORCA_TWO_HOP_SWAP_V2_INSTRUCTION_DISCRIMINATOR).with_accounts(ORCA_TWO_HOP_SWAP_V2_ACCOUNTS),
+            ix("CollectFees", &ORCA_COLLECT_FEES_INSTRUCTION_DISCRIMINATOR).with_accounts(ORCA_COLLECT_FEES_ACCOUNTS),
+            ix("CollectReward", &ORCA_COLLECT_REWARD_INSTRUCTION_DISCRIMINATOR).with_accounts(ORCA_COLLECT_REWARD_ACCOUNTS),
+            ix("CollectProtocolFees", &ORCA_COLLECT_PROTOCOL_FEES_INSTRUCTION_DISCRIMINATOR).with_accounts(ORCA_COLLECT_PROTOCOL_FEES_ACCOUNTS),
+            ix("CollectFeesV2", &ORCA_COLLECT_FEES_V2_INSTRUCTION_DISCRIMINATOR).with_accounts(ORCA_COLLECT_FEES_V2_ACCOUNTS),
+            ix("CollectRewardV2", &ORCA_COLLECT_REWARD_V2_INSTRUCTION_DISCRIMINATOR).with_accounts(ORCA_COLLECT_REWARD_V2_ACCOUNTS),
+            ix("CollectProtocolFeesV2", &ORCA_COLLECT_PROTOCOL_FEES_V2_INSTRUCTION_DISCRIMINATOR).with_accounts(ORCA_COLLECT_PROTOCOL_FEES_V2_ACCOUNTS),
+        ],
+    },
+    RegisteredDecoder {
+        protocol: "Drift",
+        program_id: DRIFT_PROGRAM_ID,
+        instructions: &[
+            ix("PlacePerpOrder", &DRIFT_PLACE_PERP_ORDER_DISCRIMINATOR).with_accounts(DRIFT_PLACE_ORDER_ACCOUNTS),
+            ix("PlaceAndTakePerpOrder", &DRIFT_PLACE_AND_TAKE_PERP_ORDER_DISCRIMINATOR).with_accounts(DRIFT_PLACE_AND_TAKE_ORDER_ACCOUNTS),
+            ix("PlaceSpotOrder", &DRIFT_PLACE_SPOT_ORDER_DISCRIMINATOR).with_accounts(DRIFT_PLACE_ORDER_ACCOUNTS),
+            ix("SettlePnl", &DRIFT_SETTLE_PNL_DISCRIMINATOR).with_accounts(DRIFT_SETTLE_PNL_ACCOUNTS),
+            ix("SettleMultiplePnls", &DRIFT_SETTLE_MULTIPLE_PNLS_DISCRIMINATOR).with_accounts(DRIFT_SETTLE_PNL_ACCOUNTS),
+            ix("SettleFundingPayment", &DRIFT_SETTLE_FUNDING_PAYMENT_DISCRIMINATOR).with_accounts(DRIFT_SETTLE_FUNDING_PAYMENT_ACCOUNTS),
+        ],
+    },
+    RegisteredDecoder {
+        protocol: "KaminoLend",
+        program_id: KAMINO_LEND_PROGRAM_ID,
+        instructions: &[
+            ix("Deposit", &KAMINO_LEND_DEPOSIT_DISCRIMINATOR).with_accounts(KAMINO_LEND_DEPOSIT_ACCOUNTS),
+            ix("Borrow", &KAMINO_LEND_BORROW_DISCRIMINATOR).with_accounts(KAMINO_LEND_BORROW_ACCOUNTS),
+            ix("Repay", &KAMINO_LEND_REPAY_DISCRIMINATOR).with_accounts(KAMINO_LEND_REPAY_ACCOUNTS),
+            ix("Liquidate", &KAMINO_LEND_LIQUIDATE_DISCRIMINATOR).with_accounts(KAMINO_LEND_LIQUIDATE_ACCOUNTS),
+            ix("DepositV2", &KAMINO_LEND_DEPOSIT_V2_DISCRIMINATOR).with_accounts(KAMINO_LEND_DEPOSIT_ACCOUNTS),
+            ix("BorrowV2", &KAMINO_LEND_BORROW_V2_DISCRIMINATOR).with_accounts(KAMINO_LEND_BORROW_ACCOUNTS),
+            ix("RepayV2", &KAMINO_LEND_REPAY_V2_DISCRIMINATOR).with_accounts(KAMINO_LEND_REPAY_ACCOUNTS),
+            ix("LiquidateV2", &KAMINO_LEND_LIQUIDATE_V2_DISCRIMINATOR).with_accounts(KAMINO_LEND_LIQUIDATE_ACCOUNTS),
+        ],
+    },
+    RegisteredDecoder {
+        protocol: "MetaplexMetadata",
+        program_id: METAPLEX_TOKEN_METADATA_PROGRAM_ID,
+        instructions: &[
+            ix("CreateMetadataAccountV3", &METAPLEX_CREATE_METADATA_ACCOUNT_V3_DISCRIMINATOR).with_accounts(METAPLEX_CREATE_METADATA_ACCOUNT_V3_ACCOUNTS),
+            ix("CreateV1", &METAPLEX_CREATE_V1_DISCRIMINATOR).with_accounts(METAPLEX_CREATE_V1_ACCOUNTS),
+        ],
+    },
+    RegisteredDecoder {
+        protocol: "AddressLookupTable",
+        program_id: ADDRESS_LOOKUP_TABLE_PROGRAM_ID,
+        instructions: &[
+            ix("CreateLookupTable", &LOOKUP_TABLE_CREATE_DISCRIMINATOR).with_accounts(LOOKUP_TABLE_CREATE_ACCOUNTS),
+            ix("FreezeLookupTable", &LOOKUP_TABLE_FREEZE_DISCRIMINATOR).with_accounts(LOOKUP_TABLE_AUTHORITY_ACCOUNTS),
+            ix("ExtendLookupTable", &LOOKUP_TABLE_EXTEND_DISCRIMINATOR).with_accounts(LOOKUP_TABLE_EXTEND_ACCOUNTS),
+            ix("DeactivateLookupTable", &LOOKUP_TABLE_DEACTIVATE_DISCRIMINATOR).with_accounts(LOOKUP_TABLE_AUTHORITY_ACCOUNTS),
+            ix("CloseLookupTable", &LOOKUP_TABLE_CLOSE_DISCRIMINATOR).with_accounts(LOOKUP_TABLE_CLOSE_ACCOUNTS),
+        ],
+    },
+    RegisteredDecoder {
+        protocol: "SplToken",
+        program_id: SPL_TOKEN_PROGRAM_ID,
+        instructions: &[
+            ix("InitializeMint", &SPL_TOKEN_INITIALIZE_MINT_DISCRIMINATOR).with_accounts(SPL_TOKEN_INITIALIZE_MINT_ACCOUNTS),
+            ix("Transfer", &SPL_TOKEN_TRANSFER_DISCRIMINATOR).with_accounts(SPL_TOKEN_TRANSFER_ACCOUNTS),
+            ix("MintTo", &SPL_TOKEN_MINT_TO_DISCRIMINATOR).with_accounts(SPL_TOKEN_MINT_TO_ACCOUNTS),
+            ix("Burn", &SPL_TOKEN_BURN_DISCRIMINATOR).with_accounts(SPL_TOKEN_BURN_ACCOUNTS),
+            ix("TransferChecked", &SPL_TOKEN_TRANSFER_CHECKED_DISCRIMINATOR).with_accounts(SPL_TOKEN_TRANSFER_CHECKED_ACCOUNTS),
+            ix("InitializeMint2", &SPL_TOKEN_INITIALIZE_MINT2_DISCRIMINATOR).with_accounts(SPL_TOKEN_INITIALIZE_MINT2_ACCOUNTS),
+        ],
+    },
+    RegisteredDecoder {
+        protocol: "Token2022",
+        program_id: SPL_TOKEN_2022_PROGRAM_ID,
+        instructions: &[
+            ix("InitializeMint", &SPL_TOKEN_INITIALIZE_MINT_DISCRIMINATOR).with_accounts(SPL_TOKEN_INITIALIZE_MINT_ACCOUNTS),
+            ix("Transfer", &SPL_TOKEN_TRANSFER_DISCRIMINATOR).with_accounts(SPL_TOKEN_TRANSFER_ACCOUNTS),
+            ix("MintTo", &SPL_TOKEN_MINT_TO_DISCRIMINATOR).with_accounts(SPL_TOKEN_MINT_TO_ACCOUNTS),
+            ix("Burn", &SPL_TOKEN_BURN_DISCRIMINATOR).with_accounts(SPL_TOKEN_BURN_ACCOUNTS),
+            ix("TransferChecked", &SPL_TOKEN_TRANSFER_CHECKED_DISCRIMINATOR).with_accounts(SPL_TOKEN_TRANSFER_CHECKED_ACCOUNTS),
+            ix("InitializeMint2", &SPL_TOKEN_INITIALIZE_MINT2_DISCRIMINATOR).with_accounts(SPL_TOKEN_INITIALIZE_MINT2_ACCOUNTS),
+            ix("InitializeTransferFeeConfig", &TOKEN_2022_INITIALIZE_TRANSFER_FEE_CONFIG_DISCRIMINATOR).with_accounts(TOKEN_2022_MINT_ACCOUNTS),
+            ix("TransferCheckedWithFee", &TOKEN_2022_TRANSFER_CHECKED_WITH_FEE_DISCRIMINATOR).with_accounts(SPL_TOKEN_TRANSFER_CHECKED_ACCOUNTS),
+            ix("WithdrawWithheldTokensFromMint", &TOKEN_2022_WITHDRAW_WITHHELD_FROM_MINT_DISCRIMINATOR).with_accounts(TOKEN_2022_WITHDRAW_WITHHELD_ACCOUNTS),
+            ix("WithdrawWithheldTokensFromAccounts", &TOKEN_2022_WITHDRAW_WITHHELD_FROM_ACCOUNTS_DISCRIMINATOR).with_accounts(TOKEN_2022_WITHDRAW_WITHHELD_ACCOUNTS),
+            ix("HarvestWithheldTokensToMint", &TOKEN_2022_HARVEST_WITHHELD_TO_MINT_DISCRIMINATOR).with_accounts(TOKEN_2022_MINT_ACCOUNTS),
+            ix("SetTransferFee", &TOKEN_2022_SET_TRANSFER_FEE_DISCRIMINATOR).with_accounts(TOKEN_2022_MINT_AUTHORITY_ACCOUNTS),
+            ix("InitializeTransferHook", &TOKEN_2022_INITIALIZE_TRANSFER_HOOK_DISCRIMINATOR).with_accounts(TOKEN_2022_MINT_ACCOUNTS),
+            ix("UpdateTransferHook", &TOKEN_2022_UPDATE_TRANSFER_HOOK_DISCRIMINATOR).with_accounts(TOKEN_2022_MINT_AUTHORITY_ACCOUNTS),
+            ix("InitializeMetadataPointer", &TOKEN_2022_INITIALIZE_METADATA_POINTER_DISCRIMINATOR).with_accounts(TOKEN_2022_MINT_ACCOUNTS),
+        ],
+    },
+];
+
+pub fn find_decoder_by_program(program_id: &Pubkey) -> Option<&'static RegisteredDecoder> {
+    REGISTERED_DECODERS.iter().find(|decoder| &decoder.program_id == program_id)
+}
+
+/// Account names of an instruction, from the built-in tables or a loaded IDL
+#[derive(Debug, Clone, Copy)]
+pub enum AccountNames {
+    Registered(&'static [&'static str]),
+    Idl(&'static [String]),
+}
+
+impl AccountNames {
+    pub fn get(&self, position: usize) -> Option<&'static str> {
+        match self {
+            AccountNames::Registered(names) => names.get(position).copied(),
+            AccountNames::Idl(names) => names.get(position).map(String::as_str),
+        }
+    }
+}
+
+/// Account names of an instruction for the standardized output. Decoders declare one
+/// `<PROTOCOL>_<INSTRUCTION>_ACCOUNTS` table per instruction, the account names in account order
+/// as the program IDL spells them, and register it with `with_accounts`. Accounts past the end of
+/// the table are remaining accounts.
+/// None for instructions without a name table, their accounts keep only the position
+pub fn find_account_names(program_id: &Pubkey, instruction_name: &str) -> Option<AccountNames> {
+    if let Some(decoder) = find_decoder_by_program(program_id) {
+        return decoder
+            .instructions
+            .iter()
+            .find(|instruction| instruction.name == instruction_name && !instruction.accounts.is_empty())
+            .map(|instruction| AccountNames::Registered(instruction.accounts));
+    }
+    find_idl_program(program_id)?
+        .instructions
+        .iter()
+        .find(|instruction| instruction.name == instruction_name)
+        .map(|instruction| AccountNames::Idl(&instruction.accounts))
+}
+
+/// Registered instruction of a program matching the leading bytes of the instruction data
+pub fn find_registered_instruction(program_id: &Pubkey, data: &[u8]) -> Option<(&'static RegisteredDecoder, &'static RegisteredInstruction)> {
+    let decoder = find_decoder_by_program(program_id)?;
+    decoder
+        .instructions
+        .iter()
+        .find(|instruction| data.starts_with(instruction.discriminator))
+        .map(|instruction| (decoder, instruction))
+}
+
+/// Decoder of a historical layout, same arguments as the `deserialize_*` functions of the decoders
+pub type LayoutDecoder = fn(&[u8], &[u8], &[Pubkey], &[bool], &[bool]) -> Result<JsonValue, String>;
+
+/// Layout an instruction had over a past slot range, before a program upgrade changed it.
+/// Slots outside every variant, live traffic included, use the current layout.
+#[derive(Debug, Clone, Copy)]
+pub struct LayoutVariant {
+    pub program_id: Pubkey,
+    pub instruction: &'static str,
+    /// Name reported in the `layout` field of the decoded instruction
+    pub layout: &'static str,
+    pub from_slot: u64,
+    /// Upgrade slot, the first one using the next layout
+    pub until_slot: u64,
+    /// Account minimum of the variant, when it differs from the current one
+    pub min_accounts: Option<usize>,
+    /// Decoder of the old data layout, None when only the account minimum changed
+    pub decode: Option<LayoutDecoder>,
+}
+
+impl LayoutVariant {
+    pub fn covers(&self, slot: u64) -> bool {
+        (self.from_slot..self.until_slot).contains(&slot)
+    }
+}
+
+/// Historical layouts pinned by slot, for replay and backfill over old ledgers.
+/// Add an entry when an upgrade changes an instruction, keeping the old handling behind its layout name.
+pub static LAYOUT_VARIANTS: &[LayoutVariant] = &[
+    // The creator fee upgrade appended the creator to the create arguments
+    LayoutVariant {
+        program_id: PUMPFUN_PROGRAM_ID,
+        instruction: "Create",
+        layout: "pumpfun_create_v1",
+        from_slot: 0,
+        until_slot: PUMPFUN_CREATOR_FEE_UPGRADE_SLOT,
+        min_accounts: None,
+        decode: Some(deserialize_pump_create_v1_instruction),
+    },
+];
+
+/// Variant in force for an instruction at a slot, None when the current layout applies
+pub fn find_layout_variant(program_id: &Pubkey, data: &[u8], slot: u64) -> Option<&'static LayoutVariant> {
+    if !LAYOUT_VARIANTS.iter().any(|variant| &variant.program_id == program_id && variant.covers(slot)) {
+        return None;
+    }
+    let (_, instruction) = find_registered_instruction(program_id, data)?;
+    LAYOUT_VARIANTS.iter().find(|variant| {
+        &variant.program_id == program_id && variant.instruction == instruction.name && variant.covers(slot)
+    })
+}
+
+/// Overlapping variants of the same instruction make the selected layout depend on table order
+pub fn detect_layout_variant_overlaps() -> Vec<(&'static LayoutVariant, &'static LayoutVariant)> {
+    let mut overlaps = Vec::new();
+    for (i, a) in LAYOUT_VARIANTS.iter().enumerate() {
+        for b in LAYOUT_VARIANTS.iter().skip(i + 1) {
+            if a.program_id == b.program_id
+                && a.instruction == b.instruction
+                && a.from_slot < b.until_slot
+                && b.from_slot < a.until_slot
+            {
+                overlaps.push((a, b));
+            }
+        }
+    }
+    overlaps
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CollisionSeverity {
+    /// Two instructions of the same program share a discriminator, one of them can never be decoded
+    Error,
+    /// Different protocols reuse a discriminator, harmless while routing is keyed by program id
+    Warning,
+}
+
+impl CollisionSeverity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CollisionSeverity::Error => "error",
+            CollisionSeverity::Warning => "warning",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DiscriminatorCollision {
+    pub severity: CollisionSeverity,
+    pub discriminator: &'static [u8],
+    /// (protocol, instruction) pairs sharing the discriminator
+    pub instructions: Vec<(&'static str, &'static str)>,
+}
+
+/// Groups every registered (program, discriminator) pair and reports the shared ones.
+/// Duplicates inside a program are errors, reuse across protocols is a warning.
+pub fn detect_discriminator_collisions() -> Vec<DiscriminatorCollision> {
+    let mut by_discriminator: BTreeMap<&'static [u8], Vec<(&RegisteredDecoder, &RegisteredInstruction)>> = BTreeMap::new();
+    for decoder in REGISTERED_DECODERS {
+        for instruction in decoder.instructions {
+            by_discriminator.entry(instruction.discriminator).or_default().push((decoder, instruction));
+        }
+    }
+
+    let mut collisions = Vec::new();
+    for (discriminator, users) in by_discriminator {
+        if users.len() < 2 {
+            continue;
+        }
+
+        let same_program = users.iter().enumerate().any(|(i, (a, _))| {
+            users.iter().skip(i + 1).any(|(b, _)| a.program_id == b.program_id)
+        });
+
+        collisions.push(DiscriminatorCollision {
+            severity: if same_program { CollisionSeverity::Error } else { CollisionSeverity::Warning },
+            discriminator,
+            instructions: users.iter().map(|(decoder, instruction)| (decoder.protocol, instruction.name)).collect(),
+        });
+    }
+
+    collisions
+}
+
+/// Logs the discriminator collision report, run once at startup
+pub fn check_discriminator_collisions() {
+    let collisions = detect_discriminator_collisions();
+    if collisions.is_empty() {
+        info!("Discriminator check: no collisions across {} registered decoders", REGISTERED_DECODERS.len());
+        return;
+    }
+
+    for collision in &collisions {
+        let users = collision
+            .instructions
+            .iter()
+            .map(|(protocol, name)| format!("{}::{}", protocol, name))
+            .collect::<Vec<_>>()
+            .join(", ");
+        match collision.severity {
+            CollisionSeverity::Error => error!(
+                "Discriminator collision within a program {:?}: {} (only the first match is ever decoded)",
+                collision.discriminator, users
+            ),
+            CollisionSeverity::Warning => warn!(
+                "Discriminator {:?} reused across protocols: {}",
+                collision.discriminator, users
+            ),
+        }
+    }
+}
+
+/// Logs overlapping layout variants, run once at startup
+pub fn check_layout_variants() {
+    for (a, b) in detect_layout_variant_overlaps() {
+        error!(
+            "Layout variants '{}' and '{}' of {} overlap over slots {}..{}, only the first one is used",
+            a.layout,
+            b.layout,
+            a.instruction,
+            a.from_slot.max(b.from_slot),
+            a.until_slot.min(b.until_slot)
+        );
+    }
+    if !LAYOUT_VARIANTS.is_empty() {
+        info!("{} historical layout variants pinned by slot", LAYOUT_VARIANTS.len());
+    }
+}
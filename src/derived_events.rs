@@ -0,0 +1,42 @@
+use json::JsonValue;
+use once_cell::sync::Lazy;
+use rustc_hash::FxHashSet as HashSet;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::broadcast;
+
+
+/// An event computed from the decoded stream rather than read from a single instruction
+#[derive(Debug)]
+pub struct DerivedEvent {
+    pub event_type: &'static str,
+    pub json: String,
+    pub timestamp: u64,
+    /// Accounts the event is about, used for per-mint filtering
+    pub accounts: HashSet<String>,
+}
+
+pub type DerivedEventSender = broadcast::Sender<Arc<DerivedEvent>>;
+
+static DERIVED_EVENTS_TX: Lazy<DerivedEventSender> = Lazy::new(|| broadcast::channel(1000).0);
+
+pub fn subscribe_derived_events() -> broadcast::Receiver<Arc<DerivedEvent>> {
+    DERIVED_EVENTS_TX.subscribe()
+}
+
+/// Publishes an event on the derived events bus, consumers delivering outside of gRPC
+/// must skip them on a standby instance
+pub fn publish_derived_event(event_type: &'static str, event: JsonValue, accounts: HashSet<String>) {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_micros() as u64;
+
+    // Sending only fails when nobody is subscribed, which is not an error here
+    let _ = DERIVED_EVENTS_TX.send(Arc::new(DerivedEvent {
+        event_type,
+        json: event.dump(),
+        timestamp,
+        accounts,
+    }));
+}
@@ -0,0 +1,197 @@
+use dashmap::DashMap;
+use json::{object, JsonValue};
+use once_cell::sync::Lazy;
+use solana_sdk::pubkey::Pubkey;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::{error, info};
+
+use crate::decoder_registry::{find_decoder_by_program, find_registered_instruction};
+
+/// Anchor programs select instructions by 8 bytes, unregistered programs are keyed the same way
+const DEFAULT_DISCRIMINATOR_LEN: usize = 8;
+
+/// Opt-in flag, instructions are not counted when disabled
+static DISCRIMINATOR_CATALOG_ENABLED: Lazy<bool> = Lazy::new(|| {
+    std::env::var("DISCRIMINATOR_CATALOG_ENABLED")
+        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+});
+
+/// Distinct discriminators kept per program, non-Anchor programs would otherwise
+/// create one entry per parameter value
+static DISCRIMINATOR_CATALOG_MAX_PER_PROGRAM: Lazy<usize> = Lazy::new(|| {
+    std::env::var("DISCRIMINATOR_CATALOG_MAX_PER_PROGRAM")
+        .unwrap_or_else(|_| "64".to_string())
+        .parse()
+        .unwrap_or(64)
+});
+
+/// Distinct programs kept in the catalog
+static DISCRIMINATOR_CATALOG_MAX_PROGRAMS: Lazy<usize> = Lazy::new(|| {
+    std::env::var("DISCRIMINATOR_CATALOG_MAX_PROGRAMS")
+        .unwrap_or_else(|_| "2000".to_string())
+        .parse()
+        .unwrap_or(2000)
+});
+
+pub fn is_discriminator_catalog_enabled() -> bool {
+    *DISCRIMINATOR_CATALOG_ENABLED
+}
+
+#[derive(Debug, Default)]
+struct ProgramDiscriminators {
+    /// Discriminator → samples
+    samples: DashMap<Vec<u8>, u64>,
+    /// Samples not counted because the program reached its discriminator limit
+    overflow: AtomicU64,
+}
+
+static CATALOG: Lazy<DashMap<Pubkey, ProgramDiscriminators>> = Lazy::new(DashMap::new);
+static DROPPED_PROGRAM_SAMPLES: AtomicU64 = AtomicU64::new(0);
+
+/// Leading bytes identifying the instruction, the registered length for programs we decode
+fn discriminator_of<'a>(program_key: &Pubkey, data: &'a [u8]) -> &'a [u8] {
+    let len = match find_registered_instruction(program_key, data) {
+        Some((_, instruction)) => instruction.discriminator.len(),
+        None => find_decoder_by_program(program_key)
+            .and_then(|decoder| decoder.instructions.iter().map(|instruction| instruction.discriminator.len()).min())
+            .unwrap_or(DEFAULT_DISCRIMINATOR_LEN),
+    };
+    &data[..len.min(data.len())]
+}
+
+/// Counts an instruction of any program, decoded or not
+pub fn observe_discriminator(program_key: &Pubkey, data: &[u8]) {
+    if data.is_empty() {
+        return;
+    }
+    let discriminator = discriminator_of(program_key, data);
+
+    let program = match CATALOG.get(program_key) {
+        Some(program) => program,
+        None => {
+            if CATALOG.len() >= *DISCRIMINATOR_CATALOG_MAX_PROGRAMS {
+                DROPPED_PROGRAM_SAMPLES.fetch_add(1, Ordering::Relaxed);
+                return;
+            }
+            CATALOG.entry(*program_key).or_default().downgrade()
+        }
+    };
+    if let Some(mut samples) = program.samples.get_mut(discriminator) {
+        *samples += 1;
+    } else if program.samples.len() < *DISCRIMINATOR_CATALOG_MAX_PER_PROGRAM {
+        *program.samples.entry(discriminator.to_vec()).or_insert(0) += 1;
+    } else {
+        program.overflow.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// One observed (program, discriminator) pair
+#[derive(Debug, Clone)]
+pub struct CatalogEntry {
+    pub program_id: Pubkey,
+    pub discriminator: Vec<u8>,
+    pub samples: u64,
+    /// (protocol, instruction) of the decoder handling it, None when nothing decodes it
+    pub decoded_as: Option<(&'static str, &'static str)>,
+}
+
+/// Snapshot of the catalog, most observed entries first
+#[derive(Debug, Clone, Default)]
+pub struct DiscriminatorCatalog {
+    pub entries: Vec<CatalogEntry>,
+    /// Samples lost to the program and per-program limits
+    pub dropped_samples: u64,
+}
+
+pub fn discriminator_catalog() -> DiscriminatorCatalog {
+    let mut entries = Vec::new();
+    let mut dropped_samples = DROPPED_PROGRAM_SAMPLES.load(Ordering::Relaxed);
+    for program in CATALOG.iter() {
+        dropped_samples += program.overflow.load(Ordering::Relaxed);
+        for discriminator in program.samples.iter() {
+            entries.push(CatalogEntry {
+                program_id: *program.key(),
+                discriminator: discriminator.key().clone(),
+                samples: *discriminator.value(),
+                decoded_as: find_registered_instruction(program.key(), discriminator.key())
+                    .map(|(decoder, instruction)| (decoder.protocol, instruction.name)),
+            });
+        }
+    }
+    entries.sort_by(|a, b| b.samples.cmp(&a.samples).then_with(|| a.program_id.cmp(&b.program_id)));
+    DiscriminatorCatalog { entries, dropped_samples }
+}
+
+impl CatalogEntry {
+    pub fn to_json(&self) -> JsonValue {
+        let (protocol, instruction) = self.decoded_as.unwrap_or_default();
+        object! {
+            "programId" => self.program_id.to_string(),
+            "discriminator" => hex::encode(&self.discriminator),
+            "samples" => self.samples,
+            "decoded" => self.decoded_as.is_some(),
+            "protocol" => (!protocol.is_empty()).then_some(protocol),
+            "instruction" => (!instruction.is_empty()).then_some(instruction),
+        }
+    }
+}
+
+/// Periodic export of the catalog to a JSON file
+#[derive(Debug, Clone)]
+pub struct DiscriminatorCatalogExportConfig {
+    pub path: String,
+    pub interval: Duration,
+}
+
+impl DiscriminatorCatalogExportConfig {
+    /// Build the configuration from env, returns None unless the catalog is enabled
+    /// and DISCRIMINATOR_CATALOG_PATH is set
+    pub fn from_env() -> Option<Self> {
+        if !is_discriminator_catalog_enabled() {
+            return None;
+        }
+        let path = std::env::var("DISCRIMINATOR_CATALOG_PATH").ok().filter(|path| !path.trim().is_empty())?;
+        let interval_secs = std::env::var("DISCRIMINATOR_CATALOG_EXPORT_INTERVAL_SECS")
+            .unwrap_or_else(|_| "300".to_string())
+            .parse()
+            .ok()
+            .filter(|secs| *secs > 0)
+            .unwrap_or(300);
+
+        Some(Self {
+            path,
+            interval: Duration::from_secs(interval_secs),
+        })
+    }
+}
+
+/// Writes the catalog through a temporary file so readers never see a partial export
+fn export_catalog(path: &str) -> std::io::Result<usize> {
+    let catalog = discriminator_catalog();
+    let document = object! {
+        "generatedAt" => SystemTime::now().duration_since(UNIX_EPOCH).expect("Time went backwards").as_micros() as u64,
+        "droppedSamples" => catalog.dropped_samples,
+        "entries" => catalog.entries.iter().map(CatalogEntry::to_json).collect::<Vec<_>>(),
+    };
+    let temporary = format!("{}.tmp", path);
+    std::fs::write(&temporary, document.pretty(2))?;
+    std::fs::rename(&temporary, path)?;
+    Ok(catalog.entries.len())
+}
+
+pub async fn run_discriminator_catalog_export(config: DiscriminatorCatalogExportConfig) {
+    info!("Discriminator catalog exported to {} every {:?}", config.path, config.interval);
+    let mut ticker = tokio::time::interval(config.interval);
+    ticker.tick().await;
+    loop {
+        ticker.tick().await;
+        let path = config.path.clone();
+        match tokio::task::spawn_blocking(move || export_catalog(&path)).await {
+            Ok(Ok(entries)) => info!("Exported {} discriminator catalog entries to {}", entries, config.path),
+            Ok(Err(e)) => error!("Failed to export the discriminator catalog to {}: {}", config.path, e),
+            Err(e) => error!("Discriminator catalog export task failed: {}", e),
+        }
+    }
+}
@@ -0,0 +1,247 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use json::{object, JsonValue};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::pubkey;
+use crate::decode_anomalies::check_parse_residue;
+use crate::utils::{create_standardized_instruction, ValueSanity};
+
+pub const DRIFT_PLACE_PERP_ORDER_DISCRIMINATOR: [u8; 8] = [69, 161, 93, 202, 120, 126, 76, 185];
+pub const DRIFT_PLACE_AND_TAKE_PERP_ORDER_DISCRIMINATOR: [u8; 8] = [213, 51, 1, 187, 108, 220, 230, 224];
+pub const DRIFT_PLACE_SPOT_ORDER_DISCRIMINATOR: [u8; 8] = [45, 79, 81, 160, 248, 90, 91, 220];
+pub const DRIFT_SETTLE_PNL_DISCRIMINATOR: [u8; 8] = [43, 61, 234, 45, 15, 95, 152, 153];
+pub const DRIFT_SETTLE_MULTIPLE_PNLS_DISCRIMINATOR: [u8; 8] = [127, 66, 117, 57, 40, 50, 152, 127];
+pub const DRIFT_SETTLE_FUNDING_PAYMENT_DISCRIMINATOR: [u8; 8] = [222, 90, 202, 94, 28, 45, 115, 183];
+
+pub const DRIFT_PROGRAM_ID: Pubkey = pubkey!("dRiftyHA39MWEi3m9aunc5MzRF1JYuBsbn6VPcn33UH");
+
+// Oracles, markets and makers follow as remaining accounts.
+pub const DRIFT_PLACE_ORDER_ACCOUNTS: &[&str] = &["state", "user", "authority"];
+pub const DRIFT_PLACE_AND_TAKE_ORDER_ACCOUNTS: &[&str] = &["state", "user", "userStats", "authority"];
+pub const DRIFT_SETTLE_PNL_ACCOUNTS: &[&str] = &["state", "user", "authority", "spotMarketVault"];
+pub const DRIFT_SETTLE_FUNDING_PAYMENT_ACCOUNTS: &[&str] = &["state", "user"];
+
+/// Bit of `OrderParams::bit_flags`, the byte was the `immediate_or_cancel` bool before
+const DRIFT_IMMEDIATE_OR_CANCEL_FLAG: u8 = 0b1;
+
+pub enum DriftInstructionType {
+    PlacePerpOrder,
+    PlaceAndTakePerpOrder,
+    PlaceSpotOrder,
+    SettlePnl,
+    SettleMultiplePnls,
+    SettleFundingPayment,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy)]
+pub enum DriftOrderType {
+    Market,
+    Limit,
+    TriggerMarket,
+    TriggerLimit,
+    Oracle,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy)]
+pub enum DriftMarketType {
+    Spot,
+    Perp,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy)]
+pub enum DriftPositionDirection {
+    Long,
+    Short,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy)]
+pub enum DriftPostOnlyParam {
+    None,
+    MustPostOnly,
+    TryPostOnly,
+    Slide,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy)]
+pub enum DriftOrderTriggerCondition {
+    Above,
+    Below,
+    TriggeredAbove,
+    TriggeredBelow,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy)]
+pub enum DriftSettlePnlMode {
+    MustSettle,
+    TrySettle,
+}
+
+/// Prices use PRICE_PRECISION (1e6), perp base amounts BASE_PRECISION (1e9) and spot
+/// base amounts the decimals of the market mint, all are emitted unscaled
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone)]
+pub struct DriftOrderParams {
+    pub order_type: DriftOrderType,
+    pub market_type: DriftMarketType,
+    pub direction: DriftPositionDirection,
+    pub user_order_id: u8,
+    pub base_asset_amount: u64,
+    pub price: u64,
+    pub market_index: u16,
+    pub reduce_only: bool,
+    pub post_only: DriftPostOnlyParam,
+    pub bit_flags: u8,
+    pub max_ts: Option<i64>,
+    pub trigger_price: Option<u64>,
+    pub trigger_condition: DriftOrderTriggerCondition,
+    pub oracle_price_offset: Option<i32>,
+    pub auction_duration: Option<u8>,
+    pub auction_start_price: Option<i64>,
+    pub auction_end_price: Option<i64>,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone)]
+pub struct DriftPlaceAndTakeParams {
+    pub params: DriftOrderParams,
+    pub success_condition: Option<u32>,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone)]
+pub struct DriftSettleMultiplePnlsParams {
+    pub market_indexes: Vec<u16>,
+    pub mode: DriftSettlePnlMode,
+}
+
+pub fn get_drift_instruction_type(data: &[u8]) -> Option<DriftInstructionType> {
+    match data.get(0..8) {
+        Some(d) if d == DRIFT_PLACE_PERP_ORDER_DISCRIMINATOR => Some(DriftInstructionType::PlacePerpOrder),
+        Some(d) if d == DRIFT_PLACE_AND_TAKE_PERP_ORDER_DISCRIMINATOR => Some(DriftInstructionType::PlaceAndTakePerpOrder),
+        Some(d) if d == DRIFT_PLACE_SPOT_ORDER_DISCRIMINATOR => Some(DriftInstructionType::PlaceSpotOrder),
+        Some(d) if d == DRIFT_SETTLE_PNL_DISCRIMINATOR => Some(DriftInstructionType::SettlePnl),
+        Some(d) if d == DRIFT_SETTLE_MULTIPLE_PNLS_DISCRIMINATOR => Some(DriftInstructionType::SettleMultiplePnls),
+        Some(d) if d == DRIFT_SETTLE_FUNDING_PAYMENT_DISCRIMINATOR => Some(DriftInstructionType::SettleFundingPayment),
+        _ => None,
+    }
+}
+
+fn order_params_json(params: &DriftOrderParams) -> JsonValue {
+    object! {
+        "order_type" => format!("{:?}", params.order_type),
+        "market_type" => format!("{:?}", params.market_type),
+        "direction" => format!("{:?}", params.direction),
+        "user_order_id" => params.user_order_id,
+        "base_asset_amount" => params.base_asset_amount.to_string(),
+        "price" => params.price.to_string(),
+        "market_index" => params.market_index,
+        "reduce_only" => params.reduce_only,
+        "post_only" => format!("{:?}", params.post_only),
+        "immediate_or_cancel" => params.bit_flags & DRIFT_IMMEDIATE_OR_CANCEL_FLAG != 0,
+        "max_ts" => params.max_ts.map(|ts| ts.to_string()),
+        "trigger_price" => params.trigger_price.map(|price| price.to_string()),
+        "trigger_condition" => format!("{:?}", params.trigger_condition),
+        "oracle_price_offset" => params.oracle_price_offset,
+        "auction_duration" => params.auction_duration,
+        "auction_start_price" => params.auction_start_price.map(|price| price.to_string()),
+        "auction_end_price" => params.auction_end_price.map(|price| price.to_string()),
+    }
+}
+
+/// The program rejects orders of the wrong market type for the instruction and empty orders
+fn check_order_params(sanity: &mut ValueSanity, params: &DriftOrderParams, expected_market_type: DriftMarketType) {
+    sanity.check_range_u128("market_type", params.market_type as u128, expected_market_type as u128, expected_market_type as u128);
+    sanity.check_nonzero("base_asset_amount", params.base_asset_amount as u128);
+}
+
+/// PlacePerpOrder (state=0, user=1, authority=2), PlaceSpotOrder (same accounts) and
+/// PlaceAndTakePerpOrder (state=0, user=1, user_stats=2, authority=3)
+pub fn deserialize_drift_order_instruction(
+    instr_type: &DriftInstructionType,
+    data: &[u8],
+    accounts_indices: &[u8],
+    account_keys: &[Pubkey],
+    is_signer: &[bool],
+    is_writable: &[bool],
+) -> Result<JsonValue, String> {
+    let mut data_ref = &data[8..];
+    let mut sanity = ValueSanity::new();
+
+    let (instruction_name, mut parsed_data) = match instr_type {
+        DriftInstructionType::PlaceAndTakePerpOrder => {
+            let args = DriftPlaceAndTakeParams::deserialize(&mut data_ref)
+                .map_err(|e| format!("Failed to deserialize DriftPlaceAndTakeParams: {:?}", e))?;
+            check_order_params(&mut sanity, &args.params, DriftMarketType::Perp);
+            let mut parsed_data = order_params_json(&args.params);
+            parsed_data["success_condition"] = args.success_condition.into();
+            ("PlaceAndTakePerpOrder", parsed_data)
+        },
+        DriftInstructionType::PlaceSpotOrder => {
+            let params = DriftOrderParams::deserialize(&mut data_ref)
+                .map_err(|e| format!("Failed to deserialize DriftOrderParams: {:?}", e))?;
+            check_order_params(&mut sanity, &params, DriftMarketType::Spot);
+            ("PlaceSpotOrder", order_params_json(&params))
+        },
+        _ => {
+            let params = DriftOrderParams::deserialize(&mut data_ref)
+                .map_err(|e| format!("Failed to deserialize DriftOrderParams: {:?}", e))?;
+            check_order_params(&mut sanity, &params, DriftMarketType::Perp);
+            ("PlacePerpOrder", order_params_json(&params))
+        },
+    };
+
+    sanity.apply(&mut parsed_data);
+    check_parse_residue(&format!("Drift {}", instruction_name), data_ref, &mut parsed_data);
+
+    Ok(create_standardized_instruction(
+        &DRIFT_PROGRAM_ID,
+        instruction_name,
+        "Drift",
+        data,
+        accounts_indices,
+        account_keys,
+        is_signer,
+        is_writable,
+        parsed_data
+    ))
+}
+
+/// SettlePnl and SettleMultiplePnls share accounts: state=0, user=1, authority=2, spot_market_vault=3.
+/// SettleFundingPayment only takes state=0, user=1 and no args.
+pub fn deserialize_drift_settle_instruction(
+    instr_type: &DriftInstructionType,
+    data: &[u8],
+    accounts_indices: &[u8],
+    account_keys: &[Pubkey],
+    is_signer: &[bool],
+    is_writable: &[bool],
+) -> Result<JsonValue, String> {
+    let mut data_ref = &data[8..];
+
+    let (instruction_name, mut parsed_data) = match instr_type {
+        DriftInstructionType::SettlePnl => {
+            let market_index = u16::deserialize(&mut data_ref)
+                .map_err(|e| format!("Failed to deserialize SettlePnl market index: {:?}", e))?;
+            ("SettlePnl", object! { "market_indexes" => vec![market_index] })
+        },
+        DriftInstructionType::SettleMultiplePnls => {
+            let args = DriftSettleMultiplePnlsParams::deserialize(&mut data_ref)
+                .map_err(|e| format!("Failed to deserialize DriftSettleMultiplePnlsParams: {:?}", e))?;
+            ("SettleMultiplePnls", object! {
+                "market_indexes" => args.market_indexes,
+                "mode" => format!("{:?}", args.mode),
+            })
+        },
+        _ => ("SettleFundingPayment", object! {}),
+    };
+
+    check_parse_residue(&format!("Drift {}", instruction_name), data_ref, &mut parsed_data);
+
+    Ok(create_standardized_instruction(
+        &DRIFT_PROGRAM_ID,
+        instruction_name,
+        "Drift",
+        data,
+        accounts_indices,
+        account_keys,
+        is_signer,
+        is_writable,
+        parsed_data
+    ))
+}
@@ -0,0 +1,228 @@
+use crate::grpc_server::transaction::{encoded_value::Kind, EncodedField, EncodedList, EncodedObject, EncodedValue};
+use crate::grpc_server::transaction::{TypedAccount, TypedInstruction, TypedMessageHeader, TypedPayload, TypedSwap, TypedTransaction};
+use json::JsonValue;
+use once_cell::sync::Lazy;
+use prost::Message;
+use tracing::warn;
+
+/// Wire encoding of the decoded output, chosen per subscriber
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputEncoding {
+    /// Indented JSON, meant for human debugging only
+    Pretty,
+    /// Single-line JSON
+    Compact,
+    /// Protobuf `EncodedValue`, mirroring the JSON document
+    Binary,
+    /// Protobuf `TypedPayload`, with dedicated messages for transactions, instructions and accounts
+    Typed,
+}
+
+impl OutputEncoding {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "pretty" => Some(OutputEncoding::Pretty),
+            "compact" | "json" => Some(OutputEncoding::Compact),
+            "binary" | "protobuf" => Some(OutputEncoding::Binary),
+            "typed" => Some(OutputEncoding::Typed),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OutputEncoding::Pretty => "pretty",
+            OutputEncoding::Compact => "compact",
+            OutputEncoding::Binary => "binary",
+            OutputEncoding::Typed => "typed",
+        }
+    }
+
+    pub(crate) fn index(&self) -> usize {
+        match self {
+            OutputEncoding::Pretty => 0,
+            OutputEncoding::Compact => 1,
+            OutputEncoding::Binary => 2,
+            OutputEncoding::Typed => 3,
+        }
+    }
+}
+
+/// Encoding used when a subscriber does not request one, compact unless overridden
+static DEFAULT_OUTPUT_ENCODING: Lazy<OutputEncoding> = Lazy::new(|| {
+    let configured = std::env::var("OUTPUT_ENCODING").unwrap_or_else(|_| "compact".to_string());
+    OutputEncoding::parse(&configured).unwrap_or_else(|| {
+        warn!("Unknown OUTPUT_ENCODING '{}', falling back to compact", configured);
+        OutputEncoding::Compact
+    })
+});
+
+pub fn default_output_encoding() -> OutputEncoding {
+    *DEFAULT_OUTPUT_ENCODING
+}
+
+/// Serialized output, text for the JSON encodings, bytes for the binary one and
+/// the message itself for the typed one
+#[derive(Debug, Clone)]
+pub enum EncodedPayload {
+    Text(String),
+    Binary(Vec<u8>),
+    Typed(TypedPayload),
+}
+
+/// The single place where decoded output gets serialized
+pub fn encode(value: &JsonValue, encoding: OutputEncoding) -> EncodedPayload {
+    match encoding {
+        OutputEncoding::Pretty => EncodedPayload::Text(value.pretty(2)),
+        OutputEncoding::Compact => EncodedPayload::Text(value.dump()),
+        OutputEncoding::Binary => EncodedPayload::Binary(to_encoded_value(value).encode_to_vec()),
+        OutputEncoding::Typed => EncodedPayload::Typed(to_typed_payload(value)),
+    }
+}
+
+pub fn to_encoded_value(value: &JsonValue) -> EncodedValue {
+    let kind = match value {
+        JsonValue::Null => Kind::NullValue(true),
+        JsonValue::Boolean(flag) => Kind::BoolValue(*flag),
+        JsonValue::Short(text) => Kind::StringValue(text.to_string()),
+        JsonValue::String(text) => Kind::StringValue(text.clone()),
+        JsonValue::Number(_) => {
+            if let Some(unsigned) = value.as_u64() {
+                Kind::UintValue(unsigned)
+            } else if let Some(signed) = value.as_i64() {
+                Kind::IntValue(signed)
+            } else {
+                Kind::DoubleValue(value.as_f64().unwrap_or(f64::NAN))
+            }
+        }
+        JsonValue::Array(items) => Kind::ListValue(EncodedList {
+            values: items.iter().map(to_encoded_value).collect(),
+        }),
+        JsonValue::Object(object) => Kind::ObjectValue(EncodedObject {
+            fields: object
+                .iter()
+                .map(|(key, field)| EncodedField {
+                    key: key.to_string(),
+                    value: Some(to_encoded_value(field)),
+                })
+                .collect(),
+        }),
+    };
+    EncodedValue { kind: Some(kind) }
+}
+
+fn to_encoded_object(value: &JsonValue) -> Option<EncodedObject> {
+    match to_encoded_value(value).kind {
+        Some(Kind::ObjectValue(object)) => Some(object),
+        _ => None,
+    }
+}
+
+/// Transaction fields with a dedicated field in `TypedTransaction`, the others go to the annotations
+const TYPED_TRANSACTION_FIELDS: &[&str] = &["signatures", "slot", "message", "memo", "cu_limit", "priority_fee_lamports"];
+
+pub fn to_typed_payload(value: &JsonValue) -> TypedPayload {
+    // Batch payloads reference accounts by position in their dictionary
+    let account_keys = &value["accountKeys"];
+    let transactions = if value["transactions"].is_array() {
+        value["transactions"].members().map(|transaction| to_typed_transaction(transaction, account_keys)).collect()
+    } else {
+        vec![to_typed_transaction(value, account_keys)]
+    };
+    TypedPayload {
+        slot: value["slot"].as_u64().unwrap_or(0),
+        transactions,
+    }
+}
+
+fn to_typed_transaction(transaction: &JsonValue, account_keys: &JsonValue) -> TypedTransaction {
+    let message = &transaction["message"];
+    let header = &message["header"];
+
+    let mut annotations = JsonValue::new_object();
+    for (key, field) in transaction.entries().filter(|(key, _)| !TYPED_TRANSACTION_FIELDS.contains(key)) {
+        annotations[key] = field.clone();
+    }
+
+    TypedTransaction {
+        signatures: transaction["signatures"].members().filter_map(|signature| signature.as_str().map(str::to_string)).collect(),
+        slot: transaction["slot"].as_u64().unwrap_or(0),
+        header: header.is_object().then(|| TypedMessageHeader {
+            num_required_signatures: header["numRequiredSignatures"].as_u32().unwrap_or(0),
+            num_readonly_signed_accounts: header["numReadonlySignedAccounts"].as_u32().unwrap_or(0),
+            num_readonly_unsigned_accounts: header["numReadonlyUnsignedAccounts"].as_u32().unwrap_or(0),
+        }),
+        recent_blockhash: message["recentBlockhash"].as_str().unwrap_or_default().to_string(),
+        instructions: message["instructions"].members().map(|instruction| to_typed_instruction(instruction, account_keys)).collect(),
+        memo: transaction["memo"].as_str().unwrap_or_default().to_string(),
+        cu_limit: transaction["cu_limit"].as_u64().unwrap_or(0),
+        priority_fee_lamports: transaction["priority_fee_lamports"].as_u64().unwrap_or(0),
+        annotations: to_encoded_object(&annotations).filter(|annotations| !annotations.fields.is_empty()),
+    }
+}
+
+fn to_typed_instruction(instruction: &JsonValue, account_keys: &JsonValue) -> TypedInstruction {
+    let inline_or_key = |value: &JsonValue, key: &JsonValue| {
+        value
+            .as_str()
+            .or_else(|| key.as_usize().and_then(|position| account_keys[position].as_str()))
+            .unwrap_or_default()
+            .to_string()
+    };
+    let swap = &instruction["swap"];
+    let amount = |field: &str| swap[field].as_str().and_then(|amount| amount.parse().ok()).unwrap_or(0);
+
+    TypedInstruction {
+        program_id: inline_or_key(&instruction["program_id"], &instruction["programIdKey"]),
+        protocol: instruction["protocol"].as_str().unwrap_or_default().to_string(),
+        instruction_name: instruction["instruction_name"].as_str().unwrap_or_default().to_string(),
+        data: instruction["raw_data"].as_str().and_then(|data| hex::decode(data).ok()).unwrap_or_default(),
+        accounts: instruction["accounts"]
+            .members()
+            .map(|account| TypedAccount {
+                index: account["index"].as_u32().unwrap_or(0),
+                name: account["name"].as_str().unwrap_or_default().to_string(),
+                pubkey: inline_or_key(&account["pubkey"], &account["key"]),
+                signer: account["signer"].as_bool().unwrap_or(false),
+                writable: account["writable"].as_bool().unwrap_or(false),
+            })
+            .collect(),
+        parsed_data: to_encoded_object(&instruction["parsed_data"]),
+        swap: swap.is_object().then(|| TypedSwap {
+            protocol: swap["protocol"].as_str().unwrap_or_default().to_string(),
+            pool: swap["pool"].as_str().unwrap_or_default().to_string(),
+            input_mint: swap["input_mint"].as_str().unwrap_or_default().to_string(),
+            output_mint: swap["output_mint"].as_str().unwrap_or_default().to_string(),
+            amount_in: amount("amount_in"),
+            min_amount_out: amount("min_amount_out"),
+            exact_in: swap["exact_in"].as_bool().unwrap_or(false),
+            user: swap["user"].as_str().unwrap_or_default().to_string(),
+        }),
+    }
+}
+
+pub fn from_encoded_value(value: &EncodedValue) -> JsonValue {
+    match &value.kind {
+        None | Some(Kind::NullValue(_)) => JsonValue::Null,
+        Some(Kind::BoolValue(flag)) => (*flag).into(),
+        Some(Kind::IntValue(signed)) => (*signed).into(),
+        Some(Kind::UintValue(unsigned)) => (*unsigned).into(),
+        Some(Kind::DoubleValue(double)) => (*double).into(),
+        Some(Kind::StringValue(text)) => text.as_str().into(),
+        Some(Kind::ListValue(list)) => JsonValue::Array(list.values.iter().map(from_encoded_value).collect()),
+        Some(Kind::ObjectValue(object)) => {
+            let mut decoded = JsonValue::new_object();
+            for field in &object.fields {
+                decoded[field.key.as_str()] = field.value.as_ref().map(from_encoded_value).unwrap_or(JsonValue::Null);
+            }
+            decoded
+        }
+    }
+}
+
+/// Decodes a `transaction_binary` payload back into the JSON document
+pub fn decode_binary(bytes: &[u8]) -> Result<JsonValue, String> {
+    EncodedValue::decode(bytes)
+        .map(|value| from_encoded_value(&value))
+        .map_err(|e| format!("Invalid binary payload: {}", e))
+}
@@ -0,0 +1,33 @@
+use once_cell::sync::Lazy;
+use solana_entry::entry::Entry;
+use tracing::warn;
+
+/// Opt-in PoH check of the entries of each payload, trading latency for integrity
+static ENTRY_VERIFICATION_ENABLED: Lazy<bool> = Lazy::new(|| {
+    std::env::var("ENTRY_VERIFICATION_ENABLED")
+        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+});
+
+pub fn is_entry_verification_enabled() -> bool {
+    *ENTRY_VERIFICATION_ENABLED
+}
+
+/// Verifies the PoH hash chain between consecutive entries of a payload. The first entry
+/// chains to the last entry of the previous FEC set, which is not known here, so only the
+/// links inside the payload are checked.
+pub fn verify_entry_chain(slot: u64, entries: &[Entry]) -> bool {
+    for (position, pair) in entries.windows(2).enumerate() {
+        let (previous, entry) = (&pair[0], &pair[1]);
+        if !entry.verify(&previous.hash) {
+            warn!(
+                "Entry hash verification failed in slot {}: entry {} does not chain to {}",
+                slot,
+                position + 1,
+                previous.hash
+            );
+            return false;
+        }
+    }
+    true
+}
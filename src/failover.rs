@@ -0,0 +1,173 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::net::UdpSocket;
+use tokio::time::Instant;
+use tracing::{debug, error, info, warn};
+
+use crate::operational_events::{publish_operational_event, Severity, FAILOVER_ROLE_EVENT};
+
+const HEARTBEAT_MAGIC: [u8; 4] = *b"SSDH";
+const HEARTBEAT_SIZE: usize = 4 + 8 + 8 + 1;
+
+/// Whether this instance is allowed to deliver output to sinks.
+/// Instances without a configured peer are always active.
+static OUTPUT_ACTIVE: AtomicBool = AtomicBool::new(true);
+
+pub fn is_output_active() -> bool {
+    OUTPUT_ACTIVE.load(Ordering::Relaxed)
+}
+
+fn set_output_active(active: bool) {
+    let previous = OUTPUT_ACTIVE.swap(active, Ordering::Relaxed);
+    if previous != active {
+        if active {
+            warn!("Failover: this instance is now ACTIVE and delivers output to sinks");
+        } else {
+            warn!("Failover: this instance is now STANDBY, sink output is suppressed");
+        }
+        let role = if active { "active" } else { "standby" };
+        publish_operational_event(
+            FAILOVER_ROLE_EVENT,
+            Severity::Warning,
+            format!("Failover: this instance is now {}", role),
+            json::object! { "role" => role },
+        );
+    }
+}
+
+/// Configuration for active/standby pairing of two decoder instances
+#[derive(Debug, Clone)]
+pub struct FailoverConfig {
+    pub bind_address: String,
+    pub peer_address: String,
+    pub priority: u64,
+    pub heartbeat_interval: Duration,
+    pub peer_timeout: Duration,
+}
+
+impl FailoverConfig {
+    /// Build the configuration from env, returns None when no peer is configured
+    pub fn from_env() -> Option<Self> {
+        let peer_address = std::env::var("FAILOVER_PEER_ADDRESS").ok().filter(|v| !v.trim().is_empty())?;
+        let bind_address = std::env::var("FAILOVER_BIND_ADDRESS").unwrap_or_else(|_| "0.0.0.0:8002".to_string());
+        let priority = std::env::var("FAILOVER_PRIORITY")
+            .unwrap_or_else(|_| "0".to_string())
+            .parse()
+            .unwrap_or(0);
+        let heartbeat_interval_ms = std::env::var("FAILOVER_HEARTBEAT_INTERVAL_MS")
+            .unwrap_or_else(|_| "200".to_string())
+            .parse()
+            .unwrap_or(200);
+        let peer_timeout_ms = std::env::var("FAILOVER_PEER_TIMEOUT_MS")
+            .unwrap_or_else(|_| "1000".to_string())
+            .parse()
+            .unwrap_or(1000);
+
+        Some(Self {
+            bind_address,
+            peer_address,
+            priority,
+            heartbeat_interval: Duration::from_millis(heartbeat_interval_ms),
+            peer_timeout: Duration::from_millis(peer_timeout_ms),
+        })
+    }
+}
+
+struct Heartbeat {
+    priority: u64,
+    instance_id: u64,
+    active: bool,
+}
+
+impl Heartbeat {
+    fn encode(&self) -> [u8; HEARTBEAT_SIZE] {
+        let mut buf = [0u8; HEARTBEAT_SIZE];
+        buf[0..4].copy_from_slice(&HEARTBEAT_MAGIC);
+        buf[4..12].copy_from_slice(&self.priority.to_le_bytes());
+        buf[12..20].copy_from_slice(&self.instance_id.to_le_bytes());
+        buf[20] = self.active as u8;
+        buf
+    }
+
+    fn decode(data: &[u8]) -> Option<Self> {
+        if data.len() != HEARTBEAT_SIZE || data[0..4] != HEARTBEAT_MAGIC {
+            return None;
+        }
+        Some(Self {
+            priority: u64::from_le_bytes(data[4..12].try_into().ok()?),
+            instance_id: u64::from_le_bytes(data[12..20].try_into().ok()?),
+            active: data[20] != 0,
+        })
+    }
+}
+
+/// Runs the heartbeat exchange and leader election with the peer instance.
+/// The instance starts as standby and only becomes active once it has either
+/// won the election against a live peer or the peer stopped sending heartbeats.
+pub async fn run_failover(config: FailoverConfig) {
+    let socket = match UdpSocket::bind(&config.bind_address).await {
+        Ok(socket) => socket,
+        Err(e) => {
+            error!("Failover: failed to bind heartbeat socket on {}: {:?}. Staying active.", config.bind_address, e);
+            return;
+        }
+    };
+
+    let instance_id = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_nanos() as u64
+        ^ ((std::process::id() as u64) << 32);
+    let local_rank = (config.priority, instance_id);
+
+    set_output_active(false);
+    info!(
+        "Failover: pairing with peer {} (priority={}, instance_id={}), starting as standby",
+        config.peer_address, config.priority, instance_id
+    );
+
+    let mut last_peer_heartbeat: Option<Instant> = None;
+    let started_at = Instant::now();
+    let mut ticker = tokio::time::interval(config.heartbeat_interval);
+    let mut buf = [0u8; 64];
+
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {
+                let heartbeat = Heartbeat { priority: config.priority, instance_id, active: is_output_active() };
+                if let Err(e) = socket.send_to(&heartbeat.encode(), &config.peer_address).await {
+                    debug!("Failover: failed to send heartbeat to {}: {:?}", config.peer_address, e);
+                }
+
+                let peer_alive = last_peer_heartbeat.map_or(false, |last| last.elapsed() < config.peer_timeout);
+                if !peer_alive && !is_output_active() && started_at.elapsed() >= config.peer_timeout {
+                    warn!("Failover: no heartbeat from peer {} for {:?}, taking over", config.peer_address, config.peer_timeout);
+                    set_output_active(true);
+                }
+            }
+            result = socket.recv_from(&mut buf) => {
+                let size = match result {
+                    Ok((size, _)) => size,
+                    Err(e) => {
+                        debug!("Failover: error receiving heartbeat: {:?}", e);
+                        continue;
+                    }
+                };
+                let Some(peer) = Heartbeat::decode(&buf[..size]) else { continue };
+                if peer.instance_id == instance_id {
+                    continue;
+                }
+                last_peer_heartbeat = Some(Instant::now());
+
+                let peer_rank = (peer.priority, peer.instance_id);
+                match (is_output_active(), peer.active) {
+                    // Both active after a partition heals: the lower ranked instance steps down
+                    (true, true) if local_rank < peer_rank => set_output_active(false),
+                    // Nobody is active yet: the higher ranked instance takes the lead
+                    (false, false) if local_rank > peer_rank => set_output_active(true),
+                    _ => {}
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
+use tracing::debug;
+
+use crate::shreds_processing::{decode_fec_block, FecBlock, FecBlockError, FecBlockTimings};
+
+/// Threads dedicated to Reed-Solomon recovery, apart from the decode workers
+static FEC_RECOVERY_THREADS: Lazy<usize> = Lazy::new(|| {
+    std::env::var("FEC_RECOVERY_THREADS")
+        .unwrap_or_else(|_| "2".to_string())
+        .parse()
+        .ok()
+        .filter(|threads| *threads > 0)
+        .unwrap_or(2)
+});
+
+/// Recoveries queued or running at once, further sets are dropped as incomplete
+static FEC_RECOVERY_QUEUE_LIMIT: Lazy<u64> = Lazy::new(|| {
+    std::env::var("FEC_RECOVERY_QUEUE_LIMIT")
+        .unwrap_or_else(|_| "64".to_string())
+        .parse()
+        .unwrap_or(64)
+});
+
+/// Recoveries accepted per slot, 0 for no limit
+static FEC_RECOVERY_SLOT_BUDGET: Lazy<u32> = Lazy::new(|| {
+    std::env::var("FEC_RECOVERY_SLOT_BUDGET")
+        .unwrap_or_else(|_| "32".to_string())
+        .parse()
+        .unwrap_or(32)
+});
+
+/// Slots kept in the budget map behind the newest one
+const BUDGET_SLOT_WINDOW: u64 = 256;
+
+static RECOVERY_POOL: Lazy<ThreadPool> = Lazy::new(|| {
+    ThreadPoolBuilder::new()
+        .num_threads(*FEC_RECOVERY_THREADS)
+        .thread_name(|index| format!("fec-recovery-{}", index))
+        .build()
+        .expect("Failed to build FEC recovery thread pool")
+});
+
+static SLOT_BUDGET_USED: Lazy<DashMap<u64, u32>> = Lazy::new(DashMap::new);
+
+static QUEUED: AtomicU64 = AtomicU64::new(0);
+static IN_FLIGHT: AtomicU64 = AtomicU64::new(0);
+static COMPLETED: AtomicU64 = AtomicU64::new(0);
+static REJECTED_QUEUE_FULL: AtomicU64 = AtomicU64::new(0);
+static REJECTED_SLOT_BUDGET: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum RecoveryRejection {
+    #[error("recovery queue full")]
+    QueueFull,
+    #[error("slot recovery budget exhausted")]
+    SlotBudgetExhausted,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RecoveryMetrics {
+    /// Recoveries waiting for or running on the pool
+    pub in_flight: u64,
+    pub queued_total: u64,
+    pub completed_total: u64,
+    pub rejected_queue_full: u64,
+    pub rejected_slot_budget: u64,
+    pub queue_limit: u64,
+    pub slot_budget: u32,
+}
+
+pub fn recovery_metrics() -> RecoveryMetrics {
+    RecoveryMetrics {
+        in_flight: IN_FLIGHT.load(Ordering::Relaxed),
+        queued_total: QUEUED.load(Ordering::Relaxed),
+        completed_total: COMPLETED.load(Ordering::Relaxed),
+        rejected_queue_full: REJECTED_QUEUE_FULL.load(Ordering::Relaxed),
+        rejected_slot_budget: REJECTED_SLOT_BUDGET.load(Ordering::Relaxed),
+        queue_limit: *FEC_RECOVERY_QUEUE_LIMIT,
+        slot_budget: *FEC_RECOVERY_SLOT_BUDGET,
+    }
+}
+
+/// Takes one unit of the slot budget, false once the slot has used all of it
+fn take_slot_budget(slot: u64) -> bool {
+    let budget = *FEC_RECOVERY_SLOT_BUDGET;
+    if budget == 0 {
+        return true;
+    }
+    let accepted = {
+        let mut used = SLOT_BUDGET_USED.entry(slot).or_insert(0);
+        if *used < budget {
+            *used += 1;
+            true
+        } else {
+            false
+        }
+    };
+    if SLOT_BUDGET_USED.len() as u64 > BUDGET_SLOT_WINDOW * 2 {
+        let oldest_kept = slot.saturating_sub(BUDGET_SLOT_WINDOW);
+        SLOT_BUDGET_USED.retain(|budget_slot, _| *budget_slot >= oldest_kept);
+    }
+    accepted
+}
+
+/// Decodes an FEC block missing data shreds on the recovery pool and hands the result to `on_done`,
+/// which runs on a pool thread. Rejected blocks are dropped, the caller counts them as incomplete.
+pub fn submit_recovery<F>(fec_block: FecBlock, on_done: F) -> Result<(), RecoveryRejection>
+where
+    F: FnOnce(FecBlock, Result<(Vec<u8>, u64, FecBlockTimings), FecBlockError>) + Send + 'static,
+{
+    let in_flight = IN_FLIGHT.fetch_add(1, Ordering::AcqRel);
+    if in_flight >= *FEC_RECOVERY_QUEUE_LIMIT {
+        IN_FLIGHT.fetch_sub(1, Ordering::AcqRel);
+        REJECTED_QUEUE_FULL.fetch_add(1, Ordering::Relaxed);
+        return Err(RecoveryRejection::QueueFull);
+    }
+    if !take_slot_budget(fec_block.slot) {
+        IN_FLIGHT.fetch_sub(1, Ordering::AcqRel);
+        REJECTED_SLOT_BUDGET.fetch_add(1, Ordering::Relaxed);
+        return Err(RecoveryRejection::SlotBudgetExhausted);
+    }
+    QUEUED.fetch_add(1, Ordering::Relaxed);
+
+    let queued_at = Instant::now();
+    RECOVERY_POOL.spawn(move || {
+        let queue_wait = queued_at.elapsed();
+        let decoded = decode_fec_block(&fec_block);
+        debug!(
+            "Recovery of slot {}, fec_set_index {} waited {:?} in queue, done in {:?}",
+            fec_block.slot,
+            fec_block.fec_set_index,
+            queue_wait,
+            queued_at.elapsed() - queue_wait
+        );
+        IN_FLIGHT.fetch_sub(1, Ordering::AcqRel);
+        COMPLETED.fetch_add(1, Ordering::Relaxed);
+        on_done(fec_block, decoded);
+    });
+    Ok(())
+}
@@ -0,0 +1,497 @@
+//! Subscriber filter expressions, compiled once per subscription and evaluated on every message.
+//!
+//! ```text
+//! protocol == "PumpAMM" && parsed.base_amount_out > 1000000
+//! instruction == "Create" || (protocol == "Raydium" && !memo)
+//! accounts contains "So11111111111111111111111111111111111111112"
+//! ```
+//!
+//! The expression is evaluated per decoded instruction and a transaction matches when one of its
+//! instructions does, so every predicate of `a && b` applies to the same instruction. Fields:
+//! `protocol`, `instruction`, `program_id`, `accounts` (pubkeys of the instruction accounts),
+//! `parsed.<field>` (the decoded arguments), and the transaction fields `slot`, `signature`, `memo`,
+//! `cu_limit`, `priority_fee_lamports`.
+//! Numbers stored as strings in the output (u64/u128 amounts) compare numerically.
+
+use json::JsonValue;
+use std::cmp::Ordering;
+
+/// Longest expression accepted, keeps per-message evaluation cheap
+const MAX_EXPRESSION_LENGTH: usize = 1024;
+
+/// Deepest nesting of parentheses and negations accepted
+const MAX_NESTING_DEPTH: usize = 32;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Identifier(String),
+    String(String),
+    Number(String),
+    True,
+    False,
+    Null,
+    And,
+    Or,
+    Not,
+    LeftParen,
+    RightParen,
+    Operator(Operator),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operator {
+    Equal,
+    NotEqual,
+    Greater,
+    GreaterOrEqual,
+    Less,
+    LessOrEqual,
+    Contains,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Literal {
+    String(String),
+    Number(String),
+    Bool(bool),
+    Null,
+}
+
+#[derive(Debug, Clone)]
+enum Expression {
+    And(Box<Expression>, Box<Expression>),
+    Or(Box<Expression>, Box<Expression>),
+    Not(Box<Expression>),
+    /// A bare field is true when present and neither false, null, 0 nor empty
+    Truthy(Vec<String>),
+    Compare(Vec<String>, Operator, Literal),
+}
+
+/// A compiled subscriber filter
+#[derive(Debug, Clone)]
+pub struct FilterExpression {
+    root: Expression,
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut position = 0;
+
+    while position < chars.len() {
+        let c = chars[position];
+        let next = chars.get(position + 1).copied();
+        match c {
+            c if c.is_whitespace() => position += 1,
+            '(' => {
+                tokens.push(Token::LeftParen);
+                position += 1;
+            }
+            ')' => {
+                tokens.push(Token::RightParen);
+                position += 1;
+            }
+            '&' if next == Some('&') => {
+                tokens.push(Token::And);
+                position += 2;
+            }
+            '|' if next == Some('|') => {
+                tokens.push(Token::Or);
+                position += 2;
+            }
+            '=' if next == Some('=') => {
+                tokens.push(Token::Operator(Operator::Equal));
+                position += 2;
+            }
+            '!' if next == Some('=') => {
+                tokens.push(Token::Operator(Operator::NotEqual));
+                position += 2;
+            }
+            '!' => {
+                tokens.push(Token::Not);
+                position += 1;
+            }
+            '>' | '<' => {
+                let or_equal = next == Some('=');
+                tokens.push(Token::Operator(match (c, or_equal) {
+                    ('>', true) => Operator::GreaterOrEqual,
+                    ('>', false) => Operator::Greater,
+                    ('<', true) => Operator::LessOrEqual,
+                    _ => Operator::Less,
+                }));
+                position += if or_equal { 2 } else { 1 };
+            }
+            '"' => {
+                let mut value = String::new();
+                position += 1;
+                loop {
+                    match chars.get(position) {
+                        None => return Err("Unterminated string literal".to_string()),
+                        Some('"') => break,
+                        Some('\\') => {
+                            let escaped = chars.get(position + 1).ok_or("Unterminated string literal")?;
+                            value.push(*escaped);
+                            position += 2;
+                        }
+                        Some(c) => {
+                            value.push(*c);
+                            position += 1;
+                        }
+                    }
+                }
+                tokens.push(Token::String(value));
+                position += 1;
+            }
+            c if c.is_ascii_digit() || (c == '-' && next.is_some_and(|n| n.is_ascii_digit())) => {
+                let start = position;
+                position += 1;
+                while position < chars.len() && (chars[position].is_ascii_digit() || chars[position] == '.') {
+                    position += 1;
+                }
+                tokens.push(Token::Number(chars[start..position].iter().collect()));
+            }
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let start = position;
+                while position < chars.len()
+                    && (chars[position].is_ascii_alphanumeric() || chars[position] == '_' || chars[position] == '.')
+                {
+                    position += 1;
+                }
+                let word: String = chars[start..position].iter().collect();
+                tokens.push(match word.as_str() {
+                    "true" => Token::True,
+                    "false" => Token::False,
+                    "null" => Token::Null,
+                    "contains" => Token::Operator(Operator::Contains),
+                    _ => Token::Identifier(word),
+                });
+            }
+            other => return Err(format!("Unexpected character '{}' at position {}", other, position)),
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn advance(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn enter(&mut self) -> Result<(), String> {
+        self.depth += 1;
+        if self.depth > MAX_NESTING_DEPTH {
+            return Err(format!("Expression nested deeper than {} levels", MAX_NESTING_DEPTH));
+        }
+        Ok(())
+    }
+
+    fn parse_or(&mut self) -> Result<Expression, String> {
+        let mut left = self.parse_and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.advance();
+            left = Expression::Or(Box::new(left), Box::new(self.parse_and()?));
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> Result<Expression, String> {
+        let mut left = self.parse_unary()?;
+        while self.peek() == Some(&Token::And) {
+            self.advance();
+            left = Expression::And(Box::new(left), Box::new(self.parse_unary()?));
+        }
+        Ok(left)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expression, String> {
+        match self.peek() {
+            Some(Token::Not) => {
+                self.advance();
+                self.enter()?;
+                let inner = self.parse_unary()?;
+                self.depth -= 1;
+                Ok(Expression::Not(Box::new(inner)))
+            }
+            Some(Token::LeftParen) => {
+                self.advance();
+                self.enter()?;
+                let inner = self.parse_or()?;
+                self.depth -= 1;
+                match self.advance() {
+                    Some(Token::RightParen) => Ok(inner),
+                    _ => Err("Missing closing parenthesis".to_string()),
+                }
+            }
+            _ => self.parse_comparison(),
+        }
+    }
+
+    fn parse_comparison(&mut self) -> Result<Expression, String> {
+        let path = match self.advance() {
+            Some(Token::Identifier(field)) => parse_field(&field)?,
+            other => return Err(format!("Expected a field name, found {:?}", other)),
+        };
+        let Some(Token::Operator(operator)) = self.peek().cloned() else {
+            return Ok(Expression::Truthy(path));
+        };
+        self.advance();
+        let literal = match self.advance() {
+            Some(Token::String(value)) => Literal::String(value),
+            Some(Token::Number(value)) => Literal::Number(value),
+            Some(Token::True) => Literal::Bool(true),
+            Some(Token::False) => Literal::Bool(false),
+            Some(Token::Null) => Literal::Null,
+            other => return Err(format!("Expected a value after {:?}, found {:?}", operator, other)),
+        };
+        if operator == Operator::Contains && !matches!(literal, Literal::String(_)) {
+            return Err("contains expects a string value".to_string());
+        }
+        Ok(Expression::Compare(path, operator, literal))
+    }
+}
+
+fn parse_field(field: &str) -> Result<Vec<String>, String> {
+    let path: Vec<String> = field.split('.').map(String::from).collect();
+    if path.iter().any(|segment| segment.is_empty()) {
+        return Err(format!("Invalid field name '{}'", field));
+    }
+    match (path[0].as_str(), path.len()) {
+        ("protocol" | "instruction" | "program_id" | "accounts" | "slot" | "signature" | "memo" | "cu_limit" | "priority_fee_lamports", 1) => Ok(path),
+        ("parsed", len) if len > 1 => Ok(path),
+        _ => Err(format!("Unknown field '{}'", field)),
+    }
+}
+
+/// One instruction of a transaction, the unit an expression is evaluated against
+struct Scope<'a> {
+    transaction: &'a JsonValue,
+    instruction: &'a JsonValue,
+    /// Dictionary of a batch payload, instruction accounts then reference it by position
+    account_keys: &'a JsonValue,
+}
+
+impl Scope<'_> {
+    fn account_pubkey<'b>(&'b self, account: &'b JsonValue) -> Option<&'b str> {
+        account["pubkey"]
+            .as_str()
+            .or_else(|| account["key"].as_usize().and_then(|position| self.account_keys[position].as_str()))
+    }
+}
+
+/// Field value as seen by the expression, resolved against one instruction of a transaction
+fn resolve<'a>(path: &[String], scope: &Scope<'a>) -> &'a JsonValue {
+    let (transaction, instruction) = (scope.transaction, scope.instruction);
+    match path[0].as_str() {
+        "protocol" => &instruction["protocol"],
+        "instruction" => &instruction["instruction_name"],
+        "program_id" => &instruction["program_id"],
+        "accounts" => &instruction["accounts"],
+        "slot" => &transaction["slot"],
+        "signature" => &transaction["signatures"][0],
+        "memo" => &transaction["memo"],
+        "cu_limit" => &transaction["cu_limit"],
+        "priority_fee_lamports" => &transaction["priority_fee_lamports"],
+        _ => path[1..].iter().fold(&instruction["parsed_data"], |value, segment| &value[segment.as_str()]),
+    }
+}
+
+fn is_truthy(value: &JsonValue) -> bool {
+    !value.is_empty()
+}
+
+/// Numeric view of a value, amounts above the f64 mantissa stay exact as i128
+fn as_number(value: &JsonValue) -> Option<(Option<i128>, f64)> {
+    let text = match value {
+        JsonValue::Number(_) => value.dump(),
+        JsonValue::Short(_) | JsonValue::String(_) => value.as_str()?.to_string(),
+        _ => return None,
+    };
+    let float = text.parse::<f64>().ok()?;
+    Some((text.parse::<i128>().ok(), float))
+}
+
+fn compare_numbers(value: &JsonValue, literal: &str) -> Option<Ordering> {
+    let (value_int, value_float) = as_number(value)?;
+    let literal_int = literal.parse::<i128>().ok();
+    let literal_float = literal.parse::<f64>().ok()?;
+    match (value_int, literal_int) {
+        (Some(a), Some(b)) => Some(a.cmp(&b)),
+        _ => value_float.partial_cmp(&literal_float),
+    }
+}
+
+fn compare(value: &JsonValue, operator: Operator, literal: &Literal, scope: &Scope) -> bool {
+    match (operator, literal) {
+        (Operator::Contains, Literal::String(needle)) => match value {
+            JsonValue::Array(items) => items
+                .iter()
+                .any(|item| item.as_str().or_else(|| scope.account_pubkey(item)) == Some(needle.as_str())),
+            _ => value.as_str().is_some_and(|text| text.contains(needle.as_str())),
+        },
+        (Operator::Equal | Operator::NotEqual, _) => {
+            let equal = match literal {
+                Literal::String(expected) => value.as_str() == Some(expected.as_str()),
+                Literal::Number(expected) => compare_numbers(value, expected) == Some(Ordering::Equal),
+                Literal::Bool(expected) => value.as_bool() == Some(*expected),
+                Literal::Null => value.is_null(),
+            };
+            equal == (operator == Operator::Equal)
+        }
+        (_, Literal::Number(expected)) => match compare_numbers(value, expected) {
+            Some(ordering) => match operator {
+                Operator::Greater => ordering == Ordering::Greater,
+                Operator::GreaterOrEqual => ordering != Ordering::Less,
+                Operator::Less => ordering == Ordering::Less,
+                Operator::LessOrEqual => ordering != Ordering::Greater,
+                _ => false,
+            },
+            None => false,
+        },
+        (_, Literal::String(expected)) => match value.as_str().map(|text| text.cmp(expected.as_str())) {
+            Some(ordering) => match operator {
+                Operator::Greater => ordering == Ordering::Greater,
+                Operator::GreaterOrEqual => ordering != Ordering::Less,
+                Operator::Less => ordering == Ordering::Less,
+                Operator::LessOrEqual => ordering != Ordering::Greater,
+                _ => false,
+            },
+            None => false,
+        },
+        _ => false,
+    }
+}
+
+fn evaluate(expression: &Expression, scope: &Scope) -> bool {
+    match expression {
+        Expression::And(left, right) => evaluate(left, scope) && evaluate(right, scope),
+        Expression::Or(left, right) => evaluate(left, scope) || evaluate(right, scope),
+        Expression::Not(inner) => !evaluate(inner, scope),
+        Expression::Truthy(path) => is_truthy(resolve(path, scope)),
+        Expression::Compare(path, operator, literal) => compare(resolve(path, scope), *operator, literal, scope),
+    }
+}
+
+impl FilterExpression {
+    pub fn compile(source: &str) -> Result<Self, String> {
+        if source.len() > MAX_EXPRESSION_LENGTH {
+            return Err(format!("Filter expression longer than {} characters", MAX_EXPRESSION_LENGTH));
+        }
+        let tokens = tokenize(source)?;
+        if tokens.is_empty() {
+            return Err("Empty filter expression".to_string());
+        }
+        let mut parser = Parser { tokens, position: 0, depth: 0 };
+        let root = parser.parse_or()?;
+        if let Some(token) = parser.peek() {
+            return Err(format!("Unexpected {:?} after the end of the expression", token));
+        }
+        Ok(Self { root })
+    }
+
+    fn matches_transaction(&self, transaction: &JsonValue, account_keys: &JsonValue) -> bool {
+        transaction["message"]["instructions"].members().any(|instruction| {
+            evaluate(&self.root, &Scope { transaction, instruction, account_keys })
+        })
+    }
+
+    /// True when a transaction of the payload, or of the batch, has a matching instruction
+    pub fn matches(&self, payload: &JsonValue) -> bool {
+        if payload["transactions"].is_array() {
+            payload["transactions"]
+                .members()
+                .any(|transaction| self.matches_transaction(transaction, &payload["accountKeys"]))
+        } else {
+            self.matches_transaction(payload, &JsonValue::Null)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transaction() -> JsonValue {
+        json::object! {
+            "slot" => 250_000_000u64,
+            "signatures" => json::array!["5ig"],
+            "cu_limit" => 200_000,
+            "message" => json::object! {
+                "instructions" => json::array![
+                    json::object! {
+                        "protocol" => "PumpAMM",
+                        "instruction_name" => "Buy",
+                        "program_id" => "pAMMBay6oceH9fJKBRHGP5D4bD4sWpmSwMn52FMfXEA",
+                        "accounts" => json::array![json::object! { "pubkey" => "So11111111111111111111111111111111111111112" }],
+                        "parsed_data" => json::object! { "base_amount_out" => "18446744073709551615", "slippage" => 2.5 },
+                    },
+                    json::object! { "protocol" => "Raydium", "instruction_name" => "SwapBaseIn", "parsed_data" => json::object! {} },
+                ],
+            },
+        }
+    }
+
+    fn matches(source: &str, payload: &JsonValue) -> bool {
+        FilterExpression::compile(source).unwrap_or_else(|e| panic!("{}: {}", source, e)).matches(payload)
+    }
+
+    #[test]
+    fn evaluates_compiled_expressions() {
+        let payload = transaction();
+        assert!(matches(r#"protocol == "PumpAMM" && parsed.base_amount_out > 1000000"#, &payload));
+        assert!(matches(r#"parsed.base_amount_out == 18446744073709551615"#, &payload));
+        assert!(matches(r#"parsed.slippage >= 2.5 && cu_limit < 300000"#, &payload));
+        assert!(matches(r#"accounts contains "So11111111111111111111111111111111111111112""#, &payload));
+        assert!(matches(r#"instruction == "SwapBaseIn" || !memo"#, &payload));
+        assert!(matches(r#"!(protocol == "Raydium") && slot == 250000000"#, &payload));
+        // Both predicates must hold for the same instruction
+        assert!(!matches(r#"protocol == "Raydium" && parsed.base_amount_out > 0"#, &payload));
+        assert!(!matches(r#"memo"#, &payload));
+    }
+
+    #[test]
+    fn resolves_batch_accounts_through_the_dictionary() {
+        let mut transaction = transaction();
+        transaction["message"]["instructions"][0]["accounts"] = json::array![json::object! { "key" => 1 }];
+        let batch = json::object! {
+            "accountKeys" => json::array!["11111111111111111111111111111111", "So11111111111111111111111111111111111111112"],
+            "transactions" => json::array![transaction],
+        };
+        assert!(matches(r#"accounts contains "So11111111111111111111111111111111111111112""#, &batch));
+        assert!(!matches(r#"accounts contains "11111111111111111111111111111111""#, &batch));
+    }
+
+    #[test]
+    fn rejects_malformed_expressions() {
+        for source in [
+            "",
+            "   ",
+            r#"protocol == "PumpAMM"#,
+            "unknown == 1",
+            "parsed == 1",
+            "parsed..amount > 1",
+            "(protocol == \"PumpAMM\"",
+            "protocol == \"PumpAMM\")",
+            "protocol ==",
+            "protocol == \"a\" &&",
+            "accounts contains 1",
+            "protocol # 1",
+        ] {
+            assert!(FilterExpression::compile(source).is_err(), "{:?} should not compile", source);
+        }
+        assert!(FilterExpression::compile(&format!("{}memo", "!".repeat(MAX_NESTING_DEPTH))).is_ok());
+        assert!(FilterExpression::compile(&format!("{}memo", "!".repeat(MAX_NESTING_DEPTH + 1))).is_err());
+        assert!(FilterExpression::compile(&format!("{}memo", "memo || ".repeat(MAX_EXPRESSION_LENGTH / 8))).is_err());
+    }
+}
@@ -1,55 +1,900 @@
 use std::pin::Pin;
 use futures_core::Stream as FutStream;
-use tonic::{transport::Server, Request, Response, Status};
+use tonic::{transport::{server::Router, Certificate, Identity, Server, ServerTlsConfig}, Request, Response, Status, Streaming};
+use tonic::codec::CompressionEncoding;
+use tonic::service::interceptor::InterceptedService;
 use tokio_stream::wrappers::BroadcastStream;
+#[cfg(unix)]
+use tokio_stream::wrappers::UnixListenerStream;
+use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
 use futures_util::StreamExt;
+use rustc_hash::FxHashSet as HashSet;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::VersionedTransaction;
+#[cfg(unix)]
+use std::os::unix::fs::FileTypeExt;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, RwLock};
+use std::sync::atomic::{AtomicU64, Ordering};
+#[cfg(unix)]
+use tokio::net::UnixListener;
+use tokio::sync::broadcast;
+use tracing::{debug, info, warn};
 
 pub mod transaction {
     tonic::include_proto!("transaction");
 }
 
 use transaction::transaction_service_server::{TransactionService, TransactionServiceServer};
-use transaction::{StreamTransactionsRequest, TransactionResponse};
+use transaction::{StreamTransactionsRequest, SubscriptionUpdate, TransactionResponse, ResumeToken, GetServerInfoRequest, ServerInfo, DecoderInfo};
+use transaction::{GetDiscriminatorReportRequest, DiscriminatorReport, DiscriminatorCollision};
+use transaction::{GetCoverageReportRequest, CoverageReport, InstructionCoverage, AccountCountBucket};
+use transaction::{FecRecoveryStats, GetSlotStatsRequest, SlotStatsReport, SlotStatsEntry};
+use transaction::{StreamDerivedEventsRequest, DerivedEventResponse};
+use transaction::{StreamPriceTicksRequest, PriceTick};
+use transaction::{StreamEventsRequest, OperationalEvent};
+use transaction::{StreamSlotStatusRequest, SlotStatus};
+use transaction::{StreamRawEntriesRequest, RawFecBlock};
+use transaction::{GetCanaryReportRequest, CanaryReport};
+use transaction::{GetDiscriminatorCatalogRequest, DiscriminatorCatalog, DiscriminatorCatalogEntry};
+
+use crate::output_message::{OutputMessage, OutputSender};
+use crate::encoder::{default_output_encoding, EncodedPayload, OutputEncoding};
+use crate::resume_buffer::{messages_after, messages_from_slot, ResumeError, ResumePoint};
+use crate::derived_events::subscribe_derived_events;
+use crate::price_ticks::subscribe_price_ticks;
+use crate::operational_events::{subscribe_operational_events, Severity};
+use crate::slot_status::subscribe_slot_status;
+use crate::raw_entries::{raw_transaction_keys, subscribe_raw_fec_blocks, RawFormat};
+use crate::utils::{get_highest_stats_slot, get_slot_stats_range};
+use crate::decoder_registry::{REGISTERED_DECODERS, detect_discriminator_collisions};
+use crate::coverage::account_count_coverage;
+use crate::canary::canary_report;
+use crate::fec_recovery::recovery_metrics;
+use crate::discriminator_catalog::discriminator_catalog;
+use crate::filter_expression::FilterExpression;
+use crate::server_info::*;
+use crate::auth::{api_token_interceptor_from_env, stream_grant, GrantScope, TokenGrant};
+use crate::subscriber_queue::{QueueReader, SubscriberQueue};
 
 type ResponseStream = Pin<Box<dyn FutStream<Item = Result<TransactionResponse, Status>> + Send + Sync>>;
+type DerivedEventStream = Pin<Box<dyn FutStream<Item = Result<DerivedEventResponse, Status>> + Send + Sync>>;
+type PriceTickStream = Pin<Box<dyn FutStream<Item = Result<PriceTick, Status>> + Send + Sync>>;
+type OperationalEventStream = Pin<Box<dyn FutStream<Item = Result<OperationalEvent, Status>> + Send + Sync>>;
+type SlotStatusStream = Pin<Box<dyn FutStream<Item = Result<SlotStatus, Status>> + Send + Sync>>;
+type RawFecBlockStream = Pin<Box<dyn FutStream<Item = Result<RawFecBlock, Status>> + Send + Sync>>;
+
+fn parse_pubkey_filter(requested: Vec<String>, kind: &str) -> Result<HashSet<String>, Status> {
+    let mut pubkeys = HashSet::default();
+    for pubkey in requested {
+        let pubkey = pubkey.trim().to_string();
+        if Pubkey::from_str(&pubkey).is_err() {
+            return Err(Status::invalid_argument(format!("Invalid {} address: {}", kind, pubkey)));
+        }
+        pubkeys.insert(pubkey);
+    }
+    Ok(pubkeys)
+}
+
+fn parse_mint_filter(requested: Vec<String>) -> Result<HashSet<String>, Status> {
+    parse_pubkey_filter(requested, "mint")
+}
+
+fn parse_name_filter(requested: Vec<String>, kind: &str) -> Result<HashSet<String>, Status> {
+    let mut names = HashSet::default();
+    for name in requested {
+        let name = name.trim().to_string();
+        if name.is_empty() {
+            return Err(Status::invalid_argument(format!("Empty {}", kind)));
+        }
+        names.insert(name);
+    }
+    Ok(names)
+}
+
+fn parse_filter_expression(requested: &str) -> Result<Option<FilterExpression>, Status> {
+    if requested.trim().is_empty() {
+        return Ok(None);
+    }
+    FilterExpression::compile(requested)
+        .map(Some)
+        .map_err(|e| Status::invalid_argument(format!("Invalid filter expression: {}", e)))
+}
+
+fn parse_encoding(requested: &str) -> Result<OutputEncoding, Status> {
+    if requested.trim().is_empty() {
+        return Ok(default_output_encoding());
+    }
+    OutputEncoding::parse(requested)
+        .ok_or_else(|| Status::invalid_argument(format!("Unknown encoding: {}", requested)))
+}
+
+/// Per-subscription message counter, advanced past dropped messages so that clients see a gap
+#[derive(Default)]
+struct StreamSequencer {
+    next: AtomicU64,
+}
+
+impl StreamSequencer {
+    fn next(&self) -> u64 {
+        self.next.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    fn skip(&self, dropped: u64) {
+        self.next.fetch_add(dropped, Ordering::Relaxed);
+    }
+
+    /// Accounts for a broadcast receive error, the only one being a lagging subscriber
+    fn record_lag(&self, error: &BroadcastStreamRecvError, stream: &str) {
+        let BroadcastStreamRecvError::Lagged(dropped) = error;
+        warn!("{} subscriber lagged behind, {} messages dropped", stream, dropped);
+        self.skip(*dropped);
+    }
+}
+
+fn transaction_response(message: &OutputMessage, encoding: OutputEncoding, stream_sequence: u64, dropped_messages: u64) -> TransactionResponse {
+    let (transaction_json, transaction_binary, transaction_typed) = match message.encoded(encoding) {
+        EncodedPayload::Text(text) => (text.clone(), Vec::new(), None),
+        EncodedPayload::Binary(bytes) => (String::new(), bytes.clone(), None),
+        EncodedPayload::Typed(typed) => (String::new(), Vec::new(), Some(typed.clone())),
+    };
+    TransactionResponse {
+        transaction_json,
+        timestamp: message.timestamp,
+        transaction_binary,
+        transaction_typed,
+        resume_token: Some(ResumeToken {
+            slot: message.slot,
+            sequence: message.sequence,
+        }),
+        stream_sequence,
+        dropped_messages,
+    }
+}
+
+/// Feeds the queue of one subscription from the output bus, applying its filters.
+/// Stops once the subscription is over.
+async fn forward_to_queue(
+    mut rx: broadcast::Receiver<Arc<OutputMessage>>,
+    queue: Arc<SubscriberQueue<Arc<OutputMessage>>>,
+    replayed_up_to: u64,
+    accepts: impl Fn(&OutputMessage) -> bool,
+) {
+    while !queue.is_closed() {
+        match rx.recv().await {
+            Ok(message) => {
+                if message.sequence > replayed_up_to && accepts(&message) && !queue.push(message) {
+                    return;
+                }
+            }
+            Err(broadcast::error::RecvError::Lagged(dropped)) => {
+                warn!("Transaction subscriber lagged behind the output bus, {} messages dropped", dropped);
+                queue.record_dropped(dropped);
+            }
+            Err(broadcast::error::RecvError::Closed) => {
+                queue.close();
+                return;
+            }
+        }
+    }
+}
+
+fn resume_error_status(error: ResumeError) -> Status {
+    match error {
+        ResumeError::OutsideRetention { oldest_sequence } => Status::out_of_range(format!(
+            "Resume token is outside the retention window, oldest retained sequence is {}",
+            oldest_sequence
+        )),
+        ResumeError::UnknownToken => Status::invalid_argument("Resume token was not issued by this server"),
+        ResumeError::SlotOutsideRetention { oldest_slot } => Status::out_of_range(format!(
+            "Replay slot is outside the retention window, oldest retained slot is {}",
+            oldest_slot
+        )),
+    }
+}
+
+/// Subscriber filters of a transaction subscription, a message must match every set filter
+struct SubscriptionFilter {
+    mints: HashSet<String>,
+    programs: HashSet<String>,
+    instructions: HashSet<String>,
+    accounts: HashSet<String>,
+    signer_only: bool,
+    expression: Option<FilterExpression>,
+}
+
+impl SubscriptionFilter {
+    fn from_request(request: &StreamTransactionsRequest) -> Result<Self, Status> {
+        Ok(Self {
+            mints: parse_mint_filter(request.mints.clone())?,
+            programs: parse_name_filter(request.programs.clone(), "program ID or protocol name")?,
+            instructions: parse_name_filter(request.instructions.clone(), "instruction name")?,
+            accounts: parse_pubkey_filter(request.accounts.clone(), "account")?,
+            signer_only: request.accounts_signer_only,
+            expression: parse_filter_expression(&request.filter)?,
+        })
+    }
+
+    /// Follow-a-token mode, program, account, instruction and expression filters: only messages matching all
+    fn accepts(&self, message: &OutputMessage) -> bool {
+        let touches_accounts = if self.signer_only { message.signed_by_any(&self.accounts) } else { message.touches_any(&self.accounts) };
+        (self.mints.is_empty() || message.touches_any(&self.mints))
+            && (self.programs.is_empty() || message.runs_any(&self.programs))
+            && (self.instructions.is_empty() || message.has_instruction(&self.instructions))
+            && (self.accounts.is_empty() || touches_accounts)
+            && self.expression.as_ref().is_none_or(|expression| expression.matches(&message.payload))
+    }
+
+    /// Applies a mid-stream update, nothing changes when part of it is invalid
+    fn apply(&mut self, update: SubscriptionUpdate) -> Result<(), Status> {
+        let replacement = update.request.as_ref().map(Self::from_request).transpose()?;
+        let (add_mints, remove_mints) = (parse_mint_filter(update.add_mints)?, parse_mint_filter(update.remove_mints)?);
+        let add_accounts = parse_pubkey_filter(update.add_accounts, "account")?;
+        let remove_accounts = parse_pubkey_filter(update.remove_accounts, "account")?;
+        let add_programs = parse_name_filter(update.add_programs, "program ID or protocol name")?;
+        let remove_programs = parse_name_filter(update.remove_programs, "program ID or protocol name")?;
+
+        if let Some(replacement) = replacement {
+            *self = replacement;
+        }
+        self.mints.retain(|mint| !remove_mints.contains(mint));
+        self.mints.extend(add_mints);
+        self.accounts.retain(|account| !remove_accounts.contains(account));
+        self.accounts.extend(add_accounts);
+        self.programs.retain(|program| !remove_programs.contains(program));
+        self.programs.extend(add_programs);
+        Ok(())
+    }
+}
+
+/// Reason a subscription was ended by an invalid update, reported to the client
+type UpdateFailure = Arc<Mutex<Option<Status>>>;
 
 #[derive(Clone)]
 pub struct MyTransactionService {
-    pub tx: tokio::sync::broadcast::Sender<(String, u64)>,
+    pub tx: OutputSender,
+}
+
+impl MyTransactionService {
+    /// Output of one transaction subscription, its filters may change while it runs
+    fn subscription_stream(
+        &self,
+        request: &StreamTransactionsRequest,
+        filter: Arc<RwLock<SubscriptionFilter>>,
+        grant: Option<Arc<TokenGrant>>,
+        failure: UpdateFailure,
+    ) -> Result<(ResponseStream, Arc<SubscriberQueue<Arc<OutputMessage>>>), Status> {
+        let encoding = parse_encoding(&request.encoding)?;
+        let accepts = move |message: &OutputMessage| {
+            grant.as_ref().is_none_or(|grant| grant.allows(message)) && filter.read().unwrap().accepts(message)
+        };
+
+        // Subscribe before reading the retained messages so nothing falls in between,
+        // live messages already replayed are skipped by sequence
+        let rx = self.tx.subscribe();
+        let backlog = match (&request.resume_from, request.replay_from_slot) {
+            (Some(_), replay_from_slot) if replay_from_slot > 0 => {
+                return Err(Status::invalid_argument("Set either resume_from or replay_from_slot, not both"));
+            }
+            (Some(token), _) => messages_after(ResumePoint { slot: token.slot, sequence: token.sequence })
+                .map_err(resume_error_status)?,
+            (None, 0) => Vec::new(),
+            (None, replay_from_slot) => messages_from_slot(replay_from_slot).map_err(resume_error_status)?,
+        };
+        let replayed_up_to = backlog.last().map(|message| message.sequence).unwrap_or(0);
+
+        let sequencer = Arc::new(StreamSequencer::default());
+        let queue = SubscriberQueue::<Arc<OutputMessage>>::from_env();
+
+        let backlog_accepts = accepts.clone();
+        let backlog_sequencer = sequencer.clone();
+        let backlog_queue = queue.clone();
+        let backlog_stream = tokio_stream::iter(backlog).filter_map(move |message| {
+            let accepted = backlog_accepts(&message);
+            let sequencer = backlog_sequencer.clone();
+            let dropped_messages = backlog_queue.dropped();
+            async move {
+                accepted.then(|| Ok(transaction_response(&message, encoding, sequencer.next(), dropped_messages)))
+            }
+        });
+
+        // Filtering and queueing keep up with the bus whatever the client's pace,
+        // a full queue only costs this subscriber messages
+        tokio::spawn(forward_to_queue(rx, queue.clone(), replayed_up_to, accepts));
+
+        let live_stream = futures_util::stream::unfold(Some(QueueReader { queue: queue.clone() }), move |reader| {
+            let sequencer = sequencer.clone();
+            let failure = failure.clone();
+            async move {
+                let reader = reader?;
+                let Some(message) = reader.queue.pop().await else {
+                    if reader.queue.overflowed() {
+                        let status = Status::resource_exhausted("Subscriber queue overflowed, resume from the last resume token");
+                        return Some((Err(status), None));
+                    }
+                    let status = failure.lock().unwrap().take()?;
+                    return Some((Err(status), None));
+                };
+                sequencer.skip(reader.queue.take_unreported_drops());
+                let response = transaction_response(&message, encoding, sequencer.next(), reader.queue.dropped());
+                Some((Ok(response), Some(reader)))
+            }
+        });
+
+        let stream = backlog_stream.chain(live_stream);
+        Ok((Box::pin(stream) as ResponseStream, queue))
+    }
 }
 
 #[tonic::async_trait]
 impl TransactionService for MyTransactionService {
     type StreamTransactionsStream = ResponseStream;
+    type StreamTransactionsBidiStream = ResponseStream;
+    type StreamDerivedEventsStream = DerivedEventStream;
+    type StreamPriceTicksStream = PriceTickStream;
+    type StreamEventsStream = OperationalEventStream;
+    type StreamSlotStatusStream = SlotStatusStream;
+    type StreamRawEntriesStream = RawFecBlockStream;
 
     async fn stream_transactions(
         &self,
-        _request: Request<StreamTransactionsRequest>,
+        request: Request<StreamTransactionsRequest>,
     ) -> Result<Response<Self::StreamTransactionsStream>, Status> {
-        let rx = self.tx.subscribe();
+        let grant = stream_grant(&request, "StreamTransactions", GrantScope::ALL)?;
+        let request = request.into_inner();
+        let filter = Arc::new(RwLock::new(SubscriptionFilter::from_request(&request)?));
+        let (stream, _) = self.subscription_stream(&request, filter, grant, UpdateFailure::default())?;
+        Ok(Response::new(stream))
+    }
 
-        let stream = BroadcastStream::new(rx).filter_map(|result| async move {
-            match result {
-                Ok((json, timestamp)) => Some(Ok(TransactionResponse {
-                    transaction_json: json,
-                    timestamp,
-                })),
-                Err(_) => None,
+    async fn stream_transactions_bidi(
+        &self,
+        request: Request<Streaming<SubscriptionUpdate>>,
+    ) -> Result<Response<Self::StreamTransactionsBidiStream>, Status> {
+        let grant = stream_grant(&request, "StreamTransactionsBidi", GrantScope::ALL)?;
+        let mut updates = request.into_inner();
+
+        // The first update opens the subscription, its request sets the encoding and resume point
+        let first = updates
+            .message()
+            .await?
+            .ok_or_else(|| Status::invalid_argument("Stream closed before the first subscription update"))?;
+        let request = first.request.clone().unwrap_or_default();
+        let mut filter = SubscriptionFilter::from_request(&request)?;
+        filter.apply(SubscriptionUpdate { request: None, ..first })?;
+        let filter = Arc::new(RwLock::new(filter));
+
+        let failure = UpdateFailure::default();
+        let (stream, queue) = self.subscription_stream(&request, filter.clone(), grant, failure.clone())?;
+
+        tokio::spawn(async move {
+            while !queue.is_closed() {
+                match updates.message().await {
+                    Ok(Some(update)) => {
+                        let applied = filter.write().unwrap().apply(update);
+                        if let Err(status) = applied {
+                            *failure.lock().unwrap() = Some(status);
+                            queue.close();
+                        }
+                    }
+                    // The client is done sending updates, the last filters stay in place
+                    Ok(None) => return,
+                    Err(status) => {
+                        debug!("Subscription update stream failed: {}", status);
+                        return;
+                    }
+                }
             }
         });
 
-        Ok(Response::new(Box::pin(stream) as Self::StreamTransactionsStream))
+        Ok(Response::new(stream))
+    }
+
+    async fn stream_derived_events(
+        &self,
+        request: Request<StreamDerivedEventsRequest>,
+    ) -> Result<Response<Self::StreamDerivedEventsStream>, Status> {
+        let grant = stream_grant(&request, "StreamDerivedEvents", GrantScope::ACCOUNTS)?;
+        let request = request.into_inner();
+        let mints = Arc::new(parse_mint_filter(request.mints)?);
+        let event_types: Arc<HashSet<String>> = Arc::new(request.event_types.into_iter().collect());
+
+        let rx = subscribe_derived_events();
+        let sequencer = Arc::new(StreamSequencer::default());
+
+        let stream = BroadcastStream::new(rx).filter_map(move |result| {
+            let mints = mints.clone();
+            let event_types = event_types.clone();
+            let grant = grant.clone();
+            let sequencer = sequencer.clone();
+            async move {
+                let event = result.map_err(|e| sequencer.record_lag(&e, "Derived event")).ok()?;
+                if !event_types.is_empty() && !event_types.contains(event.event_type) {
+                    return None;
+                }
+                if !mints.is_empty() && !mints.iter().any(|mint| event.accounts.contains(mint)) {
+                    return None;
+                }
+                // Restricted tokens only see events about their mints and accounts
+                if grant.is_some_and(|grant| !grant.allows_keys(&event.accounts, &HashSet::default())) {
+                    return None;
+                }
+                Some(Ok(DerivedEventResponse {
+                    event_type: event.event_type.to_string(),
+                    event_json: event.json.clone(),
+                    timestamp: event.timestamp,
+                    stream_sequence: sequencer.next(),
+                }))
+            }
+        });
+
+        Ok(Response::new(Box::pin(stream) as Self::StreamDerivedEventsStream))
+    }
+
+    async fn stream_price_ticks(
+        &self,
+        request: Request<StreamPriceTicksRequest>,
+    ) -> Result<Response<Self::StreamPriceTicksStream>, Status> {
+        let grant = stream_grant(&request, "StreamPriceTicks", GrantScope::ACCOUNTS)?;
+        let request = request.into_inner();
+        let mints = Arc::new(parse_mint_filter(request.mints)?);
+        let pools: Arc<HashSet<String>> = Arc::new(request.pools.into_iter().map(|pool| pool.trim().to_string()).collect());
+
+        let rx = subscribe_price_ticks();
+        let sequencer = Arc::new(StreamSequencer::default());
+
+        let stream = BroadcastStream::new(rx).filter_map(move |result| {
+            let mints = mints.clone();
+            let pools = pools.clone();
+            let grant = grant.clone();
+            let sequencer = sequencer.clone();
+            async move {
+                let tick = result.map_err(|e| sequencer.record_lag(&e, "Price tick")).ok()?;
+                if let Some(grant) = grant {
+                    let accounts: HashSet<String> = [Some(&tick.pool), tick.base_mint.as_ref(), tick.quote_mint.as_ref()]
+                        .into_iter()
+                        .flatten()
+                        .cloned()
+                        .collect();
+                    if !grant.allows_keys(&accounts, &HashSet::default()) {
+                        return None;
+                    }
+                }
+                if !pools.is_empty() && !pools.contains(&tick.pool) {
+                    return None;
+                }
+                if !mints.is_empty() {
+                    let trades_mint = [&tick.base_mint, &tick.quote_mint]
+                        .into_iter()
+                        .flatten()
+                        .any(|mint| mints.contains(mint));
+                    if !trades_mint {
+                        return None;
+                    }
+                }
+                Some(Ok(PriceTick {
+                    protocol: tick.protocol.clone(),
+                    pool: tick.pool.clone(),
+                    base_mint: tick.base_mint.clone().unwrap_or_default(),
+                    quote_mint: tick.quote_mint.clone().unwrap_or_default(),
+                    second: tick.second,
+                    open: tick.open,
+                    high: tick.high,
+                    low: tick.low,
+                    close: tick.close,
+                    base_volume: tick.base_volume,
+                    quote_volume: tick.quote_volume,
+                    swap_count: tick.swap_count,
+                    stream_sequence: sequencer.next(),
+                }))
+            }
+        });
+
+        Ok(Response::new(Box::pin(stream) as Self::StreamPriceTicksStream))
+    }
+
+    async fn stream_events(
+        &self,
+        request: Request<StreamEventsRequest>,
+    ) -> Result<Response<Self::StreamEventsStream>, Status> {
+        stream_grant(&request, "StreamEvents", GrantScope::NONE)?;
+        let request = request.into_inner();
+        let min_severity = if request.min_severity.trim().is_empty() {
+            Severity::Info
+        } else {
+            Severity::parse(&request.min_severity)
+                .ok_or_else(|| Status::invalid_argument(format!("Unknown severity: {}", request.min_severity)))?
+        };
+        let kinds: Arc<HashSet<String>> = Arc::new(request.kinds.into_iter().map(|kind| kind.trim().to_string()).collect());
+
+        let rx = subscribe_operational_events();
+        let sequencer = Arc::new(StreamSequencer::default());
+
+        let stream = BroadcastStream::new(rx).filter_map(move |result| {
+            let kinds = kinds.clone();
+            let sequencer = sequencer.clone();
+            async move {
+                let event = result.map_err(|e| sequencer.record_lag(&e, "Operational event")).ok()?;
+                if event.severity < min_severity {
+                    return None;
+                }
+                if !kinds.is_empty() && !kinds.contains(event.kind) {
+                    return None;
+                }
+                Some(Ok(OperationalEvent {
+                    kind: event.kind.to_string(),
+                    severity: event.severity.as_str().to_string(),
+                    message: event.message.clone(),
+                    details_json: event.details_json.clone(),
+                    timestamp: event.timestamp,
+                    stream_sequence: sequencer.next(),
+                }))
+            }
+        });
+
+        Ok(Response::new(Box::pin(stream) as Self::StreamEventsStream))
+    }
+
+    async fn stream_slot_status(
+        &self,
+        request: Request<StreamSlotStatusRequest>,
+    ) -> Result<Response<Self::StreamSlotStatusStream>, Status> {
+        stream_grant(&request, "StreamSlotStatus", GrantScope::NONE)?;
+        let request = request.into_inner();
+        let statuses: Arc<HashSet<String>> = Arc::new(request.statuses.into_iter().map(|status| status.trim().to_string()).collect());
+
+        let rx = subscribe_slot_status();
+        let sequencer = Arc::new(StreamSequencer::default());
+
+        let stream = BroadcastStream::new(rx).filter_map(move |result| {
+            let statuses = statuses.clone();
+            let sequencer = sequencer.clone();
+            async move {
+                let event = result.map_err(|e| sequencer.record_lag(&e, "Slot status")).ok()?;
+                if !statuses.is_empty() && !statuses.contains(event.kind.as_str()) {
+                    return None;
+                }
+                Some(Ok(SlotStatus {
+                    slot: event.slot,
+                    status: event.kind.as_str().to_string(),
+                    fec_set_index: event.fec_set_index.unwrap_or_default(),
+                    data_shreds: event.data_shreds,
+                    coding_shreds: event.coding_shreds,
+                    fec_sets_complete: event.fec_sets_complete,
+                    fec_sets_recovered: event.fec_sets_recovered,
+                    recovered_data_shreds: event.recovered_data_shreds,
+                    elapsed_micros: event.elapsed_micros,
+                    timestamp: event.timestamp,
+                    stream_sequence: sequencer.next(),
+                }))
+            }
+        });
+
+        Ok(Response::new(Box::pin(stream) as Self::StreamSlotStatusStream))
+    }
+
+    async fn stream_raw_entries(
+        &self,
+        request: Request<StreamRawEntriesRequest>,
+    ) -> Result<Response<Self::StreamRawEntriesStream>, Status> {
+        let requested = request.get_ref().format.clone();
+        let format = RawFormat::parse(&requested)
+            .ok_or_else(|| Status::invalid_argument(format!("Unknown raw format '{}', expected entries or transactions", requested)))?;
+        // Entries cannot lose transactions without breaking their hashes, only transactions are scoped
+        let grant = match format {
+            RawFormat::Entries => stream_grant(&request, "StreamRawEntries with the entries format", GrantScope::NONE)?,
+            RawFormat::Transactions => stream_grant(&request, "StreamRawEntries", GrantScope::ACCOUNTS_AND_PROGRAMS)?,
+        };
+
+        let rx = subscribe_raw_fec_blocks();
+        let sequencer = Arc::new(StreamSequencer::default());
+
+        let stream = BroadcastStream::new(rx).filter_map(move |result| {
+            let grant = grant.clone();
+            let sequencer = sequencer.clone();
+            async move {
+                let block = result.map_err(|e| sequencer.record_lag(&e, "Raw entries")).ok()?;
+                let keep = |transaction: &VersionedTransaction| {
+                    grant.as_ref().is_none_or(|grant| {
+                        let (accounts, programs) = raw_transaction_keys(transaction);
+                        grant.allows_keys(&accounts, &programs)
+                    })
+                };
+                let (entries, transactions) = match format {
+                    RawFormat::Entries => (block.payload.clone(), Vec::new()),
+                    RawFormat::Transactions => match block.transactions(keep) {
+                        Some(transactions) if transactions.is_empty() && grant.is_some() => return None,
+                        Some(transactions) => (Vec::new(), transactions),
+                        None => {
+                            warn!("Raw FEC block of slot {}, fec_set_index {} holds no entries", block.slot, block.fec_set_index);
+                            return None;
+                        }
+                    },
+                };
+                Some(Ok(RawFecBlock {
+                    slot: block.slot,
+                    fec_set_index: block.fec_set_index,
+                    entries,
+                    transactions,
+                    timestamp: block.timestamp,
+                    stream_sequence: sequencer.next(),
+                }))
+            }
+        });
+
+        Ok(Response::new(Box::pin(stream) as Self::StreamRawEntriesStream))
+    }
+
+    async fn get_server_info(
+        &self,
+        _request: Request<GetServerInfoRequest>,
+    ) -> Result<Response<ServerInfo>, Status> {
+        let decoders = REGISTERED_DECODERS
+            .iter()
+            .map(|decoder| DecoderInfo {
+                protocol: decoder.protocol.to_string(),
+                program_id: decoder.program_id.to_string(),
+            })
+            .collect();
+
+        Ok(Response::new(ServerInfo {
+            version: SERVER_VERSION.to_string(),
+            schema_version: OUTPUT_SCHEMA_VERSION,
+            decoders,
+            supported_filters: supported_filters().into_iter().map(String::from).collect(),
+            features: enabled_features().into_iter().map(String::from).collect(),
+        }))
+    }
+
+    async fn get_discriminator_report(
+        &self,
+        _request: Request<GetDiscriminatorReportRequest>,
+    ) -> Result<Response<DiscriminatorReport>, Status> {
+        let collisions = detect_discriminator_collisions()
+            .into_iter()
+            .map(|collision| DiscriminatorCollision {
+                severity: collision.severity.as_str().to_string(),
+                discriminator_hex: hex::encode(collision.discriminator),
+                instructions: collision
+                    .instructions
+                    .iter()
+                    .map(|(protocol, name)| format!("{}::{}", protocol, name))
+                    .collect(),
+            })
+            .collect();
+
+        Ok(Response::new(DiscriminatorReport { collisions }))
+    }
+
+    async fn get_coverage_report(
+        &self,
+        _request: Request<GetCoverageReportRequest>,
+    ) -> Result<Response<CoverageReport>, Status> {
+        let instructions = account_count_coverage()
+            .into_iter()
+            .map(|coverage| InstructionCoverage {
+                protocol: coverage.protocol.to_string(),
+                program_id: coverage.program_id.to_string(),
+                instruction: coverage.instruction.to_string(),
+                samples: coverage.samples,
+                min_accounts: coverage.min_accounts as u32,
+                max_accounts: coverage.max_accounts as u32,
+                configured_minimum: coverage.configured_minimum.unwrap_or(0) as u32,
+                relaxed: coverage.relaxed,
+                distribution: coverage
+                    .distribution
+                    .into_iter()
+                    .map(|(accounts, count)| AccountCountBucket { accounts: accounts as u32, count })
+                    .collect(),
+            })
+            .collect();
+
+        Ok(Response::new(CoverageReport { instructions }))
+    }
+
+    async fn get_slot_stats(
+        &self,
+        request: Request<GetSlotStatsRequest>,
+    ) -> Result<Response<SlotStatsReport>, Status> {
+        let request = request.into_inner();
+        let highest_slot = get_highest_stats_slot();
+        let end_slot = if request.end_slot == 0 { highest_slot } else { request.end_slot };
+        if request.start_slot > end_slot {
+            return Err(Status::invalid_argument(format!(
+                "start_slot {} is after end_slot {}",
+                request.start_slot, end_slot
+            )));
+        }
+
+        let slots = get_slot_stats_range(request.start_slot, end_slot)
+            .into_iter()
+            .map(|(slot, stats)| SlotStatsEntry {
+                slot,
+                fec_blocks_count: stats.fec_blocks_count,
+                tx_count: stats.tx_count,
+                fec_blocks_complete: stats.fec_blocks_complete,
+                fec_blocks_incomplete: stats.fec_blocks_incomplete,
+            })
+            .collect();
+
+        let recovery = recovery_metrics();
+        let recovery = FecRecoveryStats {
+            in_flight: recovery.in_flight,
+            queue_limit: recovery.queue_limit,
+            queued_total: recovery.queued_total,
+            completed_total: recovery.completed_total,
+            rejected_queue_full: recovery.rejected_queue_full,
+            rejected_slot_budget: recovery.rejected_slot_budget,
+            slot_budget: recovery.slot_budget,
+        };
+
+        Ok(Response::new(SlotStatsReport { slots, highest_slot, recovery: Some(recovery) }))
+    }
+
+    async fn get_canary_report(
+        &self,
+        _request: Request<GetCanaryReportRequest>,
+    ) -> Result<Response<CanaryReport>, Status> {
+        let Some(report) = canary_report() else {
+            return Ok(Response::new(CanaryReport::default()));
+        };
+
+        Ok(Response::new(CanaryReport {
+            available: true,
+            connected: report.connected,
+            messages_total: report.messages_total,
+            missed_total: report.missed_total,
+            decode_failures_total: report.decode_failures_total,
+            reconnects_total: report.reconnects_total,
+            window_messages: report.window_messages,
+            window_missed: report.window_missed,
+            gap_rate: report.gap_rate,
+            latency_p50_us: report.latency_p50_us,
+            latency_p99_us: report.latency_p99_us,
+            latency_max_us: report.latency_max_us,
+            window_end: report.window_end,
+        }))
+    }
+
+    async fn get_discriminator_catalog(
+        &self,
+        request: Request<GetDiscriminatorCatalogRequest>,
+    ) -> Result<Response<DiscriminatorCatalog>, Status> {
+        let request = request.into_inner();
+        let catalog = discriminator_catalog();
+        let entries = catalog
+            .entries
+            .into_iter()
+            .filter(|entry| entry.samples >= request.min_samples)
+            .filter(|entry| !request.undecoded_only || entry.decoded_as.is_none())
+            .map(|entry| {
+                let (protocol, instruction) = entry.decoded_as.unwrap_or_default();
+                DiscriminatorCatalogEntry {
+                    program_id: entry.program_id.to_string(),
+                    discriminator_hex: hex::encode(&entry.discriminator),
+                    samples: entry.samples,
+                    decoded: entry.decoded_as.is_some(),
+                    protocol: protocol.to_string(),
+                    instruction: instruction.to_string(),
+                }
+            })
+            .collect();
+
+        Ok(Response::new(DiscriminatorCatalog {
+            entries,
+            dropped_samples: catalog.dropped_samples,
+        }))
     }
 }
 
+fn read_pem(variable: &str, path: &str) -> Result<Vec<u8>, String> {
+    std::fs::read(path).map_err(|e| format!("Failed to read {} '{}': {}", variable, path, e))
+}
+
+/// TLS is enabled by GRPC_TLS_CERT_PATH and GRPC_TLS_KEY_PATH (PEM), mutual TLS by
+/// additionally setting GRPC_TLS_CLIENT_CA_PATH to the CA that signs client certificates
+fn grpc_tls_config() -> Result<Option<ServerTlsConfig>, String> {
+    let path = |variable: &str| std::env::var(variable).ok().filter(|path| !path.trim().is_empty());
+    let (cert_path, key_path, client_ca_path) = (
+        path("GRPC_TLS_CERT_PATH"),
+        path("GRPC_TLS_KEY_PATH"),
+        path("GRPC_TLS_CLIENT_CA_PATH"),
+    );
+
+    let (cert_path, key_path) = match (cert_path, key_path) {
+        (Some(cert_path), Some(key_path)) => (cert_path, key_path),
+        (None, None) if client_ca_path.is_none() => return Ok(None),
+        (None, None) => return Err("GRPC_TLS_CLIENT_CA_PATH requires GRPC_TLS_CERT_PATH and GRPC_TLS_KEY_PATH".to_string()),
+        _ => return Err("GRPC_TLS_CERT_PATH and GRPC_TLS_KEY_PATH must be set together".to_string()),
+    };
+
+    let identity = Identity::from_pem(
+        read_pem("GRPC_TLS_CERT_PATH", &cert_path)?,
+        read_pem("GRPC_TLS_KEY_PATH", &key_path)?,
+    );
+    let mut config = ServerTlsConfig::new().identity(identity);
+    if let Some(client_ca_path) = client_ca_path {
+        config = config.client_ca_root(Certificate::from_pem(read_pem("GRPC_TLS_CLIENT_CA_PATH", &client_ca_path)?));
+        info!("gRPC server requires client certificates signed by {}", client_ca_path);
+    } else {
+        info!("gRPC server uses TLS");
+    }
+    Ok(Some(config))
+}
+
+/// Encodings offered to clients, from GRPC_COMPRESSION ("gzip,zstd" by default, "none" disables).
+/// Responses are only compressed for clients announcing the encoding in grpc-accept-encoding.
+fn grpc_compression_encodings() -> Result<Vec<CompressionEncoding>, String> {
+    let configured = std::env::var("GRPC_COMPRESSION").unwrap_or_else(|_| "gzip,zstd".to_string());
+    if configured.trim().eq_ignore_ascii_case("none") {
+        return Ok(Vec::new());
+    }
+    configured
+        .split(',')
+        .map(str::trim)
+        .filter(|encoding| !encoding.is_empty())
+        .map(|encoding| match encoding.to_ascii_lowercase().as_str() {
+            "gzip" => Ok(CompressionEncoding::Gzip),
+            "zstd" => Ok(CompressionEncoding::Zstd),
+            _ => Err(format!("Unknown GRPC_COMPRESSION encoding '{}', expected gzip, zstd or none", encoding)),
+        })
+        .collect()
+}
+
+fn grpc_router(
+    tx: OutputSender,
+    tls: bool,
+) -> Result<Router, Box<dyn std::error::Error>> {
+    let service = MyTransactionService { tx };
+    let mut builder = Server::builder();
+    if tls {
+        if let Some(tls_config) = grpc_tls_config()? {
+            builder = builder.tls_config(tls_config)?;
+        }
+    }
+    let mut server = TransactionServiceServer::new(service);
+    for encoding in grpc_compression_encodings()? {
+        server = server.send_compressed(encoding).accept_compressed(encoding);
+    }
+    Ok(match api_token_interceptor_from_env()? {
+        Some(interceptor) => builder.add_service(InterceptedService::new(server, interceptor)),
+        None => builder.add_service(server),
+    })
+}
+
+/// Binds GRPC_UDS_PATH for co-located consumers, a socket left over by a previous run is replaced
+#[cfg(unix)]
+fn bind_unix_socket(path: &str) -> Result<UnixListenerStream, Box<dyn std::error::Error>> {
+    if let Ok(metadata) = std::fs::symlink_metadata(path) {
+        if !metadata.file_type().is_socket() {
+            return Err(format!("GRPC_UDS_PATH '{}' exists and is not a socket", path).into());
+        }
+        std::fs::remove_file(path)?;
+    }
+    let listener = UnixListener::bind(path)?;
+    info!("gRPC server listening on unix socket {}", path);
+    Ok(UnixListenerStream::new(listener))
+}
+
+/// Serves TCP on `addr` and, when GRPC_UDS_PATH is set, the same service on a Unix domain
+/// socket. TLS only applies to TCP, access to the socket is governed by its file permissions.
 pub async fn serve_grpc(
     addr: std::net::SocketAddr,
-    tx: tokio::sync::broadcast::Sender<(String, u64)>,
+    tx: OutputSender,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let service = MyTransactionService { tx };
-    Server::builder()
-        .add_service(TransactionServiceServer::new(service))
-        .serve(addr)
-        .await?;
+    let uds_path = std::env::var("GRPC_UDS_PATH").ok().filter(|path| !path.trim().is_empty());
+    let tcp = grpc_router(tx.clone(), true)?.serve(addr);
+
+    match uds_path {
+        #[cfg(unix)]
+        Some(path) => {
+            let incoming = bind_unix_socket(&path)?;
+            let uds = grpc_router(tx, false)?.serve_with_incoming(incoming);
+            tokio::try_join!(tcp, uds)?;
+        }
+        #[cfg(not(unix))]
+        Some(path) => return Err(format!("GRPC_UDS_PATH '{}' is set but Unix domain sockets are only available on Unix", path).into()),
+        None => tcp.await?,
+    }
     Ok(())
-}
\ No newline at end of file
+}
@@ -0,0 +1,435 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use json::{object, JsonValue};
+use once_cell::sync::OnceCell;
+use sha2::{Digest, Sha256};
+use solana_sdk::pubkey::Pubkey;
+use tracing::{debug, info, warn};
+use crate::byte_parser::*;
+use crate::decode_anomalies::check_parse_residue;
+use crate::decoder_registry::find_decoder_by_program;
+use crate::utils::create_standardized_instruction;
+
+/// Nesting limit of defined types, recursive IDL types would otherwise never end on crafted data
+const MAX_TYPE_DEPTH: usize = 32;
+
+/// Borsh layout of an IDL argument or field
+#[derive(Debug, Clone)]
+pub enum IdlType {
+    Bool,
+    U8,
+    I8,
+    U16,
+    I16,
+    U32,
+    I32,
+    F32,
+    U64,
+    I64,
+    F64,
+    U128,
+    I128,
+    String,
+    Bytes,
+    Pubkey,
+    Option(Box<IdlType>),
+    Vec(Box<IdlType>),
+    Array(Box<IdlType>, usize),
+    Defined(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct IdlField {
+    pub name: String,
+    pub ty: IdlType,
+}
+
+#[derive(Debug, Clone)]
+pub enum IdlFields {
+    Named(Vec<IdlField>),
+    Tuple(Vec<IdlType>),
+}
+
+#[derive(Debug, Clone)]
+pub struct IdlVariant {
+    pub name: String,
+    pub fields: Option<IdlFields>,
+}
+
+#[derive(Debug, Clone)]
+pub enum IdlTypeDef {
+    Struct(IdlFields),
+    Enum(Vec<IdlVariant>),
+}
+
+#[derive(Debug, Clone)]
+pub struct IdlInstruction {
+    /// PascalCase like the instruction names of the built-in decoders
+    pub name: String,
+    pub discriminator: Vec<u8>,
+    pub args: Vec<IdlField>,
+    /// Flattened account names, optional accounts are passed as the program id and keep their slot
+    pub accounts: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct IdlProgram {
+    pub protocol: String,
+    pub program_id: Pubkey,
+    pub instructions: Vec<IdlInstruction>,
+    pub types: HashMap<String, IdlTypeDef>,
+}
+
+impl IdlProgram {
+    /// Instruction matching the leading bytes of the instruction data, like the registry lookup
+    pub fn find_instruction(&self, data: &[u8]) -> Option<&IdlInstruction> {
+        self.instructions
+            .iter()
+            .find(|instruction| data.starts_with(&instruction.discriminator))
+    }
+}
+
+static IDL_PROGRAMS: OnceCell<HashMap<Pubkey, IdlProgram>> = OnceCell::new();
+
+/// Loads every Anchor IDL (*.json) of IDL_DIRECTORY. Both the legacy (< 0.30) and the current
+/// IDL format are understood. Files that fail to parse are skipped with a warning, programs
+/// with a built-in decoder keep it.
+pub fn init_idl_decoders() -> Result<(), String> {
+    let Some(directory) = std::env::var("IDL_DIRECTORY").ok().filter(|v| !v.trim().is_empty()) else {
+        debug!("No IDL_DIRECTORY configured, only built-in decoders are used");
+        return Ok(());
+    };
+    let entries = std::fs::read_dir(&directory).map_err(|e| format!("Failed to read {}: {}", directory, e))?;
+
+    let mut programs = HashMap::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|extension| extension.to_str()) != Some("json") {
+            continue;
+        }
+        let program = std::fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|content| json::parse(&content).map_err(|e| e.to_string()))
+            .and_then(|idl| parse_idl(&idl));
+        match program {
+            Ok(program) if find_decoder_by_program(&program.program_id).is_some() => {
+                warn!("IDL {}: {} has a built-in decoder, the IDL is ignored", path.display(), program.program_id);
+            }
+            Ok(program) => {
+                info!(
+                    "IDL {}: {} ({}) with {} instructions",
+                    path.display(),
+                    program.protocol,
+                    program.program_id,
+                    program.instructions.len()
+                );
+                programs.insert(program.program_id, program);
+            }
+            Err(e) => warn!("IDL {} skipped: {}", path.display(), e),
+        }
+    }
+
+    info!("Loaded {} IDL decoders from {}", programs.len(), directory);
+    IDL_PROGRAMS.set(programs).map_err(|_| "IDL decoders already initialized".to_string())
+}
+
+pub fn find_idl_program(program_id: &Pubkey) -> Option<&'static IdlProgram> {
+    IDL_PROGRAMS.get()?.get(program_id)
+}
+
+pub fn idl_program_count() -> usize {
+    IDL_PROGRAMS.get().map_or(0, HashMap::len)
+}
+
+fn parse_idl(idl: &JsonValue) -> Result<IdlProgram, String> {
+    let address = idl["address"].as_str().or_else(|| idl["metadata"]["address"].as_str())
+        .ok_or("missing program address")?;
+    let program_id = Pubkey::from_str(address).map_err(|e| format!("invalid program address {}: {}", address, e))?;
+    let protocol = idl["metadata"]["name"].as_str().or_else(|| idl["name"].as_str())
+        .ok_or("missing program name")?;
+
+    let mut types = HashMap::new();
+    for type_def in idl["types"].members().chain(idl["accounts"].members()) {
+        // Accounts only carry a type in the legacy format, the current one lists them under types
+        if type_def["type"].is_null() {
+            continue;
+        }
+        let name = type_def["name"].as_str().ok_or("type without a name")?;
+        types.insert(name.to_string(), parse_type_def(&type_def["type"]).map_err(|e| format!("type {}: {}", name, e))?);
+    }
+
+    let mut instructions = Vec::new();
+    for instruction in idl["instructions"].members() {
+        let name = instruction["name"].as_str().ok_or("instruction without a name")?;
+        let discriminator = if instruction["discriminator"].is_array() {
+            instruction["discriminator"].members().map(|byte| byte.as_u8().ok_or("invalid discriminator byte")).collect::<Result<Vec<u8>, _>>()?
+        } else {
+            anchor_discriminator(&to_snake_case(name)).to_vec()
+        };
+        let args = parse_named_fields(&instruction["args"]).map_err(|e| format!("instruction {}: {}", name, e))?;
+        let mut accounts = Vec::new();
+        flatten_accounts(&instruction["accounts"], &mut accounts);
+
+        instructions.push(IdlInstruction {
+            name: to_pascal_case(name),
+            discriminator,
+            args,
+            accounts,
+        });
+    }
+
+    Ok(IdlProgram {
+        protocol: to_pascal_case(protocol),
+        program_id,
+        instructions,
+        types,
+    })
+}
+
+fn anchor_discriminator(snake_name: &str) -> [u8; 8] {
+    let hash = Sha256::digest(format!("global:{}", snake_name).as_bytes());
+    let mut discriminator = [0u8; 8];
+    discriminator.copy_from_slice(&hash[..8]);
+    discriminator
+}
+
+// Account groups (composite accounts) are inlined in declaration order, names are
+// camelCase like the built-in account tables whatever the IDL format
+fn flatten_accounts(accounts: &JsonValue, names: &mut Vec<String>) {
+    for account in accounts.members() {
+        if account["accounts"].is_array() {
+            flatten_accounts(&account["accounts"], names);
+        } else if let Some(name) = account["name"].as_str() {
+            names.push(to_camel_case(name));
+        }
+    }
+}
+
+fn parse_type_def(type_def: &JsonValue) -> Result<IdlTypeDef, String> {
+    match type_def["kind"].as_str() {
+        Some("struct") => Ok(IdlTypeDef::Struct(parse_fields(&type_def["fields"])?)),
+        Some("enum") => {
+            let variants = type_def["variants"]
+                .members()
+                .map(|variant| {
+                    let name = variant["name"].as_str().ok_or("variant without a name")?;
+                    let fields = if variant["fields"].is_array() { Some(parse_fields(&variant["fields"])?) } else { None };
+                    Ok(IdlVariant { name: name.to_string(), fields })
+                })
+                .collect::<Result<Vec<_>, String>>()?;
+            Ok(IdlTypeDef::Enum(variants))
+        }
+        other => Err(format!("unsupported type kind {:?}", other)),
+    }
+}
+
+// Fields are named ({name, type}) or a tuple of bare types
+fn parse_fields(fields: &JsonValue) -> Result<IdlFields, String> {
+    if fields.members().all(|field| field["name"].is_string()) {
+        Ok(IdlFields::Named(parse_named_fields(fields)?))
+    } else {
+        Ok(IdlFields::Tuple(fields.members().map(parse_type).collect::<Result<Vec<_>, _>>()?))
+    }
+}
+
+fn parse_named_fields(fields: &JsonValue) -> Result<Vec<IdlField>, String> {
+    fields
+        .members()
+        .map(|field| {
+            let name = field["name"].as_str().ok_or("field without a name")?;
+            Ok(IdlField { name: name.to_string(), ty: parse_type(&field["type"])? })
+        })
+        .collect()
+}
+
+fn parse_type(ty: &JsonValue) -> Result<IdlType, String> {
+    if let Some(name) = ty.as_str() {
+        return match name {
+            "bool" => Ok(IdlType::Bool),
+            "u8" => Ok(IdlType::U8),
+            "i8" => Ok(IdlType::I8),
+            "u16" => Ok(IdlType::U16),
+            "i16" => Ok(IdlType::I16),
+            "u32" => Ok(IdlType::U32),
+            "i32" => Ok(IdlType::I32),
+            "f32" => Ok(IdlType::F32),
+            "u64" => Ok(IdlType::U64),
+            "i64" => Ok(IdlType::I64),
+            "f64" => Ok(IdlType::F64),
+            "u128" => Ok(IdlType::U128),
+            "i128" => Ok(IdlType::I128),
+            "string" => Ok(IdlType::String),
+            "bytes" => Ok(IdlType::Bytes),
+            "pubkey" | "publicKey" => Ok(IdlType::Pubkey),
+            other => Err(format!("unsupported type {}", other)),
+        };
+    }
+    if !ty["option"].is_null() {
+        return Ok(IdlType::Option(Box::new(parse_type(&ty["option"])?)));
+    }
+    if !ty["vec"].is_null() {
+        return Ok(IdlType::Vec(Box::new(parse_type(&ty["vec"])?)));
+    }
+    if ty["array"].is_array() {
+        let len = ty["array"][1].as_usize().ok_or("array without a literal length")?;
+        return Ok(IdlType::Array(Box::new(parse_type(&ty["array"][0])?), len));
+    }
+    // Legacy: {"defined": "Name"}, current: {"defined": {"name": "Name"}}
+    if let Some(name) = ty["defined"].as_str().or_else(|| ty["defined"]["name"].as_str()) {
+        return Ok(IdlType::Defined(name.to_string()));
+    }
+    Err(format!("unsupported type {}", ty.dump()))
+}
+
+/// Decodes the arguments of an IDL instruction, the discriminator excluded
+pub fn deserialize_idl_instruction(
+    program: &IdlProgram,
+    instruction: &IdlInstruction,
+    data: &[u8],
+    accounts_indices: &[u8],
+    account_keys: &[Pubkey],
+    is_signer: &[bool],
+    is_writable: &[bool],
+) -> Result<JsonValue, String> {
+    let mut offset = instruction.discriminator.len();
+    let mut parsed_data = JsonValue::new_object();
+    for arg in &instruction.args {
+        let value = read_value(program, &arg.ty, data, &mut offset, 0)
+            .map_err(|e| format!("{} {} argument {}: {}", program.protocol, instruction.name, arg.name, e))?;
+        parsed_data[arg.name.as_str()] = value;
+    }
+
+    check_parse_residue(&format!("{} {}", program.protocol, instruction.name), remaining(data, offset), &mut parsed_data);
+
+    Ok(create_standardized_instruction(
+        &program.program_id,
+        &instruction.name,
+        &program.protocol,
+        data,
+        accounts_indices,
+        account_keys,
+        is_signer,
+        is_writable,
+        parsed_data
+    ))
+}
+
+// 64 and 128 bit integers are strings, like the amounts of the built-in decoders
+fn read_value(program: &IdlProgram, ty: &IdlType, data: &[u8], offset: &mut usize, depth: usize) -> Result<JsonValue, String> {
+    if depth > MAX_TYPE_DEPTH {
+        return Err("type nesting too deep".to_string());
+    }
+    Ok(match ty {
+        IdlType::Bool => parse_bool(data, offset)?.into(),
+        IdlType::U8 => parse_u8(data, offset)?.into(),
+        IdlType::I8 => (parse_u8(data, offset)? as i8).into(),
+        IdlType::U16 => parse_u16(data, offset)?.into(),
+        IdlType::I16 => (parse_u16(data, offset)? as i16).into(),
+        IdlType::U32 => parse_u32(data, offset)?.into(),
+        IdlType::I32 => parse_i32(data, offset)?.into(),
+        IdlType::F32 => f32::from_le_bytes(parse_fixed_bytes(data, offset)?).into(),
+        IdlType::U64 => parse_u64(data, offset)?.to_string().into(),
+        IdlType::I64 => parse_i64(data, offset)?.to_string().into(),
+        IdlType::F64 => parse_f64(data, offset)?.into(),
+        IdlType::U128 => parse_u128(data, offset)?.to_string().into(),
+        IdlType::I128 => parse_i128(data, offset)?.to_string().into(),
+        IdlType::String => parse_string(data, offset)?.into(),
+        IdlType::Bytes => {
+            let len = parse_u32(data, offset)? as usize;
+            let bytes = remaining(data, *offset).get(..len).ok_or("bytes longer than the data")?;
+            *offset += len;
+            hex::encode(bytes).into()
+        }
+        IdlType::Pubkey => parse_pubkey(data, offset)?.to_string().into(),
+        IdlType::Option(inner) => match parse_u8(data, offset)? {
+            0 => JsonValue::Null,
+            1 => read_value(program, inner, data, offset, depth + 1)?,
+            tag => return Err(format!("invalid option tag {}", tag)),
+        },
+        IdlType::Vec(inner) => {
+            let len = parse_u32(data, offset)? as usize;
+            // Every element takes at least one byte, larger lengths can only be garbage
+            if len > remaining(data, *offset).len() {
+                return Err(format!("vec length {} exceeds the data", len));
+            }
+            read_values(program, inner, len, data, offset, depth)?
+        }
+        IdlType::Array(inner, len) => read_values(program, inner, *len, data, offset, depth)?,
+        IdlType::Defined(name) => {
+            let type_def = program.types.get(name).ok_or_else(|| format!("undefined type {}", name))?;
+            read_defined(program, type_def, data, offset, depth + 1)?
+        }
+    })
+}
+
+fn read_values(program: &IdlProgram, ty: &IdlType, len: usize, data: &[u8], offset: &mut usize, depth: usize) -> Result<JsonValue, String> {
+    let values = (0..len)
+        .map(|_| read_value(program, ty, data, offset, depth + 1))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(values.into())
+}
+
+fn read_fields(program: &IdlProgram, fields: &IdlFields, data: &[u8], offset: &mut usize, depth: usize) -> Result<JsonValue, String> {
+    match fields {
+        IdlFields::Named(fields) => {
+            let mut value = JsonValue::new_object();
+            for field in fields {
+                value[field.name.as_str()] = read_value(program, &field.ty, data, offset, depth)?;
+            }
+            Ok(value)
+        }
+        IdlFields::Tuple(types) => {
+            let values = types
+                .iter()
+                .map(|ty| read_value(program, ty, data, offset, depth))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(values.into())
+        }
+    }
+}
+
+// Unit variants are their name, variants with fields an object keyed by the name
+fn read_defined(program: &IdlProgram, type_def: &IdlTypeDef, data: &[u8], offset: &mut usize, depth: usize) -> Result<JsonValue, String> {
+    match type_def {
+        IdlTypeDef::Struct(fields) => read_fields(program, fields, data, offset, depth),
+        IdlTypeDef::Enum(variants) => {
+            let tag = parse_u8(data, offset)?;
+            let variant = variants.get(tag as usize).ok_or_else(|| format!("invalid enum variant {}", tag))?;
+            match &variant.fields {
+                None => Ok(variant.name.as_str().into()),
+                Some(fields) => Ok(object! { variant.name.as_str() => read_fields(program, fields, data, offset, depth)? }),
+            }
+        }
+    }
+}
+
+fn to_snake_case(name: &str) -> String {
+    let mut snake = String::with_capacity(name.len() + 4);
+    for (i, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() {
+            if i > 0 {
+                snake.push('_');
+            }
+            snake.push(c.to_ascii_lowercase());
+        } else {
+            snake.push(c);
+        }
+    }
+    snake
+}
+
+fn to_pascal_case(name: &str) -> String {
+    name.split(|c: char| c == '_' || c == '-')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            chars.next().map(|first| first.to_ascii_uppercase().to_string() + chars.as_str()).unwrap_or_default()
+        })
+        .collect()
+}
+
+fn to_camel_case(name: &str) -> String {
+    let pascal = to_pascal_case(name);
+    let mut chars = pascal.chars();
+    chars.next().map(|first| first.to_ascii_lowercase().to_string() + chars.as_str()).unwrap_or_default()
+}
@@ -0,0 +1,99 @@
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::latency_breakdown::FecBlockTimings;
+use crate::output_message::OutputSender;
+use crate::shreds_processing::extract_transactions_from_payload;
+use crate::utils::increment_slot_counters;
+
+pub mod shredstream {
+    tonic::include_proto!("shredstream");
+}
+
+use shredstream::shredstream_proxy_client::ShredstreamProxyClient;
+use shredstream::SubscribeEntriesRequest;
+
+const RECONNECT_DELAY: Duration = Duration::from_secs(2);
+
+/// Where shreds come from, UDP and any number of proxy gRPC APIs can run side by side
+#[derive(Debug, Clone)]
+pub struct IngestSources {
+    /// UDP packets pushed by a shredstream proxy to UDP_BUFFER_SOCKET
+    pub udp: bool,
+    /// Endpoints of Jito shredstream proxies whose SubscribeEntries gRPC API is pulled
+    pub jito_grpc: Vec<String>,
+}
+
+impl IngestSources {
+    /// INGEST_SOURCE is a comma list of "udp" (the default) and "jito_grpc", the latter with
+    /// JITO_SHREDSTREAM_ENDPOINT holding one or more comma-separated endpoints
+    pub fn from_env() -> Result<Self, String> {
+        let configured = std::env::var("INGEST_SOURCE").ok().filter(|v| !v.trim().is_empty()).unwrap_or_else(|| "udp".to_string());
+        let mut sources = IngestSources { udp: false, jito_grpc: Vec::new() };
+        for source in configured.split(',').map(|source| source.trim().to_ascii_lowercase()).filter(|source| !source.is_empty()) {
+            match source.as_str() {
+                "udp" => sources.udp = true,
+                "jito_grpc" => {
+                    sources.jito_grpc = std::env::var("JITO_SHREDSTREAM_ENDPOINT")
+                        .unwrap_or_default()
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|endpoint| !endpoint.is_empty())
+                        .map(String::from)
+                        .collect();
+                    if sources.jito_grpc.is_empty() {
+                        return Err("INGEST_SOURCE=jito_grpc requires JITO_SHREDSTREAM_ENDPOINT".to_string());
+                    }
+                }
+                other => return Err(format!("Unknown INGEST_SOURCE '{}', expected udp and/or jito_grpc", other)),
+            }
+        }
+        Ok(sources)
+    }
+
+    /// The same transactions arrive more than once, through different sources
+    pub fn is_redundant(&self) -> bool {
+        usize::from(self.udp) + self.jito_grpc.len() > 1
+    }
+}
+
+/// Decodes the entries streamed by a shredstream proxy, reconnecting whenever the stream ends.
+/// The proxy deshreds on its side, so the shred collection, FEC recovery and slot status
+/// stages do not run in this mode and the entries go straight to transaction extraction.
+pub async fn run_jito_ingest(endpoint: String, broadcast_tx: OutputSender) {
+    loop {
+        match ShredstreamProxyClient::connect(endpoint.clone()).await {
+            Ok(mut client) => match client.subscribe_entries(SubscribeEntriesRequest {}).await {
+                Ok(response) => {
+                    info!("Subscribed to shredstream entries at {}", endpoint);
+                    let mut stream = response.into_inner();
+                    loop {
+                        match stream.message().await {
+                            Ok(Some(entry)) => {
+                                let (_, tx_count) = extract_transactions_from_payload(
+                                    entry.slot,
+                                    entry.entries,
+                                    broadcast_tx.clone(),
+                                    FecBlockTimings::default(),
+                                )
+                                .await;
+                                increment_slot_counters(entry.slot, 0, tx_count as u64, 0, 0);
+                            }
+                            Ok(None) => {
+                                warn!("Shredstream entries stream from {} ended", endpoint);
+                                break;
+                            }
+                            Err(e) => {
+                                warn!("Shredstream entries stream from {} failed: {}", endpoint, e);
+                                break;
+                            }
+                        }
+                    }
+                }
+                Err(e) => warn!("SubscribeEntries at {} failed: {}", endpoint, e),
+            },
+            Err(e) => warn!("Failed to connect to shredstream proxy at {}: {}", endpoint, e),
+        }
+        tokio::time::sleep(RECONNECT_DELAY).await;
+    }
+}
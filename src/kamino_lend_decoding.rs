@@ -0,0 +1,172 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use json::{object, JsonValue};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::pubkey;
+use crate::decode_anomalies::check_parse_residue;
+use crate::utils::{create_standardized_instruction, ValueSanity};
+
+pub const KAMINO_LEND_DEPOSIT_DISCRIMINATOR: [u8; 8] = [129, 199, 4, 2, 222, 39, 26, 46];
+pub const KAMINO_LEND_BORROW_DISCRIMINATOR: [u8; 8] = [121, 127, 18, 204, 73, 245, 225, 65];
+pub const KAMINO_LEND_REPAY_DISCRIMINATOR: [u8; 8] = [145, 178, 13, 225, 76, 240, 147, 72];
+pub const KAMINO_LEND_LIQUIDATE_DISCRIMINATOR: [u8; 8] = [177, 71, 154, 188, 226, 133, 74, 55];
+// V2 variants append the farm accounts of the reserves, the args are unchanged
+pub const KAMINO_LEND_DEPOSIT_V2_DISCRIMINATOR: [u8; 8] = [216, 224, 191, 27, 204, 151, 102, 175];
+pub const KAMINO_LEND_BORROW_V2_DISCRIMINATOR: [u8; 8] = [161, 128, 143, 245, 171, 199, 194, 6];
+pub const KAMINO_LEND_REPAY_V2_DISCRIMINATOR: [u8; 8] = [116, 174, 213, 76, 180, 53, 210, 144];
+pub const KAMINO_LEND_LIQUIDATE_V2_DISCRIMINATOR: [u8; 8] = [162, 161, 35, 143, 30, 187, 185, 103];
+
+pub const KAMINO_LEND_PROGRAM_ID: Pubkey = pubkey!("KLend2g3cP87fffoy8q1mQqGKjrxjC8boSyAYavgmjD");
+
+// V1 instructions take the leading accounts of the V2 tables.
+pub const KAMINO_LEND_DEPOSIT_ACCOUNTS: &[&str] = &[
+    "owner", "obligation", "lendingMarket", "lendingMarketAuthority", "reserve", "reserveLiquidityMint",
+    "reserveLiquiditySupply", "reserveCollateralMint", "reserveDestinationDepositCollateral",
+    "userSourceLiquidity", "placeholderUserDestinationCollateral", "collateralTokenProgram",
+    "liquidityTokenProgram", "instructionSysvarAccount", "obligationFarmUserState", "reserveFarmState",
+    "farmsProgram",
+];
+pub const KAMINO_LEND_BORROW_ACCOUNTS: &[&str] = &[
+    "owner", "obligation", "lendingMarket", "lendingMarketAuthority", "borrowReserve",
+    "borrowReserveLiquidityMint", "reserveSourceLiquidity", "borrowReserveLiquidityFeeReceiver",
+    "userDestinationLiquidity", "referrerTokenState", "tokenProgram", "instructionSysvarAccount",
+    "obligationFarmUserState", "reserveFarmState", "farmsProgram",
+];
+pub const KAMINO_LEND_REPAY_ACCOUNTS: &[&str] = &[
+    "owner", "obligation", "lendingMarket", "repayReserve", "reserveLiquidityMint",
+    "reserveDestinationLiquidity", "userSourceLiquidity", "tokenProgram", "instructionSysvarAccount",
+    "obligationFarmUserState", "reserveFarmState", "farmsProgram",
+];
+pub const KAMINO_LEND_LIQUIDATE_ACCOUNTS: &[&str] = &[
+    "liquidator", "obligation", "lendingMarket", "lendingMarketAuthority", "repayReserve",
+    "repayReserveLiquidityMint", "repayReserveLiquiditySupply", "withdrawReserve",
+    "withdrawReserveLiquidityMint", "withdrawReserveCollateralMint", "withdrawReserveCollateralSupply",
+    "withdrawReserveLiquiditySupply", "withdrawReserveLiquidityFeeReceiver", "userSourceLiquidity",
+    "userDestinationCollateral", "userDestinationLiquidity", "collateralTokenProgram",
+    "repayLiquidityTokenProgram", "withdrawLiquidityTokenProgram", "instructionSysvarAccount",
+    "collateralObligationFarmUserState", "collateralReserveFarmState", "debtObligationFarmUserState",
+    "debtReserveFarmState", "farmsProgram",
+];
+
+/// Upper bound of max_allowed_ltv_override_percent, 0 keeps the reserve's own LTV
+const KAMINO_LEND_MAX_LTV_OVERRIDE_PERCENT: u64 = 100;
+
+pub enum KaminoLendInstructionType {
+    Deposit,
+    Borrow,
+    Repay,
+    Liquidate,
+    DepositV2,
+    BorrowV2,
+    RepayV2,
+    LiquidateV2,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
+pub struct KaminoLendLiquidityParams {
+    pub liquidity_amount: u64,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
+pub struct KaminoLendLiquidateParams {
+    pub liquidity_amount: u64,
+    pub min_acceptable_received_liquidity_amount: u64,
+    pub max_allowed_ltv_override_percent: u64,
+}
+
+pub fn get_kamino_lend_instruction_type(data: &[u8]) -> Option<KaminoLendInstructionType> {
+    match data.get(0..8) {
+        Some(d) if d == KAMINO_LEND_DEPOSIT_DISCRIMINATOR => Some(KaminoLendInstructionType::Deposit),
+        Some(d) if d == KAMINO_LEND_BORROW_DISCRIMINATOR => Some(KaminoLendInstructionType::Borrow),
+        Some(d) if d == KAMINO_LEND_REPAY_DISCRIMINATOR => Some(KaminoLendInstructionType::Repay),
+        Some(d) if d == KAMINO_LEND_LIQUIDATE_DISCRIMINATOR => Some(KaminoLendInstructionType::Liquidate),
+        Some(d) if d == KAMINO_LEND_DEPOSIT_V2_DISCRIMINATOR => Some(KaminoLendInstructionType::DepositV2),
+        Some(d) if d == KAMINO_LEND_BORROW_V2_DISCRIMINATOR => Some(KaminoLendInstructionType::BorrowV2),
+        Some(d) if d == KAMINO_LEND_REPAY_V2_DISCRIMINATOR => Some(KaminoLendInstructionType::RepayV2),
+        Some(d) if d == KAMINO_LEND_LIQUIDATE_V2_DISCRIMINATOR => Some(KaminoLendInstructionType::LiquidateV2),
+        _ => None,
+    }
+}
+
+/// Deposit (owner=0, obligation=1, lending_market=2, reserve=4, reserve_liquidity_mint=5, ...),
+/// Borrow (owner=0, obligation=1, lending_market=2, borrow_reserve=4, borrow_reserve_liquidity_mint=5, ...)
+/// and Repay (owner=0, obligation=1, lending_market=2, repay_reserve=3, reserve_liquidity_mint=4, ...)
+/// only carry the liquidity amount, u64::MAX repays the whole debt
+pub fn deserialize_kamino_lend_liquidity_instruction(
+    instruction_name: &str,
+    data: &[u8],
+    accounts_indices: &[u8],
+    account_keys: &[Pubkey],
+    is_signer: &[bool],
+    is_writable: &[bool],
+) -> Result<JsonValue, String> {
+    let mut data_ref = &data[8..];
+    let args = KaminoLendLiquidityParams::deserialize(&mut data_ref)
+        .map_err(|e| format!("Failed to deserialize KaminoLendLiquidityParams: {:?}", e))?;
+
+    let mut parsed_data = object! {
+        "liquidity_amount" => args.liquidity_amount.to_string(),
+    };
+
+    let mut sanity = ValueSanity::new();
+    sanity.check_nonzero("liquidity_amount", args.liquidity_amount as u128);
+    sanity.apply(&mut parsed_data);
+
+    check_parse_residue(&format!("KaminoLend {}", instruction_name), data_ref, &mut parsed_data);
+
+    Ok(create_standardized_instruction(
+        &KAMINO_LEND_PROGRAM_ID,
+        instruction_name,
+        "KaminoLend",
+        data,
+        accounts_indices,
+        account_keys,
+        is_signer,
+        is_writable,
+        parsed_data
+    ))
+}
+
+/// Accounts: liquidator=0, obligation=1, lending_market=2, repay_reserve=4,
+/// repay_reserve_liquidity_mint=5, withdraw_reserve=7, withdraw_reserve_liquidity_mint=8, ...
+pub fn deserialize_kamino_lend_liquidate_instruction(
+    instruction_name: &str,
+    data: &[u8],
+    accounts_indices: &[u8],
+    account_keys: &[Pubkey],
+    is_signer: &[bool],
+    is_writable: &[bool],
+) -> Result<JsonValue, String> {
+    let mut data_ref = &data[8..];
+    let args = KaminoLendLiquidateParams::deserialize(&mut data_ref)
+        .map_err(|e| format!("Failed to deserialize KaminoLendLiquidateParams: {:?}", e))?;
+
+    let mut parsed_data = object! {
+        "liquidity_amount" => args.liquidity_amount.to_string(),
+        "min_acceptable_received_liquidity_amount" => args.min_acceptable_received_liquidity_amount.to_string(),
+        "max_allowed_ltv_override_percent" => args.max_allowed_ltv_override_percent.to_string(),
+    };
+
+    let mut sanity = ValueSanity::new();
+    sanity.check_nonzero("liquidity_amount", args.liquidity_amount as u128);
+    sanity.check_range_u128(
+        "max_allowed_ltv_override_percent",
+        args.max_allowed_ltv_override_percent as u128,
+        0,
+        KAMINO_LEND_MAX_LTV_OVERRIDE_PERCENT as u128,
+    );
+    sanity.apply(&mut parsed_data);
+
+    check_parse_residue(&format!("KaminoLend {}", instruction_name), data_ref, &mut parsed_data);
+
+    Ok(create_standardized_instruction(
+        &KAMINO_LEND_PROGRAM_ID,
+        instruction_name,
+        "KaminoLend",
+        data,
+        accounts_indices,
+        account_keys,
+        is_signer,
+        is_writable,
+        parsed_data
+    ))
+}
@@ -0,0 +1,76 @@
+use once_cell::sync::Lazy;
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+/// Opt-in flag, the per-stage measurements are skipped entirely when disabled
+static LATENCY_BREAKDOWN_ENABLED: Lazy<bool> = Lazy::new(|| {
+    std::env::var("LATENCY_BREAKDOWN_ENABLED")
+        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+});
+
+pub fn is_latency_breakdown_enabled() -> bool {
+    *LATENCY_BREAKDOWN_ENABLED
+}
+
+/// Start of a measured stage, None without reading the clock when the breakdown is disabled
+pub fn stage_start() -> Option<Instant> {
+    is_latency_breakdown_enabled().then(Instant::now)
+}
+
+/// Duration of a stage started with `stage_start`, zero when the breakdown is disabled
+pub fn stage_elapsed(start: Option<Instant>) -> Duration {
+    start.map(|start| start.elapsed()).unwrap_or_default()
+}
+
+/// Timings shared by every transaction reconstructed from the same FEC block
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FecBlockTimings {
+    pub shred_wait: Duration,
+    pub rs_recovery: Duration,
+    pub deshred: Duration,
+    pub bincode: Duration,
+}
+
+/// Per-transaction breakdown of where time was spent between the first shred and the broadcast
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LatencyBreakdown {
+    pub fec: FecBlockTimings,
+    pub alt_resolve: Duration,
+    pub decode: Duration,
+    pub serialize: Duration,
+    pub enqueue: Duration,
+}
+
+impl LatencyBreakdown {
+    pub fn total(&self) -> Duration {
+        self.fec.shred_wait
+            + self.fec.rs_recovery
+            + self.fec.deshred
+            + self.fec.bincode
+            + self.alt_resolve
+            + self.decode
+            + self.serialize
+            + self.enqueue
+    }
+
+    /// Emits the breakdown on the dedicated `latency_breakdown` debug target,
+    /// enable it with RUST_LOG=latency_breakdown=debug
+    pub fn emit(&self, slot: u64, signature: &str) {
+        debug!(
+            target: "latency_breakdown",
+            "slot={} signature={} total={:?} shred_wait={:?} rs_recovery={:?} deshred={:?} bincode={:?} alt_resolve={:?} decode={:?} serialize={:?} enqueue={:?}",
+            slot,
+            signature,
+            self.total(),
+            self.fec.shred_wait,
+            self.fec.rs_recovery,
+            self.fec.deshred,
+            self.fec.bincode,
+            self.alt_resolve,
+            self.decode,
+            self.serialize,
+            self.enqueue,
+        );
+    }
+}
@@ -0,0 +1,210 @@
+use json::JsonValue;
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{info, warn};
+
+use crate::creation_events::TOKEN_LAUNCH_EVENT;
+use crate::derived_events::subscribe_derived_events;
+use crate::failover::is_output_active;
+
+/// Token launch alerts posted to a Telegram chat and/or a Discord channel
+#[derive(Debug, Clone)]
+pub struct LaunchAlertConfig {
+    pub telegram: Option<TelegramTarget>,
+    pub discord_webhook_url: Option<String>,
+    /// Alerts per minute, launches beyond it are counted and skipped
+    pub max_per_minute: u32,
+    /// Launchpads to alert on ("Pumpfun", "Boop", ...), all when empty
+    pub platforms: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TelegramTarget {
+    pub bot_token: String,
+    pub chat_id: String,
+}
+
+impl LaunchAlertConfig {
+    /// Build the configuration from env, returns None unless a Telegram bot
+    /// (LAUNCH_ALERT_TELEGRAM_BOT_TOKEN and LAUNCH_ALERT_TELEGRAM_CHAT_ID) or
+    /// LAUNCH_ALERT_DISCORD_WEBHOOK_URL is configured
+    pub fn from_env() -> Option<Self> {
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.trim().is_empty());
+        let telegram = match (var("LAUNCH_ALERT_TELEGRAM_BOT_TOKEN"), var("LAUNCH_ALERT_TELEGRAM_CHAT_ID")) {
+            (Some(bot_token), Some(chat_id)) => Some(TelegramTarget { bot_token, chat_id }),
+            (None, None) => None,
+            _ => {
+                warn!("LAUNCH_ALERT_TELEGRAM_BOT_TOKEN and LAUNCH_ALERT_TELEGRAM_CHAT_ID must be set together, Telegram alerts disabled");
+                None
+            }
+        };
+        let discord_webhook_url = var("LAUNCH_ALERT_DISCORD_WEBHOOK_URL");
+        if telegram.is_none() && discord_webhook_url.is_none() {
+            return None;
+        }
+        let max_per_minute = std::env::var("LAUNCH_ALERT_MAX_PER_MINUTE")
+            .unwrap_or_else(|_| "20".to_string())
+            .parse()
+            .ok()
+            .filter(|max| *max > 0)
+            .unwrap_or(20);
+        let platforms = std::env::var("LAUNCH_ALERT_PLATFORMS")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|platform| !platform.is_empty())
+            .map(String::from)
+            .collect();
+
+        Some(Self { telegram, discord_webhook_url, max_per_minute, platforms })
+    }
+}
+
+/// Fixed one-minute windows, simple and good enough to keep chat APIs from throttling us
+struct RateLimit {
+    max_per_window: u32,
+    window_start: Instant,
+    sent: u32,
+    skipped: u64,
+}
+
+impl RateLimit {
+    fn new(max_per_window: u32) -> Self {
+        Self { max_per_window, window_start: Instant::now(), sent: 0, skipped: 0 }
+    }
+
+    fn try_acquire(&mut self) -> bool {
+        if self.window_start.elapsed() >= Duration::from_secs(60) {
+            if self.skipped > 0 {
+                info!("Launch alerts skipped {} launches over the rate limit", self.skipped);
+            }
+            self.window_start = Instant::now();
+            self.sent = 0;
+            self.skipped = 0;
+        }
+        if self.sent < self.max_per_window {
+            self.sent += 1;
+            true
+        } else {
+            self.skipped += 1;
+            false
+        }
+    }
+}
+
+fn links(platform: &str, mint: &str) -> Vec<(&'static str, String)> {
+    let mut links = Vec::new();
+    if platform == "Pumpfun" {
+        links.push(("pump.fun", format!("https://pump.fun/coin/{}", mint)));
+    }
+    links.push(("Solscan", format!("https://solscan.io/token/{}", mint)));
+    links.push(("DEX Screener", format!("https://dexscreener.com/solana/{}", mint)));
+    links
+}
+
+/// Telegram HTML message, user-controlled fields are escaped
+fn telegram_text(launch: &JsonValue) -> String {
+    let escape = |value: &str| value.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
+    let field = |name: &str| escape(launch[name].as_str().unwrap_or("?"));
+    let platform = launch["platform"].as_str().unwrap_or_default();
+    let mint = launch["mint"].as_str().unwrap_or_default();
+    let links = links(platform, mint)
+        .into_iter()
+        .map(|(label, url)| format!("<a href=\"{}\">{}</a>", url, label))
+        .collect::<Vec<_>>()
+        .join(" | ");
+    format!(
+        "🚀 <b>{}</b> ({}) launched on {}\nMint: <code>{}</code>\nCreator: <code>{}</code>\n{}",
+        field("name"),
+        field("symbol"),
+        field("platform"),
+        field("mint"),
+        field("creator"),
+        links
+    )
+}
+
+fn discord_body(launch: &JsonValue) -> JsonValue {
+    let field = |name: &str| launch[name].as_str().unwrap_or("?").to_string();
+    let platform = launch["platform"].as_str().unwrap_or_default();
+    let mint = launch["mint"].as_str().unwrap_or_default();
+    let links = links(platform, mint)
+        .into_iter()
+        .map(|(label, url)| format!("[{}]({})", label, url))
+        .collect::<Vec<_>>()
+        .join(" | ");
+    json::object! {
+        // Names and symbols are chosen by the creator, they must not ping anyone
+        "allowed_mentions" => json::object! { "parse" => json::array![] },
+        "embeds" => json::array![json::object! {
+            "title" => format!("{} ({}) launched on {}", field("name"), field("symbol"), field("platform")),
+            "url" => format!("https://solscan.io/token/{}", mint),
+            "fields" => json::array![
+                json::object! { "name" => "Mint", "value" => format!("`{}`", field("mint")) },
+                json::object! { "name" => "Creator", "value" => format!("`{}`", field("creator")) },
+                json::object! { "name" => "Links", "value" => links },
+            ],
+        }],
+    }
+}
+
+async fn post_alert(client: &reqwest::Client, config: &LaunchAlertConfig, launch: &JsonValue) {
+    if let Some(telegram) = &config.telegram {
+        let body = json::object! {
+            "chat_id" => telegram.chat_id.as_str(),
+            "text" => telegram_text(launch),
+            "parse_mode" => "HTML",
+            "disable_web_page_preview" => true,
+        };
+        let url = format!("https://api.telegram.org/bot{}/sendMessage", telegram.bot_token);
+        match client.post(&url).header("content-type", "application/json").body(body.dump()).send().await {
+            Ok(response) if !response.status().is_success() => warn!("Telegram launch alert got HTTP {}", response.status()),
+            Ok(_) => {}
+            // The error would print the URL, which holds the bot token
+            Err(e) => warn!("Telegram launch alert failed: {}", e.without_url()),
+        }
+    }
+    if let Some(url) = &config.discord_webhook_url {
+        match client.post(url).header("content-type", "application/json").body(discord_body(launch).dump()).send().await {
+            Ok(response) if !response.status().is_success() => warn!("Discord launch alert got HTTP {}", response.status()),
+            Ok(_) => {}
+            Err(e) => warn!("Discord launch alert failed: {}", e.without_url()),
+        }
+    }
+}
+
+/// Posts an alert for every token_launch derived event, within the rate limit
+pub async fn run_launch_alerts(config: LaunchAlertConfig) {
+    info!(
+        "Launch alerts to{}{}, at most {} per minute",
+        if config.telegram.is_some() { " Telegram" } else { "" },
+        if config.discord_webhook_url.is_some() { " Discord" } else { "" },
+        config.max_per_minute
+    );
+    let client = reqwest::Client::new();
+    let mut rate_limit = RateLimit::new(config.max_per_minute);
+    let mut rx = subscribe_derived_events();
+
+    loop {
+        let event = match rx.recv().await {
+            Ok(event) => event,
+            Err(RecvError::Lagged(skipped)) => {
+                warn!("Launch alerts lagged behind the derived events bus, {} events skipped", skipped);
+                continue;
+            }
+            Err(RecvError::Closed) => break,
+        };
+        // The active instance of a failover pair sends the alerts
+        if event.event_type != TOKEN_LAUNCH_EVENT || !is_output_active() {
+            continue;
+        }
+        let Ok(launch) = json::parse(&event.json) else { continue };
+        let platform = launch["platform"].as_str().unwrap_or_default();
+        if !config.platforms.is_empty() && !config.platforms.iter().any(|allowed| allowed == platform) {
+            continue;
+        }
+        if rate_limit.try_acquire() {
+            post_alert(&client, &config, &launch).await;
+        }
+    }
+}
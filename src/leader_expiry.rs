@@ -0,0 +1,169 @@
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+use crate::operational_events::{publish_operational_event, Severity, RPC_FAILURE_EVENT};
+use crate::utils::get_highest_stats_slot;
+
+/// Recovery window of an FEC block before it is declared incomplete
+static FEC_BLOCK_EXPIRY_SECS: Lazy<u64> = Lazy::new(|| {
+    std::env::var("FEC_BLOCK_EXPIRY_SECS")
+        .unwrap_or_else(|_| "20".to_string())
+        .parse()
+        .ok()
+        .filter(|secs| *secs > 0)
+        .unwrap_or(20)
+});
+
+/// Recovery window granted to slots of leaders with poor shred propagation to us
+static POOR_LEADER_FEC_BLOCK_EXPIRY_SECS: Lazy<u64> = Lazy::new(|| {
+    std::env::var("POOR_LEADER_FEC_BLOCK_EXPIRY_SECS")
+        .unwrap_or_else(|_| "45".to_string())
+        .parse()
+        .unwrap_or(45)
+});
+
+/// Completion rate under which a leader counts as poorly propagating
+static POOR_LEADER_COMPLETION_RATE: Lazy<f64> = Lazy::new(|| {
+    std::env::var("POOR_LEADER_COMPLETION_RATE")
+        .unwrap_or_else(|_| "0.9".to_string())
+        .parse()
+        .unwrap_or(0.9)
+});
+
+/// FEC blocks seen from a leader before its completion rate is trusted
+const MIN_LEADER_SAMPLES: u64 = 50;
+
+/// Slots fetched ahead of the decoder on every schedule refresh
+const LEADER_LOOKAHEAD_SLOTS: u64 = 1000;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LeaderCompletionStats {
+    pub complete: u64,
+    pub incomplete: u64,
+}
+
+impl LeaderCompletionStats {
+    pub fn samples(&self) -> u64 {
+        self.complete + self.incomplete
+    }
+
+    pub fn completion_rate(&self) -> f64 {
+        match self.samples() {
+            0 => 1.0,
+            samples => self.complete as f64 / samples as f64,
+        }
+    }
+}
+
+static SLOT_LEADERS: Lazy<DashMap<u64, Pubkey>> = Lazy::new(DashMap::new);
+static LEADER_STATS: Lazy<DashMap<Pubkey, LeaderCompletionStats>> = Lazy::new(DashMap::new);
+
+/// Leader-aware expiry, enabled when a schedule refresher is running
+#[derive(Debug, Clone)]
+pub struct LeaderExpiryConfig {
+    pub rpc_endpoint: String,
+    pub refresh_interval: Duration,
+}
+
+impl LeaderExpiryConfig {
+    /// Build the configuration from env, returns None unless LEADER_AWARE_EXPIRY_ENABLED is set
+    pub fn from_env(rpc_endpoint: &str) -> Option<Self> {
+        let enabled = std::env::var("LEADER_AWARE_EXPIRY_ENABLED")
+            .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+            .unwrap_or(false);
+        if !enabled {
+            return None;
+        }
+        let refresh_interval_secs = std::env::var("LEADER_SCHEDULE_REFRESH_SECS")
+            .unwrap_or_else(|_| "60".to_string())
+            .parse()
+            .unwrap_or(60);
+
+        Some(Self {
+            rpc_endpoint: rpc_endpoint.to_string(),
+            refresh_interval: Duration::from_secs(refresh_interval_secs),
+        })
+    }
+}
+
+/// Counts the outcome of an FEC block against the leader of its slot, when known
+pub fn record_fec_outcome(slot: u64, complete: bool) {
+    let Some(leader) = SLOT_LEADERS.get(&slot).map(|leader| *leader) else { return };
+    let mut stats = LEADER_STATS.entry(leader).or_default();
+    if complete {
+        stats.complete += 1;
+    } else {
+        stats.incomplete += 1;
+    }
+}
+
+pub fn leader_completion_stats(leader: &Pubkey) -> Option<LeaderCompletionStats> {
+    LEADER_STATS.get(leader).map(|stats| *stats)
+}
+
+/// Time an FEC block of this slot may stay incomplete before it is dropped.
+/// Slots of leaders with a poor completion history get the longer window.
+pub fn fec_block_expiry(slot: u64) -> Duration {
+    let base = Duration::from_secs(*FEC_BLOCK_EXPIRY_SECS);
+    let Some(leader) = SLOT_LEADERS.get(&slot).map(|leader| *leader) else { return base };
+    let Some(stats) = leader_completion_stats(&leader) else { return base };
+
+    if stats.samples() >= MIN_LEADER_SAMPLES && stats.completion_rate() < *POOR_LEADER_COMPLETION_RATE {
+        base.max(Duration::from_secs(*POOR_LEADER_FEC_BLOCK_EXPIRY_SECS))
+    } else {
+        base
+    }
+}
+
+/// Keeps the slot → leader map filled ahead of the decoder and drops slots left behind
+pub async fn run_leader_schedule_refresher(config: LeaderExpiryConfig) {
+    let rpc_client = RpcClient::new_with_commitment(config.rpc_endpoint.clone(), CommitmentConfig::confirmed());
+    let mut ticker = tokio::time::interval(config.refresh_interval);
+    info!("Leader-aware FEC block expiry enabled, refreshing the schedule every {:?}", config.refresh_interval);
+
+    loop {
+        ticker.tick().await;
+
+        let start_slot = match get_highest_stats_slot() {
+            0 => match rpc_client.get_slot().await {
+                Ok(slot) => slot,
+                Err(e) => {
+                    warn!("Leader schedule: failed to fetch the current slot: {}", e);
+                    publish_operational_event(
+                        RPC_FAILURE_EVENT,
+                        Severity::Warning,
+                        format!("Leader schedule: failed to fetch the current slot: {}", e),
+                        json::object! { "call" => "getSlot", "endpoint" => config.rpc_endpoint.clone() },
+                    );
+                    continue;
+                }
+            },
+            slot => slot,
+        };
+
+        match rpc_client.get_slot_leaders(start_slot, LEADER_LOOKAHEAD_SLOTS).await {
+            Ok(leaders) => {
+                for (offset, leader) in leaders.into_iter().enumerate() {
+                    SLOT_LEADERS.insert(start_slot + offset as u64, leader);
+                }
+                let oldest_kept = start_slot.saturating_sub(LEADER_LOOKAHEAD_SLOTS);
+                SLOT_LEADERS.retain(|slot, _| *slot >= oldest_kept);
+                debug!("Leader schedule refreshed from slot {}, {} slots known", start_slot, SLOT_LEADERS.len());
+            }
+            Err(e) => {
+                warn!("Leader schedule: failed to fetch slot leaders from {}: {}", start_slot, e);
+                publish_operational_event(
+                    RPC_FAILURE_EVENT,
+                    Severity::Warning,
+                    format!("Leader schedule: failed to fetch slot leaders from {}: {}", start_slot, e),
+                    json::object! { "call" => "getSlotLeaders", "endpoint" => config.rpc_endpoint.clone() },
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,79 @@
+pub mod utils;
+pub mod address_lookup_table_cache;
+pub mod address_lookup_table_decoding;
+pub mod pumpfun_decoding;
+pub mod raydium_decoding;
+pub mod moonit_decoding;
+pub mod raydium_launchlab_decoding;
+pub mod raydium_cpmm_decoding;
+pub mod raydium_clmm_decoding;
+pub mod pumpamm_decoding;
+pub mod meteora_vcurve_decoding;
+pub mod boop_decoding;
+pub mod meteoradyn_decoding;
+pub mod meteora_amm_v2_decoding;
+pub mod meteora_dlmm_decoding;
+pub mod shreds_processing;
+pub mod orca_decoding;
+pub mod drift_decoding;
+pub mod kamino_lend_decoding;
+pub mod memo_decoding;
+pub mod compute_budget_decoding;
+pub mod spl_token_decoding;
+pub mod token_2022_decoding;
+pub mod metaplex_metadata_decoding;
+pub mod raw_instruction_decoding;
+pub mod idl_decoding;
+pub mod mint_index;
+pub mod output_message;
+pub mod derived_events;
+pub mod creation_events;
+pub mod sniper_detection;
+pub mod swap_events;
+pub mod price_ticks;
+pub mod wallet_clusters;
+pub mod grpc_server;
+pub mod auth;
+pub mod subscriber_queue;
+pub mod failover;
+pub mod latency_breakdown;
+pub mod decoder_registry;
+pub mod server_info;
+pub mod batch_output;
+pub mod slot_lag_monitor;
+pub mod decode_anomalies;
+pub mod byte_parser;
+pub mod encoder;
+pub mod resume_buffer;
+pub mod post_processing;
+pub mod coverage;
+pub mod entry_verification;
+pub mod shred_tee;
+pub mod jito_ingest;
+pub mod shred_dedup;
+pub mod pcap_replay;
+pub mod shred_recorder;
+#[cfg(target_os = "linux")]
+pub mod batch_receive;
+#[cfg(target_os = "linux")]
+pub mod uring_receive;
+pub mod sinks;
+pub mod segment_uploader;
+pub mod leader_expiry;
+pub mod fec_recovery;
+pub mod operational_events;
+pub mod write_locks;
+pub mod slot_summary;
+pub mod slot_status;
+pub mod raw_entries;
+pub mod canary;
+pub mod launch_alerts;
+pub mod filter_expression;
+pub mod program_validation;
+pub mod blockhash_age;
+pub mod archive;
+#[cfg(feature = "parquet")]
+pub mod parquet_archive;
+#[cfg(feature = "postgres")]
+pub mod postgres_sink;
+pub mod discriminator_catalog;
@@ -1,25 +1,34 @@
-mod utils;
-mod address_lookup_table_cache;
-mod pumpfun_decoding;
-mod raydium_decoding;
-mod moonit_decoding;
-mod raydium_launchlab_decoding;
-mod raydium_cpmm_decoding;
-mod pumpamm_decoding;
-mod meteora_vcurve_decoding;
-mod boop_decoding;
-mod meteoradyn_decoding;
-mod meteora_amm_v2_decoding;
-mod shreds_processing;
-
-mod orca_decoding;
-mod grpc_server;
-
-use crate::utils::*;
-use crate::shreds_processing::*;
-
-use crate::address_lookup_table_cache::*;
-use crate::grpc_server::*;
+use shredstream_decoder::utils::*;
+use shredstream_decoder::shreds_processing::*;
+
+use shredstream_decoder::address_lookup_table_cache::*;
+use shredstream_decoder::grpc_server::*;
+use shredstream_decoder::failover::*;
+use shredstream_decoder::output_message::{OutputMessage, OutputSender};
+use shredstream_decoder::price_ticks::run_price_tick_flusher;
+use shredstream_decoder::slot_summary::run_slot_summary_publisher;
+use shredstream_decoder::canary::{run_canary, CanaryConfig};
+use shredstream_decoder::launch_alerts::{run_launch_alerts, LaunchAlertConfig};
+use shredstream_decoder::discriminator_catalog::{run_discriminator_catalog_export, DiscriminatorCatalogExportConfig};
+use shredstream_decoder::program_validation::{run_program_validation, ProgramValidationConfig};
+use shredstream_decoder::slot_lag_monitor::*;
+use shredstream_decoder::server_info::log_startup_banner;
+use shredstream_decoder::decoder_registry::{check_discriminator_collisions, check_layout_variants};
+use shredstream_decoder::shred_tee::{ShredTee, ShredTeeConfig};
+use shredstream_decoder::jito_ingest::{run_jito_ingest, IngestSources};
+use shredstream_decoder::shred_dedup::{enable_transaction_dedup, log_transaction_dedup_counters, ShredDeduper};
+use shredstream_decoder::pcap_replay::{ReplayOptions, ReplayReader};
+use shredstream_decoder::shred_recorder::{ShredRecorder, ShredRecorderConfig};
+#[cfg(target_os = "linux")]
+use shredstream_decoder::batch_receive::{spawn_batch_receiver, udp_recv_batch};
+#[cfg(target_os = "linux")]
+use shredstream_decoder::uring_receive::{spawn_uring_receiver, ReceiveBackend};
+use shredstream_decoder::sinks::init_sink_manager;
+use shredstream_decoder::segment_uploader::{start_segment_uploader, SegmentUploadConfig};
+use shredstream_decoder::idl_decoding::init_idl_decoders;
+use shredstream_decoder::leader_expiry::*;
+use shredstream_decoder::fec_recovery::recovery_metrics;
+use shredstream_decoder::operational_events::{feed_stall_timeout, record_shred_received, run_feed_stall_watchdog};
 
 use dashmap::{DashMap, DashSet};
 use dotenv::dotenv;
@@ -28,15 +37,15 @@ use tokio::sync::{broadcast};
 use std::time::Duration;
 use std::sync::Arc;
 use std::time::{Instant};
-use std::fs;
-use std::path::Path;
 // For logging with tracing
 use tracing::{info, warn, error, debug};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, fmt, EnvFilter};
 ////////////////////////////////////////////////////////////////////////////////
+#[cfg(not(target_env = "msvc"))]
 use jemallocator::Jemalloc;
  
 
+#[cfg(not(target_env = "msvc"))]
 #[global_allocator]
 static GLOBAL: Jemalloc = Jemalloc;
 
@@ -58,6 +67,15 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     
     dotenv().ok();
 
+    // Runtime decoders from Anchor IDL files, loaded before the banner lists the features
+    if let Err(e) = init_idl_decoders() {
+        error!("Failed to load IDL decoders: {}", e);
+        return Err(e.into());
+    }
+    log_startup_banner();
+    check_discriminator_collisions();
+    check_layout_variants();
+
     // Initialize Address Lookup Table Cache
     let rpc_endpoint = std::env::var("RPC_ENDPOINT")
         .unwrap_or_else(|_| "https://mainnet.helius-rpc.com/?api-key=50b3223c-e0fb-4eaa-8923-b11042258828".to_string());
@@ -82,18 +100,100 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .expect("Failed to build Rayon thread pool");
 
     // DashMap structure to store FecBlocks
-    let fec_blocks = Arc::new(DashMap::<(u64, u32), FecBlock>::new());
+    let fec_blocks = Arc::new(DashMap::<FecBlockKey, FecBlock>::new());
     let processed_blocks = Arc::new(DashSet::new());
     
-    // Socket with buffer to receive shreds
-    let udp_address = env("UDP_BUFFER_SOCKET");
-    let socket: Arc<tokio::net::UdpSocket> = Arc::new(create_udp_socket_with_buffer(&udp_address, 256 * 1024).await);
+    // Shreds come from UDP packets and/or the proxy gRPC API as listed in INGEST_SOURCE,
+    // or from a capture or recorded shred logs with --replay-pcap and --replay-shreds
+    let ingest_sources = IngestSources::from_env()?;
+    let replay = ReplayOptions::from_args(std::env::args().skip(1))?;
+
+    // Optional re-broadcast of the raw shreds to other hosts
+    let shred_tee = match ShredTeeConfig::from_env().map(ShredTee::bind).transpose() {
+        Ok(tee) => tee.map(Arc::new),
+        Err(e) => {
+            error!("Failed to bind the shred tee socket: {}", e);
+            return Err(e.into());
+        }
+    };
+
+    // Upload of closed sink segments, started first so that no closed segment is missed
+    if let Some(upload_config) = SegmentUploadConfig::from_env() {
+        if let Err(e) = start_segment_uploader(upload_config) {
+            error!("Failed to start the segment uploader: {}", e);
+            return Err(e.into());
+        }
+    }
+
+    // Log of every shred received for later replay, off while replaying one
+    let shred_recorder = match ShredRecorderConfig::from_env().filter(|_| replay.is_none()) {
+        Some(config) => Some(Arc::new(ShredRecorder::start(config)?)),
+        None => None,
+    };
+
+    // Sinks and the routing table deciding which of them receive each message
+    if let Err(e) = init_sink_manager() {
+        error!("Failed to initialize sink routing: {}", e);
+        return Err(e.into());
+    }
 
     // Broadcast channel for transactions
-    let (broadcast_tx, _) = broadcast::channel::<(String, u64)>(1000);
+    let (broadcast_tx, _) = broadcast::channel::<Arc<OutputMessage>>(1000);
 
 
 
+    // Active/standby pairing with a peer instance
+    if let Some(failover_config) = FailoverConfig::from_env() {
+        tokio::spawn(async move {
+            run_failover(failover_config).await;
+        });
+    }
+
+    // Alert when the decoder falls behind the confirmed tip
+    if let Some(slot_lag_config) = SlotLagConfig::from_env(&rpc_endpoint) {
+        tokio::spawn(async move {
+            run_slot_lag_monitor(slot_lag_config).await;
+        });
+    }
+
+    // Catch program ids left stale by a protocol redeployment
+    if let Some(program_validation_config) = ProgramValidationConfig::from_env(&rpc_endpoint) {
+        tokio::spawn(async move {
+            run_program_validation(program_validation_config).await;
+        });
+    }
+
+    // Longer FEC recovery window for slots of poorly propagating leaders
+    if let Some(leader_expiry_config) = LeaderExpiryConfig::from_env(&rpc_endpoint) {
+        tokio::spawn(async move {
+            run_leader_schedule_refresher(leader_expiry_config).await;
+        });
+    }
+
+    // Report on the event stream when the proxy stops sending shreds
+    if let Some(timeout) = feed_stall_timeout() {
+        tokio::spawn(async move {
+            run_feed_stall_watchdog(timeout).await;
+        });
+    }
+
+    // Flush one-second price ticks of pools that stopped trading
+    tokio::spawn(async move {
+        run_price_tick_flusher().await;
+    });
+
+    // Per-slot summaries with the write-lock hotspots
+    tokio::spawn(async move {
+        run_slot_summary_publisher().await;
+    });
+
+    // Observed-discriminator catalog, a roadmap of the decoders worth adding
+    if let Some(catalog_config) = DiscriminatorCatalogExportConfig::from_env() {
+        tokio::spawn(async move {
+            run_discriminator_catalog_export(catalog_config).await;
+        });
+    }
+
     // gRPC Server
     let broadcast_tx_clone = broadcast_tx.clone();
     tokio::spawn(async move {
@@ -104,84 +204,115 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     });
 
-    // Task for receiving shreds from Proxy
-    let socket_task = {
-        let fec_blocks_clone = Arc::clone(&fec_blocks);
-        let processed_blocks_gc: Arc<DashSet<(u64, u32)>> = Arc::clone(&processed_blocks);
-        let broadcast_tx_clone = broadcast_tx.clone();
+    // End-to-end self-check subscribing to our own gRPC stream
+    if let Some(canary_config) = CanaryConfig::from_env(&env("GRPC_SERVER_ENDPOINT")) {
+        tokio::spawn(async move {
+            run_canary(canary_config).await;
+        });
+    }
 
+    // Token launch alerts to Telegram and Discord
+    if let Some(alert_config) = LaunchAlertConfig::from_env() {
         tokio::spawn(async move {
-            info!("Shredstream Decoder started ! Starting to listen for shred packets...");
-            let mut buf = [0u8; 1232];
-
-            loop {
-                debug!("socket_task: Top of recv_from loop, about to call socket.recv_from().await");
-                match socket.recv_from(&mut buf).await {
-                    Ok((size, _)) => {
-                        if size > buf.len() {
-                            warn!("Received data size {} exceeds buffer length {}", size, buf.len());
+            run_launch_alerts(alert_config).await;
+        });
+    }
+
+    // Task for receiving shreds from Proxy
+    let mut shred_deduper = None;
+    let socket_task = if let Some(replay) = replay {
+        let intake = ShredIntake {
+            fec_blocks: Arc::clone(&fec_blocks),
+            processed_blocks: Arc::clone(&processed_blocks),
+            broadcast_tx: broadcast_tx.clone(),
+            shred_tee: shred_tee.clone(),
+            deduper: None,
+            recorder: None,
+        };
+        tokio::spawn(replay_shreds(replay, intake))
+    } else {
+        // Transactions coming through several sources are emitted once
+        if ingest_sources.is_redundant() {
+            enable_transaction_dedup();
+        }
+        let mut receivers = Vec::new();
+        for endpoint in &ingest_sources.jito_grpc {
+            receivers.push(tokio::spawn(run_jito_ingest(endpoint.clone(), broadcast_tx.clone())));
+        }
+
+        // One socket per UDP_BUFFER_SOCKET address, redundant feeds are deduplicated
+        let udp_addresses: Vec<String> = if ingest_sources.udp {
+            env("UDP_BUFFER_SOCKET")
+                .split(',')
+                .map(str::trim)
+                .filter(|address| !address.is_empty())
+                .map(String::from)
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let deduper = ShredDeduper::is_enabled(udp_addresses.len()).then(|| Arc::new(ShredDeduper::default()));
+        shred_deduper = deduper.clone();
+
+        // Several SO_REUSEPORT sockets per address spread a single feed across receive tasks
+        let sockets_per_address = udp_receive_sockets();
+        let intake = ShredIntake {
+            fec_blocks: Arc::clone(&fec_blocks),
+            processed_blocks: Arc::clone(&processed_blocks),
+            broadcast_tx: broadcast_tx.clone(),
+            shred_tee: shred_tee.clone(),
+            deduper: deduper.clone(),
+            recorder: shred_recorder.clone(),
+        };
+        // Batched receive through recvmmsg or io_uring on Linux unless UDP_RECV_BATCH is 1
+        #[cfg(target_os = "linux")]
+        let (recv_batch, recv_backend) = (udp_recv_batch(), ReceiveBackend::from_env());
+        for udp_address in &udp_addresses {
+            for socket in create_udp_sockets_with_buffer(udp_address, 256 * 1024, sockets_per_address).await {
+                #[cfg(target_os = "linux")]
+                let socket = if recv_backend == ReceiveBackend::IoUring {
+                    match spawn_uring_receiver(socket, recv_batch) {
+                        Ok(packets_rx) => {
+                            receivers.push(tokio::spawn(receive_shred_batches(packets_rx, intake.clone())));
                             continue;
                         }
-                        debug!("socket_task: Successfully received {} bytes.", size);
-                        let shred_data = Box::from(&buf[..size]);
-
-                        let fec_blocks_clone_inner = Arc::clone(&fec_blocks_clone);
-                        let processed_blocks_clone_inner = Arc::clone(&processed_blocks_gc);
-                        let broadcast_tx_clone_inner = broadcast_tx_clone.clone();
-
-                        if let Err(e) = async {
-                            if let Err(e) = collect_shred(
-                                &shred_data,
-                                &fec_blocks_clone_inner,
-                                &processed_blocks_clone_inner,
-                                broadcast_tx_clone_inner,
-                            ).await
-                            {
-                                error!("CollectShredError: {:?}", e);
-                            }
-
-                            Ok::<(), ()>(())
-                        }
-                        .await
-                        {
-                            error!("Task in socket_task failed: {:?}", e);
+                        Err((socket, e)) => {
+                            warn!("io_uring receive unavailable on {}, using the tokio socket: {}", udp_address, e);
+                            socket
                         }
                     }
-                    Err(e) => {
-                        error!("Error receiving shred: {:?}", e);
-                    }
+                } else {
+                    socket
+                };
+                #[cfg(target_os = "linux")]
+                if recv_batch > 1 {
+                    let packets_rx = spawn_batch_receiver(socket, recv_batch);
+                    receivers.push(tokio::spawn(receive_shred_batches(packets_rx, intake.clone())));
+                    continue;
+                }
+                receivers.push(tokio::spawn(receive_shreds(socket, intake.clone())));
+            }
+        }
+        info!(
+            "Shredstream Decoder started ! Listening for shred packets on {:?} and entries from {:?} with {} ingest tasks",
+            udp_addresses,
+            ingest_sources.jito_grpc,
+            receivers.len()
+        );
+
+        tokio::spawn(async move {
+            for receiver in receivers {
+                if let Err(e) = receiver.await {
+                    error!("Shred ingest task failed: {:?}", e);
                 }
             }
         })
     };
 
     // Garbage collector eraser
-    let fec_blocks_gc: Arc<DashMap<(u64, u32), FecBlock>> = Arc::clone(&fec_blocks);
-    let processed_blocks_gc: Arc<DashSet<(u64, u32)>> = Arc::clone(&processed_blocks);
+    let fec_blocks_gc: Arc<DashMap<FecBlockKey, FecBlock>> = Arc::clone(&fec_blocks);
+    let processed_blocks_gc: Arc<DashSet<FecBlockKey>> = Arc::clone(&processed_blocks);
     
-    // Task for periodically deleting the slot_stats.json file
-    tokio::spawn(async move {
-        loop {
-            tokio::time::sleep(Duration::from_secs(30)).await;
-            
-            let file_path = "slot_stats.json";
-            
-            if Path::new(file_path).exists() {
-                match fs::File::create(file_path) {
-                    Ok(_) => {
-                        info!("The slot_stats.json file was successfully cleaned.");
-                        if let Err(e) = fs::write(file_path, "[]") {
-                            error!("Error initializing the slot_stats.json file: {}", e);
-                        }
-                    },
-                    Err(e) => {
-                        error!("Error cleaning the slot_stats.json file: {}", e);
-                    }
-                }
-            }
-        }
-    });
-
     // Task for periodically cleaning the fec_blocks and processed_blocks
     tokio::spawn(async move {
         loop {
@@ -193,10 +324,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             let now = Instant::now();
     
             fec_blocks_gc.retain(|_, fec_block| {
-                now.duration_since(fec_block.collection_start.expect("COLLECTION_START not initialized")) < Duration::from_secs(20)
+                let retained = now.duration_since(fec_block.collection_start.expect("COLLECTION_START not initialized")) < fec_block_expiry(fec_block.slot);
+                if !retained {
+                    record_fec_outcome(fec_block.slot, false);
+                }
+                retained
             });
     
-            processed_blocks_gc.retain(|&(_, timestamp)| {
+            processed_blocks_gc.retain(|&(_, timestamp, _)| {
                 let block_time = Instant::now() - Duration::from_secs(20);
                 u64::from(timestamp) > block_time.elapsed().as_secs()
             });
@@ -209,6 +344,31 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 removed_fec,
                 removed_processed
             );
+
+            let recovery = recovery_metrics();
+            info!(
+                "FEC recovery: in_flight = {}/{}, queued = {}, completed = {}, rejected_queue_full = {}, rejected_slot_budget = {} (budget {} per slot)",
+                recovery.in_flight,
+                recovery.queue_limit,
+                recovery.queued_total,
+                recovery.completed_total,
+                recovery.rejected_queue_full,
+                recovery.rejected_slot_budget,
+                recovery.slot_budget
+            );
+
+            if let Some(tee) = &shred_tee {
+                let (forwarded, dropped) = tee.counters();
+                info!("Shred relay: forwarded = {}, dropped = {}", forwarded, dropped);
+            }
+            if let Some(deduper) = &shred_deduper {
+                deduper.log_counters();
+            }
+            log_transaction_dedup_counters();
+            if let Some(recorder) = &shred_recorder {
+                let (recorded, dropped) = recorder.counters();
+                info!("Shred recorder: recorded = {}, dropped = {}", recorded, dropped);
+            }
         }
     });
 
@@ -222,4 +382,103 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     )?;
         
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Where received packets go: the tee and the dedup, then shred collection
+#[derive(Clone)]
+struct ShredIntake {
+    fec_blocks: Arc<DashMap<FecBlockKey, FecBlock>>,
+    processed_blocks: Arc<DashSet<FecBlockKey>>,
+    broadcast_tx: OutputSender,
+    shred_tee: Option<Arc<ShredTee>>,
+    deduper: Option<Arc<ShredDeduper>>,
+    recorder: Option<Arc<ShredRecorder>>,
+}
+
+impl ShredIntake {
+    async fn handle(&self, packet: &[u8]) {
+        record_shred_received();
+        if let Some(tee) = &self.shred_tee {
+            tee.forward(packet);
+        }
+        if let Some(recorder) = &self.recorder {
+            recorder.record(packet);
+        }
+        if self.deduper.as_ref().is_some_and(|deduper| !deduper.first_seen(packet)) {
+            return;
+        }
+        if let Err(e) = collect_shred(packet, &self.fec_blocks, &self.processed_blocks, self.broadcast_tx.clone()).await {
+            error!("CollectShredError: {:?}", e);
+        }
+    }
+}
+
+/// Receive loop of one UDP socket, one recv_from per packet
+async fn receive_shreds(socket: tokio::net::UdpSocket, intake: ShredIntake) {
+    let mut buf = [0u8; 1232];
+
+    loop {
+        debug!("socket_task: Top of recv_from loop, about to call socket.recv_from().await");
+        match socket.recv_from(&mut buf).await {
+            Ok((size, _)) => {
+                if size > buf.len() {
+                    warn!("Received data size {} exceeds buffer length {}", size, buf.len());
+                    continue;
+                }
+                debug!("socket_task: Successfully received {} bytes.", size);
+                intake.handle(&buf[..size]).await;
+            }
+            Err(e) => {
+                error!("Error receiving shred: {:?}", e);
+            }
+        }
+    }
+}
+
+/// Consumer of the packet batches of one recvmmsg or io_uring receiver
+#[cfg(target_os = "linux")]
+async fn receive_shred_batches(mut packets_rx: tokio::sync::mpsc::Receiver<Vec<Box<[u8]>>>, intake: ShredIntake) {
+    while let Some(packets) = packets_rx.recv().await {
+        for packet in &packets {
+            intake.handle(packet).await;
+        }
+    }
+}
+
+/// Pushes the shreds of a capture or of recorded logs through shred collection, spaced as
+/// received divided by the replay speed. Never returns: once the replay is exhausted the task
+/// parks so that the gRPC server and the sinks keep serving until the process is stopped.
+async fn replay_shreds(replay: ReplayOptions, intake: ShredIntake) {
+    let mut reader = match ReplayReader::open(&replay) {
+        Ok(reader) => reader,
+        Err(e) => {
+            error!("Failed to open the replay input: {}", e);
+            return;
+        }
+    };
+    info!("Replaying shreds from {:?} at speed {}", replay.input, replay.speed);
+
+    let started = tokio::time::Instant::now();
+    let mut first_timestamp = None;
+    let mut replayed = 0u64;
+    loop {
+        let (timestamp_nanos, shred) = match reader.next_shred() {
+            Ok(Some(shred)) => shred,
+            Ok(None) => break,
+            Err(e) => {
+                error!("Replay stopped: {}", e);
+                break;
+            }
+        };
+        if replay.speed > 0.0 {
+            let first = *first_timestamp.get_or_insert(timestamp_nanos);
+            let offset = timestamp_nanos.saturating_sub(first) as f64 / replay.speed;
+            tokio::time::sleep_until(started + Duration::from_nanos(offset as u64)).await;
+        }
+        intake.handle(&shred).await;
+        replayed += 1;
+    }
+    info!("Replay finished: {} shreds replayed in {:?}, still serving until stopped", replayed, started.elapsed());
+    // Ending would end main, the decodes still in flight and their subscribers need the server
+    std::future::pending::<()>().await;
+}
@@ -0,0 +1,19 @@
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::pubkey;
+
+pub const MEMO_PROGRAM_ID: Pubkey = pubkey!("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+pub const MEMO_V1_PROGRAM_ID: Pubkey = pubkey!("Memo1UhkJRfHyvLMcVucJwxXeuD728EAVzDmagWfv3Y");
+
+pub fn is_memo_program(program_id: &Pubkey) -> bool {
+    program_id == &MEMO_PROGRAM_ID || program_id == &MEMO_V1_PROGRAM_ID
+}
+
+/// The memo program takes the raw UTF-8 text as instruction data, without discriminator.
+/// Invalid sequences are replaced rather than dropping the memo, the on-chain program
+/// would reject them anyway so this only matters for failing transactions.
+pub fn decode_memo(data: &[u8]) -> Option<String> {
+    if data.is_empty() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(data).into_owned())
+}
@@ -0,0 +1,238 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use json::{object, JsonValue};
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use crate::decode_anomalies::check_parse_residue;
+use crate::utils::{create_standardized_instruction, ValueSanity};
+
+pub const METAPLEX_TOKEN_METADATA_PROGRAM_ID: Pubkey = pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
+pub const METAPLEX_CREATE_METADATA_ACCOUNT_V3_ACCOUNTS: &[&str] = &[
+    "metadata", "mint", "mintAuthority", "payer", "updateAuthority", "systemProgram", "rent",
+];
+pub const METAPLEX_CREATE_V1_ACCOUNTS: &[&str] = &[
+    "metadata", "masterEdition", "mint", "authority", "payer", "updateAuthority", "systemProgram",
+    "sysvarInstructions", "splTokenProgram",
+];
+
+/// Token Metadata is a Shank program, instructions are selected by their first byte.
+/// Create is versioned by the first byte of its args.
+pub const METAPLEX_CREATE_METADATA_ACCOUNT_V3_DISCRIMINATOR: [u8; 1] = [33];
+pub const METAPLEX_CREATE_V1_DISCRIMINATOR: [u8; 2] = [42, 0];
+
+/// Upper bound of seller_fee_basis_points, a 100% royalty
+const MAX_SELLER_FEE_BASIS_POINTS: u16 = 10_000;
+
+pub enum MetaplexMetadataInstructionType {
+    CreateMetadataAccountV3,
+    CreateV1,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone)]
+pub struct MetadataCreator {
+    pub address: Pubkey,
+    pub verified: bool,
+    pub share: u8,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone)]
+pub struct MetadataCollection {
+    pub verified: bool,
+    pub key: Pubkey,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy)]
+pub enum MetadataUseMethod {
+    Burn,
+    Multiple,
+    Single,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone)]
+pub struct MetadataUses {
+    pub use_method: MetadataUseMethod,
+    pub remaining: u64,
+    pub total: u64,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone)]
+pub enum MetadataCollectionDetails {
+    V1 { size: u64 },
+    V2 { padding: [u8; 8] },
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy)]
+pub enum MetadataTokenStandard {
+    NonFungible,
+    FungibleAsset,
+    Fungible,
+    NonFungibleEdition,
+    ProgrammableNonFungible,
+    ProgrammableNonFungibleEdition,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy)]
+pub enum MetadataPrintSupply {
+    Zero,
+    Limited(u64),
+    Unlimited,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone)]
+pub struct MetadataDataV2 {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub seller_fee_basis_points: u16,
+    pub creators: Option<Vec<MetadataCreator>>,
+    pub collection: Option<MetadataCollection>,
+    pub uses: Option<MetadataUses>,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone)]
+pub struct CreateMetadataAccountArgsV3 {
+    pub data: MetadataDataV2,
+    pub is_mutable: bool,
+    pub collection_details: Option<MetadataCollectionDetails>,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone)]
+pub struct MetadataAssetData {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub seller_fee_basis_points: u16,
+    pub creators: Option<Vec<MetadataCreator>>,
+    pub primary_sale_happened: bool,
+    pub is_mutable: bool,
+    pub token_standard: MetadataTokenStandard,
+    pub collection: Option<MetadataCollection>,
+    pub uses: Option<MetadataUses>,
+    pub collection_details: Option<MetadataCollectionDetails>,
+    pub rule_set: Option<Pubkey>,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone)]
+pub struct CreateV1Args {
+    pub asset_data: MetadataAssetData,
+    pub decimals: Option<u8>,
+    pub print_supply: Option<MetadataPrintSupply>,
+}
+
+pub fn get_metaplex_metadata_instruction_type(data: &[u8]) -> Option<MetaplexMetadataInstructionType> {
+    match data.first() {
+        Some(33) => Some(MetaplexMetadataInstructionType::CreateMetadataAccountV3),
+        Some(42) if data.get(1) == Some(&0) => Some(MetaplexMetadataInstructionType::CreateV1),
+        _ => None,
+    }
+}
+
+fn creators_json(creators: &Option<Vec<MetadataCreator>>) -> JsonValue {
+    match creators {
+        Some(creators) => creators
+            .iter()
+            .map(|creator| object! {
+                "address" => creator.address.to_string(),
+                "verified" => creator.verified,
+                "share" => creator.share,
+            })
+            .collect::<Vec<_>>()
+            .into(),
+        None => JsonValue::Null,
+    }
+}
+
+/// Royalty and creator shares, which must add up to 100 when creators are listed
+fn check_royalties(sanity: &mut ValueSanity, seller_fee_basis_points: u16, creators: &Option<Vec<MetadataCreator>>) {
+    sanity.check_range_u128("seller_fee_basis_points", seller_fee_basis_points as u128, 0, MAX_SELLER_FEE_BASIS_POINTS as u128);
+    if let Some(creators) = creators.as_ref().filter(|creators| !creators.is_empty()) {
+        let shares: u128 = creators.iter().map(|creator| creator.share as u128).sum();
+        sanity.check_range_u128("creator shares", shares, 100, 100);
+    }
+}
+
+/// Accounts: metadata=0, mint=1, mint_authority=2, payer=3, update_authority=4, system_program=5, rent (optional)
+pub fn deserialize_metaplex_create_metadata_account_v3_instruction(
+    data: &[u8],
+    accounts_indices: &[u8],
+    account_keys: &[Pubkey],
+    is_signer: &[bool],
+    is_writable: &[bool],
+) -> Result<JsonValue, String> {
+    let mut data_ref = &data[1..];
+    let args = CreateMetadataAccountArgsV3::deserialize(&mut data_ref)
+        .map_err(|e| format!("Failed to deserialize CreateMetadataAccountV3 args: {:?}", e))?;
+
+    let mut parsed_data = object! {
+        "name" => args.data.name.as_str(),
+        "symbol" => args.data.symbol.as_str(),
+        "uri" => args.data.uri.as_str(),
+        "seller_fee_basis_points" => args.data.seller_fee_basis_points,
+        "creators" => creators_json(&args.data.creators),
+        "collection" => args.data.collection.as_ref().map(|collection| collection.key.to_string()),
+        "is_mutable" => args.is_mutable,
+    };
+
+    let mut sanity = ValueSanity::new();
+    check_royalties(&mut sanity, args.data.seller_fee_basis_points, &args.data.creators);
+    sanity.apply(&mut parsed_data);
+
+    check_parse_residue("MetaplexMetadata CreateMetadataAccountV3", data_ref, &mut parsed_data);
+
+    Ok(create_standardized_instruction(
+        &METAPLEX_TOKEN_METADATA_PROGRAM_ID,
+        "CreateMetadataAccountV3",
+        "MetaplexMetadata",
+        data,
+        accounts_indices,
+        account_keys,
+        is_signer,
+        is_writable,
+        parsed_data
+    ))
+}
+
+/// Accounts: metadata=0, master_edition=1, mint=2, authority=3, payer=4, update_authority=5,
+/// system_program=6, sysvar_instructions=7, spl_token_program=8
+pub fn deserialize_metaplex_create_v1_instruction(
+    data: &[u8],
+    accounts_indices: &[u8],
+    account_keys: &[Pubkey],
+    is_signer: &[bool],
+    is_writable: &[bool],
+) -> Result<JsonValue, String> {
+    let mut data_ref = &data[2..];
+    let args = CreateV1Args::deserialize(&mut data_ref)
+        .map_err(|e| format!("Failed to deserialize CreateV1 args: {:?}", e))?;
+    let asset = &args.asset_data;
+
+    let mut parsed_data = object! {
+        "name" => asset.name.as_str(),
+        "symbol" => asset.symbol.as_str(),
+        "uri" => asset.uri.as_str(),
+        "seller_fee_basis_points" => asset.seller_fee_basis_points,
+        "creators" => creators_json(&asset.creators),
+        "collection" => asset.collection.as_ref().map(|collection| collection.key.to_string()),
+        "is_mutable" => asset.is_mutable,
+        "token_standard" => format!("{:?}", asset.token_standard),
+        "decimals" => args.decimals,
+    };
+
+    let mut sanity = ValueSanity::new();
+    check_royalties(&mut sanity, asset.seller_fee_basis_points, &asset.creators);
+    sanity.apply(&mut parsed_data);
+
+    check_parse_residue("MetaplexMetadata CreateV1", data_ref, &mut parsed_data);
+
+    Ok(create_standardized_instruction(
+        &METAPLEX_TOKEN_METADATA_PROGRAM_ID,
+        "CreateV1",
+        "MetaplexMetadata",
+        data,
+        accounts_indices,
+        account_keys,
+        is_signer,
+        is_writable,
+        parsed_data
+    ))
+}
@@ -1,10 +1,36 @@
+use borsh::{BorshDeserialize, BorshSerialize};
 use json::{object, JsonValue};
 use solana_sdk::pubkey::Pubkey;
 use solana_sdk::pubkey;
-use crate::utils::create_standardized_instruction;
+use crate::decode_anomalies::check_parse_residue;
+use crate::utils::{create_standardized_instruction, ValueSanity};
 
 pub const METEORA_AMM_V2_PROGRAM_ID: Pubkey = pubkey!("cpamdpZCGKUy5JxQXB4dcpGPiikHawvSWAd6mEn1sGG");
 
+pub const METEORA_AMM_V2_CREATE_POOL1_ACCOUNTS: &[&str] = &[
+    "creator", "positionNftMint", "positionNftAccount", "payer", "config", "poolAuthority", "pool",
+    "position", "tokenAMint", "tokenBMint", "tokenAVault", "tokenBVault", "payerTokenA",
+    "payerTokenB", "tokenAProgram", "tokenBProgram", "token2022Program", "systemProgram",
+    "eventAuthority", "program",
+];
+pub const METEORA_AMM_V2_CREATE_POOL2_ACCOUNTS: &[&str] = &[
+    "creator", "positionNftMint", "positionNftAccount", "payer", "poolCreatorAuthority", "config",
+    "poolAuthority", "pool", "position", "tokenAMint", "tokenBMint", "tokenAVault", "tokenBVault",
+    "payerTokenA", "payerTokenB", "tokenAProgram", "tokenBProgram", "token2022Program",
+    "systemProgram", "eventAuthority", "program",
+];
+pub const METEORA_AMM_V2_CREATE_POOL3_ACCOUNTS: &[&str] = &[
+    "creator", "positionNftMint", "positionNftAccount", "payer", "poolAuthority", "pool",
+    "position", "tokenAMint", "tokenBMint", "tokenAVault", "tokenBVault", "payerTokenA",
+    "payerTokenB", "tokenAProgram", "tokenBProgram", "token2022Program", "systemProgram",
+    "eventAuthority", "program",
+];
+pub const METEORA_AMM_V2_SWAP_ACCOUNTS: &[&str] = &[
+    "poolAuthority", "pool", "inputTokenAccount", "outputTokenAccount", "tokenAVault",
+    "tokenBVault", "tokenAMint", "tokenBMint", "payer", "tokenAProgram", "tokenBProgram",
+    "referralTokenAccount", "eventAuthority", "program",
+];
+
 pub const SYSTEM_PROGRAM_ID: Pubkey = pubkey!("11111111111111111111111111111111");
 pub const TOKEN_PROGRAM_ID: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
 pub const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey = pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
@@ -18,29 +44,26 @@ pub const CREATE_POOL_3_DISCRIMINATOR: [u8; 8] = [20, 161, 241, 24, 189, 221, 18
 // Swap discriminator
 pub const SWAP_DISCRIMINATOR: [u8; 8] = [248, 198, 158, 145, 225, 117, 135, 200];
 
-// Add Liquidity discriminators
-pub const ADD_LIQUIDITY_1_DISCRIMINATOR: [u8; 8] = [181, 157, 89, 67, 143, 182, 52, 72];
-pub const ADD_LIQUIDITY_2_DISCRIMINATOR: [u8; 8] = [95, 180, 10, 172, 84, 174, 232, 40];
-pub const ADD_LIQUIDITY_3_DISCRIMINATOR: [u8; 8] = [149, 82, 72, 197, 253, 252, 68, 15];
-pub const ADD_LIQUIDITY_4_DISCRIMINATOR: [u8; 8] = [20, 161, 241, 24, 189, 221, 180, 2];
+// Add Liquidity discriminator
+pub const ADD_LIQUIDITY_DISCRIMINATOR: [u8; 8] = [181, 157, 89, 67, 143, 182, 52, 72];
 
 // Remove Liquidity discriminators
 pub const REMOVE_LIQUIDITY_1_DISCRIMINATOR: [u8; 8] = [80, 85, 209, 72, 24, 206, 177, 108];
 pub const REMOVE_LIQUIDITY_2_DISCRIMINATOR: [u8; 8] = [10, 51, 61, 35, 112, 105, 24, 85];
 
+/// Sqrt price bounds (Q64.64) accepted by the program
+pub const METEORA_AMM_V2_MIN_SQRT_PRICE: u128 = 4295048016;
+pub const METEORA_AMM_V2_MAX_SQRT_PRICE: u128 = 79226673521066979257578248091;
+
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub enum MeteoraAmmV2InstructionType {
     CreatePool1,
     CreatePool2,
     CreatePool3,
     Swap,
-    AddLiquidity1,
-    AddLiquidity2,
-    AddLiquidity3,
-    AddLiquidity4,
+    AddLiquidity,
     RemoveLiquidity1,
     RemoveLiquidity2,
-    Unknown,
 }
 
 pub fn get_meteora_amm_v2_instruction_type(data: &[u8]) -> Option<MeteoraAmmV2InstructionType> {
@@ -54,16 +77,91 @@ pub fn get_meteora_amm_v2_instruction_type(data: &[u8]) -> Option<MeteoraAmmV2In
         d if d == CREATE_POOL_2_DISCRIMINATOR => Some(MeteoraAmmV2InstructionType::CreatePool2),
         d if d == CREATE_POOL_3_DISCRIMINATOR => Some(MeteoraAmmV2InstructionType::CreatePool3),
         d if d == SWAP_DISCRIMINATOR => Some(MeteoraAmmV2InstructionType::Swap),
-        d if d == ADD_LIQUIDITY_1_DISCRIMINATOR => Some(MeteoraAmmV2InstructionType::AddLiquidity1),
-        d if d == ADD_LIQUIDITY_2_DISCRIMINATOR => Some(MeteoraAmmV2InstructionType::AddLiquidity2),
-        d if d == ADD_LIQUIDITY_3_DISCRIMINATOR => Some(MeteoraAmmV2InstructionType::AddLiquidity3),
-        d if d == ADD_LIQUIDITY_4_DISCRIMINATOR => Some(MeteoraAmmV2InstructionType::AddLiquidity4),
+        d if d == ADD_LIQUIDITY_DISCRIMINATOR => Some(MeteoraAmmV2InstructionType::AddLiquidity),
         d if d == REMOVE_LIQUIDITY_1_DISCRIMINATOR => Some(MeteoraAmmV2InstructionType::RemoveLiquidity1),
         d if d == REMOVE_LIQUIDITY_2_DISCRIMINATOR => Some(MeteoraAmmV2InstructionType::RemoveLiquidity2),
-        _ => Some(MeteoraAmmV2InstructionType::Unknown),
+        _ => None,
     }
 }
 
+/// initialize_pool (CreatePool1) args
+#[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
+pub struct DammInitializePoolParameters {
+    pub liquidity: u128,
+    pub sqrt_price: u128,
+    pub activation_point: Option<u64>,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
+pub struct DammBaseFeeParameters {
+    pub cliff_fee_numerator: u64,
+    pub number_of_period: u16,
+    pub period_frequency: u64,
+    pub reduction_factor: u64,
+    pub fee_scheduler_mode: u8,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
+pub struct DammDynamicFeeParameters {
+    pub bin_step: u16,
+    pub bin_step_u128: u128,
+    pub filter_period: u16,
+    pub decay_period: u16,
+    pub reduction_factor: u16,
+    pub max_volatility_accumulator: u32,
+    pub variable_fee_control: u32,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
+pub struct DammPoolFeeParameters {
+    pub base_fee: DammBaseFeeParameters,
+    pub padding: [u8; 3],
+    pub dynamic_fee: Option<DammDynamicFeeParameters>,
+}
+
+/// initialize_pool_with_dynamic_config (CreatePool2) and initialize_customizable_pool (CreatePool3) args
+#[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
+pub struct DammInitializeCustomizablePoolParameters {
+    pub pool_fees: DammPoolFeeParameters,
+    pub sqrt_min_price: u128,
+    pub sqrt_max_price: u128,
+    pub has_alpha_vault: bool,
+    pub liquidity: u128,
+    pub sqrt_price: u128,
+    pub activation_type: u8,
+    pub collect_fee_mode: u8,
+    pub activation_point: Option<u64>,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
+pub struct DammSwapParameters {
+    pub amount_in: u64,
+    pub minimum_amount_out: u64,
+}
+
+/// add_liquidity and remove_liquidity args
+#[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
+pub struct DammLiquidityParameters {
+    pub liquidity_delta: u128,
+    pub token_a_amount_threshold: u64,
+    pub token_b_amount_threshold: u64,
+}
+
+/// remove_all_liquidity args
+#[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
+pub struct DammRemoveAllLiquidityParameters {
+    pub token_a_amount_threshold: u64,
+    pub token_b_amount_threshold: u64,
+}
+
+fn check_sqrt_price(sanity: &mut ValueSanity, field: &str, sqrt_price: u128) {
+    sanity.check_range_u128(field, sqrt_price, METEORA_AMM_V2_MIN_SQRT_PRICE, METEORA_AMM_V2_MAX_SQRT_PRICE);
+}
+
+
+/// CreatePool1 accounts: pool=6, token_a_mint=8, token_b_mint=9.
+/// CreatePool2 accounts: pool=7, token_a_mint=9, token_b_mint=10.
+/// CreatePool3 accounts: pool=5, token_a_mint=7, token_b_mint=8.
 pub fn deserialize_meteora_amm_v2_create_pool_instruction(
     instruction_type: MeteoraAmmV2InstructionType,
     data: &[u8],
@@ -72,19 +170,6 @@ pub fn deserialize_meteora_amm_v2_create_pool_instruction(
     is_signer: &[bool],
     is_writable: &[bool]
 ) -> Result<JsonValue, String> {
-    let safely_get_account = |idx: usize| -> String {
-        if idx >= accounts_indices.len() {
-            return "Unknown".to_string();
-        }
-        
-        let account_idx = accounts_indices[idx] as usize;
-        if account_idx >= account_keys.len() {
-            return "Unknown".to_string();
-        }
-        
-        bs58::encode(&account_keys[account_idx]).into_string()
-    };
-
     let (pool_idx, mint_a_idx, mint_b_idx) = match instruction_type {
         MeteoraAmmV2InstructionType::CreatePool1 => (6, 8, 9),
         MeteoraAmmV2InstructionType::CreatePool2 => (7, 9, 10),
@@ -92,13 +177,46 @@ pub fn deserialize_meteora_amm_v2_create_pool_instruction(
         _ => return Err("Invalid create pool instruction type".to_string()),
     };
 
-    let parsed_data = object! {
+    let mut data_ref = &data[8..];
+    let mut sanity = ValueSanity::new();
+    let mut parsed_data = object! {
         "instruction_variant" => format!("{:?}", instruction_type),
         "pool_index" => pool_idx,
         "mint_a_index" => mint_a_idx,
         "mint_b_index" => mint_b_idx,
     };
 
+    let liquidity = if instruction_type == MeteoraAmmV2InstructionType::CreatePool1 {
+        let params = DammInitializePoolParameters::deserialize(&mut data_ref)
+            .map_err(|e| format!("Failed to deserialize CreatePool params: {:?}", e))?;
+        parsed_data["liquidity"] = params.liquidity.to_string().into();
+        parsed_data["sqrt_price"] = params.sqrt_price.to_string().into();
+        parsed_data["activation_point"] = params.activation_point.map(|point| point.to_string()).into();
+        check_sqrt_price(&mut sanity, "sqrt_price", params.sqrt_price);
+        params.liquidity
+    } else {
+        let params = DammInitializeCustomizablePoolParameters::deserialize(&mut data_ref)
+            .map_err(|e| format!("Failed to deserialize CreatePool params: {:?}", e))?;
+        parsed_data["liquidity"] = params.liquidity.to_string().into();
+        parsed_data["sqrt_price"] = params.sqrt_price.to_string().into();
+        parsed_data["sqrt_min_price"] = params.sqrt_min_price.to_string().into();
+        parsed_data["sqrt_max_price"] = params.sqrt_max_price.to_string().into();
+        parsed_data["cliff_fee_numerator"] = params.pool_fees.base_fee.cliff_fee_numerator.to_string().into();
+        parsed_data["dynamic_fee"] = params.pool_fees.dynamic_fee.is_some().into();
+        parsed_data["has_alpha_vault"] = params.has_alpha_vault.into();
+        parsed_data["activation_type"] = params.activation_type.into();
+        parsed_data["collect_fee_mode"] = params.collect_fee_mode.into();
+        parsed_data["activation_point"] = params.activation_point.map(|point| point.to_string()).into();
+        check_sqrt_price(&mut sanity, "sqrt_price", params.sqrt_price);
+        check_sqrt_price(&mut sanity, "sqrt_min_price", params.sqrt_min_price);
+        check_sqrt_price(&mut sanity, "sqrt_max_price", params.sqrt_max_price);
+        sanity.check_range_u128("sqrt_price", params.sqrt_price, params.sqrt_min_price, params.sqrt_max_price);
+        params.liquidity
+    };
+    sanity.check_nonzero("liquidity", liquidity);
+    sanity.apply(&mut parsed_data);
+    check_parse_residue("MeteoraAmmV2 CreatePool", data_ref, &mut parsed_data);
+
     Ok(create_standardized_instruction(
         &METEORA_AMM_V2_PROGRAM_ID,
         "CreatePool",
@@ -112,6 +230,8 @@ pub fn deserialize_meteora_amm_v2_create_pool_instruction(
     ))
 }
 
+/// Accounts: pool_authority, pool=1, input_token_account, output_token_account,
+/// token_a_vault, token_b_vault, token_a_mint=6, token_b_mint=7, payer=8, ...
 pub fn deserialize_meteora_amm_v2_swap_instruction(
     data: &[u8],
     accounts_indices: &[u8],
@@ -119,22 +239,15 @@ pub fn deserialize_meteora_amm_v2_swap_instruction(
     is_signer: &[bool],
     is_writable: &[bool]
 ) -> Result<JsonValue, String> {
-    let safely_get_account = |idx: usize| -> String {
-        if idx >= accounts_indices.len() {
-            return "Unknown".to_string();
-        }
-        
-        let account_idx = accounts_indices[idx] as usize;
-        if account_idx >= account_keys.len() {
-            return "Unknown".to_string();
-        }
-        
-        bs58::encode(&account_keys[account_idx]).into_string()
-    };
+    let mut data_ref = &data[8..];
+    let params = DammSwapParameters::deserialize(&mut data_ref)
+        .map_err(|e| format!("Failed to deserialize Swap params: {:?}", e))?;
 
-    let parsed_data = object! {
-        "instruction_type" => "swap",
+    let mut parsed_data = object! {
+        "amount_in" => params.amount_in.to_string(),
+        "minimum_amount_out" => params.minimum_amount_out.to_string(),
     };
+    check_parse_residue("MeteoraAmmV2 Swap", data_ref, &mut parsed_data);
 
     Ok(create_standardized_instruction(
         &METEORA_AMM_V2_PROGRAM_ID,
@@ -149,41 +262,27 @@ pub fn deserialize_meteora_amm_v2_swap_instruction(
     ))
 }
 
+/// Accounts: pool=0, position, token_a_account, token_b_account, token_a_vault=4, token_b_vault=5, ...
 pub fn deserialize_meteora_amm_v2_add_liquidity_instruction(
-    instruction_type: MeteoraAmmV2InstructionType,
     data: &[u8],
     accounts_indices: &[u8],
     account_keys: &[Pubkey],
     is_signer: &[bool],
     is_writable: &[bool]
 ) -> Result<JsonValue, String> {
-    let safely_get_account = |idx: usize| -> String {
-        if idx >= accounts_indices.len() {
-            return "Unknown".to_string();
-        }
-        
-        let account_idx = accounts_indices[idx] as usize;
-        if account_idx >= account_keys.len() {
-            return "Unknown".to_string();
-        }
-        
-        bs58::encode(&account_keys[account_idx]).into_string()
-    };
+    let mut data_ref = &data[8..];
+    let params = DammLiquidityParameters::deserialize(&mut data_ref)
+        .map_err(|e| format!("Failed to deserialize AddLiquidity params: {:?}", e))?;
 
-    let (pool_idx, vault_a_idx, vault_b_idx) = match instruction_type {
-        MeteoraAmmV2InstructionType::AddLiquidity1 => (0, 4, 5),
-        MeteoraAmmV2InstructionType::AddLiquidity2 => (0, 10, 11),
-        MeteoraAmmV2InstructionType::AddLiquidity3 => (7, 11, 12),
-        MeteoraAmmV2InstructionType::AddLiquidity4 => (5, 9, 10),
-        _ => return Err("Invalid add liquidity instruction type".to_string()),
-    };
-
-    let parsed_data = object! {
-        "instruction_variant" => format!("{:?}", instruction_type),
-        "pool_index" => pool_idx,
-        "vault_a_index" => vault_a_idx,
-        "vault_b_index" => vault_b_idx,
+    let mut parsed_data = object! {
+        "liquidity_delta" => params.liquidity_delta.to_string(),
+        "token_a_amount_threshold" => params.token_a_amount_threshold.to_string(),
+        "token_b_amount_threshold" => params.token_b_amount_threshold.to_string(),
     };
+    let mut sanity = ValueSanity::new();
+    sanity.check_nonzero("liquidity_delta", params.liquidity_delta);
+    sanity.apply(&mut parsed_data);
+    check_parse_residue("MeteoraAmmV2 AddLiquidity", data_ref, &mut parsed_data);
 
     Ok(create_standardized_instruction(
         &METEORA_AMM_V2_PROGRAM_ID,
@@ -198,6 +297,8 @@ pub fn deserialize_meteora_amm_v2_add_liquidity_instruction(
     ))
 }
 
+/// RemoveLiquidity1 is remove_liquidity with a liquidity delta, RemoveLiquidity2 is
+/// remove_all_liquidity with only the minimum token amounts
 pub fn deserialize_meteora_amm_v2_remove_liquidity_instruction(
     instruction_type: MeteoraAmmV2InstructionType,
     data: &[u8],
@@ -206,23 +307,29 @@ pub fn deserialize_meteora_amm_v2_remove_liquidity_instruction(
     is_signer: &[bool],
     is_writable: &[bool]
 ) -> Result<JsonValue, String> {
-    let safely_get_account = |idx: usize| -> String {
-        if idx >= accounts_indices.len() {
-            return "Unknown".to_string();
-        }
-        
-        let account_idx = accounts_indices[idx] as usize;
-        if account_idx >= account_keys.len() {
-            return "Unknown".to_string();
-        }
-        
-        bs58::encode(&account_keys[account_idx]).into_string()
-    };
-
-    let parsed_data = object! {
+    let mut data_ref = &data[8..];
+    let mut parsed_data = object! {
         "instruction_variant" => format!("{:?}", instruction_type),
     };
 
+    match instruction_type {
+        MeteoraAmmV2InstructionType::RemoveLiquidity1 => {
+            let params = DammLiquidityParameters::deserialize(&mut data_ref)
+                .map_err(|e| format!("Failed to deserialize RemoveLiquidity params: {:?}", e))?;
+            parsed_data["liquidity_delta"] = params.liquidity_delta.to_string().into();
+            parsed_data["token_a_amount_threshold"] = params.token_a_amount_threshold.to_string().into();
+            parsed_data["token_b_amount_threshold"] = params.token_b_amount_threshold.to_string().into();
+        }
+        MeteoraAmmV2InstructionType::RemoveLiquidity2 => {
+            let params = DammRemoveAllLiquidityParameters::deserialize(&mut data_ref)
+                .map_err(|e| format!("Failed to deserialize RemoveAllLiquidity params: {:?}", e))?;
+            parsed_data["token_a_amount_threshold"] = params.token_a_amount_threshold.to_string().into();
+            parsed_data["token_b_amount_threshold"] = params.token_b_amount_threshold.to_string().into();
+        }
+        _ => return Err("Invalid remove liquidity instruction type".to_string()),
+    }
+    check_parse_residue("MeteoraAmmV2 RemoveLiquidity", data_ref, &mut parsed_data);
+
     Ok(create_standardized_instruction(
         &METEORA_AMM_V2_PROGRAM_ID,
         "RemoveLiquidity",
@@ -234,4 +341,4 @@ pub fn deserialize_meteora_amm_v2_remove_liquidity_instruction(
         is_writable,
         parsed_data
     ))
-}
\ No newline at end of file
+}
@@ -0,0 +1,351 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use json::{object, JsonValue};
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use crate::decode_anomalies::check_parse_residue;
+use crate::utils::{create_standardized_instruction, ValueSanity};
+
+pub const METEORA_DLMM_PROGRAM_ID: Pubkey = pubkey!("LBUZKhRxPF3XUpBCjp4YzTKgLccjZhTSDM9YuVaPwxo");
+
+pub const DLMM_ADD_LIQUIDITY_BY_STRATEGY_ACCOUNTS: &[&str] = &[
+    "position", "lbPair", "binArrayBitmapExtension", "userTokenX", "userTokenY", "reserveX",
+    "reserveY", "tokenXMint", "tokenYMint", "binArrayLower", "binArrayUpper", "sender",
+    "tokenXProgram", "tokenYProgram", "eventAuthority", "program",
+];
+pub const DLMM_ADD_LIQUIDITY_BY_STRATEGY2_ACCOUNTS: &[&str] = &[
+    "position", "lbPair", "binArrayBitmapExtension", "userTokenX", "userTokenY", "reserveX",
+    "reserveY", "tokenXMint", "tokenYMint", "sender", "tokenXProgram", "tokenYProgram",
+    "eventAuthority", "program",
+];
+pub const DLMM_ADD_LIQUIDITY_ONE_SIDE_ACCOUNTS: &[&str] = &[
+    "position", "lbPair", "binArrayBitmapExtension", "userToken", "reserve", "tokenMint",
+    "binArrayLower", "binArrayUpper", "sender", "tokenProgram", "eventAuthority", "program",
+];
+pub const DLMM_SWAP_ACCOUNTS: &[&str] = &[
+    "lbPair", "binArrayBitmapExtension", "reserveX", "reserveY", "userTokenIn", "userTokenOut",
+    "tokenXMint", "tokenYMint", "oracle", "hostFeeIn", "user", "tokenXProgram", "tokenYProgram",
+    "eventAuthority", "program",
+];
+pub const DLMM_SWAP2_ACCOUNTS: &[&str] = &[
+    "lbPair", "binArrayBitmapExtension", "reserveX", "reserveY", "userTokenIn", "userTokenOut",
+    "tokenXMint", "tokenYMint", "oracle", "hostFeeIn", "user", "tokenXProgram", "tokenYProgram",
+    "memoProgram", "eventAuthority", "program",
+];
+pub const DLMM_REMOVE_LIQUIDITY_ACCOUNTS: &[&str] = &[
+    "position", "lbPair", "binArrayBitmapExtension", "userTokenX", "userTokenY", "reserveX",
+    "reserveY", "tokenXMint", "tokenYMint", "binArrayLower", "binArrayUpper", "sender",
+    "tokenXProgram", "tokenYProgram", "eventAuthority", "program",
+];
+
+pub const DLMM_ADD_LIQUIDITY_BY_STRATEGY_DISCRIMINATOR: [u8; 8] = [7, 3, 150, 127, 148, 40, 61, 200];
+pub const DLMM_ADD_LIQUIDITY_BY_STRATEGY2_DISCRIMINATOR: [u8; 8] = [3, 221, 149, 218, 111, 141, 118, 213];
+pub const DLMM_ADD_LIQUIDITY_BY_STRATEGY_ONE_SIDE_DISCRIMINATOR: [u8; 8] = [41, 5, 238, 175, 100, 225, 6, 205];
+pub const DLMM_ADD_LIQUIDITY_ONE_SIDE_DISCRIMINATOR: [u8; 8] = [94, 155, 103, 151, 70, 95, 220, 165];
+pub const DLMM_SWAP_DISCRIMINATOR: [u8; 8] = [248, 198, 158, 145, 225, 117, 135, 200];
+pub const DLMM_SWAP2_DISCRIMINATOR: [u8; 8] = [65, 75, 63, 76, 235, 91, 91, 136];
+pub const DLMM_SWAP_EXACT_OUT_DISCRIMINATOR: [u8; 8] = [250, 73, 101, 33, 38, 207, 75, 184];
+pub const DLMM_REMOVE_LIQUIDITY_DISCRIMINATOR: [u8; 8] = [80, 85, 209, 72, 24, 206, 177, 108];
+
+/// Basis points of a full position, the upper bound of `bps_to_remove`
+const BASIS_POINT_MAX: u16 = 10_000;
+
+pub enum MeteoraDlmmInstructionType {
+    AddLiquidityByStrategy,
+    AddLiquidityByStrategy2,
+    AddLiquidityByStrategyOneSide,
+    AddLiquidityOneSide,
+    Swap,
+    Swap2,
+    SwapExactOut,
+    RemoveLiquidity,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy)]
+pub enum DlmmStrategyType {
+    SpotOneSide,
+    CurveOneSide,
+    BidAskOneSide,
+    SpotBalanced,
+    CurveBalanced,
+    BidAskBalanced,
+    SpotImBalanced,
+    CurveImBalanced,
+    BidAskImBalanced,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone)]
+pub struct DlmmStrategyParameters {
+    pub min_bin_id: i32,
+    pub max_bin_id: i32,
+    pub strategy_type: DlmmStrategyType,
+    /// Reserved by the program, the field name carries its typo
+    pub parameteres: [u8; 64],
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone)]
+pub struct DlmmLiquidityParameterByStrategy {
+    pub amount_x: u64,
+    pub amount_y: u64,
+    pub active_id: i32,
+    pub max_active_bin_slippage: i32,
+    pub strategy_parameters: DlmmStrategyParameters,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone)]
+pub struct DlmmLiquidityParameterByStrategyOneSide {
+    pub amount: u64,
+    pub active_id: i32,
+    pub max_active_bin_slippage: i32,
+    pub strategy_parameters: DlmmStrategyParameters,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone)]
+pub struct DlmmBinLiquidityDistributionByWeight {
+    pub bin_id: i32,
+    pub weight: u16,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone)]
+pub struct DlmmLiquidityOneSideParameter {
+    pub amount: u64,
+    pub active_id: i32,
+    pub max_active_bin_slippage: i32,
+    pub bin_liquidity_dist: Vec<DlmmBinLiquidityDistributionByWeight>,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone)]
+pub struct DlmmBinLiquidityReduction {
+    pub bin_id: i32,
+    pub bps_to_remove: u16,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy)]
+pub enum DlmmAccountsType {
+    TransferHookX,
+    TransferHookY,
+    TransferHookReward,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone)]
+pub struct DlmmRemainingAccountsSlice {
+    pub accounts_type: DlmmAccountsType,
+    pub length: u8,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Default)]
+pub struct DlmmRemainingAccountsInfo {
+    pub slices: Vec<DlmmRemainingAccountsSlice>,
+}
+
+pub fn get_meteora_dlmm_instruction_type(data: &[u8]) -> Option<MeteoraDlmmInstructionType> {
+    match data.get(0..8) {
+        Some(d) if d == DLMM_ADD_LIQUIDITY_BY_STRATEGY_DISCRIMINATOR => Some(MeteoraDlmmInstructionType::AddLiquidityByStrategy),
+        Some(d) if d == DLMM_ADD_LIQUIDITY_BY_STRATEGY2_DISCRIMINATOR => Some(MeteoraDlmmInstructionType::AddLiquidityByStrategy2),
+        Some(d) if d == DLMM_ADD_LIQUIDITY_BY_STRATEGY_ONE_SIDE_DISCRIMINATOR => Some(MeteoraDlmmInstructionType::AddLiquidityByStrategyOneSide),
+        Some(d) if d == DLMM_ADD_LIQUIDITY_ONE_SIDE_DISCRIMINATOR => Some(MeteoraDlmmInstructionType::AddLiquidityOneSide),
+        Some(d) if d == DLMM_SWAP_DISCRIMINATOR => Some(MeteoraDlmmInstructionType::Swap),
+        Some(d) if d == DLMM_SWAP2_DISCRIMINATOR => Some(MeteoraDlmmInstructionType::Swap2),
+        Some(d) if d == DLMM_SWAP_EXACT_OUT_DISCRIMINATOR => Some(MeteoraDlmmInstructionType::SwapExactOut),
+        Some(d) if d == DLMM_REMOVE_LIQUIDITY_DISCRIMINATOR => Some(MeteoraDlmmInstructionType::RemoveLiquidity),
+        _ => None,
+    }
+}
+
+fn strategy_json(strategy: &DlmmStrategyParameters) -> JsonValue {
+    object! {
+        "min_bin_id" => strategy.min_bin_id,
+        "max_bin_id" => strategy.max_bin_id,
+        "strategy_type" => format!("{:?}", strategy.strategy_type),
+    }
+}
+
+fn dlmm_instruction(
+    instruction_name: &str,
+    data: &[u8],
+    accounts_indices: &[u8],
+    account_keys: &[Pubkey],
+    is_signer: &[bool],
+    is_writable: &[bool],
+    mut parsed_data: JsonValue,
+    residue: &[u8],
+) -> JsonValue {
+    check_parse_residue(&format!("MeteoraDLMM {}", instruction_name), residue, &mut parsed_data);
+    create_standardized_instruction(
+        &METEORA_DLMM_PROGRAM_ID,
+        instruction_name,
+        "MeteoraDLMM",
+        data,
+        accounts_indices,
+        account_keys,
+        is_signer,
+        is_writable,
+        parsed_data
+    )
+}
+
+/// AddLiquidityByStrategy and AddLiquidityByStrategy2, the latter carrying transfer hook
+/// account slices instead of bin array accounts. Accounts: position, lb pair=1, bitmap extension,
+/// user token x, user token y, reserve x, reserve y, token x mint=7, token y mint=8, ...
+pub fn deserialize_meteora_dlmm_add_liquidity_by_strategy_instruction(
+    instruction_name: &str,
+    with_remaining_accounts: bool,
+    data: &[u8],
+    accounts_indices: &[u8],
+    account_keys: &[Pubkey],
+    is_signer: &[bool],
+    is_writable: &[bool],
+) -> Result<JsonValue, String> {
+    let mut data_ref = &data[8..];
+
+    let params = DlmmLiquidityParameterByStrategy::deserialize(&mut data_ref)
+        .map_err(|e| format!("Failed to deserialize {} params: {:?}", instruction_name, e))?;
+    let remaining_accounts = if with_remaining_accounts {
+        DlmmRemainingAccountsInfo::deserialize(&mut data_ref)
+            .map_err(|e| format!("Failed to deserialize {} remaining accounts: {:?}", instruction_name, e))?
+    } else {
+        DlmmRemainingAccountsInfo::default()
+    };
+
+    let mut parsed_data = object! {
+        "amount_x" => params.amount_x.to_string(),
+        "amount_y" => params.amount_y.to_string(),
+        "active_id" => params.active_id,
+        "max_active_bin_slippage" => params.max_active_bin_slippage,
+        "strategy" => strategy_json(&params.strategy_parameters),
+    };
+    if with_remaining_accounts {
+        parsed_data["remaining_accounts_slices"] = remaining_accounts.slices.len().into();
+    }
+
+    Ok(dlmm_instruction(instruction_name, data, accounts_indices, account_keys, is_signer, is_writable, parsed_data, data_ref))
+}
+
+/// Accounts: position, lb pair=1, bitmap extension, user token, reserve, token mint=5, ...
+pub fn deserialize_meteora_dlmm_add_liquidity_by_strategy_one_side_instruction(
+    data: &[u8],
+    accounts_indices: &[u8],
+    account_keys: &[Pubkey],
+    is_signer: &[bool],
+    is_writable: &[bool],
+) -> Result<JsonValue, String> {
+    let mut data_ref = &data[8..];
+
+    let params = DlmmLiquidityParameterByStrategyOneSide::deserialize(&mut data_ref)
+        .map_err(|e| format!("Failed to deserialize AddLiquidityByStrategyOneSide params: {:?}", e))?;
+
+    let parsed_data = object! {
+        "amount" => params.amount.to_string(),
+        "active_id" => params.active_id,
+        "max_active_bin_slippage" => params.max_active_bin_slippage,
+        "strategy" => strategy_json(&params.strategy_parameters),
+    };
+
+    Ok(dlmm_instruction("AddLiquidityByStrategyOneSide", data, accounts_indices, account_keys, is_signer, is_writable, parsed_data, data_ref))
+}
+
+/// Same accounts as AddLiquidityByStrategyOneSide, with an explicit weight per bin
+pub fn deserialize_meteora_dlmm_add_liquidity_one_side_instruction(
+    data: &[u8],
+    accounts_indices: &[u8],
+    account_keys: &[Pubkey],
+    is_signer: &[bool],
+    is_writable: &[bool],
+) -> Result<JsonValue, String> {
+    let mut data_ref = &data[8..];
+
+    let params = DlmmLiquidityOneSideParameter::deserialize(&mut data_ref)
+        .map_err(|e| format!("Failed to deserialize AddLiquidityOneSide params: {:?}", e))?;
+
+    let distribution: Vec<JsonValue> = params
+        .bin_liquidity_dist
+        .iter()
+        .map(|bin| object! { "bin_id" => bin.bin_id, "weight" => bin.weight })
+        .collect();
+    let parsed_data = object! {
+        "amount" => params.amount.to_string(),
+        "active_id" => params.active_id,
+        "max_active_bin_slippage" => params.max_active_bin_slippage,
+        "bin_liquidity_dist" => distribution,
+    };
+
+    Ok(dlmm_instruction("AddLiquidityOneSide", data, accounts_indices, account_keys, is_signer, is_writable, parsed_data, data_ref))
+}
+
+/// Swap, Swap2 and SwapExactOut. Accounts: lb pair=0, bitmap extension, reserve x, reserve y,
+/// user token in=4, user token out=5, token x mint=6, token y mint=7, oracle, host fee, user=10, ...
+pub fn deserialize_meteora_dlmm_swap_instruction(
+    instruction_type: &MeteoraDlmmInstructionType,
+    data: &[u8],
+    accounts_indices: &[u8],
+    account_keys: &[Pubkey],
+    is_signer: &[bool],
+    is_writable: &[bool],
+) -> Result<JsonValue, String> {
+    let mut data_ref = &data[8..];
+    let mut read_u64 = |field: &str| {
+        u64::deserialize(&mut data_ref).map_err(|e| format!("Failed to deserialize DLMM swap {}: {:?}", field, e))
+    };
+
+    let (instruction_name, mut parsed_data) = match instruction_type {
+        MeteoraDlmmInstructionType::SwapExactOut => {
+            let max_in_amount = read_u64("max_in_amount")?;
+            let out_amount = read_u64("out_amount")?;
+            ("SwapExactOut", object! {
+                "max_in_amount" => max_in_amount.to_string(),
+                "out_amount" => out_amount.to_string(),
+            })
+        }
+        MeteoraDlmmInstructionType::Swap2 => {
+            let amount_in = read_u64("amount_in")?;
+            let min_amount_out = read_u64("min_amount_out")?;
+            ("Swap2", object! {
+                "amount_in" => amount_in.to_string(),
+                "min_amount_out" => min_amount_out.to_string(),
+            })
+        }
+        _ => {
+            let amount_in = read_u64("amount_in")?;
+            let min_amount_out = read_u64("min_amount_out")?;
+            ("Swap", object! {
+                "amount_in" => amount_in.to_string(),
+                "min_amount_out" => min_amount_out.to_string(),
+            })
+        }
+    };
+    if matches!(instruction_type, MeteoraDlmmInstructionType::Swap2) {
+        let remaining_accounts = DlmmRemainingAccountsInfo::deserialize(&mut data_ref)
+            .map_err(|e| format!("Failed to deserialize Swap2 remaining accounts: {:?}", e))?;
+        parsed_data["remaining_accounts_slices"] = remaining_accounts.slices.len().into();
+    }
+
+    Ok(dlmm_instruction(instruction_name, data, accounts_indices, account_keys, is_signer, is_writable, parsed_data, data_ref))
+}
+
+/// Accounts: position, lb pair=1, bitmap extension, user token x, user token y, reserve x,
+/// reserve y, token x mint=7, token y mint=8, bin arrays, sender, ...
+pub fn deserialize_meteora_dlmm_remove_liquidity_instruction(
+    data: &[u8],
+    accounts_indices: &[u8],
+    account_keys: &[Pubkey],
+    is_signer: &[bool],
+    is_writable: &[bool],
+) -> Result<JsonValue, String> {
+    let mut data_ref = &data[8..];
+
+    let reductions = Vec::<DlmmBinLiquidityReduction>::deserialize(&mut data_ref)
+        .map_err(|e| format!("Failed to deserialize RemoveLiquidity params: {:?}", e))?;
+
+    let mut sanity = ValueSanity::new();
+    for reduction in &reductions {
+        sanity.check_range_u128("bps_to_remove", reduction.bps_to_remove as u128, 0, BASIS_POINT_MAX as u128);
+    }
+    let mut parsed_data = object! {
+        "bin_liquidity_removal" => reductions
+            .iter()
+            .map(|reduction| object! { "bin_id" => reduction.bin_id, "bps_to_remove" => reduction.bps_to_remove })
+            .collect::<Vec<_>>(),
+    };
+    sanity.apply(&mut parsed_data);
+
+    Ok(dlmm_instruction("RemoveLiquidity", data, accounts_indices, account_keys, is_signer, is_writable, parsed_data, data_ref))
+}
@@ -2,11 +2,32 @@ use borsh::{BorshDeserialize, BorshSerialize};
 use json::{object, JsonValue};
 use solana_sdk::pubkey::Pubkey;
 use solana_sdk::pubkey;
-use crate::utils::create_standardized_instruction;
+use crate::decode_anomalies::check_parse_residue;
+use crate::utils::{associated_token_address, create_standardized_instruction};
 
 pub const INITIALIZE_VIRTUAL_POOL_WITH_SPL_TOKEN_DISCRIMINATOR: [u8; 8] = [140, 85, 215, 176, 102, 54, 104, 79];
+pub const INITIALIZE_VIRTUAL_POOL_WITH_TOKEN2022_DISCRIMINATOR: [u8; 8] = [169, 118, 51, 78, 145, 110, 220, 155];
+pub const METEORA_VCURVE_SWAP_DISCRIMINATOR: [u8; 8] = [248, 198, 158, 145, 225, 117, 135, 200];
+pub const MIGRATION_DAMM_V2_DISCRIMINATOR: [u8; 8] = [156, 169, 230, 103, 53, 228, 80, 64];
+pub const MIGRATE_METEORA_DAMM_DISCRIMINATOR: [u8; 8] = [27, 1, 48, 22, 180, 63, 118, 217];
 
 pub const METEORA_VCURVE_PROGRAM_ID: Pubkey = pubkey!("dbcij3LWUppWqq96dh6gJWwBifmcGfLSB5D4DuSMaqN");
+
+pub const METEORA_VCURVE_INITIALIZE_SPL_ACCOUNTS: &[&str] = &[
+    "config", "poolAuthority", "creator", "baseMint", "quoteMint", "pool", "baseVault",
+    "quoteVault", "mintMetadata", "metadataProgram", "payer", "tokenQuoteProgram", "tokenProgram",
+    "systemProgram", "eventAuthority", "program",
+];
+pub const METEORA_VCURVE_INITIALIZE_TOKEN2022_ACCOUNTS: &[&str] = &[
+    "config", "poolAuthority", "creator", "baseMint", "quoteMint", "pool", "baseVault",
+    "quoteVault", "payer", "tokenQuoteProgram", "tokenProgram", "systemProgram", "eventAuthority",
+    "program",
+];
+pub const METEORA_VCURVE_SWAP_ACCOUNTS: &[&str] = &[
+    "poolAuthority", "config", "pool", "inputTokenAccount", "outputTokenAccount", "baseVault",
+    "quoteVault", "baseMint", "quoteMint", "payer", "tokenBaseProgram", "tokenQuoteProgram",
+    "referralTokenAccount", "eventAuthority", "program",
+];
 pub const SYSTEM_PROGRAM_ID: Pubkey = pubkey!("11111111111111111111111111111111");
 pub const TOKEN_PROGRAM_ID: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
 pub const METADATA_PROGRAM_ID: Pubkey = pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
@@ -15,6 +36,10 @@ pub const EVENT_AUTHORITY: Pubkey = pubkey!("8Ks12pbrD6PXxfty1hVQiE9sc289zgU1zHk
 
 pub enum MeteoraVCurveInstructionType {
     InitializeVirtualPoolWithSplToken,
+    InitializeVirtualPoolWithToken2022,
+    Swap,
+    MigrationDammV2,
+    MigrateMeteoraDamm,
 }
 
 #[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
@@ -24,6 +49,12 @@ pub struct InitializePoolParameters {
     pub uri: String,
 }
 
+#[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
+pub struct VCurveSwapParameters {
+    pub amount_in: u64,
+    pub minimum_amount_out: u64,
+}
+
 pub fn get_meteora_vcurve_instruction_type(data: &[u8]) -> Option<MeteoraVCurveInstructionType> {
     if data.len() < 8 {
         return None;
@@ -33,11 +64,18 @@ pub fn get_meteora_vcurve_instruction_type(data: &[u8]) -> Option<MeteoraVCurveI
     
     match discriminator {
         d if d == INITIALIZE_VIRTUAL_POOL_WITH_SPL_TOKEN_DISCRIMINATOR => Some(MeteoraVCurveInstructionType::InitializeVirtualPoolWithSplToken),
+        d if d == INITIALIZE_VIRTUAL_POOL_WITH_TOKEN2022_DISCRIMINATOR => Some(MeteoraVCurveInstructionType::InitializeVirtualPoolWithToken2022),
+        d if d == METEORA_VCURVE_SWAP_DISCRIMINATOR => Some(MeteoraVCurveInstructionType::Swap),
+        d if d == MIGRATION_DAMM_V2_DISCRIMINATOR => Some(MeteoraVCurveInstructionType::MigrationDammV2),
+        d if d == MIGRATE_METEORA_DAMM_DISCRIMINATOR => Some(MeteoraVCurveInstructionType::MigrateMeteoraDamm),
         _ => None,
     }
 }
 
+/// SPL token and Token-2022 pools share the args, accounts: config, pool_authority, creator=2,
+/// base_mint=3, quote_mint=4, pool=5, ...
 pub fn deserialize_meteora_vcurve_initialize_virtual_pool_instruction(
+    instruction_name: &str,
     data: &[u8],
     accounts_indices: &[u8],
     account_keys: &[Pubkey],
@@ -58,15 +96,17 @@ pub fn deserialize_meteora_vcurve_initialize_virtual_pool_instruction(
         }
     };
 
-    let parsed_data = object! {
+    let mut parsed_data = object! {
         "name" => params.name,
         "symbol" => params.symbol,
         "uri" => params.uri,
     };
     
+    check_parse_residue(&format!("MeteoraVCurve {}", instruction_name), remaining_data_ref, &mut parsed_data);
+
     Ok(create_standardized_instruction(
         &METEORA_VCURVE_PROGRAM_ID,
-        "InitializeVirtualPoolWithSplToken",
+        instruction_name,
         "MeteoraVCurve", 
         data,
         accounts_indices,
@@ -76,3 +116,84 @@ pub fn deserialize_meteora_vcurve_initialize_virtual_pool_instruction(
         parsed_data
     ))
 }
+
+fn account_at(accounts_indices: &[u8], account_keys: &[Pubkey], position: usize) -> Option<Pubkey> {
+    accounts_indices.get(position).and_then(|&index| account_keys.get(index as usize)).copied()
+}
+
+/// The args do not carry the direction, a swap paying out to the payer's base mint ATA is a buy
+/// and one spending from it a sell. None when neither side is that ATA.
+fn swap_direction(accounts_indices: &[u8], account_keys: &[Pubkey]) -> Option<&'static str> {
+    let account = |position| account_at(accounts_indices, account_keys, position);
+    let (input, output, base_mint, payer, token_base_program) = (account(3)?, account(4)?, account(7)?, account(9)?, account(10)?);
+    let base_ata = associated_token_address(&payer, &base_mint, &token_base_program);
+    if output == base_ata {
+        Some("buy")
+    } else if input == base_ata {
+        Some("sell")
+    } else {
+        None
+    }
+}
+
+/// Accounts: pool_authority, config, pool=2, input_token_account=3, output_token_account=4,
+/// base_vault, quote_vault, base_mint=7, quote_mint=8, payer=9, token_base_program=10, ...
+pub fn deserialize_meteora_vcurve_swap_instruction(
+    data: &[u8],
+    accounts_indices: &[u8],
+    account_keys: &[Pubkey],
+    is_signer: &[bool],
+    is_writable: &[bool],
+) -> Result<JsonValue, String> {
+    let mut data_ref = &data[8..];
+    let params = VCurveSwapParameters::deserialize(&mut data_ref)
+        .map_err(|e| format!("Failed to deserialize SwapParameters: {:?}", e))?;
+
+    let mut parsed_data = object! {
+        "amount_in" => params.amount_in.to_string(),
+        "minimum_amount_out" => params.minimum_amount_out.to_string(),
+        "direction" => swap_direction(accounts_indices, account_keys),
+    };
+
+    check_parse_residue("MeteoraVCurve Swap", data_ref, &mut parsed_data);
+
+    Ok(create_standardized_instruction(
+        &METEORA_VCURVE_PROGRAM_ID,
+        "Swap",
+        "MeteoraVCurve",
+        data,
+        accounts_indices,
+        account_keys,
+        is_signer,
+        is_writable,
+        parsed_data
+    ))
+}
+
+/// Graduation of a curve, the args are empty.
+/// MigrationDammV2 accounts: virtual_pool=0, migration_metadata, config, pool_authority, pool=4, ..., base_mint=13, quote_mint=14, ...
+/// MigrateMeteoraDamm accounts: virtual_pool=0, migration_metadata, config, pool_authority, pool=4, damm_config, lp_mint, token_a_mint=7, token_b_mint=8, ...
+pub fn deserialize_meteora_vcurve_migration_instruction(
+    instruction_name: &str,
+    data: &[u8],
+    accounts_indices: &[u8],
+    account_keys: &[Pubkey],
+    is_signer: &[bool],
+    is_writable: &[bool],
+) -> Result<JsonValue, String> {
+    let mut parsed_data = object! {};
+
+    check_parse_residue(&format!("MeteoraVCurve {}", instruction_name), &data[8..], &mut parsed_data);
+
+    Ok(create_standardized_instruction(
+        &METEORA_VCURVE_PROGRAM_ID,
+        instruction_name,
+        "MeteoraVCurve",
+        data,
+        accounts_indices,
+        account_keys,
+        is_signer,
+        is_writable,
+        parsed_data
+    ))
+}
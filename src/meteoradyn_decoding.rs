@@ -12,7 +12,7 @@ pub const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey = pubkey!("ATokenGPvbdGVxr1b2hvZbs
 pub const RENT_PROGRAM_ID: Pubkey = pubkey!("SysvarRent111111111111111111111111111111111");
 pub const METADATA_PROGRAM_ID: Pubkey = pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
 
-const INIT_PERMISSIONLESS_POOL_IX_DISCRIMINATOR: [u8; 8] = [66, 5, 221, 69, 105, 8, 127, 249];
+pub const INIT_PERMISSIONLESS_POOL_IX_DISCRIMINATOR: [u8; 8] = [66, 5, 221, 69, 105, 8, 127, 249];
 
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub enum MeteoraDynInstructionType {
@@ -0,0 +1,100 @@
+use dashmap::DashMap;
+use json::JsonValue;
+use once_cell::sync::Lazy;
+use rustc_hash::FxHashSet as HashSet;
+
+/// Pool account -> mints traded by that pool, learned from pool creations and
+/// from swaps that name both the pool and its mints
+static POOL_MINTS: Lazy<DashMap<String, [String; 2]>> = Lazy::new(DashMap::new);
+
+static POOL_REGISTRY_MAX_ENTRIES: Lazy<usize> = Lazy::new(|| {
+    std::env::var("POOL_REGISTRY_MAX_ENTRIES")
+        .unwrap_or_else(|_| "200000".to_string())
+        .parse()
+        .unwrap_or(200_000)
+});
+
+/// Account positions (pool, mint A, mint B) of the instructions that reveal a pool's mints
+fn pool_account_layout(protocol: &str, instruction_name: &str) -> Option<(usize, usize, usize)> {
+    match (protocol, instruction_name) {
+        ("Pumpfun", "Create") => Some((2, 0, 0)),
+        ("Pumpfun", "Buy") => Some((3, 2, 2)),
+        ("Moonit", "Buy") | ("Moonit", "Sell") => Some((2, 6, 6)),
+        ("Raydium", "Initialize2") => Some((4, 8, 9)),
+        ("RaydiumCPMM", "Initialize") => Some((3, 4, 5)),
+        ("RaydiumCPMM", "Deposit") | ("RaydiumCPMM", "Withdraw") => Some((2, 10, 11)),
+        ("RaydiumCLMM", "CreatePool") => Some((2, 3, 4)),
+        ("RaydiumLaunchLab", "Initialize") => Some((5, 6, 7)),
+        ("RaydiumLaunchLab", "BuyExactIn" | "BuyExactOut" | "SellExactIn" | "SellExactOut") => Some((4, 9, 10)),
+        // Graduations reveal the new AMM / CPMM pool of the launched mint
+        ("RaydiumLaunchLab", "MigrateToAmm") => Some((13, 1, 2)),
+        ("RaydiumLaunchLab", "MigrateToCpswap") => Some((5, 1, 2)),
+        ("PumpAMM", "CreatePool" | "Buy" | "Sell" | "Deposit" | "Withdraw") => Some((0, 3, 4)),
+        ("MeteoraVCurve", "InitializeVirtualPoolWithSplToken" | "InitializeVirtualPoolWithToken2022") => Some((5, 3, 4)),
+        ("MeteoraVCurve", "Swap") => Some((2, 7, 8)),
+        // Graduations reveal the DAMM pool the curve migrated to
+        ("MeteoraVCurve", "MigrationDammV2") => Some((4, 13, 14)),
+        ("MeteoraVCurve", "MigrateMeteoraDamm") => Some((4, 7, 8)),
+        ("MeteoraAmmV2", "Swap") => Some((1, 6, 7)),
+        ("MeteoraDLMM", "Swap" | "Swap2" | "SwapExactOut") => Some((0, 6, 7)),
+        ("MeteoraDLMM", "AddLiquidityByStrategy" | "AddLiquidityByStrategy2" | "RemoveLiquidity") => Some((1, 7, 8)),
+        ("Orca", "InitializePool") => Some((4, 1, 2)),
+        ("Orca", "InitializePoolV2") => Some((6, 1, 2)),
+        ("Orca", "SwapV2") => Some((4, 5, 6)),
+        _ => None,
+    }
+}
+
+fn account_at(instruction: &JsonValue, position: usize) -> Option<&str> {
+    instruction["accounts"][position]["pubkey"]
+        .as_str()
+        .filter(|pubkey| *pubkey != "unknown")
+}
+
+fn learn_pool(instruction: &JsonValue) {
+    let (Some(protocol), Some(name)) = (instruction["protocol"].as_str(), instruction["instruction_name"].as_str()) else {
+        return;
+    };
+    let Some((pool_idx, mint_a_idx, mint_b_idx)) = pool_account_layout(protocol, name) else { return };
+    let (Some(pool), Some(mint_a), Some(mint_b)) = (
+        account_at(instruction, pool_idx),
+        account_at(instruction, mint_a_idx),
+        account_at(instruction, mint_b_idx),
+    ) else {
+        return;
+    };
+
+    if POOL_MINTS.contains_key(pool) || POOL_MINTS.len() >= *POOL_REGISTRY_MAX_ENTRIES {
+        return;
+    }
+    POOL_MINTS.insert(pool.to_string(), [mint_a.to_string(), mint_b.to_string()]);
+}
+
+/// Returns every account touched by the decoded instructions of a transaction,
+/// extended with the mints of the known pools among them. Swaps that only
+/// reference the pool (e.g. Orca Swap) are attributed to its mints this way.
+pub fn index_transaction_accounts(json_transaction: &JsonValue) -> HashSet<String> {
+    let mut accounts = HashSet::default();
+
+    for instruction in json_transaction["message"]["instructions"].members() {
+        learn_pool(instruction);
+
+        for account in instruction["accounts"].members() {
+            let Some(pubkey) = account["pubkey"].as_str() else { continue };
+            if pubkey == "unknown" {
+                continue;
+            }
+            if let Some(mints) = POOL_MINTS.get(pubkey) {
+                accounts.extend(mints.iter().cloned());
+            }
+            accounts.insert(pubkey.to_string());
+        }
+    }
+
+    accounts
+}
+
+/// Mints of a pool seen earlier in the stream, as (A, B) or (base, quote)
+pub fn pool_mints(pool: &str) -> Option<[String; 2]> {
+    POOL_MINTS.get(pool).map(|mints| mints.clone())
+}
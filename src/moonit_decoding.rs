@@ -2,11 +2,23 @@ use borsh::{BorshDeserialize, BorshSerialize};
 use json::{object, JsonValue};
 use solana_sdk::pubkey::Pubkey;
 use solana_sdk::pubkey;
-use crate::utils::create_standardized_instruction;
+use crate::decode_anomalies::check_parse_residue;
+use crate::utils::{create_standardized_instruction, ValueSanity};
 
 pub const MOONIT_TOKEN_MINT_INSTRUCTION_DISCRIMINATOR: [u8; 8] = [3, 44, 164, 184, 123, 13, 245, 179];
+pub const MOONIT_BUY_INSTRUCTION_DISCRIMINATOR: [u8; 8] = [102, 6, 61, 18, 1, 218, 235, 234];
+pub const MOONIT_SELL_INSTRUCTION_DISCRIMINATOR: [u8; 8] = [51, 230, 133, 164, 1, 127, 131, 173];
 
 pub const MOONIT_PROGRAM_ID: Pubkey = pubkey!("MoonCVVNZFSYkqNXP6bxHLPL6QQJiMagDL3qcqUQTrG");
+
+pub const MOONIT_TOKEN_MINT_ACCOUNTS: &[&str] = &[
+    "sender", "backendAuthority", "curveAccount", "mint", "mintMetadata", "curveTokenAccount",
+    "configAccount", "tokenProgram", "associatedTokenProgram", "mplTokenMetadata", "systemProgram",
+];
+pub const MOONIT_TRADE_ACCOUNTS: &[&str] = &[
+    "sender", "senderTokenAccount", "curveAccount", "curveTokenAccount", "dexFee", "helioFee",
+    "mint", "configAccount", "tokenProgram", "associatedTokenProgram", "systemProgram",
+];
 pub const SYSTEM_PROGRAM_ID: Pubkey = pubkey!("11111111111111111111111111111111");
 pub const TOKEN_PROGRAM_ID: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
 pub const ASSOCIATED_TOKEN_PROGRAM: Pubkey = pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
@@ -14,6 +26,8 @@ pub const MPL_TOKEN_METADATA: Pubkey = pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb
 
 pub enum MoonitInstructionType {
     TokenMint,
+    Buy,
+    Sell,
 }
 
 #[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
@@ -28,9 +42,20 @@ pub struct TokenMintParams {
     pub migration_target: u8,
 }
 
+/// Buy and Sell args. `fixed_side` tells which amount is exact, the other one is bounded by `slippage_bps`
+#[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
+pub struct MoonitTradeParams {
+    pub token_amount: u64,
+    pub collateral_amount: u64,
+    pub fixed_side: u8,
+    pub slippage_bps: u64,
+}
+
 pub fn get_moonit_instruction_type(data: &[u8]) -> Option<MoonitInstructionType> {
     match data.get(0..8) {
         Some(d) if d == MOONIT_TOKEN_MINT_INSTRUCTION_DISCRIMINATOR => Some(MoonitInstructionType::TokenMint),
+        Some(d) if d == MOONIT_BUY_INSTRUCTION_DISCRIMINATOR => Some(MoonitInstructionType::Buy),
+        Some(d) if d == MOONIT_SELL_INSTRUCTION_DISCRIMINATOR => Some(MoonitInstructionType::Sell),
         _ => None,
     }
 }
@@ -67,7 +92,7 @@ pub fn deserialize_moonit_token_mint_instruction(data: &[u8], accounts_indices:
         _ => "Unknown",
     };
 
-    let parsed_data = object! {
+    let mut parsed_data = object! {
         "name" => args.name,
         "symbol" => args.symbol,
         "uri" => args.uri,
@@ -78,6 +103,8 @@ pub fn deserialize_moonit_token_mint_instruction(data: &[u8], accounts_indices:
         "migration_target" => migration_target,
     };
     
+    check_parse_residue("Moonit TokenMint", remaining_data_ref, &mut parsed_data);
+
     Ok(create_standardized_instruction(
         &MOONIT_PROGRAM_ID,
         "TokenMint",
@@ -90,3 +117,53 @@ pub fn deserialize_moonit_token_mint_instruction(data: &[u8], accounts_indices:
         parsed_data
     ))
 }
+
+/// Buy and Sell share their args and accounts: sender=0, sender_token_account=1, curve_account=2,
+/// curve_token_account=3, dex_fee=4, helio_fee=5, mint=6, config_account=7, ...
+pub fn deserialize_moonit_trade_instruction(
+    instruction_name: &str,
+    data: &[u8],
+    accounts_indices: &[u8],
+    account_keys: &[Pubkey],
+    is_signer: &[bool],
+    is_writable: &[bool],
+) -> Result<JsonValue, String> {
+    let mut remaining_data_ref = &data[8..];
+
+    let args = MoonitTradeParams::deserialize(&mut remaining_data_ref)
+        .map_err(|e| format!("Failed to deserialize MoonitTradeParams: {:?}", e))?;
+
+    let fixed_side = match args.fixed_side {
+        0 => "ExactIn",
+        1 => "ExactOut",
+        _ => "Unknown",
+    };
+
+    let mut parsed_data = object! {
+        "token_amount" => args.token_amount.to_string(),
+        "collateral_amount" => args.collateral_amount.to_string(),
+        "fixed_side" => fixed_side,
+        "slippage_bps" => args.slippage_bps.to_string(),
+    };
+
+    // Moonit curves are SOL collateralized, the collateral amount is in lamports
+    let mut sanity = ValueSanity::new();
+    sanity.check_nonzero("token_amount", args.token_amount as u128);
+    sanity.check_lamports("collateral_amount", args.collateral_amount);
+    sanity.check_bps("slippage_bps", args.slippage_bps);
+    sanity.apply(&mut parsed_data);
+
+    check_parse_residue(&format!("Moonit {}", instruction_name), remaining_data_ref, &mut parsed_data);
+
+    Ok(create_standardized_instruction(
+        &MOONIT_PROGRAM_ID,
+        instruction_name,
+        "Moonit",
+        data,
+        accounts_indices,
+        account_keys,
+        is_signer,
+        is_writable,
+        parsed_data
+    ))
+}
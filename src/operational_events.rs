@@ -0,0 +1,144 @@
+use json::JsonValue;
+use once_cell::sync::Lazy;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::broadcast;
+use tracing::{info, warn};
+
+pub const RPC_FAILURE_EVENT: &str = "rpc_failure";
+pub const SINK_DROPPED_EVENT: &str = "sink_dropped";
+pub const SINK_DELIVERY_FAILED_EVENT: &str = "sink_delivery_failed";
+pub const FEED_STALLED_EVENT: &str = "feed_stalled";
+pub const FEED_RESUMED_EVENT: &str = "feed_resumed";
+pub const DECODER_PANIC_EVENT: &str = "decoder_panic_recovered";
+pub const FAILOVER_ROLE_EVENT: &str = "failover_role_changed";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "info" => Some(Self::Info),
+            "warning" | "warn" => Some(Self::Warning),
+            "error" => Some(Self::Error),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Info => "info",
+            Self::Warning => "warning",
+            Self::Error => "error",
+        }
+    }
+}
+
+/// Something that happened to the decoder itself rather than on chain, for automation that
+/// would otherwise scrape the logs
+#[derive(Debug)]
+pub struct OperationalEvent {
+    pub kind: &'static str,
+    pub severity: Severity,
+    pub message: String,
+    /// Kind-specific fields as a JSON object
+    pub details_json: String,
+    pub timestamp: u64,
+}
+
+static OPERATIONAL_EVENTS_TX: Lazy<broadcast::Sender<Arc<OperationalEvent>>> = Lazy::new(|| broadcast::channel(1000).0);
+
+pub fn subscribe_operational_events() -> broadcast::Receiver<Arc<OperationalEvent>> {
+    OPERATIONAL_EVENTS_TX.subscribe()
+}
+
+/// Publishes an event on the operational bus. Unlike the output streams these are
+/// published on a standby instance as well, they describe the instance itself.
+pub fn publish_operational_event(kind: &'static str, severity: Severity, message: impl Into<String>, details: JsonValue) {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_micros() as u64;
+
+    // Sending only fails when nobody is subscribed, which is not an error here
+    let _ = OPERATIONAL_EVENTS_TX.send(Arc::new(OperationalEvent {
+        kind,
+        severity,
+        message: message.into(),
+        details_json: details.dump(),
+        timestamp,
+    }));
+}
+
+/// Time without any shred received before the feed is reported as stalled, 0 disables
+static FEED_STALL_TIMEOUT_SECS: Lazy<u64> = Lazy::new(|| {
+    std::env::var("FEED_STALL_TIMEOUT_SECS")
+        .unwrap_or_else(|_| "5".to_string())
+        .parse()
+        .unwrap_or(5)
+});
+
+/// Milliseconds since the epoch of the last shred received from the proxy
+static LAST_SHRED_RECEIVED_MS: AtomicU64 = AtomicU64::new(0);
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_millis() as u64
+}
+
+pub fn record_shred_received() {
+    LAST_SHRED_RECEIVED_MS.store(now_millis(), Ordering::Relaxed);
+}
+
+pub fn feed_stall_timeout() -> Option<Duration> {
+    match *FEED_STALL_TIMEOUT_SECS {
+        0 => None,
+        secs => Some(Duration::from_secs(secs)),
+    }
+}
+
+/// Reports the shred feed as stalled once nothing arrived for the timeout, and again when it resumes.
+/// Nothing is reported before the first shred.
+pub async fn run_feed_stall_watchdog(timeout: Duration) {
+    let mut ticker = tokio::time::interval((timeout / 4).max(Duration::from_millis(250)));
+    let mut stalled = false;
+    info!("Feed stall watchdog started (timeout={:?})", timeout);
+
+    loop {
+        ticker.tick().await;
+
+        let last_received = LAST_SHRED_RECEIVED_MS.load(Ordering::Relaxed);
+        if last_received == 0 {
+            continue;
+        }
+        let silent_ms = now_millis().saturating_sub(last_received);
+
+        if silent_ms >= timeout.as_millis() as u64 && !stalled {
+            stalled = true;
+            warn!("No shred received for {} ms, the shred feed looks stalled", silent_ms);
+            publish_operational_event(
+                FEED_STALLED_EVENT,
+                Severity::Error,
+                format!("No shred received for {} ms", silent_ms),
+                json::object! { "silentMs" => silent_ms, "timeoutMs" => timeout.as_millis() as u64 },
+            );
+        } else if silent_ms < timeout.as_millis() as u64 && stalled {
+            stalled = false;
+            info!("Shred feed resumed");
+            publish_operational_event(
+                FEED_RESUMED_EVENT,
+                Severity::Info,
+                "Shred feed resumed",
+                json::object! { "timeoutMs" => timeout.as_millis() as u64 },
+            );
+        }
+    }
+}
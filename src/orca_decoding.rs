@@ -2,19 +2,132 @@ use borsh::{BorshDeserialize, BorshSerialize};
 use json::{object, JsonValue};
 use solana_sdk::pubkey::Pubkey;
 use solana_sdk::pubkey;
-use crate::utils::create_standardized_instruction;
+use crate::utils::{create_standardized_instruction, ValueSanity};
 
 
 pub const ORCA_SWAP_INSTRUCTION_DISCRIMINATOR: [u8; 8] = [248, 198, 158, 145, 225, 117, 135, 200];
 pub const ORCA_INCREASE_LIQUIDITY_INSTRUCTION_DISCRIMINATOR: [u8; 8] = [46, 156, 243, 118, 13, 205, 251, 178];
-pub const ORCA_DECREASE_LIQUIDITY_INSTRUCTION_DISCRIMINATOR: [u8; 8] = [58, 127, 188, 62, 79, 82, 196, 96];
+pub const ORCA_DECREASE_LIQUIDITY_INSTRUCTION_DISCRIMINATOR: [u8; 8] = [160, 38, 208, 111, 104, 91, 44, 1];
 pub const ORCA_INITIALIZE_POOL_INSTRUCTION_DISCRIMINATOR: [u8; 8] = [95, 180, 10, 172, 84, 174, 232, 40];
 pub const ORCA_OPEN_POSITION_INSTRUCTION_DISCRIMINATOR: [u8; 8] = [135, 128, 47, 77, 15, 152, 240, 49];
 pub const ORCA_CLOSE_POSITION_INSTRUCTION_DISCRIMINATOR: [u8; 8] = [123, 134, 81, 0, 49, 68, 98, 98];
 pub const ORCA_SWAP_V2_INSTRUCTION_DISCRIMINATOR: [u8; 8] = [43, 4, 237, 11, 26, 201, 30, 98];
-pub const ORCA_TWO_HOP_SWAP_INSTRUCTION_DISCRIMINATOR: [u8; 8] = [235, 47, 68, 120, 187, 155, 176, 203];
+pub const ORCA_TWO_HOP_SWAP_INSTRUCTION_DISCRIMINATOR: [u8; 8] = [195, 96, 237, 108, 68, 162, 219, 230];
+pub const ORCA_OPEN_BUNDLED_POSITION_INSTRUCTION_DISCRIMINATOR: [u8; 8] = [169, 113, 126, 171, 213, 172, 212, 49];
+pub const ORCA_CLOSE_BUNDLED_POSITION_INSTRUCTION_DISCRIMINATOR: [u8; 8] = [41, 36, 216, 245, 27, 85, 103, 67];
+pub const ORCA_OPEN_POSITION_WITH_TOKEN_EXTENSIONS_INSTRUCTION_DISCRIMINATOR: [u8; 8] = [212, 47, 95, 92, 114, 102, 131, 250];
+pub const ORCA_CLOSE_POSITION_WITH_TOKEN_EXTENSIONS_INSTRUCTION_DISCRIMINATOR: [u8; 8] = [1, 182, 135, 59, 155, 25, 99, 223];
+// V2 variants take per-mint token programs so pools over Token-2022 mints only emit these
+pub const ORCA_INITIALIZE_POOL_V2_INSTRUCTION_DISCRIMINATOR: [u8; 8] = [207, 45, 87, 242, 27, 63, 204, 67];
+pub const ORCA_INCREASE_LIQUIDITY_V2_INSTRUCTION_DISCRIMINATOR: [u8; 8] = [133, 29, 89, 223, 69, 238, 176, 10];
+pub const ORCA_DECREASE_LIQUIDITY_V2_INSTRUCTION_DISCRIMINATOR: [u8; 8] = [58, 127, 188, 62, 79, 82, 196, 96];
+pub const ORCA_TWO_HOP_SWAP_V2_INSTRUCTION_DISCRIMINATOR: [u8; 8] = [186, 143, 209, 29, 254, 2, 194, 117];
+pub const ORCA_COLLECT_FEES_INSTRUCTION_DISCRIMINATOR: [u8; 8] = [164, 152, 207, 99, 30, 186, 19, 182];
+pub const ORCA_COLLECT_REWARD_INSTRUCTION_DISCRIMINATOR: [u8; 8] = [70, 5, 132, 87, 86, 235, 177, 34];
+pub const ORCA_COLLECT_PROTOCOL_FEES_INSTRUCTION_DISCRIMINATOR: [u8; 8] = [22, 67, 23, 98, 150, 178, 70, 220];
+pub const ORCA_COLLECT_FEES_V2_INSTRUCTION_DISCRIMINATOR: [u8; 8] = [207, 117, 95, 191, 229, 180, 226, 15];
+pub const ORCA_COLLECT_REWARD_V2_INSTRUCTION_DISCRIMINATOR: [u8; 8] = [177, 107, 37, 180, 160, 19, 49, 209];
+pub const ORCA_COLLECT_PROTOCOL_FEES_V2_INSTRUCTION_DISCRIMINATOR: [u8; 8] = [103, 128, 222, 134, 114, 200, 22, 200];
 
+/// Whirlpool sqrt price bounds (Q64.64), matching MIN_SQRT_PRICE_X64/MAX_SQRT_PRICE_X64 of the program
+pub const WHIRLPOOL_MIN_SQRT_PRICE_X64: u128 = 4295048016;
+pub const WHIRLPOOL_MAX_SQRT_PRICE_X64: u128 = 79226673515401279992447579055;
+/// A whirlpool has NUM_REWARDS = 3 reward slots
+pub const WHIRLPOOL_NUM_REWARDS: u8 = 3;
 pub const ORCA_WHIRLPOOL_PROGRAM_ID: Pubkey = pubkey!("whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc");
+
+pub const ORCA_SWAP_ACCOUNTS: &[&str] = &[
+    "tokenProgram", "tokenAuthority", "whirlpool", "tokenOwnerAccountA", "tokenVaultA",
+    "tokenOwnerAccountB", "tokenVaultB", "tickArray0", "tickArray1", "tickArray2", "oracle",
+];
+pub const ORCA_SWAP_V2_ACCOUNTS: &[&str] = &[
+    "tokenProgramA", "tokenProgramB", "memoProgram", "tokenAuthority", "whirlpool", "tokenMintA",
+    "tokenMintB", "tokenOwnerAccountA", "tokenVaultA", "tokenOwnerAccountB", "tokenVaultB",
+    "tickArray0", "tickArray1", "tickArray2", "oracle",
+];
+pub const ORCA_LIQUIDITY_ACCOUNTS: &[&str] = &[
+    "whirlpool", "tokenProgram", "positionAuthority", "position", "positionTokenAccount",
+    "tokenOwnerAccountA", "tokenOwnerAccountB", "tokenVaultA", "tokenVaultB", "tickArrayLower",
+    "tickArrayUpper",
+];
+pub const ORCA_LIQUIDITY_V2_ACCOUNTS: &[&str] = &[
+    "whirlpool", "tokenProgramA", "tokenProgramB", "memoProgram", "positionAuthority", "position",
+    "positionTokenAccount", "tokenMintA", "tokenMintB", "tokenOwnerAccountA", "tokenOwnerAccountB",
+    "tokenVaultA", "tokenVaultB", "tickArrayLower", "tickArrayUpper",
+];
+pub const ORCA_INITIALIZE_POOL_ACCOUNTS: &[&str] = &[
+    "whirlpoolsConfig", "tokenMintA", "tokenMintB", "funder", "whirlpool", "tokenVaultA",
+    "tokenVaultB", "feeTier", "tokenProgram", "systemProgram", "rent",
+];
+pub const ORCA_INITIALIZE_POOL_V2_ACCOUNTS: &[&str] = &[
+    "whirlpoolsConfig", "tokenMintA", "tokenMintB", "tokenBadgeA", "tokenBadgeB", "funder",
+    "whirlpool", "tokenVaultA", "tokenVaultB", "feeTier", "tokenProgramA", "tokenProgramB",
+    "systemProgram", "rent",
+];
+pub const ORCA_OPEN_POSITION_ACCOUNTS: &[&str] = &[
+    "funder", "owner", "position", "positionMint", "positionTokenAccount", "whirlpool",
+    "tokenProgram", "systemProgram", "rent", "associatedTokenProgram",
+];
+pub const ORCA_CLOSE_POSITION_ACCOUNTS: &[&str] = &[
+    "positionAuthority", "receiver", "position", "positionMint", "positionTokenAccount",
+    "tokenProgram",
+];
+pub const ORCA_TWO_HOP_SWAP_ACCOUNTS: &[&str] = &[
+    "tokenProgram", "tokenAuthority", "whirlpoolOne", "whirlpoolTwo", "tokenOwnerAccountOneA",
+    "tokenVaultOneA", "tokenOwnerAccountOneB", "tokenVaultOneB", "tokenOwnerAccountTwoA",
+    "tokenVaultTwoA", "tokenOwnerAccountTwoB", "tokenVaultTwoB", "tickArrayOne0", "tickArrayOne1",
+    "tickArrayOne2", "tickArrayTwo0", "tickArrayTwo1", "tickArrayTwo2", "oracleOne", "oracleTwo",
+];
+pub const ORCA_TWO_HOP_SWAP_V2_ACCOUNTS: &[&str] = &[
+    "whirlpoolOne", "whirlpoolTwo", "tokenMintInput", "tokenMintIntermediate", "tokenMintOutput",
+    "tokenProgramInput", "tokenProgramIntermediate", "tokenProgramOutput", "tokenOwnerAccountInput",
+    "tokenVaultOneInput", "tokenVaultOneIntermediate", "tokenVaultTwoIntermediate",
+    "tokenVaultTwoOutput", "tokenOwnerAccountOutput", "tokenAuthority", "tickArrayOne0",
+    "tickArrayOne1", "tickArrayOne2", "tickArrayTwo0", "tickArrayTwo1", "tickArrayTwo2",
+    "oracleOne", "oracleTwo", "memoProgram",
+];
+pub const ORCA_OPEN_BUNDLED_POSITION_ACCOUNTS: &[&str] = &[
+    "bundledPosition", "positionBundle", "positionBundleTokenAccount", "positionBundleAuthority",
+    "whirlpool", "funder", "systemProgram", "rent",
+];
+pub const ORCA_CLOSE_BUNDLED_POSITION_ACCOUNTS: &[&str] = &[
+    "bundledPosition", "positionBundle", "positionBundleTokenAccount", "positionBundleAuthority",
+    "receiver",
+];
+pub const ORCA_OPEN_POSITION_WITH_TOKEN_EXTENSIONS_ACCOUNTS: &[&str] = &[
+    "funder", "owner", "position", "positionMint", "positionTokenAccount", "whirlpool",
+    "token2022Program", "systemProgram", "associatedTokenProgram", "metadataUpdateAuth",
+];
+pub const ORCA_CLOSE_POSITION_WITH_TOKEN_EXTENSIONS_ACCOUNTS: &[&str] = &[
+    "positionAuthority", "receiver", "position", "positionMint", "positionTokenAccount",
+    "token2022Program",
+];
+pub const ORCA_COLLECT_FEES_ACCOUNTS: &[&str] = &[
+    "whirlpool", "positionAuthority", "position", "positionTokenAccount", "tokenOwnerAccountA",
+    "tokenVaultA", "tokenOwnerAccountB", "tokenVaultB", "tokenProgram",
+];
+pub const ORCA_COLLECT_FEES_V2_ACCOUNTS: &[&str] = &[
+    "whirlpool", "positionAuthority", "position", "positionTokenAccount", "tokenMintA",
+    "tokenMintB", "tokenOwnerAccountA", "tokenVaultA", "tokenOwnerAccountB", "tokenVaultB",
+    "tokenProgramA", "tokenProgramB", "memoProgram",
+];
+pub const ORCA_COLLECT_REWARD_ACCOUNTS: &[&str] = &[
+    "whirlpool", "positionAuthority", "position", "positionTokenAccount", "rewardOwnerAccount",
+    "rewardVault", "tokenProgram",
+];
+pub const ORCA_COLLECT_REWARD_V2_ACCOUNTS: &[&str] = &[
+    "whirlpool", "positionAuthority", "position", "positionTokenAccount", "rewardOwnerAccount",
+    "rewardMint", "rewardVault", "rewardTokenProgram", "memoProgram",
+];
+pub const ORCA_COLLECT_PROTOCOL_FEES_ACCOUNTS: &[&str] = &[
+    "whirlpoolsConfig", "whirlpool", "collectProtocolFeesAuthority", "tokenVaultA", "tokenVaultB",
+    "tokenDestinationA", "tokenDestinationB", "tokenProgram",
+];
+pub const ORCA_COLLECT_PROTOCOL_FEES_V2_ACCOUNTS: &[&str] = &[
+    "whirlpoolsConfig", "whirlpool", "collectProtocolFeesAuthority", "tokenMintA", "tokenMintB",
+    "tokenVaultA", "tokenVaultB", "tokenDestinationA", "tokenDestinationB", "tokenProgramA",
+    "tokenProgramB", "memoProgram",
+];
 pub const SYSTEM_PROGRAM_ID: Pubkey = pubkey!("11111111111111111111111111111111");
 pub const TOKEN_PROGRAM_ID: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
 pub const TOKEN_2022_PROGRAM_ID: Pubkey = pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
@@ -29,6 +142,20 @@ pub enum OrcaInstructionType {
     ClosePosition,
     SwapV2,
     TwoHopSwap,
+    OpenBundledPosition,
+    CloseBundledPosition,
+    OpenPositionWithTokenExtensions,
+    ClosePositionWithTokenExtensions,
+    InitializePoolV2,
+    IncreaseLiquidityV2,
+    DecreaseLiquidityV2,
+    TwoHopSwapV2,
+    CollectFees,
+    CollectReward,
+    CollectProtocolFees,
+    CollectFeesV2,
+    CollectRewardV2,
+    CollectProtocolFeesV2,
 }
 
 #[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
@@ -64,6 +191,22 @@ pub struct DecreaseLiquidityParams {
     pub token_min_b: u64,
 }
 
+#[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
+pub struct IncreaseLiquidityV2Params {
+    pub liquidity_amount: u128,
+    pub token_max_a: u64,
+    pub token_max_b: u64,
+    pub remaining_accounts_info: Option<RemainingAccountsInfo>,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
+pub struct DecreaseLiquidityV2Params {
+    pub liquidity_amount: u128,
+    pub token_min_a: u64,
+    pub token_min_b: u64,
+    pub remaining_accounts_info: Option<RemainingAccountsInfo>,
+}
+
 #[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
 pub struct InitializePoolParams {
     pub bumps: WhirlpoolBumps,
@@ -71,6 +214,13 @@ pub struct InitializePoolParams {
     pub initial_sqrt_price: u128,
 }
 
+/// The V2 pool has no bumps argument, the whirlpool PDA bump is derived on chain
+#[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
+pub struct InitializePoolV2Params {
+    pub tick_spacing: u16,
+    pub initial_sqrt_price: u128,
+}
+
 #[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
 pub struct OpenPositionParams {
     pub bumps: OpenPositionBumps,
@@ -78,6 +228,25 @@ pub struct OpenPositionParams {
     pub tick_upper_index: i32,
 }
 
+#[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
+pub struct OpenBundledPositionParams {
+    pub bundle_index: u16,
+    pub tick_lower_index: i32,
+    pub tick_upper_index: i32,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
+pub struct CloseBundledPositionParams {
+    pub bundle_index: u16,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
+pub struct OpenPositionWithTokenExtensionsParams {
+    pub tick_lower_index: i32,
+    pub tick_upper_index: i32,
+    pub with_token_metadata_extension: bool,
+}
+
 #[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
 pub struct TwoHopSwapParams {
     pub amount: u64,
@@ -89,6 +258,34 @@ pub struct TwoHopSwapParams {
     pub sqrt_price_limit_two: u128,
 }
 
+#[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
+pub struct TwoHopSwapV2Params {
+    pub amount: u64,
+    pub other_amount_threshold: u64,
+    pub amount_specified_is_input: bool,
+    pub a_to_b_one: bool,
+    pub a_to_b_two: bool,
+    pub sqrt_price_limit_one: u128,
+    pub sqrt_price_limit_two: u128,
+    pub remaining_accounts_info: Option<RemainingAccountsInfo>,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
+pub struct CollectRewardParams {
+    pub reward_index: u8,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
+pub struct CollectRewardV2Params {
+    pub reward_index: u8,
+    pub remaining_accounts_info: Option<RemainingAccountsInfo>,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
+pub struct CollectV2Params {
+    pub remaining_accounts_info: Option<RemainingAccountsInfo>,
+}
+
 #[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
 pub struct WhirlpoolBumps {
     pub whirlpool_bump: u8,
@@ -139,6 +336,20 @@ pub fn get_orca_instruction_type(data: &[u8]) -> Option<OrcaInstructionType> {
         Some(d) if d == ORCA_OPEN_POSITION_INSTRUCTION_DISCRIMINATOR => Some(OrcaInstructionType::OpenPosition),
         Some(d) if d == ORCA_CLOSE_POSITION_INSTRUCTION_DISCRIMINATOR => Some(OrcaInstructionType::ClosePosition),
         Some(d) if d == ORCA_TWO_HOP_SWAP_INSTRUCTION_DISCRIMINATOR => Some(OrcaInstructionType::TwoHopSwap),
+        Some(d) if d == ORCA_OPEN_BUNDLED_POSITION_INSTRUCTION_DISCRIMINATOR => Some(OrcaInstructionType::OpenBundledPosition),
+        Some(d) if d == ORCA_CLOSE_BUNDLED_POSITION_INSTRUCTION_DISCRIMINATOR => Some(OrcaInstructionType::CloseBundledPosition),
+        Some(d) if d == ORCA_OPEN_POSITION_WITH_TOKEN_EXTENSIONS_INSTRUCTION_DISCRIMINATOR => Some(OrcaInstructionType::OpenPositionWithTokenExtensions),
+        Some(d) if d == ORCA_CLOSE_POSITION_WITH_TOKEN_EXTENSIONS_INSTRUCTION_DISCRIMINATOR => Some(OrcaInstructionType::ClosePositionWithTokenExtensions),
+        Some(d) if d == ORCA_INITIALIZE_POOL_V2_INSTRUCTION_DISCRIMINATOR => Some(OrcaInstructionType::InitializePoolV2),
+        Some(d) if d == ORCA_INCREASE_LIQUIDITY_V2_INSTRUCTION_DISCRIMINATOR => Some(OrcaInstructionType::IncreaseLiquidityV2),
+        Some(d) if d == ORCA_DECREASE_LIQUIDITY_V2_INSTRUCTION_DISCRIMINATOR => Some(OrcaInstructionType::DecreaseLiquidityV2),
+        Some(d) if d == ORCA_TWO_HOP_SWAP_V2_INSTRUCTION_DISCRIMINATOR => Some(OrcaInstructionType::TwoHopSwapV2),
+        Some(d) if d == ORCA_COLLECT_FEES_INSTRUCTION_DISCRIMINATOR => Some(OrcaInstructionType::CollectFees),
+        Some(d) if d == ORCA_COLLECT_REWARD_INSTRUCTION_DISCRIMINATOR => Some(OrcaInstructionType::CollectReward),
+        Some(d) if d == ORCA_COLLECT_PROTOCOL_FEES_INSTRUCTION_DISCRIMINATOR => Some(OrcaInstructionType::CollectProtocolFees),
+        Some(d) if d == ORCA_COLLECT_FEES_V2_INSTRUCTION_DISCRIMINATOR => Some(OrcaInstructionType::CollectFeesV2),
+        Some(d) if d == ORCA_COLLECT_REWARD_V2_INSTRUCTION_DISCRIMINATOR => Some(OrcaInstructionType::CollectRewardV2),
+        Some(d) if d == ORCA_COLLECT_PROTOCOL_FEES_V2_INSTRUCTION_DISCRIMINATOR => Some(OrcaInstructionType::CollectProtocolFeesV2),
         _ => None,
     }
 }
@@ -163,7 +374,7 @@ pub fn deserialize_orca_swap_instruction(data: &[u8], accounts_indices: &[u8], a
 
     let params = SwapParams::try_from_slice(&data[8..]).map_err(|e| format!("Failed to deserialize swap params: {}", e))?;
 
-    let parsed_data = object! {
+    let mut parsed_data = object! {
         "amount" => params.amount.to_string(),
         "otherAmountThreshold" => params.other_amount_threshold.to_string(),
         "sqrtPriceLimit" => params.sqrt_price_limit.to_string(),
@@ -171,6 +382,11 @@ pub fn deserialize_orca_swap_instruction(data: &[u8], accounts_indices: &[u8], a
         "aToB" => params.a_to_b,
     };
 
+    let mut sanity = ValueSanity::new();
+    sanity.check_nonzero("amount", params.amount as u128);
+    check_sqrt_price_limit(&mut sanity, "sqrtPriceLimit", params.sqrt_price_limit);
+    sanity.apply(&mut parsed_data);
+
     Ok(create_standardized_instruction(
         &ORCA_WHIRLPOOL_PROGRAM_ID,
         "Swap",
@@ -204,7 +420,7 @@ pub fn deserialize_orca_swap_v2_instruction(data: &[u8], accounts_indices: &[u8]
 
     let params = SwapV2Params::try_from_slice(&data[8..]).map_err(|e| format!("Failed to deserialize swapV2 params: {}", e))?;
 
-    let parsed_data = object! {
+    let mut parsed_data = object! {
         "amount" => params.amount.to_string(),
         "otherAmountThreshold" => params.other_amount_threshold.to_string(),
         "sqrtPriceLimit" => params.sqrt_price_limit.to_string(),
@@ -212,6 +428,11 @@ pub fn deserialize_orca_swap_v2_instruction(data: &[u8], accounts_indices: &[u8]
         "aToB" => params.a_to_b,
     };
 
+    let mut sanity = ValueSanity::new();
+    sanity.check_nonzero("amount", params.amount as u128);
+    check_sqrt_price_limit(&mut sanity, "sqrtPriceLimit", params.sqrt_price_limit);
+    sanity.apply(&mut parsed_data);
+
     Ok(create_standardized_instruction(
         &ORCA_WHIRLPOOL_PROGRAM_ID,
         "SwapV2",
@@ -245,12 +466,16 @@ pub fn deserialize_orca_increase_liquidity_instruction(data: &[u8], accounts_ind
 
     let params = IncreaseLiquidityParams::try_from_slice(&data[8..]).map_err(|e| format!("Failed to deserialize increaseLiquidity params: {}", e))?;
 
-    let parsed_data = object! {
+    let mut parsed_data = object! {
         "liquidityAmount" => params.liquidity_amount.to_string(),
         "tokenMaxA" => params.token_max_a.to_string(),
         "tokenMaxB" => params.token_max_b.to_string(),
     };
 
+    let mut sanity = ValueSanity::new();
+    sanity.check_nonzero("liquidityAmount", params.liquidity_amount);
+    sanity.apply(&mut parsed_data);
+
     Ok(create_standardized_instruction(
         &ORCA_WHIRLPOOL_PROGRAM_ID,
         "IncreaseLiquidity",
@@ -284,12 +509,16 @@ pub fn deserialize_orca_decrease_liquidity_instruction(data: &[u8], accounts_ind
 
     let params = DecreaseLiquidityParams::try_from_slice(&data[8..]).map_err(|e| format!("Failed to deserialize decreaseLiquidity params: {}", e))?;
 
-    let parsed_data = object! {
+    let mut parsed_data = object! {
         "liquidityAmount" => params.liquidity_amount.to_string(),
         "tokenMinA" => params.token_min_a.to_string(),
         "tokenMinB" => params.token_min_b.to_string(),
     };
 
+    let mut sanity = ValueSanity::new();
+    sanity.check_nonzero("liquidityAmount", params.liquidity_amount);
+    sanity.apply(&mut parsed_data);
+
     Ok(create_standardized_instruction(
         &ORCA_WHIRLPOOL_PROGRAM_ID,
         "DecreaseLiquidity",
@@ -303,6 +532,70 @@ pub fn deserialize_orca_decrease_liquidity_instruction(data: &[u8], accounts_ind
     ))
 }
 
+/// Accounts: whirlpool=0, tokenProgramA=1, tokenProgramB=2, memoProgram=3, positionAuthority=4,
+/// position=5, positionTokenAccount=6, tokenMintA=7, tokenMintB=8, tokenOwnerAccountA=9,
+/// tokenOwnerAccountB=10, tokenVaultA=11, tokenVaultB=12, tickArrayLower=13, tickArrayUpper=14
+pub fn deserialize_orca_increase_liquidity_v2_instruction(data: &[u8], accounts_indices: &[u8], account_keys: &[Pubkey], is_signer: &[bool], is_writable: &[bool]) -> Result<JsonValue, String> {
+    if data.len() < ORCA_INCREASE_LIQUIDITY_V2_INSTRUCTION_DISCRIMINATOR.len() {
+        return Err("Data length is insufficient for an 'increaseLiquidityV2' instruction.".to_string());
+    }
+
+    let params = IncreaseLiquidityV2Params::try_from_slice(&data[8..]).map_err(|e| format!("Failed to deserialize increaseLiquidityV2 params: {}", e))?;
+
+    let mut parsed_data = object! {
+        "liquidityAmount" => params.liquidity_amount.to_string(),
+        "tokenMaxA" => params.token_max_a.to_string(),
+        "tokenMaxB" => params.token_max_b.to_string(),
+    };
+
+    let mut sanity = ValueSanity::new();
+    sanity.check_nonzero("liquidityAmount", params.liquidity_amount);
+    sanity.apply(&mut parsed_data);
+
+    Ok(create_standardized_instruction(
+        &ORCA_WHIRLPOOL_PROGRAM_ID,
+        "IncreaseLiquidityV2",
+        "Orca",
+        data,
+        accounts_indices,
+        account_keys,
+        is_signer,
+        is_writable,
+        parsed_data
+    ))
+}
+
+/// Same accounts as IncreaseLiquidityV2
+pub fn deserialize_orca_decrease_liquidity_v2_instruction(data: &[u8], accounts_indices: &[u8], account_keys: &[Pubkey], is_signer: &[bool], is_writable: &[bool]) -> Result<JsonValue, String> {
+    if data.len() < ORCA_DECREASE_LIQUIDITY_V2_INSTRUCTION_DISCRIMINATOR.len() {
+        return Err("Data length is insufficient for a 'decreaseLiquidityV2' instruction.".to_string());
+    }
+
+    let params = DecreaseLiquidityV2Params::try_from_slice(&data[8..]).map_err(|e| format!("Failed to deserialize decreaseLiquidityV2 params: {}", e))?;
+
+    let mut parsed_data = object! {
+        "liquidityAmount" => params.liquidity_amount.to_string(),
+        "tokenMinA" => params.token_min_a.to_string(),
+        "tokenMinB" => params.token_min_b.to_string(),
+    };
+
+    let mut sanity = ValueSanity::new();
+    sanity.check_nonzero("liquidityAmount", params.liquidity_amount);
+    sanity.apply(&mut parsed_data);
+
+    Ok(create_standardized_instruction(
+        &ORCA_WHIRLPOOL_PROGRAM_ID,
+        "DecreaseLiquidityV2",
+        "Orca",
+        data,
+        accounts_indices,
+        account_keys,
+        is_signer,
+        is_writable,
+        parsed_data
+    ))
+}
+
 pub fn deserialize_orca_initialize_pool_instruction(data: &[u8], accounts_indices: &[u8], account_keys: &[Pubkey], is_signer: &[bool], is_writable: &[bool]) -> Result<JsonValue, String> {
     if data.len() < ORCA_INITIALIZE_POOL_INSTRUCTION_DISCRIMINATOR.len() {
         return Err("Data length is insufficient for an 'initializePool' instruction.".to_string());
@@ -323,11 +616,16 @@ pub fn deserialize_orca_initialize_pool_instruction(data: &[u8], accounts_indice
 
     let params = InitializePoolParams::try_from_slice(&data[8..]).map_err(|e| format!("Failed to deserialize initializePool params: {}", e))?;
 
-    let parsed_data = object! {
+    let mut parsed_data = object! {
         "tickSpacing" => params.tick_spacing,
         "initialSqrtPrice" => params.initial_sqrt_price.to_string(),
     };
 
+    let mut sanity = ValueSanity::new();
+    sanity.check_nonzero("tickSpacing", params.tick_spacing as u128);
+    sanity.check_range_u128("initialSqrtPrice", params.initial_sqrt_price, WHIRLPOOL_MIN_SQRT_PRICE_X64, WHIRLPOOL_MAX_SQRT_PRICE_X64);
+    sanity.apply(&mut parsed_data);
+
     Ok(create_standardized_instruction(
         &ORCA_WHIRLPOOL_PROGRAM_ID,
         "InitializePool",
@@ -341,6 +639,38 @@ pub fn deserialize_orca_initialize_pool_instruction(data: &[u8], accounts_indice
     ))
 }
 
+/// Accounts: whirlpoolsConfig=0, tokenMintA=1, tokenMintB=2, tokenBadgeA=3, tokenBadgeB=4, funder=5,
+/// whirlpool=6, tokenVaultA=7, tokenVaultB=8, feeTier=9, tokenProgramA=10, tokenProgramB=11
+pub fn deserialize_orca_initialize_pool_v2_instruction(data: &[u8], accounts_indices: &[u8], account_keys: &[Pubkey], is_signer: &[bool], is_writable: &[bool]) -> Result<JsonValue, String> {
+    if data.len() < ORCA_INITIALIZE_POOL_V2_INSTRUCTION_DISCRIMINATOR.len() {
+        return Err("Data length is insufficient for an 'initializePoolV2' instruction.".to_string());
+    }
+
+    let params = InitializePoolV2Params::try_from_slice(&data[8..]).map_err(|e| format!("Failed to deserialize initializePoolV2 params: {}", e))?;
+
+    let mut parsed_data = object! {
+        "tickSpacing" => params.tick_spacing,
+        "initialSqrtPrice" => params.initial_sqrt_price.to_string(),
+    };
+
+    let mut sanity = ValueSanity::new();
+    sanity.check_nonzero("tickSpacing", params.tick_spacing as u128);
+    sanity.check_range_u128("initialSqrtPrice", params.initial_sqrt_price, WHIRLPOOL_MIN_SQRT_PRICE_X64, WHIRLPOOL_MAX_SQRT_PRICE_X64);
+    sanity.apply(&mut parsed_data);
+
+    Ok(create_standardized_instruction(
+        &ORCA_WHIRLPOOL_PROGRAM_ID,
+        "InitializePoolV2",
+        "Orca",
+        data,
+        accounts_indices,
+        account_keys,
+        is_signer,
+        is_writable,
+        parsed_data
+    ))
+}
+
 pub fn deserialize_orca_open_position_instruction(data: &[u8], accounts_indices: &[u8], account_keys: &[Pubkey], is_signer: &[bool], is_writable: &[bool]) -> Result<JsonValue, String> {
     if data.len() < ORCA_OPEN_POSITION_INSTRUCTION_DISCRIMINATOR.len() {
         return Err("Data length is insufficient for an 'openPosition' instruction.".to_string());
@@ -412,6 +742,114 @@ pub fn deserialize_orca_close_position_instruction(data: &[u8], accounts_indices
     ))
 }
 
+/// Accounts: bundledPosition=0, positionBundle=1, positionBundleTokenAccount=2,
+/// positionBundleAuthority=3, whirlpool=4, funder=5
+pub fn deserialize_orca_open_bundled_position_instruction(data: &[u8], accounts_indices: &[u8], account_keys: &[Pubkey], is_signer: &[bool], is_writable: &[bool]) -> Result<JsonValue, String> {
+    if data.len() < ORCA_OPEN_BUNDLED_POSITION_INSTRUCTION_DISCRIMINATOR.len() {
+        return Err("Data length is insufficient for an 'openBundledPosition' instruction.".to_string());
+    }
+
+    let params = OpenBundledPositionParams::try_from_slice(&data[8..]).map_err(|e| format!("Failed to deserialize openBundledPosition params: {}", e))?;
+
+    let parsed_data = object! {
+        "bundleIndex" => params.bundle_index,
+        "tickLowerIndex" => params.tick_lower_index,
+        "tickUpperIndex" => params.tick_upper_index,
+        "positionKind" => "bundled",
+    };
+
+    Ok(create_standardized_instruction(
+        &ORCA_WHIRLPOOL_PROGRAM_ID,
+        "OpenBundledPosition",
+        "Orca",
+        data,
+        accounts_indices,
+        account_keys,
+        is_signer,
+        is_writable,
+        parsed_data
+    ))
+}
+
+/// Accounts: bundledPosition=0, positionBundle=1, positionBundleTokenAccount=2,
+/// positionBundleAuthority=3, receiver=4
+pub fn deserialize_orca_close_bundled_position_instruction(data: &[u8], accounts_indices: &[u8], account_keys: &[Pubkey], is_signer: &[bool], is_writable: &[bool]) -> Result<JsonValue, String> {
+    if data.len() < ORCA_CLOSE_BUNDLED_POSITION_INSTRUCTION_DISCRIMINATOR.len() {
+        return Err("Data length is insufficient for a 'closeBundledPosition' instruction.".to_string());
+    }
+
+    let params = CloseBundledPositionParams::try_from_slice(&data[8..]).map_err(|e| format!("Failed to deserialize closeBundledPosition params: {}", e))?;
+
+    let parsed_data = object! {
+        "bundleIndex" => params.bundle_index,
+        "positionKind" => "bundled",
+    };
+
+    Ok(create_standardized_instruction(
+        &ORCA_WHIRLPOOL_PROGRAM_ID,
+        "CloseBundledPosition",
+        "Orca",
+        data,
+        accounts_indices,
+        account_keys,
+        is_signer,
+        is_writable,
+        parsed_data
+    ))
+}
+
+/// Token-2022 position NFT. Accounts: funder=0, owner=1, position=2, positionMint=3,
+/// positionTokenAccount=4, whirlpool=5
+pub fn deserialize_orca_open_position_with_token_extensions_instruction(data: &[u8], accounts_indices: &[u8], account_keys: &[Pubkey], is_signer: &[bool], is_writable: &[bool]) -> Result<JsonValue, String> {
+    if data.len() < ORCA_OPEN_POSITION_WITH_TOKEN_EXTENSIONS_INSTRUCTION_DISCRIMINATOR.len() {
+        return Err("Data length is insufficient for an 'openPositionWithTokenExtensions' instruction.".to_string());
+    }
+
+    let params = OpenPositionWithTokenExtensionsParams::try_from_slice(&data[8..]).map_err(|e| format!("Failed to deserialize openPositionWithTokenExtensions params: {}", e))?;
+
+    let parsed_data = object! {
+        "tickLowerIndex" => params.tick_lower_index,
+        "tickUpperIndex" => params.tick_upper_index,
+        "withTokenMetadataExtension" => params.with_token_metadata_extension,
+        "positionKind" => "token_extensions",
+    };
+
+    Ok(create_standardized_instruction(
+        &ORCA_WHIRLPOOL_PROGRAM_ID,
+        "OpenPositionWithTokenExtensions",
+        "Orca",
+        data,
+        accounts_indices,
+        account_keys,
+        is_signer,
+        is_writable,
+        parsed_data
+    ))
+}
+
+/// Accounts: positionAuthority=0, receiver=1, position=2, positionMint=3, positionTokenAccount=4
+pub fn deserialize_orca_close_position_with_token_extensions_instruction(data: &[u8], accounts_indices: &[u8], account_keys: &[Pubkey], is_signer: &[bool], is_writable: &[bool]) -> Result<JsonValue, String> {
+    if data.len() < ORCA_CLOSE_POSITION_WITH_TOKEN_EXTENSIONS_INSTRUCTION_DISCRIMINATOR.len() {
+        return Err("Data length is insufficient for a 'closePositionWithTokenExtensions' instruction.".to_string());
+    }
+
+    let parsed_data = object! {
+        "positionKind" => "token_extensions",
+    };
+
+    Ok(create_standardized_instruction(
+        &ORCA_WHIRLPOOL_PROGRAM_ID,
+        "ClosePositionWithTokenExtensions",
+        "Orca",
+        data,
+        accounts_indices,
+        account_keys,
+        is_signer,
+        is_writable,
+        parsed_data
+    ))
+}
+
 pub fn deserialize_orca_two_hop_swap_instruction(data: &[u8], accounts_indices: &[u8], account_keys: &[Pubkey], is_signer: &[bool], is_writable: &[bool]) -> Result<JsonValue, String> {
     if data.len() < ORCA_TWO_HOP_SWAP_INSTRUCTION_DISCRIMINATOR.len() {
         return Err("Data length is insufficient for a 'twoHopSwap' instruction.".to_string());
@@ -432,7 +870,14 @@ pub fn deserialize_orca_two_hop_swap_instruction(data: &[u8], accounts_indices:
 
     let params = TwoHopSwapParams::try_from_slice(&data[8..]).map_err(|e| format!("Failed to deserialize twoHopSwap params: {}", e))?;
 
-    let parsed_data = object! {
+    // Accounts: whirlpoolOne=2, whirlpoolTwo=3, tokenVaultOneA=5, tokenVaultOneB=7, tokenVaultTwoA=9, tokenVaultTwoB=11
+    let hops = flatten_two_hop_route(
+        &params,
+        [safely_get_account(2), safely_get_account(5), safely_get_account(7)],
+        [safely_get_account(3), safely_get_account(9), safely_get_account(11)],
+    );
+
+    let mut parsed_data = object! {
         "amount" => params.amount.to_string(),
         "otherAmountThreshold" => params.other_amount_threshold.to_string(),
         "amountSpecifiedIsInput" => params.amount_specified_is_input,
@@ -440,8 +885,15 @@ pub fn deserialize_orca_two_hop_swap_instruction(data: &[u8], accounts_indices:
         "aToBTwo" => params.a_to_b_two,
         "sqrtPriceLimitOne" => params.sqrt_price_limit_one.to_string(),
         "sqrtPriceLimitTwo" => params.sqrt_price_limit_two.to_string(),
+        "hops" => hops,
     };
 
+    let mut sanity = ValueSanity::new();
+    sanity.check_nonzero("amount", params.amount as u128);
+    check_sqrt_price_limit(&mut sanity, "sqrtPriceLimitOne", params.sqrt_price_limit_one);
+    check_sqrt_price_limit(&mut sanity, "sqrtPriceLimitTwo", params.sqrt_price_limit_two);
+    sanity.apply(&mut parsed_data);
+
     Ok(create_standardized_instruction(
         &ORCA_WHIRLPOOL_PROGRAM_ID,
         "TwoHopSwap",
@@ -453,4 +905,200 @@ pub fn deserialize_orca_two_hop_swap_instruction(data: &[u8], accounts_indices:
         is_writable,
         parsed_data
     ))
-}
\ No newline at end of file
+}
+
+pub fn deserialize_orca_two_hop_swap_v2_instruction(data: &[u8], accounts_indices: &[u8], account_keys: &[Pubkey], is_signer: &[bool], is_writable: &[bool]) -> Result<JsonValue, String> {
+    if data.len() < ORCA_TWO_HOP_SWAP_V2_INSTRUCTION_DISCRIMINATOR.len() {
+        return Err("Data length is insufficient for a 'twoHopSwapV2' instruction.".to_string());
+    }
+
+    let safely_get_account = |idx: usize| -> String {
+        if idx >= accounts_indices.len() {
+            return "Unknown".to_string();
+        }
+        
+        let account_idx = accounts_indices[idx] as usize;
+        if account_idx >= account_keys.len() {
+            return "Unknown".to_string();
+        }
+        
+        account_keys[account_idx].to_string()
+    };
+
+    let params = TwoHopSwapV2Params::try_from_slice(&data[8..]).map_err(|e| format!("Failed to deserialize twoHopSwapV2 params: {}", e))?;
+    let route = TwoHopSwapParams {
+        amount: params.amount,
+        other_amount_threshold: params.other_amount_threshold,
+        amount_specified_is_input: params.amount_specified_is_input,
+        a_to_b_one: params.a_to_b_one,
+        a_to_b_two: params.a_to_b_two,
+        sqrt_price_limit_one: params.sqrt_price_limit_one,
+        sqrt_price_limit_two: params.sqrt_price_limit_two,
+    };
+
+    // Accounts: whirlpoolOne=0, whirlpoolTwo=1, tokenVaultOneInput=9, tokenVaultOneIntermediate=10,
+    // tokenVaultTwoIntermediate=11, tokenVaultTwoOutput=12. V2 orders vaults along the route,
+    // they are put back in A/B order for flatten_two_hop_route
+    let vaults_a_b = |a_to_b: bool, input: usize, output: usize| {
+        if a_to_b { (safely_get_account(input), safely_get_account(output)) } else { (safely_get_account(output), safely_get_account(input)) }
+    };
+    let (one_a, one_b) = vaults_a_b(params.a_to_b_one, 9, 10);
+    let (two_a, two_b) = vaults_a_b(params.a_to_b_two, 11, 12);
+    let hops = flatten_two_hop_route(
+        &route,
+        [safely_get_account(0), one_a, one_b],
+        [safely_get_account(1), two_a, two_b],
+    );
+
+    let mut parsed_data = object! {
+        "amount" => params.amount.to_string(),
+        "otherAmountThreshold" => params.other_amount_threshold.to_string(),
+        "amountSpecifiedIsInput" => params.amount_specified_is_input,
+        "aToBOne" => params.a_to_b_one,
+        "aToBTwo" => params.a_to_b_two,
+        "sqrtPriceLimitOne" => params.sqrt_price_limit_one.to_string(),
+        "sqrtPriceLimitTwo" => params.sqrt_price_limit_two.to_string(),
+        "hops" => hops,
+    };
+
+    let mut sanity = ValueSanity::new();
+    sanity.check_nonzero("amount", params.amount as u128);
+    check_sqrt_price_limit(&mut sanity, "sqrtPriceLimitOne", params.sqrt_price_limit_one);
+    check_sqrt_price_limit(&mut sanity, "sqrtPriceLimitTwo", params.sqrt_price_limit_two);
+    sanity.apply(&mut parsed_data);
+
+    Ok(create_standardized_instruction(
+        &ORCA_WHIRLPOOL_PROGRAM_ID,
+        "TwoHopSwapV2",
+        "Orca",
+        data,
+        accounts_indices,
+        account_keys,
+        is_signer,
+        is_writable,
+        parsed_data
+    ))
+}
+
+/// CollectFees and CollectProtocolFees take no arguments, their V2 forms only the transfer hook accounts.
+/// The collected amounts are not in the instruction, the owed fees are settled on chain.
+/// CollectFees accounts: whirlpool=0, positionAuthority=1, position=2, positionTokenAccount=3,
+/// tokenOwnerAccountA=4, tokenVaultA=5, tokenOwnerAccountB=6, tokenVaultB=7.
+/// CollectFeesV2 accounts: whirlpool=0, positionAuthority=1, position=2, positionTokenAccount=3,
+/// tokenMintA=4, tokenMintB=5, tokenOwnerAccountA=6, tokenVaultA=7, tokenOwnerAccountB=8, tokenVaultB=9.
+/// CollectProtocolFees accounts: whirlpoolsConfig=0, whirlpool=1, collectProtocolFeesAuthority=2,
+/// tokenVaultA=3, tokenVaultB=4, tokenDestinationA=5, tokenDestinationB=6
+pub fn deserialize_orca_collect_fees_instruction(instr_type: &OrcaInstructionType, data: &[u8], accounts_indices: &[u8], account_keys: &[Pubkey], is_signer: &[bool], is_writable: &[bool]) -> Result<JsonValue, String> {
+    let (instruction_name, fee_kind, is_v2) = match instr_type {
+        OrcaInstructionType::CollectFees => ("CollectFees", "position", false),
+        OrcaInstructionType::CollectFeesV2 => ("CollectFeesV2", "position", true),
+        OrcaInstructionType::CollectProtocolFees => ("CollectProtocolFees", "protocol", false),
+        OrcaInstructionType::CollectProtocolFeesV2 => ("CollectProtocolFeesV2", "protocol", true),
+        _ => return Err("Not an Orca fee collection instruction.".to_string()),
+    };
+
+    if data.len() < ORCA_COLLECT_FEES_INSTRUCTION_DISCRIMINATOR.len() {
+        return Err(format!("Data length is insufficient for a '{}' instruction.", instruction_name));
+    }
+
+    if is_v2 {
+        CollectV2Params::try_from_slice(&data[8..]).map_err(|e| format!("Failed to deserialize {} params: {}", instruction_name, e))?;
+    } else if data.len() > 8 {
+        return Err(format!("Unexpected arguments for a '{}' instruction.", instruction_name));
+    }
+
+    let parsed_data = object! {
+        "feeKind" => fee_kind,
+    };
+
+    Ok(create_standardized_instruction(
+        &ORCA_WHIRLPOOL_PROGRAM_ID,
+        instruction_name,
+        "Orca",
+        data,
+        accounts_indices,
+        account_keys,
+        is_signer,
+        is_writable,
+        parsed_data
+    ))
+}
+
+/// CollectReward accounts: whirlpool=0, positionAuthority=1, position=2, positionTokenAccount=3,
+/// rewardOwnerAccount=4, rewardVault=5. CollectRewardV2 adds rewardMint=5 before rewardVault=6
+pub fn deserialize_orca_collect_reward_instruction(instr_type: &OrcaInstructionType, data: &[u8], accounts_indices: &[u8], account_keys: &[Pubkey], is_signer: &[bool], is_writable: &[bool]) -> Result<JsonValue, String> {
+    let instruction_name = match instr_type {
+        OrcaInstructionType::CollectReward => "CollectReward",
+        OrcaInstructionType::CollectRewardV2 => "CollectRewardV2",
+        _ => return Err("Not an Orca reward collection instruction.".to_string()),
+    };
+
+    if data.len() < ORCA_COLLECT_REWARD_INSTRUCTION_DISCRIMINATOR.len() {
+        return Err(format!("Data length is insufficient for a '{}' instruction.", instruction_name));
+    }
+
+    let reward_index = match instr_type {
+        OrcaInstructionType::CollectRewardV2 => CollectRewardV2Params::try_from_slice(&data[8..]).map(|params| params.reward_index),
+        _ => CollectRewardParams::try_from_slice(&data[8..]).map(|params| params.reward_index),
+    }.map_err(|e| format!("Failed to deserialize {} params: {}", instruction_name, e))?;
+
+    let mut parsed_data = object! {
+        "rewardIndex" => reward_index,
+    };
+
+    let mut sanity = ValueSanity::new();
+    sanity.check_range_u128("rewardIndex", reward_index as u128, 0, (WHIRLPOOL_NUM_REWARDS - 1) as u128);
+    sanity.apply(&mut parsed_data);
+
+    Ok(create_standardized_instruction(
+        &ORCA_WHIRLPOOL_PROGRAM_ID,
+        instruction_name,
+        "Orca",
+        data,
+        accounts_indices,
+        account_keys,
+        is_signer,
+        is_writable,
+        parsed_data
+    ))
+}
+
+// A zero limit means "no explicit limit" for the program, anything else must lie within the price bounds
+fn check_sqrt_price_limit(sanity: &mut ValueSanity, field: &str, sqrt_price_limit: u128) {
+    if sqrt_price_limit != 0 {
+        sanity.check_range_u128(field, sqrt_price_limit, WHIRLPOOL_MIN_SQRT_PRICE_X64, WHIRLPOOL_MAX_SQRT_PRICE_X64);
+    }
+}
+
+// Builds one sub-record per hop so that multi-hop routes can be consumed like single swaps.
+// Each hop is given as [pool, vaultA, vaultB]. Only the route edges carry known amounts:
+// exact-in routes fix the first hop input and bound the last hop output, exact-out the reverse.
+pub fn flatten_two_hop_route(params: &TwoHopSwapParams, hop_one: [String; 3], hop_two: [String; 3]) -> Vec<JsonValue> {
+    let amount = params.amount.to_string();
+    let threshold = params.other_amount_threshold.to_string();
+
+    let route_hop = |index: usize, [pool, vault_a, vault_b]: [String; 3], a_to_b: bool, sqrt_price_limit: u128| {
+        let (input_vault, output_vault) = if a_to_b { (vault_a, vault_b) } else { (vault_b, vault_a) };
+        let is_first = index == 0;
+        let is_last = index == 1;
+        let exact_in = params.amount_specified_is_input;
+
+        object! {
+            "hop" => index,
+            "pool" => pool,
+            "aToB" => a_to_b,
+            "inputVault" => input_vault,
+            "outputVault" => output_vault,
+            "sqrtPriceLimit" => sqrt_price_limit.to_string(),
+            "amountIn" => if is_first && exact_in { JsonValue::from(amount.clone()) } else { JsonValue::Null },
+            "maxAmountIn" => if is_first && !exact_in { JsonValue::from(threshold.clone()) } else { JsonValue::Null },
+            "amountOut" => if is_last && !exact_in { JsonValue::from(amount.clone()) } else { JsonValue::Null },
+            "minAmountOut" => if is_last && exact_in { JsonValue::from(threshold.clone()) } else { JsonValue::Null },
+        }
+    };
+
+    vec![
+        route_hop(0, hop_one, params.a_to_b_one, params.sqrt_price_limit_one),
+        route_hop(1, hop_two, params.a_to_b_two, params.sqrt_price_limit_two),
+    ]
+}
@@ -0,0 +1,106 @@
+use crate::encoder::{encode, EncodedPayload, OutputEncoding};
+use json::JsonValue;
+use once_cell::sync::OnceCell;
+use rustc_hash::FxHashSet as HashSet;
+use std::sync::Arc;
+
+/// A decoded output item as delivered to subscribers, together with the
+/// metadata subscribers filter on
+#[derive(Debug, Clone)]
+pub struct OutputMessage {
+    pub payload: JsonValue,
+    /// Slot of the decoded transactions, 0 when the payload carries none
+    pub slot: u64,
+    /// Position in the output stream, assigned when the message is published
+    pub sequence: u64,
+    pub timestamp: u64,
+    /// Accounts referenced by the decoded instructions, including the mints of known pools
+    pub accounts: HashSet<String>,
+    /// Program IDs and protocol names of the decoded instructions
+    pub programs: HashSet<String>,
+    /// Accounts signing one of the decoded instructions
+    pub signers: HashSet<String>,
+    /// Names of the decoded instructions, e.g. "Create" or "Swap"
+    pub instructions: HashSet<String>,
+    /// Serialized forms, each built once by the first subscriber asking for it
+    encoded: [OnceCell<EncodedPayload>; 4],
+}
+
+impl OutputMessage {
+    pub fn new(payload: JsonValue, timestamp: u64, accounts: HashSet<String>) -> Self {
+        let InstructionIndex { programs, signers, instructions } = index_instructions(&payload);
+        Self {
+            slot: payload["slot"].as_u64().unwrap_or(0),
+            sequence: 0,
+            programs,
+            signers,
+            instructions,
+            payload,
+            timestamp,
+            accounts,
+            encoded: Default::default(),
+        }
+    }
+
+    pub fn touches_any(&self, accounts: &HashSet<String>) -> bool {
+        accounts.iter().any(|account| self.accounts.contains(account))
+    }
+
+    pub fn signed_by_any(&self, accounts: &HashSet<String>) -> bool {
+        accounts.iter().any(|account| self.signers.contains(account))
+    }
+
+    pub fn has_instruction(&self, names: &HashSet<String>) -> bool {
+        names.iter().any(|name| self.instructions.contains(name))
+    }
+
+    pub fn runs_any(&self, programs: &HashSet<String>) -> bool {
+        programs.iter().any(|program| self.programs.contains(program))
+    }
+
+    pub fn encoded(&self, encoding: OutputEncoding) -> &EncodedPayload {
+        self.encoded[encoding.index()].get_or_init(|| encode(&self.payload, encoding))
+    }
+}
+
+#[derive(Default)]
+struct InstructionIndex {
+    programs: HashSet<String>,
+    signers: HashSet<String>,
+    instructions: HashSet<String>,
+}
+
+/// Programs, signers and names of the payload's instructions. Batch payloads reference
+/// accounts through the shared account-key dictionary.
+fn index_instructions(payload: &JsonValue) -> InstructionIndex {
+    let mut index = InstructionIndex::default();
+    let dictionary = |key: &JsonValue| key.as_usize().and_then(|key| payload["accountKeys"][key].as_str());
+    let transactions: Vec<&JsonValue> = if payload["transactions"].is_array() {
+        payload["transactions"].members().collect()
+    } else {
+        vec![payload]
+    };
+
+    for transaction in transactions {
+        for instruction in transaction["message"]["instructions"].members() {
+            if let Some(protocol) = instruction["protocol"].as_str() {
+                index.programs.insert(protocol.to_string());
+            }
+            if let Some(program_id) = instruction["program_id"].as_str().or_else(|| dictionary(&instruction["programIdKey"])) {
+                index.programs.insert(program_id.to_string());
+            }
+            if let Some(name) = instruction["instruction_name"].as_str() {
+                index.instructions.insert(name.to_string());
+            }
+            let signing = instruction["accounts"].members().filter(|account| account["signer"].as_bool() == Some(true));
+            for account in signing {
+                if let Some(pubkey) = account["pubkey"].as_str().or_else(|| dictionary(&account["key"])) {
+                    index.signers.insert(pubkey.to_string());
+                }
+            }
+        }
+    }
+    index
+}
+
+pub type OutputSender = tokio::sync::broadcast::Sender<Arc<OutputMessage>>;
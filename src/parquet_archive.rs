@@ -0,0 +1,204 @@
+use arrow_array::cast::AsArray;
+use arrow_array::types::{UInt32Type, UInt64Type};
+use arrow_array::{Array, ArrayRef, RecordBatch, StringArray, UInt32Array, UInt64Array};
+use arrow_schema::{ArrowError, DataType, Field, Schema};
+use json::JsonValue;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::arrow::ArrowWriter;
+use parquet::basic::{Compression, ZstdLevel};
+use parquet::file::properties::WriterProperties;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::archive::ArchiveRecord;
+use crate::output_message::OutputMessage;
+
+pub const PARQUET_EXTENSION: &str = "parquet";
+
+/// Rows buffered before they are written out as one row group
+const ROWS_PER_BATCH: usize = 16_384;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ParquetArchiveError {
+    #[error("parquet I/O failed: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("parquet encoding failed: {0}")]
+    Parquet(#[from] parquet::errors::ParquetError),
+    #[error("arrow batch failed: {0}")]
+    Arrow(#[from] arrow_schema::ArrowError),
+}
+
+/// One row per decoded instruction, the parsed params are kept as a JSON string
+/// since every protocol has its own
+fn schema() -> Arc<Schema> {
+    Arc::new(Schema::new(vec![
+        Field::new("slot", DataType::UInt64, false),
+        Field::new("timestamp", DataType::UInt64, false),
+        Field::new("signature", DataType::Utf8, false),
+        Field::new("instruction_index", DataType::UInt32, false),
+        Field::new("program_id", DataType::Utf8, true),
+        Field::new("protocol", DataType::Utf8, true),
+        Field::new("instruction_name", DataType::Utf8, true),
+        Field::new("parsed_data", DataType::Utf8, true),
+    ]))
+}
+
+#[derive(Default)]
+struct Columns {
+    slot: Vec<u64>,
+    timestamp: Vec<u64>,
+    signature: Vec<String>,
+    instruction_index: Vec<u32>,
+    program_id: Vec<Option<String>>,
+    protocol: Vec<Option<String>>,
+    instruction_name: Vec<Option<String>>,
+    parsed_data: Vec<Option<String>>,
+}
+
+impl Columns {
+    fn len(&self) -> usize {
+        self.slot.len()
+    }
+
+    fn push_transaction(&mut self, slot: u64, timestamp: u64, transaction: &JsonValue, program_id: impl Fn(&JsonValue) -> Option<String>) {
+        let signature = transaction["signatures"][0].as_str().unwrap_or_default();
+        for (position, instruction) in transaction["message"]["instructions"].members().enumerate() {
+            let text = |field: &str| instruction[field].as_str().map(str::to_string);
+            self.slot.push(slot);
+            self.timestamp.push(timestamp);
+            self.signature.push(signature.to_string());
+            self.instruction_index.push(position as u32);
+            self.program_id.push(program_id(instruction));
+            self.protocol.push(text("protocol"));
+            self.instruction_name.push(text("instruction_name"));
+            self.parsed_data.push(Some(&instruction["parsed_data"]).filter(|parsed| !parsed.is_null()).map(JsonValue::dump));
+        }
+    }
+
+    fn take_batch(&mut self) -> Result<RecordBatch, ParquetArchiveError> {
+        let Columns { slot, timestamp, signature, instruction_index, program_id, protocol, instruction_name, parsed_data } =
+            std::mem::take(self);
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(UInt64Array::from(slot)),
+            Arc::new(UInt64Array::from(timestamp)),
+            Arc::new(StringArray::from(signature)),
+            Arc::new(UInt32Array::from(instruction_index)),
+            Arc::new(StringArray::from(program_id)),
+            Arc::new(StringArray::from(protocol)),
+            Arc::new(StringArray::from(instruction_name)),
+            Arc::new(StringArray::from(parsed_data)),
+        ];
+        Ok(RecordBatch::try_new(schema(), columns)?)
+    }
+}
+
+/// Writes one Parquet file under a temporary name, renamed into place by `finish`
+/// so that a file with the final name is always complete
+pub struct ParquetSegmentWriter {
+    writer: ArrowWriter<File>,
+    columns: Columns,
+    temporary_path: PathBuf,
+    path: PathBuf,
+}
+
+impl ParquetSegmentWriter {
+    pub fn create(path: &Path) -> Result<Self, ParquetArchiveError> {
+        let mut temporary_name = path.as_os_str().to_owned();
+        temporary_name.push(".tmp");
+        let temporary_path = PathBuf::from(temporary_name);
+        let properties = WriterProperties::builder()
+            .set_compression(Compression::ZSTD(ZstdLevel::default()))
+            .build();
+        let writer = ArrowWriter::try_new(File::create(&temporary_path)?, schema(), Some(properties))?;
+        Ok(Self { writer, columns: Columns::default(), temporary_path, path: path.to_path_buf() })
+    }
+
+    pub fn append(&mut self, message: &OutputMessage) -> Result<(), ParquetArchiveError> {
+        let payload = &message.payload;
+        if payload["transactions"].is_array() {
+            // Batch payloads reference program IDs through the shared account-key dictionary
+            let program_id = |instruction: &JsonValue| {
+                instruction["programIdKey"].as_usize().and_then(|key| payload["accountKeys"][key].as_str()).map(str::to_string)
+            };
+            for transaction in payload["transactions"].members() {
+                self.columns.push_transaction(message.slot, message.timestamp, transaction, program_id);
+            }
+        } else {
+            let program_id = |instruction: &JsonValue| instruction["program_id"].as_str().map(str::to_string);
+            self.columns.push_transaction(message.slot, message.timestamp, payload, program_id);
+        }
+
+        if self.columns.len() >= ROWS_PER_BATCH {
+            let batch = self.columns.take_batch()?;
+            self.writer.write(&batch)?;
+        }
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> Result<PathBuf, ParquetArchiveError> {
+        if self.columns.len() > 0 {
+            let batch = self.columns.take_batch()?;
+            self.writer.write(&batch)?;
+        }
+        self.writer.close()?;
+        std::fs::rename(&self.temporary_path, &self.path)?;
+        Ok(self.path)
+    }
+}
+
+/// Reads back a Parquet segment, the rows of each transaction are folded into one record
+/// holding its signature and decoded instructions. Only the archived columns come back,
+/// account keys and balances are not part of the Parquet schema.
+pub fn read_parquet_segment(path: &Path) -> Result<Vec<ArchiveRecord>, ParquetArchiveError> {
+    let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(path)?)?.build()?;
+    let mut records: Vec<ArchiveRecord> = Vec::new();
+    for batch in reader {
+        let batch = batch?;
+        let column = |name: &str| {
+            batch
+                .column_by_name(name)
+                .cloned()
+                .ok_or_else(|| ArrowError::SchemaError(format!("missing column '{}'", name)))
+        };
+        let (slot, timestamp, signature) = (column("slot")?, column("timestamp")?, column("signature")?);
+        let (instruction_index, program_id) = (column("instruction_index")?, column("program_id")?);
+        let (protocol, instruction_name, parsed_data) = (column("protocol")?, column("instruction_name")?, column("parsed_data")?);
+        let slot = slot.as_primitive::<UInt64Type>();
+        let timestamp = timestamp.as_primitive::<UInt64Type>();
+        let signature = signature.as_string::<i32>();
+        let instruction_index = instruction_index.as_primitive::<UInt32Type>();
+        let (program_id, protocol) = (program_id.as_string::<i32>(), protocol.as_string::<i32>());
+        let (instruction_name, parsed_data) = (instruction_name.as_string::<i32>(), parsed_data.as_string::<i32>());
+
+        for row in 0..batch.num_rows() {
+            let text = |column: &StringArray| column.is_valid(row).then(|| column.value(row).to_string());
+            let mut instruction = json::object! {
+                "program_id" => text(program_id),
+                "protocol" => text(protocol),
+                "instruction_name" => text(instruction_name),
+            };
+            if let Some(parsed) = text(parsed_data) {
+                instruction["parsed_data"] = json::parse(&parsed).unwrap_or(JsonValue::String(parsed));
+            }
+
+            // Instruction 0 starts a transaction, the following rows belong to it
+            let continues = instruction_index.value(row) > 0
+                && records.last().is_some_and(|record| record.payload["signatures"][0] == signature.value(row));
+            if !continues {
+                records.push(ArchiveRecord {
+                    slot: slot.value(row),
+                    timestamp: timestamp.value(row),
+                    payload: json::object! {
+                        "slot" => slot.value(row),
+                        "signatures" => json::array![signature.value(row)],
+                        "message" => json::object! { "instructions" => json::array![] },
+                    },
+                });
+            }
+            let record = records.last_mut().expect("record pushed above");
+            record.payload["message"]["instructions"].push(instruction).expect("instructions is an array");
+        }
+    }
+    Ok(records)
+}
@@ -0,0 +1,581 @@
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
+
+use crate::shred_recorder::ShredLogReader;
+
+/// What a replay reads shreds from
+#[derive(Debug, Clone)]
+pub enum ReplayInput {
+    /// UDP datagrams of a pcap or pcapng capture
+    Pcap(PathBuf),
+    /// Logs of the shred recorder, a file or a directory of logs
+    ShredLog(PathBuf),
+}
+
+/// `--replay-pcap FILE` or `--replay-shreds FILE|DIR`, with `[--replay-speed X] [--replay-port PORT]`:
+/// shreds come from a capture or from recorded logs instead of the UDP sockets. Speed 1 keeps
+/// the original timing, 0 replays as fast as possible.
+#[derive(Debug, Clone)]
+pub struct ReplayOptions {
+    pub input: ReplayInput,
+    pub speed: f64,
+    /// Only UDP datagrams to this destination port, every UDP datagram when unset. Captures only.
+    pub port: Option<u16>,
+}
+
+impl ReplayOptions {
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Result<Option<Self>, String> {
+        let (mut input, mut speed, mut port) = (None, None, None);
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--replay-pcap" => input = Some(ReplayInput::Pcap(PathBuf::from(args.next().ok_or("--replay-pcap requires a capture file")?))),
+                "--replay-shreds" => input = Some(ReplayInput::ShredLog(PathBuf::from(args.next().ok_or("--replay-shreds requires a shred log or directory")?))),
+                "--replay-speed" => {
+                    let value = args.next().ok_or("--replay-speed requires a value")?;
+                    speed = Some(value.parse().ok().filter(|speed: &f64| *speed >= 0.0).ok_or_else(|| format!("Invalid --replay-speed value: {}", value))?);
+                }
+                "--replay-port" => {
+                    let value = args.next().ok_or("--replay-port requires a port")?;
+                    port = Some(value.parse().map_err(|_| format!("Invalid --replay-port value: {}", value))?);
+                }
+                _ => {}
+            }
+        }
+        match input {
+            Some(input) => Ok(Some(Self { input, speed: speed.unwrap_or(1.0), port })),
+            None if speed.is_some() || port.is_some() => Err("--replay-speed and --replay-port need --replay-pcap or --replay-shreds".to_string()),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Shreds of a replay in their original order
+pub enum ReplayReader {
+    Pcap { reader: PcapReader, port: Option<u16> },
+    ShredLog(ShredLogReader),
+}
+
+impl ReplayReader {
+    pub fn open(options: &ReplayOptions) -> Result<Self, String> {
+        match &options.input {
+            ReplayInput::Pcap(path) => Ok(ReplayReader::Pcap { reader: PcapReader::open(path)?, port: options.port }),
+            ReplayInput::ShredLog(path) => Ok(ReplayReader::ShredLog(ShredLogReader::open(path)?)),
+        }
+    }
+
+    /// Next shred with its original receive time in nanoseconds, None at the end
+    pub fn next_shred(&mut self) -> Result<Option<(u64, Vec<u8>)>, String> {
+        match self {
+            ReplayReader::Pcap { reader, port } => loop {
+                let Some(datagram) = reader.next_datagram()? else {
+                    return Ok(None);
+                };
+                if port.is_none_or(|wanted| wanted == datagram.destination_port) {
+                    return Ok(Some((datagram.timestamp_nanos, datagram.payload)));
+                }
+            },
+            ReplayReader::ShredLog(reader) => Ok(reader.next_shred()?.map(|(micros, shred)| (micros * 1000, shred))),
+        }
+    }
+}
+
+/// UDP payload of a captured frame
+#[derive(Debug)]
+pub struct CapturedDatagram {
+    /// Capture time in nanoseconds since the epoch
+    pub timestamp_nanos: u64,
+    pub destination_port: u16,
+    pub payload: Vec<u8>,
+}
+
+const PCAPNG_SECTION_HEADER: u32 = 0x0A0D_0D0A;
+const PCAPNG_BYTE_ORDER_MAGIC: u32 = 0x1A2B_3C4D;
+const PCAPNG_INTERFACE_DESCRIPTION: u32 = 1;
+const PCAPNG_SIMPLE_PACKET: u32 = 3;
+const PCAPNG_ENHANCED_PACKET: u32 = 6;
+const PCAPNG_OPTION_TSRESOL: u16 = 9;
+
+/// Longest frame buffered, a UDP datagram fits in 64 KiB. Longer frames are skipped unread.
+const MAX_FRAME_LEN: usize = 64 * 1024;
+/// Room around a frame in a pcapng block, for the block header, trailer and options
+const MAX_PCAPNG_BLOCK_OVERHEAD: usize = 4 * 1024;
+
+#[derive(Debug, Clone, Copy)]
+struct Interface {
+    link_type: u32,
+    /// Timestamp units per second
+    units_per_second: u64,
+}
+
+enum Format {
+    /// Records longer than the snaplen of the header are corrupt
+    Pcap { interface: Interface, nanosecond: bool, snaplen: u32 },
+    PcapNg { interfaces: Vec<Interface> },
+}
+
+/// Reads the UDP datagrams of a pcap or pcapng capture, frames of other protocols are skipped.
+/// Link types: Ethernet (with VLAN tags), Linux cooked v1 and v2, BSD loopback and raw IP.
+pub struct PcapReader {
+    reader: BufReader<File>,
+    big_endian: bool,
+    format: Format,
+    last_timestamp_nanos: u64,
+}
+
+impl PcapReader {
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let file = File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+        let mut reader = BufReader::new(file);
+        let mut magic = [0u8; 4];
+        reader.read_exact(&mut magic).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+
+        let mut capture = match u32::from_le_bytes(magic) {
+            0xA1B2_C3D4 => Self::pcap(reader, false, false),
+            0xD4C3_B2A1 => Self::pcap(reader, true, false),
+            0xA1B2_3C4D => Self::pcap(reader, false, true),
+            0x4D3C_B2A1 => Self::pcap(reader, true, true),
+            PCAPNG_SECTION_HEADER => Self { reader, big_endian: false, format: Format::PcapNg { interfaces: Vec::new() }, last_timestamp_nanos: 0 },
+            other => return Err(format!("{} is not a pcap or pcapng capture (magic {:#010x})", path.display(), other)),
+        };
+
+        match capture.format {
+            Format::Pcap { .. } => {
+                // version, thiszone, sigfigs and snaplen precede the link type
+                let header = capture.read_bytes(20).map_err(|e| format!("Truncated pcap header in {}: {}", path.display(), e))?;
+                let link_type = capture.u32_at(&header, 16) & 0xFFFF;
+                let header_snaplen = capture.u32_at(&header, 12);
+                if let Format::Pcap { interface, snaplen, .. } = &mut capture.format {
+                    interface.link_type = link_type;
+                    *snaplen = header_snaplen;
+                }
+            }
+            Format::PcapNg { .. } => capture.read_section_header().map_err(|e| format!("Invalid pcapng section in {}: {}", path.display(), e))?,
+        }
+        Ok(capture)
+    }
+
+    fn pcap(reader: BufReader<File>, big_endian: bool, nanosecond: bool) -> Self {
+        let units_per_second = if nanosecond { 1_000_000_000 } else { 1_000_000 };
+        Self {
+            reader,
+            big_endian,
+            format: Format::Pcap { interface: Interface { link_type: 0, units_per_second }, nanosecond, snaplen: 0 },
+            last_timestamp_nanos: 0,
+        }
+    }
+
+    fn read_bytes(&mut self, len: usize) -> std::io::Result<Vec<u8>> {
+        let mut bytes = vec![0u8; len];
+        self.reader.read_exact(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Discards bytes without buffering them
+    fn skip_bytes(&mut self, len: usize) -> std::io::Result<()> {
+        let skipped = std::io::copy(&mut (&mut self.reader).take(len as u64), &mut std::io::sink())?;
+        if skipped < len as u64 {
+            return Err(std::io::ErrorKind::UnexpectedEof.into());
+        }
+        Ok(())
+    }
+
+    /// Reads 4 bytes, None at a clean end of file
+    fn read_word(&mut self) -> std::io::Result<Option<[u8; 4]>> {
+        let mut word = [0u8; 4];
+        let mut filled = 0;
+        while filled < 4 {
+            match self.reader.read(&mut word[filled..])? {
+                0 if filled == 0 => return Ok(None),
+                0 => return Err(std::io::ErrorKind::UnexpectedEof.into()),
+                read => filled += read,
+            }
+        }
+        Ok(Some(word))
+    }
+
+    fn u16_at(&self, bytes: &[u8], offset: usize) -> u16 {
+        let raw = [bytes[offset], bytes[offset + 1]];
+        if self.big_endian { u16::from_be_bytes(raw) } else { u16::from_le_bytes(raw) }
+    }
+
+    fn u32_at(&self, bytes: &[u8], offset: usize) -> u32 {
+        let raw = [bytes[offset], bytes[offset + 1], bytes[offset + 2], bytes[offset + 3]];
+        if self.big_endian { u32::from_be_bytes(raw) } else { u32::from_le_bytes(raw) }
+    }
+
+    /// Rest of a section header block once its type was read, the byte order magic sets the endianness
+    fn read_section_header(&mut self) -> Result<(), String> {
+        let header = self.read_bytes(8).map_err(|e| e.to_string())?;
+        self.big_endian = match u32::from_le_bytes([header[4], header[5], header[6], header[7]]) {
+            PCAPNG_BYTE_ORDER_MAGIC => false,
+            magic if magic.swap_bytes() == PCAPNG_BYTE_ORDER_MAGIC => true,
+            magic => return Err(format!("unknown byte order magic {:#010x}", magic)),
+        };
+        let total_len = self.u32_at(&header, 0) as usize;
+        if total_len < 12 + 16 {
+            return Err(format!("section header of {} bytes", total_len));
+        }
+        // Version, section length, options and the trailing length
+        self.skip_bytes(total_len - 12).map_err(|e| e.to_string())?;
+        if let Format::PcapNg { interfaces } = &mut self.format {
+            interfaces.clear();
+        }
+        Ok(())
+    }
+
+    /// Next UDP datagram of the capture, None at the end
+    pub fn next_datagram(&mut self) -> Result<Option<CapturedDatagram>, String> {
+        loop {
+            let Some((interface, timestamp_units, frame)) = self.next_frame()? else {
+                return Ok(None);
+            };
+            if let Some(units) = timestamp_units {
+                self.last_timestamp_nanos = (units as u128 * 1_000_000_000 / interface.units_per_second.max(1) as u128) as u64;
+            }
+            if let Some((destination_port, payload)) = udp_payload(interface.link_type, &frame) {
+                return Ok(Some(CapturedDatagram {
+                    timestamp_nanos: self.last_timestamp_nanos,
+                    destination_port,
+                    payload: payload.to_vec(),
+                }));
+            }
+        }
+    }
+
+    /// Next captured frame with its interface and raw timestamp, pcapng simple packets have no timestamp
+    fn next_frame(&mut self) -> Result<Option<(Interface, Option<u64>, Vec<u8>)>, String> {
+        loop {
+            let Some(word) = self.read_word().map_err(|e| format!("Truncated capture: {}", e))? else {
+                return Ok(None);
+            };
+            match self.format {
+                Format::Pcap { interface, nanosecond, snaplen } => {
+                    let header = self.read_bytes(12).map_err(|e| format!("Truncated pcap record: {}", e))?;
+                    let seconds = self.u32_at(&word, 0) as u64;
+                    let fraction = self.u32_at(&header, 0) as u64;
+                    let captured_len = self.u32_at(&header, 4);
+                    if snaplen > 0 && captured_len > snaplen {
+                        return Err(format!("Corrupted pcap record of {} bytes, the snaplen is {}", captured_len, snaplen));
+                    }
+                    if captured_len as usize > MAX_FRAME_LEN {
+                        self.skip_bytes(captured_len as usize).map_err(|e| format!("Truncated pcap record: {}", e))?;
+                        continue;
+                    }
+                    let frame = self.read_bytes(captured_len as usize).map_err(|e| format!("Truncated pcap record: {}", e))?;
+                    let units = seconds * if nanosecond { 1_000_000_000 } else { 1_000_000 } + fraction;
+                    return Ok(Some((interface, Some(units), frame)));
+                }
+                Format::PcapNg { .. } => {
+                    let block_type = self.u32_at(&word, 0);
+                    if block_type == PCAPNG_SECTION_HEADER {
+                        self.read_section_header()?;
+                        continue;
+                    }
+                    let len_word = self.read_bytes(4).map_err(|e| format!("Truncated pcapng block: {}", e))?;
+                    let total_len = self.u32_at(&len_word, 0) as usize;
+                    if total_len < 12 || total_len % 4 != 0 {
+                        return Err(format!("Invalid pcapng block length {}", total_len));
+                    }
+                    if total_len > MAX_FRAME_LEN + MAX_PCAPNG_BLOCK_OVERHEAD {
+                        self.skip_bytes(total_len - 8).map_err(|e| format!("Truncated pcapng block: {}", e))?;
+                        continue;
+                    }
+                    let mut body = self.read_bytes(total_len - 8).map_err(|e| format!("Truncated pcapng block: {}", e))?;
+                    body.truncate(total_len - 12);
+                    if let Some(frame) = self.pcapng_block(block_type, body)? {
+                        return Ok(Some(frame));
+                    }
+                }
+            }
+        }
+    }
+
+    fn pcapng_block(&mut self, block_type: u32, body: Vec<u8>) -> Result<Option<(Interface, Option<u64>, Vec<u8>)>, String> {
+        match block_type {
+            PCAPNG_INTERFACE_DESCRIPTION if body.len() >= 8 => {
+                let interface = Interface { link_type: self.u16_at(&body, 0) as u32, units_per_second: self.tsresol(&body[8..]) };
+                if let Format::PcapNg { interfaces } = &mut self.format {
+                    interfaces.push(interface);
+                }
+                Ok(None)
+            }
+            PCAPNG_ENHANCED_PACKET if body.len() >= 20 => {
+                let interface = self.interface(self.u32_at(&body, 0) as usize)?;
+                let units = ((self.u32_at(&body, 4) as u64) << 32) | self.u32_at(&body, 8) as u64;
+                let captured_len = (self.u32_at(&body, 12) as usize).min(body.len() - 20);
+                Ok(Some((interface, Some(units), body[20..20 + captured_len].to_vec())))
+            }
+            PCAPNG_SIMPLE_PACKET if body.len() >= 4 => {
+                let interface = self.interface(0)?;
+                let captured_len = (self.u32_at(&body, 0) as usize).min(body.len() - 4);
+                Ok(Some((interface, None, body[4..4 + captured_len].to_vec())))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn interface(&self, id: usize) -> Result<Interface, String> {
+        match &self.format {
+            Format::PcapNg { interfaces } => interfaces.get(id).copied().ok_or_else(|| format!("Packet of undeclared interface {}", id)),
+            Format::Pcap { interface, .. } => Ok(*interface),
+        }
+    }
+
+    /// if_tsresol of an interface description, a power of 10 or of 2, microseconds by default
+    fn tsresol(&self, mut options: &[u8]) -> u64 {
+        while options.len() >= 4 {
+            let (code, len) = (self.u16_at(options, 0), self.u16_at(options, 2) as usize);
+            let padded = 4 + len.div_ceil(4) * 4;
+            if code == PCAPNG_OPTION_TSRESOL && len >= 1 && options.len() > 4 {
+                let resolution = options[4];
+                let exponent = (resolution & 0x7F) as u32;
+                return if resolution & 0x80 == 0 { 10u64.saturating_pow(exponent) } else { 2u64.saturating_pow(exponent) };
+            }
+            if code == 0 || padded > options.len() {
+                break;
+            }
+            options = &options[padded..];
+        }
+        1_000_000
+    }
+}
+
+const ETHERTYPE_IPV4: u16 = 0x0800;
+const ETHERTYPE_IPV6: u16 = 0x86DD;
+const ETHERTYPE_VLAN: u16 = 0x8100;
+const ETHERTYPE_QINQ: u16 = 0x88A8;
+const IP_PROTOCOL_UDP: u8 = 17;
+
+fn be_u16(bytes: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_be_bytes([*bytes.get(offset)?, *bytes.get(offset + 1)?]))
+}
+
+/// Destination port and payload of a UDP frame, None for anything else
+fn udp_payload(link_type: u32, frame: &[u8]) -> Option<(u16, &[u8])> {
+    let (ethertype, packet) = match link_type {
+        // Ethernet
+        1 => {
+            let mut offset = 12;
+            let mut ethertype = be_u16(frame, offset)?;
+            while ethertype == ETHERTYPE_VLAN || ethertype == ETHERTYPE_QINQ {
+                offset += 4;
+                ethertype = be_u16(frame, offset)?;
+            }
+            (ethertype, frame.get(offset + 2..)?)
+        }
+        // Linux cooked capture v1 and v2
+        113 => (be_u16(frame, 14)?, frame.get(16..)?),
+        276 => (be_u16(frame, 0)?, frame.get(20..)?),
+        // BSD loopback, the address family in host order
+        0 => {
+            let family = u32::from_ne_bytes(frame.get(..4)?.try_into().ok()?);
+            (if family == 2 { ETHERTYPE_IPV4 } else { ETHERTYPE_IPV6 }, frame.get(4..)?)
+        }
+        // Raw IP, the version nibble tells the protocol
+        12 | 101 | 228 | 229 => {
+            let ethertype = if frame.first()? >> 4 == 4 { ETHERTYPE_IPV4 } else { ETHERTYPE_IPV6 };
+            (ethertype, frame)
+        }
+        _ => return None,
+    };
+
+    let datagram = match ethertype {
+        ETHERTYPE_IPV4 => {
+            let header_len = ((packet.first()? & 0x0F) as usize) * 4;
+            let total_len = be_u16(packet, 2)? as usize;
+            // Fragments are skipped, shreds fit in a single datagram
+            let fragment = be_u16(packet, 6)?;
+            if *packet.get(9)? != IP_PROTOCOL_UDP || fragment & 0x3FFF != 0 {
+                return None;
+            }
+            packet.get(header_len..total_len.min(packet.len()))?
+        }
+        ETHERTYPE_IPV6 => {
+            let payload_len = be_u16(packet, 4)? as usize;
+            let (mut next_header, mut offset) = (*packet.get(6)?, 40);
+            // Hop-by-hop, routing and destination options headers before the UDP header
+            while matches!(next_header, 0 | 43 | 60) {
+                next_header = *packet.get(offset)?;
+                offset += (*packet.get(offset + 1)? as usize + 1) * 8;
+            }
+            if next_header != IP_PROTOCOL_UDP {
+                return None;
+            }
+            packet.get(offset..(40 + payload_len).min(packet.len()))?
+        }
+        _ => return None,
+    };
+
+    let destination_port = be_u16(datagram, 2)?;
+    let udp_len = be_u16(datagram, 4)? as usize;
+    Some((destination_port, datagram.get(8..udp_len.min(datagram.len()))?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Ethernet, IPv4 and UDP headers around a payload
+    fn udp_frame(destination_port: u16, payload: &[u8]) -> Vec<u8> {
+        let mut frame = vec![0u8; 12];
+        frame.extend_from_slice(&ETHERTYPE_IPV4.to_be_bytes());
+        frame.extend_from_slice(&[0x45, 0]);
+        frame.extend_from_slice(&((20 + 8 + payload.len()) as u16).to_be_bytes());
+        frame.extend_from_slice(&[0, 0, 0x40, 0, 64, IP_PROTOCOL_UDP, 0, 0, 10, 0, 0, 1, 10, 0, 0, 2]);
+        frame.extend_from_slice(&8000u16.to_be_bytes());
+        frame.extend_from_slice(&destination_port.to_be_bytes());
+        frame.extend_from_slice(&((8 + payload.len()) as u16).to_be_bytes());
+        frame.extend_from_slice(&[0, 0]);
+        frame.extend_from_slice(payload);
+        frame
+    }
+
+    fn pcap_header(snaplen: u32) -> Vec<u8> {
+        let mut header = 0xA1B2_C3D4u32.to_le_bytes().to_vec();
+        header.extend_from_slice(&[2, 0, 4, 0]);
+        header.extend_from_slice(&[0; 8]);
+        header.extend_from_slice(&snaplen.to_le_bytes());
+        header.extend_from_slice(&1u32.to_le_bytes());
+        header
+    }
+
+    fn pcap_record(seconds: u32, micros: u32, captured_len: u32, frame: &[u8]) -> Vec<u8> {
+        let mut record = Vec::new();
+        for field in [seconds, micros, captured_len, frame.len() as u32] {
+            record.extend_from_slice(&field.to_le_bytes());
+        }
+        record.extend_from_slice(frame);
+        record
+    }
+
+    fn pcapng_block(block_type: u32, body: &[u8]) -> Vec<u8> {
+        let padded = body.len().div_ceil(4) * 4;
+        let total_len = (12 + padded) as u32;
+        let mut block = block_type.to_le_bytes().to_vec();
+        block.extend_from_slice(&total_len.to_le_bytes());
+        block.extend_from_slice(body);
+        block.resize(8 + padded, 0);
+        block.extend_from_slice(&total_len.to_le_bytes());
+        block
+    }
+
+    fn pcapng_capture(frames: &[(u64, Vec<u8>)]) -> Vec<u8> {
+        let mut section = PCAPNG_BYTE_ORDER_MAGIC.to_le_bytes().to_vec();
+        section.extend_from_slice(&[1, 0, 0, 0]);
+        section.extend_from_slice(&u64::MAX.to_le_bytes());
+        let mut capture = pcapng_block(PCAPNG_SECTION_HEADER, &section);
+        let mut interface = 1u16.to_le_bytes().to_vec();
+        interface.extend_from_slice(&[0, 0]);
+        interface.extend_from_slice(&65535u32.to_le_bytes());
+        capture.extend(pcapng_block(PCAPNG_INTERFACE_DESCRIPTION, &interface));
+        for (micros, frame) in frames {
+            let mut packet = 0u32.to_le_bytes().to_vec();
+            packet.extend_from_slice(&((micros >> 32) as u32).to_le_bytes());
+            packet.extend_from_slice(&(*micros as u32).to_le_bytes());
+            packet.extend_from_slice(&(frame.len() as u32).to_le_bytes());
+            packet.extend_from_slice(&(frame.len() as u32).to_le_bytes());
+            packet.extend_from_slice(frame);
+            capture.extend(pcapng_block(PCAPNG_ENHANCED_PACKET, &packet));
+        }
+        capture
+    }
+
+    fn capture_file(name: &str, bytes: &[u8]) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("pcap_replay_{}_{}", std::process::id(), name));
+        std::fs::write(&path, bytes).unwrap();
+        path
+    }
+
+    fn read_all(path: &Path) -> Result<Vec<CapturedDatagram>, String> {
+        let mut reader = PcapReader::open(path)?;
+        let mut datagrams = Vec::new();
+        while let Some(datagram) = reader.next_datagram()? {
+            datagrams.push(datagram);
+        }
+        Ok(datagrams)
+    }
+
+    #[test]
+    fn reads_back_pcap_datagrams() {
+        let (first, second) = (udp_frame(8001, b"shred one"), udp_frame(9000, b"shred two"));
+        let mut capture = pcap_header(65535);
+        capture.extend(pcap_record(1, 500, first.len() as u32, &first));
+        capture.extend(pcap_record(2, 0, second.len() as u32, &second));
+        let path = capture_file("roundtrip.pcap", &capture);
+
+        let datagrams = read_all(&path).unwrap();
+        assert_eq!(datagrams.len(), 2);
+        assert_eq!((datagrams[0].timestamp_nanos, datagrams[0].destination_port), (1_000_500_000, 8001));
+        assert_eq!(datagrams[0].payload, b"shred one");
+        assert_eq!((datagrams[1].timestamp_nanos, datagrams[1].destination_port), (2_000_000_000, 9000));
+
+        let options = ReplayOptions { input: ReplayInput::Pcap(path.clone()), speed: 0.0, port: Some(9000) };
+        let mut replay = ReplayReader::open(&options).unwrap();
+        assert_eq!(replay.next_shred().unwrap(), Some((2_000_000_000, b"shred two".to_vec())));
+        assert_eq!(replay.next_shred().unwrap(), None);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn reads_back_pcapng_datagrams() {
+        let capture = pcapng_capture(&[(3_000_001, udp_frame(8001, b"odd length shred"))]);
+        let path = capture_file("roundtrip.pcapng", &capture);
+
+        let datagrams = read_all(&path).unwrap();
+        assert_eq!(datagrams.len(), 1);
+        assert_eq!((datagrams[0].timestamp_nanos, datagrams[0].destination_port), (3_000_001_000, 8001));
+        assert_eq!(datagrams[0].payload, b"odd length shred");
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn skips_frames_longer_than_a_datagram() {
+        let (jumbo, frame) = (vec![0u8; MAX_FRAME_LEN + 1], udp_frame(8001, b"after the jumbo frame"));
+        let mut capture = pcap_header(262_144);
+        capture.extend(pcap_record(1, 0, jumbo.len() as u32, &jumbo));
+        capture.extend(pcap_record(2, 0, frame.len() as u32, &frame));
+        let path = capture_file("jumbo.pcap", &capture);
+
+        let datagrams = read_all(&path).unwrap();
+        assert_eq!(datagrams.len(), 1);
+        assert_eq!(datagrams[0].payload, b"after the jumbo frame");
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn rejects_malformed_captures() {
+        let frame = udp_frame(8001, b"shred");
+        let mut over_snaplen = pcap_header(64);
+        over_snaplen.extend(pcap_record(1, 0, u32::MAX, &frame));
+        let mut truncated = pcap_header(65535);
+        truncated.extend(pcap_record(1, 0, frame.len() as u32 + 16, &frame));
+        let mut misaligned_block = pcapng_capture(&[]);
+        misaligned_block.extend_from_slice(&PCAPNG_ENHANCED_PACKET.to_le_bytes());
+        misaligned_block.extend_from_slice(&13u32.to_le_bytes());
+
+        for (name, capture) in [
+            ("magic.pcap", b"not a capture".to_vec()),
+            ("header.pcap", pcap_header(65535)[..12].to_vec()),
+            ("snaplen.pcap", over_snaplen),
+            ("truncated.pcap", truncated),
+            ("block.pcapng", misaligned_block),
+        ] {
+            let path = capture_file(name, &capture);
+            assert!(read_all(&path).is_err(), "{} should be rejected", name);
+            std::fs::remove_file(path).unwrap();
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_replay_options() {
+        let args = |args: &[&str]| ReplayOptions::from_args(args.iter().map(|arg| arg.to_string()));
+        assert!(args(&[]).unwrap().is_none());
+        assert_eq!(args(&["--replay-pcap", "capture.pcap", "--replay-port", "8001"]).unwrap().unwrap().port, Some(8001));
+        assert!(args(&["--replay-pcap"]).is_err());
+        assert!(args(&["--replay-pcap", "capture.pcap", "--replay-speed", "-1"]).is_err());
+        assert!(args(&["--replay-pcap", "capture.pcap", "--replay-port", "70000"]).is_err());
+        assert!(args(&["--replay-speed", "2"]).is_err());
+    }
+}
@@ -0,0 +1,199 @@
+use json::JsonValue;
+use once_cell::sync::Lazy;
+use rustc_hash::FxHashSet as HashSet;
+use solana_sdk::pubkey::Pubkey;
+use std::sync::{Arc, RwLock};
+use tracing::{info, warn};
+
+use crate::creation_events::{pool_created_events, token_launch_events};
+use crate::derived_events::publish_derived_event;
+use crate::price_ticks::record_swaps;
+use crate::swap_events::{normalize_swap_instruction, normalize_swaps};
+use crate::wallet_clusters::tag_creation_cluster;
+use crate::write_locks::record_write_locks;
+
+/// A decoded transaction on its way to the output, as seen by the post-processors
+pub struct DecodedTransaction<'a> {
+    pub slot: u64,
+    pub json: JsonValue,
+    /// Account keys with lookup tables resolved, the fee payer first
+    pub account_keys: &'a [Pubkey],
+    /// False when the transaction is decoded again (e.g. a correction of a partial emission),
+    /// processors keeping state must not count it twice
+    pub first_emission: bool,
+}
+
+/// An event a post-processor derives from a transaction, published on the derived events bus
+pub struct PostProcessorEvent {
+    pub event_type: &'static str,
+    pub json: JsonValue,
+    pub accounts: HashSet<String>,
+}
+
+/// Extension point run on every decoded transaction before it is emitted.
+/// Processors may augment the transaction in place and return derived events.
+pub trait PostProcessor: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    fn process(&self, transaction: &mut DecodedTransaction) -> Vec<PostProcessorEvent>;
+}
+
+/// Tags token creations with the funding cluster of the creator
+pub struct WalletClusterTagger;
+
+impl PostProcessor for WalletClusterTagger {
+    fn name(&self) -> &'static str {
+        "wallet_clusters"
+    }
+
+    fn process(&self, transaction: &mut DecodedTransaction) -> Vec<PostProcessorEvent> {
+        if let Some(fee_payer) = transaction.account_keys.first() {
+            tag_creation_cluster(&mut transaction.json, fee_payer);
+        }
+        Vec::new()
+    }
+}
+
+/// Attaches a protocol independent `swap` object to every swap instruction
+pub struct SwapNormalizer;
+
+impl PostProcessor for SwapNormalizer {
+    fn name(&self) -> &'static str {
+        "swaps"
+    }
+
+    fn process(&self, transaction: &mut DecodedTransaction) -> Vec<PostProcessorEvent> {
+        for instruction in transaction.json["message"]["instructions"].members_mut() {
+            if let Some(swap) = normalize_swap_instruction(instruction) {
+                instruction["swap"] = swap.to_json();
+            }
+        }
+        Vec::new()
+    }
+}
+
+/// Feeds normalized swaps into the one-second price ticks
+pub struct PriceTickRecorder;
+
+impl PostProcessor for PriceTickRecorder {
+    fn name(&self) -> &'static str {
+        "price_ticks"
+    }
+
+    fn process(&self, transaction: &mut DecodedTransaction) -> Vec<PostProcessorEvent> {
+        if transaction.first_emission {
+            record_swaps(normalize_swaps(&transaction.json));
+        }
+        Vec::new()
+    }
+}
+
+/// Publishes a token_launch derived event for every launchpad token creation
+pub struct TokenLaunchPublisher;
+
+impl PostProcessor for TokenLaunchPublisher {
+    fn name(&self) -> &'static str {
+        "token_launches"
+    }
+
+    fn process(&self, transaction: &mut DecodedTransaction) -> Vec<PostProcessorEvent> {
+        if !transaction.first_emission {
+            return Vec::new();
+        }
+        token_launch_events(transaction.slot, &transaction.json)
+    }
+}
+
+/// Publishes a pool_created derived event for every AMM pool creation
+pub struct PoolCreationPublisher;
+
+impl PostProcessor for PoolCreationPublisher {
+    fn name(&self) -> &'static str {
+        "pool_creations"
+    }
+
+    fn process(&self, transaction: &mut DecodedTransaction) -> Vec<PostProcessorEvent> {
+        if !transaction.first_emission {
+            return Vec::new();
+        }
+        pool_created_events(transaction.slot, &transaction.json)
+    }
+}
+
+/// Feeds the per-slot write-lock counters behind the slot summary hotspots
+pub struct WriteLockRecorder;
+
+impl PostProcessor for WriteLockRecorder {
+    fn name(&self) -> &'static str {
+        "write_locks"
+    }
+
+    fn process(&self, transaction: &mut DecodedTransaction) -> Vec<PostProcessorEvent> {
+        if transaction.first_emission {
+            record_write_locks(transaction.slot, &transaction.json);
+        }
+        Vec::new()
+    }
+}
+
+fn built_in_post_processor(name: &str) -> Option<Arc<dyn PostProcessor>> {
+    match name {
+        "wallet_clusters" => Some(Arc::new(WalletClusterTagger)),
+        "swaps" => Some(Arc::new(SwapNormalizer)),
+        "price_ticks" => Some(Arc::new(PriceTickRecorder)),
+        "token_launches" => Some(Arc::new(TokenLaunchPublisher)),
+        "pool_creations" => Some(Arc::new(PoolCreationPublisher)),
+        "write_locks" => Some(Arc::new(WriteLockRecorder)),
+        _ => None,
+    }
+}
+
+/// Ordered chain, configured with a comma-separated list of built-in processors in POST_PROCESSORS
+static POST_PROCESSOR_CHAIN: Lazy<RwLock<Vec<Arc<dyn PostProcessor>>>> = Lazy::new(|| {
+    let configured = std::env::var("POST_PROCESSORS").unwrap_or_else(|_| "wallet_clusters,swaps,price_ticks,token_launches,pool_creations,write_locks".to_string());
+    let chain: Vec<Arc<dyn PostProcessor>> = configured
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .filter_map(|name| {
+            let processor = built_in_post_processor(name);
+            if processor.is_none() {
+                warn!("Unknown post-processor '{}' in POST_PROCESSORS, ignored", name);
+            }
+            processor
+        })
+        .collect();
+    info!(
+        "Post-processor chain: {}",
+        chain.iter().map(|processor| processor.name()).collect::<Vec<_>>().join(" -> ")
+    );
+    RwLock::new(chain)
+});
+
+/// Appends a processor to the end of the chain, for library users plugging in their own
+pub fn register_post_processor(processor: Arc<dyn PostProcessor>) {
+    POST_PROCESSOR_CHAIN
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .push(processor);
+}
+
+pub fn post_processor_names() -> Vec<&'static str> {
+    POST_PROCESSOR_CHAIN
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .iter()
+        .map(|processor| processor.name())
+        .collect()
+}
+
+/// Runs the chain in order and publishes the derived events, returns the augmented transaction
+pub fn run_post_processors(mut transaction: DecodedTransaction) -> JsonValue {
+    let chain = POST_PROCESSOR_CHAIN.read().unwrap_or_else(|poisoned| poisoned.into_inner());
+    for processor in chain.iter() {
+        for event in processor.process(&mut transaction) {
+            publish_derived_event(event.event_type, event.json, event.accounts);
+        }
+    }
+    transaction.json
+}
@@ -0,0 +1,141 @@
+use json::JsonValue;
+use sqlx::postgres::{PgPool, PgPoolOptions};
+use sqlx::{Postgres, Transaction};
+use std::sync::Arc;
+
+use crate::creation_events::token_launch_events;
+use crate::output_message::OutputMessage;
+use crate::swap_events::normalize_swap_instruction;
+
+/// Connects and brings the schema up to date, the migrations are embedded in the binary
+pub async fn connect(url: &str, max_connections: u32) -> Result<PgPool, String> {
+    let pool = PgPoolOptions::new()
+        .max_connections(max_connections)
+        .connect(url)
+        .await
+        .map_err(|e| format!("cannot connect: {}", e))?;
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .map_err(|e| format!("migration failed: {}", e))?;
+    Ok(pool)
+}
+
+/// Upserts a batch of messages in one database transaction. Batch payloads keep program IDs
+/// in their account-key dictionary and accounts unresolved, they get no launch rows.
+pub async fn write_messages(pool: &PgPool, messages: &[Arc<OutputMessage>]) -> Result<(), sqlx::Error> {
+    let mut db = pool.begin().await?;
+    for message in messages {
+        let payload = &message.payload;
+        if payload["transactions"].is_array() {
+            for transaction in payload["transactions"].members() {
+                let dictionary = &payload["accountKeys"];
+                write_transaction(
+                    &mut db,
+                    message,
+                    transaction,
+                    |instruction| instruction["programIdKey"].as_usize().and_then(|key| dictionary[key].as_str()),
+                    false,
+                )
+                .await?;
+            }
+        } else {
+            write_transaction(&mut db, message, payload, |instruction| instruction["program_id"].as_str(), true).await?;
+        }
+    }
+    db.commit().await
+}
+
+async fn write_transaction<'a>(
+    db: &mut Transaction<'static, Postgres>,
+    message: &OutputMessage,
+    transaction: &'a JsonValue,
+    program_id: impl Fn(&'a JsonValue) -> Option<&'a str>,
+    resolved_accounts: bool,
+) -> Result<(), sqlx::Error> {
+    let Some(signature) = transaction["signatures"][0].as_str() else {
+        return Ok(());
+    };
+    let slot = message.slot as i64;
+
+    sqlx::query(
+        "INSERT INTO transactions (signature, slot, timestamp_micros, payload) VALUES ($1, $2, $3, $4::jsonb)
+         ON CONFLICT (signature) DO UPDATE SET slot = EXCLUDED.slot, timestamp_micros = EXCLUDED.timestamp_micros, payload = EXCLUDED.payload",
+    )
+    .bind(signature)
+    .bind(slot)
+    .bind(message.timestamp as i64)
+    .bind(transaction.dump())
+    .execute(&mut **db)
+    .await?;
+
+    for (position, instruction) in transaction["message"]["instructions"].members().enumerate() {
+        let parsed_data = Some(&instruction["parsed_data"]).filter(|parsed| !parsed.is_null()).map(JsonValue::dump);
+        sqlx::query(
+            "INSERT INTO instructions (signature, instruction_index, slot, program_id, protocol, instruction_name, parsed_data)
+             VALUES ($1, $2, $3, $4, $5, $6, $7::jsonb)
+             ON CONFLICT (signature, instruction_index) DO UPDATE SET slot = EXCLUDED.slot, program_id = EXCLUDED.program_id,
+                 protocol = EXCLUDED.protocol, instruction_name = EXCLUDED.instruction_name, parsed_data = EXCLUDED.parsed_data",
+        )
+        .bind(signature)
+        .bind(position as i32)
+        .bind(slot)
+        .bind(program_id(instruction))
+        .bind(instruction["protocol"].as_str())
+        .bind(instruction["instruction_name"].as_str())
+        .bind(parsed_data)
+        .execute(&mut **db)
+        .await?;
+
+        // The swaps post-processor already attached the normalized swap, otherwise it is derived here
+        let swap = if instruction["swap"].is_object() {
+            Some(instruction["swap"].clone())
+        } else if resolved_accounts {
+            normalize_swap_instruction(instruction).map(|swap| swap.to_json())
+        } else {
+            None
+        };
+        if let Some(swap) = swap {
+            sqlx::query(
+                "INSERT INTO swaps (signature, instruction_index, slot, protocol, pool, input_mint, output_mint, amount_in, min_amount_out, exact_in, \"user\")
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8::numeric, $9::numeric, $10, $11)
+                 ON CONFLICT (signature, instruction_index) DO NOTHING",
+            )
+            .bind(signature)
+            .bind(position as i32)
+            .bind(slot)
+            .bind(swap["protocol"].as_str().unwrap_or_default())
+            .bind(swap["pool"].as_str().unwrap_or_default())
+            .bind(swap["input_mint"].as_str())
+            .bind(swap["output_mint"].as_str())
+            .bind(swap["amount_in"].as_str().unwrap_or("0"))
+            .bind(swap["min_amount_out"].as_str().unwrap_or("0"))
+            .bind(swap["exact_in"].as_bool().unwrap_or(true))
+            .bind(swap["user"].as_str())
+            .execute(&mut **db)
+            .await?;
+        }
+    }
+
+    if resolved_accounts {
+        for launch in token_launch_events(message.slot, transaction) {
+            let event = &launch.json;
+            sqlx::query(
+                "INSERT INTO token_launches (mint, platform, name, symbol, uri, creator, slot, signature)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                 ON CONFLICT (mint) DO NOTHING",
+            )
+            .bind(event["mint"].as_str())
+            .bind(event["platform"].as_str())
+            .bind(event["name"].as_str())
+            .bind(event["symbol"].as_str())
+            .bind(event["uri"].as_str())
+            .bind(event["creator"].as_str())
+            .bind(slot)
+            .bind(signature)
+            .execute(&mut **db)
+            .await?;
+        }
+    }
+    Ok(())
+}
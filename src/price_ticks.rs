@@ -0,0 +1,133 @@
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::broadcast;
+use tracing::debug;
+
+use crate::swap_events::SwapEvent;
+
+/// One-second OHLC-lite bar of a pool, built from shred-time swap intents.
+/// Prices are quote per base in raw units and only approximate executed prices.
+#[derive(Debug, Clone)]
+pub struct PriceTick {
+    pub protocol: String,
+    pub pool: String,
+    pub base_mint: Option<String>,
+    pub quote_mint: Option<String>,
+    pub second: u64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub base_volume: u64,
+    pub quote_volume: u64,
+    pub swap_count: u32,
+}
+
+impl PriceTick {
+    fn open_with(second: u64, swap: &SwapEvent, price: f64) -> Self {
+        Self {
+            protocol: swap.protocol.clone(),
+            pool: swap.pool.clone(),
+            base_mint: swap.base_mint.clone(),
+            quote_mint: swap.quote_mint.clone(),
+            second,
+            open: price,
+            high: price,
+            low: price,
+            close: price,
+            base_volume: swap.base_amount,
+            quote_volume: swap.quote_amount,
+            swap_count: 1,
+        }
+    }
+
+    fn update(&mut self, swap: &SwapEvent, price: f64) {
+        self.high = self.high.max(price);
+        self.low = self.low.min(price);
+        self.close = price;
+        self.base_volume = self.base_volume.saturating_add(swap.base_amount);
+        self.quote_volume = self.quote_volume.saturating_add(swap.quote_amount);
+        self.swap_count += 1;
+        if self.base_mint.is_none() {
+            self.base_mint = swap.base_mint.clone();
+            self.quote_mint = swap.quote_mint.clone();
+        }
+    }
+}
+
+/// Open bars keyed by pool, flushed once their second is over
+static OPEN_TICKS: Lazy<DashMap<String, PriceTick>> = Lazy::new(DashMap::new);
+
+static PRICE_TICKS_TX: Lazy<broadcast::Sender<Arc<PriceTick>>> = Lazy::new(|| broadcast::channel(4096).0);
+
+pub fn subscribe_price_ticks() -> broadcast::Receiver<Arc<PriceTick>> {
+    PRICE_TICKS_TX.subscribe()
+}
+
+fn current_second() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_secs()
+}
+
+fn publish(tick: PriceTick) {
+    // Sending only fails when nobody is subscribed
+    let _ = PRICE_TICKS_TX.send(Arc::new(tick));
+}
+
+/// Folds the swaps of a transaction into the open bars of their pools
+pub fn record_swaps(swaps: Vec<SwapEvent>) {
+    if swaps.is_empty() {
+        return;
+    }
+    let second = current_second();
+
+    for swap in swaps {
+        let Some(price) = swap.price() else { continue };
+
+        let mut finished = None;
+        OPEN_TICKS
+            .entry(swap.pool.clone())
+            .and_modify(|tick| {
+                if tick.second == second {
+                    tick.update(&swap, price);
+                } else {
+                    finished = Some(std::mem::replace(tick, PriceTick::open_with(second, &swap, price)));
+                }
+            })
+            .or_insert_with(|| PriceTick::open_with(second, &swap, price));
+
+        if let Some(tick) = finished {
+            publish(tick);
+        }
+    }
+}
+
+/// Flushes the bars of pools that stopped trading, runs every second
+pub async fn run_price_tick_flusher() {
+    let mut ticker = tokio::time::interval(Duration::from_secs(1));
+    loop {
+        ticker.tick().await;
+        let second = current_second();
+
+        let mut finished = Vec::new();
+        OPEN_TICKS.retain(|_, tick| {
+            if tick.second < second {
+                finished.push(tick.clone());
+                false
+            } else {
+                true
+            }
+        });
+
+        if !finished.is_empty() {
+            debug!("Flushing {} price ticks", finished.len());
+        }
+        for tick in finished {
+            publish(tick);
+        }
+    }
+}
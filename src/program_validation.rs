@@ -0,0 +1,207 @@
+use dashmap::DashMap;
+use json::object;
+use once_cell::sync::Lazy;
+use solana_account_decoder_client_types::{UiAccountEncoding, UiDataSliceConfig};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcAccountInfoConfig;
+use solana_sdk::bpf_loader_upgradeable::{self, UpgradeableLoaderState};
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+use crate::decoder_registry::REGISTERED_DECODERS;
+use crate::operational_events::{publish_operational_event, Severity, RPC_FAILURE_EVENT};
+
+pub const PROGRAM_MISSING_EVENT: &str = "program_missing";
+pub const PROGRAM_REDEPLOYED_EVENT: &str = "program_redeployed";
+
+/// Program data accounts hold the whole binary, only the loader header is fetched
+const PROGRAM_DATA_HEADER_SIZE: usize = 45;
+
+/// Checks that every registered program id still exists on chain and is executable
+#[derive(Debug, Clone)]
+pub struct ProgramValidationConfig {
+    pub rpc_endpoint: String,
+    /// None validates at startup only
+    pub interval: Option<Duration>,
+}
+
+impl ProgramValidationConfig {
+    /// Build the configuration from env, returns None when PROGRAM_VALIDATION_ENABLED is false
+    pub fn from_env(rpc_endpoint: &str) -> Option<Self> {
+        let enabled = std::env::var("PROGRAM_VALIDATION_ENABLED")
+            .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+            .unwrap_or(true);
+        if !enabled {
+            return None;
+        }
+        let interval_secs = std::env::var("PROGRAM_VALIDATION_INTERVAL_SECS")
+            .unwrap_or_else(|_| "3600".to_string())
+            .parse()
+            .unwrap_or(3600);
+
+        Some(Self {
+            rpc_endpoint: rpc_endpoint.to_string(),
+            interval: (interval_secs > 0).then(|| Duration::from_secs(interval_secs)),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgramStatus {
+    /// Executable, with the slot of the last deployment for upgradeable programs
+    Deployed { last_deploy_slot: Option<u64> },
+    Missing,
+    NotExecutable,
+}
+
+/// Last deployment slot seen per program, a change between two checks is a redeployment
+static LAST_DEPLOY_SLOTS: Lazy<DashMap<Pubkey, u64>> = Lazy::new(DashMap::new);
+
+fn tracked_programs() -> Vec<(Pubkey, Vec<&'static str>)> {
+    let mut programs: Vec<(Pubkey, Vec<&'static str>)> = Vec::new();
+    for decoder in REGISTERED_DECODERS {
+        match programs.iter_mut().find(|(program_id, _)| *program_id == decoder.program_id) {
+            Some((_, protocols)) => protocols.push(decoder.protocol),
+            None => programs.push((decoder.program_id, vec![decoder.protocol])),
+        }
+    }
+    programs
+}
+
+async fn fetch_program_statuses(rpc_client: &RpcClient, program_ids: &[Pubkey]) -> Result<Vec<ProgramStatus>, String> {
+    let accounts = rpc_client
+        .get_multiple_accounts(program_ids)
+        .await
+        .map_err(|e| format!("getMultipleAccounts for programs failed: {}", e))?;
+
+    // Upgradeable programs point to a program data account holding the deployment slot
+    let program_data_addresses: Vec<Option<Pubkey>> = accounts
+        .iter()
+        .map(|account| {
+            let account = account.as_ref().filter(|account| account.owner == bpf_loader_upgradeable::id())?;
+            match bincode::deserialize::<UpgradeableLoaderState>(&account.data) {
+                Ok(UpgradeableLoaderState::Program { programdata_address }) => Some(programdata_address),
+                _ => None,
+            }
+        })
+        .collect();
+    let lookups: Vec<Pubkey> = program_data_addresses.iter().flatten().copied().collect();
+    let program_data = if lookups.is_empty() {
+        Vec::new()
+    } else {
+        let config = RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            data_slice: Some(UiDataSliceConfig { offset: 0, length: PROGRAM_DATA_HEADER_SIZE }),
+            commitment: Some(CommitmentConfig::confirmed()),
+            min_context_slot: None,
+        };
+        rpc_client
+            .get_multiple_accounts_with_config(&lookups, config)
+            .await
+            .map_err(|e| format!("getMultipleAccounts for program data failed: {}", e))?
+            .value
+    };
+    let mut program_data = program_data.into_iter();
+
+    Ok(accounts
+        .iter()
+        .zip(program_data_addresses)
+        .map(|(account, program_data_address)| {
+            let Some(account) = account else { return ProgramStatus::Missing };
+            let last_deploy_slot = program_data_address.and_then(|_| {
+                let data = program_data.next().flatten()?;
+                match bincode::deserialize::<UpgradeableLoaderState>(&data.data) {
+                    Ok(UpgradeableLoaderState::ProgramData { slot, .. }) => Some(slot),
+                    _ => None,
+                }
+            });
+            // A closed upgradeable program keeps its account but loses its program data
+            if !account.executable || (program_data_address.is_some() && last_deploy_slot.is_none()) {
+                return ProgramStatus::NotExecutable;
+            }
+            ProgramStatus::Deployed { last_deploy_slot }
+        })
+        .collect())
+}
+
+/// Validates the registered program ids once, logging and publishing every problem found
+pub async fn validate_registered_programs(rpc_client: &RpcClient) {
+    let programs = tracked_programs();
+    let program_ids: Vec<Pubkey> = programs.iter().map(|(program_id, _)| *program_id).collect();
+
+    let statuses = match fetch_program_statuses(rpc_client, &program_ids).await {
+        Ok(statuses) => statuses,
+        Err(e) => {
+            warn!("Program validation skipped: {}", e);
+            publish_operational_event(
+                RPC_FAILURE_EVENT,
+                Severity::Warning,
+                format!("Program validation skipped: {}", e),
+                object! { "call" => "getMultipleAccounts" },
+            );
+            return;
+        }
+    };
+
+    let mut problems = 0;
+    for ((program_id, protocols), status) in programs.iter().zip(statuses) {
+        let protocols = protocols.join(", ");
+        match status {
+            ProgramStatus::Deployed { last_deploy_slot: Some(slot) } => {
+                let previous = LAST_DEPLOY_SLOTS.insert(*program_id, slot);
+                if let Some(previous) = previous.filter(|previous| *previous != slot) {
+                    warn!(
+                        "Program {} ({}) was redeployed at slot {} (previously {}), check the decoder layouts",
+                        program_id, protocols, slot, previous
+                    );
+                    publish_operational_event(
+                        PROGRAM_REDEPLOYED_EVENT,
+                        Severity::Warning,
+                        format!("Program {} ({}) was redeployed at slot {}", program_id, protocols, slot),
+                        object! {
+                            "programId" => program_id.to_string(),
+                            "protocols" => protocols.clone(),
+                            "deploySlot" => slot,
+                            "previousDeploySlot" => previous,
+                        },
+                    );
+                }
+            }
+            ProgramStatus::Deployed { last_deploy_slot: None } => {}
+            ProgramStatus::Missing | ProgramStatus::NotExecutable => {
+                problems += 1;
+                let reason = if status == ProgramStatus::Missing { "missing" } else { "not_executable" };
+                error!(
+                    "Program {} ({}) is {} on chain, its decoder will never match, the program id may be stale",
+                    program_id, protocols, reason
+                );
+                publish_operational_event(
+                    PROGRAM_MISSING_EVENT,
+                    Severity::Error,
+                    format!("Program {} ({}) is {} on chain", program_id, protocols, reason),
+                    object! { "programId" => program_id.to_string(), "protocols" => protocols, "reason" => reason },
+                );
+            }
+        }
+    }
+
+    if problems == 0 {
+        info!("Program validation: all {} registered programs are deployed", programs.len());
+    }
+}
+
+/// Validates at startup, then on every interval when one is configured
+pub async fn run_program_validation(config: ProgramValidationConfig) {
+    let rpc_client = RpcClient::new_with_commitment(config.rpc_endpoint.clone(), CommitmentConfig::confirmed());
+    validate_registered_programs(&rpc_client).await;
+
+    let Some(interval) = config.interval else { return };
+    let mut ticker = tokio::time::interval(interval);
+    ticker.tick().await;
+    loop {
+        ticker.tick().await;
+        validate_registered_programs(&rpc_client).await;
+    }
+}
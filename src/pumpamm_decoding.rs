@@ -3,6 +3,7 @@ use json::{object, JsonValue};
 use solana_sdk::pubkey::Pubkey;
 use solana_sdk::pubkey;
 use tracing::{error};
+use crate::decode_anomalies::check_parse_residue;
 use crate::utils::create_standardized_instruction;
 
 // Main trading instruction discriminators from IDL
@@ -33,6 +34,29 @@ pub const PUMPAMM_EXTEND_ACCOUNT_DISCRIMINATOR: [u8; 8] = [234, 102, 194, 203, 1
 
 pub const PUMPAMM_PROGRAM_ID: Pubkey = pubkey!("pAMMBay6oceH9fJKBRHGP5D4bD4sWpmSwMn52FMfXEA");
 
+pub const PUMPAMM_TRADE_ACCOUNTS: &[&str] = &[
+    "pool", "user", "globalConfig", "baseMint", "quoteMint", "userBaseTokenAccount",
+    "userQuoteTokenAccount", "poolBaseTokenAccount", "poolQuoteTokenAccount",
+    "protocolFeeRecipient", "protocolFeeRecipientTokenAccount", "baseTokenProgram",
+    "quoteTokenProgram", "systemProgram", "associatedTokenProgram", "eventAuthority", "program",
+    "coinCreatorVaultAta", "coinCreatorVaultAuthority",
+];
+pub const PUMPAMM_CREATE_POOL_ACCOUNTS: &[&str] = &[
+    "pool", "globalConfig", "creator", "baseMint", "quoteMint", "lpMint", "userBaseTokenAccount",
+    "userQuoteTokenAccount", "userPoolTokenAccount", "poolBaseTokenAccount",
+    "poolQuoteTokenAccount", "systemProgram", "token2022Program", "baseTokenProgram",
+    "quoteTokenProgram", "associatedTokenProgram", "eventAuthority", "program",
+];
+pub const PUMPAMM_LIQUIDITY_ACCOUNTS: &[&str] = &[
+    "pool", "globalConfig", "user", "baseMint", "quoteMint", "lpMint", "userBaseTokenAccount",
+    "userQuoteTokenAccount", "userPoolTokenAccount", "poolBaseTokenAccount",
+    "poolQuoteTokenAccount", "tokenProgram", "token2022Program", "eventAuthority", "program",
+];
+pub const PUMPAMM_COLLECT_COIN_CREATOR_FEE_ACCOUNTS: &[&str] = &[
+    "quoteMint", "quoteTokenProgram", "coinCreator", "coinCreatorVaultAuthority",
+    "coinCreatorVaultAta", "coinCreatorTokenAccount", "eventAuthority", "program",
+];
+
 pub const SYSTEM_PROGRAM_ID: Pubkey = pubkey!("11111111111111111111111111111111");
 pub const TOKEN_PROGRAM_ID: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
 pub const TOKEN_2022_PROGRAM_ID: Pubkey = pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
@@ -48,6 +72,7 @@ pub enum PumpAmmInstructionType {
     CreatePool,
     Deposit,
     Withdraw,
+    CollectCoinCreatorFee,
     
     Unknown,
 }
@@ -100,6 +125,7 @@ pub fn get_pumpamm_instruction_type(data: &[u8]) -> Option<PumpAmmInstructionTyp
         d if d == PUMPAMM_CREATE_POOL_DISCRIMINATOR => Some(PumpAmmInstructionType::CreatePool),
         d if d == PUMPAMM_DEPOSIT_DISCRIMINATOR => Some(PumpAmmInstructionType::Deposit),
         d if d == PUMPAMM_WITHDRAW_DISCRIMINATOR => Some(PumpAmmInstructionType::Withdraw),
+        d if d == PUMPAMM_COLLECT_COIN_CREATOR_FEE_DISCRIMINATOR => Some(PumpAmmInstructionType::CollectCoinCreatorFee),
         
         _ => Some(PumpAmmInstructionType::Unknown),
     }
@@ -119,6 +145,7 @@ pub fn deserialize_pumpamm_instruction(data: &[u8], accounts_indices: &[u8], acc
         Some(PumpAmmInstructionType::CreatePool) => deserialize_pumpamm_create_pool_instruction(data, accounts_indices, account_keys, is_signer, is_writable),
         Some(PumpAmmInstructionType::Deposit) => deserialize_pumpamm_deposit_instruction(data, accounts_indices, account_keys, is_signer, is_writable),
         Some(PumpAmmInstructionType::Withdraw) => deserialize_pumpamm_withdraw_instruction(data, accounts_indices, account_keys, is_signer, is_writable),
+        Some(PumpAmmInstructionType::CollectCoinCreatorFee) => deserialize_pumpamm_collect_coin_creator_fee_instruction(data, accounts_indices, account_keys, is_signer, is_writable),
         
         Some(PumpAmmInstructionType::Unknown) => Err("Unknown PumpAMM instruction type.".to_string()),
         None => Err("Could not identify the PumpAMM instruction type.".to_string()),
@@ -148,18 +175,21 @@ pub fn deserialize_pumpamm_buy_instruction(data: &[u8], accounts_indices: &[u8],
     };
 
     let remaining_data = &data[8..];
-    let params = match BuyParams::deserialize(&mut &remaining_data[..]) {
+    let mut remaining_data_ref = &remaining_data[..];
+    let params = match BuyParams::deserialize(&mut remaining_data_ref) {
         Ok(parsed) => parsed,
         Err(e) => {
             return Err(format!("Error deserializing Buy parameters: {:?}", e));
         }
     };
 
-    let parsed_data = object! {
+    let mut parsed_data = object! {
         "base_amount_out" => params.base_amount_out.to_string(),
         "max_quote_amount_in" => params.max_quote_amount_in.to_string()
     };
     
+    check_parse_residue("PumpAMM Buy", remaining_data_ref, &mut parsed_data);
+
     Ok(create_standardized_instruction(
         &PUMPAMM_PROGRAM_ID,
         "Buy",
@@ -196,18 +226,21 @@ pub fn deserialize_pumpamm_sell_instruction(data: &[u8], accounts_indices: &[u8]
     };
 
     let remaining_data = &data[8..];
-    let params = match SellParams::deserialize(&mut &remaining_data[..]) {
+    let mut remaining_data_ref = &remaining_data[..];
+    let params = match SellParams::deserialize(&mut remaining_data_ref) {
         Ok(parsed) => parsed,
         Err(e) => {
             return Err(format!("Error deserializing PSell parameters: {:?}", e));
         }
     };
 
-    let parsed_data = object! {
+    let mut parsed_data = object! {
         "base_amount_in" => params.base_amount_in.to_string(),
         "min_quote_amount_out" => params.min_quote_amount_out.to_string(),
     };
     
+    check_parse_residue("PumpAMM Sell", remaining_data_ref, &mut parsed_data);
+
     Ok(create_standardized_instruction(
         &PUMPAMM_PROGRAM_ID,
         "Sell",
@@ -255,13 +288,15 @@ pub fn deserialize_pumpamm_create_pool_instruction(data: &[u8], accounts_indices
         }
     };
 
-    let parsed_data = object! {
+    let mut parsed_data = object! {
         "index" => args.index.to_string(),
         "baseAmountIn" => args.base_amount_in.to_string(),
         "quoteAmountIn" => args.quote_amount_in.to_string(),
         "coinCreator" => bs58::encode(args.coin_creator.to_bytes()).into_string()
     };
     
+    check_parse_residue("PumpAMM CreatePool", remaining_data_ref, &mut parsed_data);
+
     Ok(create_standardized_instruction(
         &PUMPAMM_PROGRAM_ID,
         "CreatePool",
@@ -294,19 +329,22 @@ pub fn deserialize_pumpamm_deposit_instruction(data: &[u8], accounts_indices: &[
     };
 
     let remaining_data = &data[8..];
-    let params = match DepositParams::deserialize(&mut &remaining_data[..]) {
+    let mut remaining_data_ref = &remaining_data[..];
+    let params = match DepositParams::deserialize(&mut remaining_data_ref) {
         Ok(parsed) => parsed,
         Err(e) => {
             return Err(format!("Error deserializing Deposit parameters: {:?}", e));
         }
     };
 
-    let parsed_data = object! {
+    let mut parsed_data = object! {
         "lpTokenAmountOut" => params.lp_token_amount_out.to_string(),
         "maxBaseAmountIn" => params.max_base_amount_in.to_string(),
         "maxQuoteAmountIn" => params.max_quote_amount_in.to_string()
     };
     
+    check_parse_residue("PumpAMM Deposit", remaining_data_ref, &mut parsed_data);
+
     Ok(create_standardized_instruction(
         &PUMPAMM_PROGRAM_ID,
         "Deposit",
@@ -339,19 +377,22 @@ pub fn deserialize_pumpamm_withdraw_instruction(data: &[u8], accounts_indices: &
     };
 
     let remaining_data = &data[8..];
-    let params = match WithdrawParams::deserialize(&mut &remaining_data[..]) {
+    let mut remaining_data_ref = &remaining_data[..];
+    let params = match WithdrawParams::deserialize(&mut remaining_data_ref) {
         Ok(parsed) => parsed,
         Err(e) => {
             return Err(format!("Error deserializing Withdraw parameters: {:?}", e));
         }
     };
 
-    let parsed_data = object! {
+    let mut parsed_data = object! {
         "lpTokenAmountIn" => params.lp_token_amount_in.to_string(),
         "minBaseAmountOut" => params.min_base_amount_out.to_string(),
         "minQuoteAmountOut" => params.min_quote_amount_out.to_string()
     };
     
+    check_parse_residue("PumpAMM Withdraw", remaining_data_ref, &mut parsed_data);
+
     Ok(create_standardized_instruction(
         &PUMPAMM_PROGRAM_ID,
         "Withdraw",
@@ -365,6 +406,31 @@ pub fn deserialize_pumpamm_withdraw_instruction(data: &[u8], accounts_indices: &
     ))
 }
 
+/// Takes no arguments, the coin creator and its vault are the interesting part.
+/// Accounts: quote_mint=0, quote_token_program=1, coin_creator=2, coin_creator_vault_authority=3,
+/// coin_creator_vault_ata=4, coin_creator_token_account=5, event_authority=6, program=7
+pub fn deserialize_pumpamm_collect_coin_creator_fee_instruction(data: &[u8], accounts_indices: &[u8], account_keys: &[Pubkey], is_signer: &[bool], is_writable: &[bool]) -> Result<JsonValue, String> {
+    if data.len() < PUMPAMM_COLLECT_COIN_CREATOR_FEE_DISCRIMINATOR.len() {
+        return Err("Data is insufficient for a 'collect_coin_creator_fee' instruction.".to_string());
+    }
+
+    let mut parsed_data = object! {};
+
+    check_parse_residue("PumpAMM CollectCoinCreatorFee", &data[8..], &mut parsed_data);
+
+    Ok(create_standardized_instruction(
+        &PUMPAMM_PROGRAM_ID,
+        "CollectCoinCreatorFee",
+        "PumpAMM",
+        data,
+        accounts_indices,
+        account_keys,
+        is_signer,
+        is_writable,
+        parsed_data
+    ))
+}
+
 pub fn deserialize_pumpamm_generic_instruction(instruction_name: &str, data: &[u8], accounts_indices: &[u8], account_keys: &[Pubkey], is_signer: &[bool], is_writable: &[bool]) -> Result<JsonValue, String> {
     let safely_get_account = |idx: usize| -> String {
         if idx >= accounts_indices.len() {
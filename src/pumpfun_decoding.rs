@@ -2,6 +2,7 @@ use borsh::{BorshDeserialize, BorshSerialize};
 use json::{object, JsonValue};
 use solana_sdk::pubkey::Pubkey;
 use solana_sdk::pubkey;
+use crate::decode_anomalies::check_parse_residue;
 use crate::utils::create_standardized_instruction;
 
 pub const PUMP_CREATE_INSTRUCTION_DISCRIMINATOR: [u8; 8] = [0x18, 0x1e, 0xc8, 0x28, 0x05, 0x1c, 0x07, 0x77];
@@ -11,6 +12,19 @@ pub const PUMP_BUY_INSTRUCTION_DISCRIMINATOR: [u8; 8] = [0x66, 0x06, 0x3d, 0x12,
 pub const SYSTEM_PROGRAM_ID: Pubkey = pubkey!("11111111111111111111111111111111");
 pub const PUMP_MIGRATION_PROGRAM: Pubkey = pubkey!("39azUYFWPz3VHgKCf3VChUwbpURdCHRxjWVowf5jUJjg");
 pub const PUMPFUN_PROGRAM_ID: Pubkey = pubkey!("6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P");
+/// First slot of the May 2025 creator fee upgrade, earlier creates have no creator argument
+pub const PUMPFUN_CREATOR_FEE_UPGRADE_SLOT: u64 = 337_400_000;
+
+pub const PUMP_CREATE_ACCOUNTS: &[&str] = &[
+    "mint", "mintAuthority", "bondingCurve", "associatedBondingCurve", "global", "mplTokenMetadata",
+    "metadata", "user", "systemProgram", "tokenProgram", "associatedTokenProgram", "rent",
+    "eventAuthority", "program",
+];
+pub const PUMP_BUY_ACCOUNTS: &[&str] = &[
+    "global", "feeRecipient", "mint", "bondingCurve", "associatedBondingCurve", "associatedUser",
+    "user", "systemProgram", "tokenProgram", "creatorVault", "eventAuthority", "program",
+    "globalVolumeAccumulator", "userVolumeAccumulator", "feeConfig", "feeProgram",
+];
 pub const PUMPFUN_MINT_AUTHORITY: Pubkey = pubkey!("TSLvdd1pWpHVjahSpsvCXUbgwsL3JAcvokwaKt1eokM");
 pub const PUMPFUN_GLOBAL: Pubkey = pubkey!("4wTV1YmiEkRvAtNtsSGPtUrqRYQMe5SKy2uB4Jjaxnjf");
 pub const TOKEN_PROGRAM_ID: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
@@ -32,6 +46,14 @@ pub struct CreateParams {
     pub creator: Pubkey,
 }
 
+/// Create arguments before the creator fee upgrade
+#[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
+pub struct CreateV1Params {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+}
+
 #[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
 pub struct BuyParams {
     amount: u64,
@@ -75,13 +97,15 @@ pub fn deserialize_pump_create_instruction(data: &[u8], accounts_indices: &[u8],
         }
     };
 
-    let parsed_data = object! {
+    let mut parsed_data = object! {
         "name" => args.name,
         "symbol" => args.symbol,
         "uri" => args.uri,
         "creator" => bs58::encode(args.creator.to_bytes()).into_string(),
     };
     
+    check_parse_residue("Pumpfun Create", remaining_data_ref, &mut parsed_data);
+
     Ok(create_standardized_instruction(
         &PUMPFUN_PROGRAM_ID,
         "Create",
@@ -95,6 +119,37 @@ pub fn deserialize_pump_create_instruction(data: &[u8], accounts_indices: &[u8],
     ))
 }
 
+/// Create of the `pumpfun_create_v1` layout, pinned by slot in the layout variants
+pub fn deserialize_pump_create_v1_instruction(data: &[u8], accounts_indices: &[u8], account_keys: &[Pubkey], is_signer: &[bool], is_writable: &[bool]) -> Result<JsonValue, String> {
+    if data.len() < PUMP_CREATE_INSTRUCTION_DISCRIMINATOR.len() {
+        return Err("Data length is insufficient for a 'create' instruction.".to_string());
+    }
+
+    let mut remaining_data_ref = &data[8..];
+    let args = CreateV1Params::deserialize(&mut remaining_data_ref)
+        .map_err(|e| format!("Failed to deserialize PumpCreateV1Args: {:?}", e))?;
+
+    let mut parsed_data = object! {
+        "name" => args.name,
+        "symbol" => args.symbol,
+        "uri" => args.uri,
+    };
+
+    check_parse_residue("Pumpfun Create v1", remaining_data_ref, &mut parsed_data);
+
+    Ok(create_standardized_instruction(
+        &PUMPFUN_PROGRAM_ID,
+        "Create",
+        "Pumpfun",
+        data,
+        accounts_indices,
+        account_keys,
+        is_signer,
+        is_writable,
+        parsed_data
+    ))
+}
+
 pub fn deserialize_pump_buy_instruction(data: &[u8], accounts_indices: &[u8], account_keys: &[Pubkey], is_signer: &[bool], is_writable: &[bool]) -> Result<JsonValue, String> {
     if data.len() < PUMP_BUY_INSTRUCTION_DISCRIMINATOR.len() {
         return Err("Data length is insufficient for a 'buy' instruction.".to_string());
@@ -110,11 +165,13 @@ pub fn deserialize_pump_buy_instruction(data: &[u8], accounts_indices: &[u8], ac
         }
     };
 
-    let parsed_data = object! {
+    let mut parsed_data = object! {
         "amount" => args.amount.to_string(),
         "max_sol_cost" => args.max_sol_cost.to_string(),
     };
-    
+
+    check_parse_residue("Pumpfun Buy", remaining_data_ref, &mut parsed_data);
+
     Ok(create_standardized_instruction(
         &PUMPFUN_PROGRAM_ID,
         "Buy",
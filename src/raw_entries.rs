@@ -0,0 +1,88 @@
+use once_cell::sync::Lazy;
+use rustc_hash::FxHashSet as HashSet;
+use solana_entry::entry::Entry;
+use solana_sdk::transaction::VersionedTransaction;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::broadcast;
+
+
+/// Shape of the raw output of a FEC block
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RawFormat {
+    /// The deshredded payload as is, a bincode `Vec<Entry>`
+    Entries,
+    /// Each transaction of the block as a bincode `VersionedTransaction`, in entry order
+    Transactions,
+}
+
+impl RawFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "" | "entries" => Some(RawFormat::Entries),
+            "transactions" => Some(RawFormat::Transactions),
+            _ => None,
+        }
+    }
+}
+
+/// Payload of a decoded FEC block, before any of our decoding
+#[derive(Debug, Clone)]
+pub struct RawFecBlock {
+    pub slot: u64,
+    pub fec_set_index: u32,
+    pub payload: Vec<u8>,
+    pub timestamp: u64,
+}
+
+impl RawFecBlock {
+    /// Serialized transactions of the block kept by `keep`, None when the payload is not a list of entries
+    pub fn transactions(&self, keep: impl Fn(&VersionedTransaction) -> bool) -> Option<Vec<Vec<u8>>> {
+        let entries = bincode::deserialize::<Vec<Entry>>(&self.payload).ok()?;
+        entries
+            .iter()
+            .flat_map(|entry| entry.transactions.iter())
+            .filter(|transaction| keep(transaction))
+            .map(|transaction| bincode::serialize(transaction).ok())
+            .collect()
+    }
+}
+
+/// Static accounts and invoked programs of a raw transaction, accounts loaded through
+/// lookup tables are not resolved at this stage
+pub fn raw_transaction_keys(transaction: &VersionedTransaction) -> (HashSet<String>, HashSet<String>) {
+    let keys = transaction.message.static_account_keys();
+    let programs = transaction
+        .message
+        .instructions()
+        .iter()
+        .filter_map(|instruction| keys.get(instruction.program_id_index as usize))
+        .map(|program| program.to_string())
+        .collect();
+    (keys.iter().map(|key| key.to_string()).collect(), programs)
+}
+
+static RAW_FEC_BLOCK_TX: Lazy<broadcast::Sender<Arc<RawFecBlock>>> = Lazy::new(|| broadcast::channel(1024).0);
+
+pub fn subscribe_raw_fec_blocks() -> broadcast::Receiver<Arc<RawFecBlock>> {
+    RAW_FEC_BLOCK_TX.subscribe()
+}
+
+/// Publishes a deshredded FEC block, the payload is only copied when someone is subscribed
+pub fn publish_raw_fec_block(slot: u64, fec_set_index: u32, payload: &[u8]) {
+    if RAW_FEC_BLOCK_TX.receiver_count() == 0 {
+        return;
+    }
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_micros() as u64;
+
+    // Sending only fails when the last subscriber left in between
+    let _ = RAW_FEC_BLOCK_TX.send(Arc::new(RawFecBlock {
+        slot,
+        fec_set_index,
+        payload: payload.to_vec(),
+        timestamp,
+    }));
+}
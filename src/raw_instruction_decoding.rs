@@ -0,0 +1,56 @@
+use json::{object, JsonValue};
+use once_cell::sync::Lazy;
+use solana_sdk::pubkey::Pubkey;
+use crate::decoder_registry::find_decoder_by_program;
+use crate::idl_decoding::find_idl_program;
+use crate::utils::create_standardized_instruction;
+
+/// Leading bytes reported as the discriminator, the Anchor length. Native programs select on fewer
+const RAW_DISCRIMINATOR_LEN: usize = 8;
+
+/// Opt-in flag, instructions of programs without a decoder are dropped when disabled
+static RAW_INSTRUCTIONS_ENABLED: Lazy<bool> = Lazy::new(|| {
+    std::env::var("RAW_INSTRUCTIONS_ENABLED")
+        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+});
+
+pub fn is_raw_instructions_enabled() -> bool {
+    *RAW_INSTRUCTIONS_ENABLED
+}
+
+/// Programs emitted as raw instructions. Programs with a decoder or an IDL never fall back,
+/// their unknown discriminators are reported instead
+pub fn is_raw_program(program_id: &Pubkey) -> bool {
+    is_raw_instructions_enabled() && find_decoder_by_program(program_id).is_none() && find_idl_program(program_id).is_none()
+}
+
+/// Like token movements, raw instructions do not make a transaction relevant on their own
+pub fn is_raw_instruction(instruction: &JsonValue) -> bool {
+    instruction["protocol"] == "Raw"
+}
+
+pub fn deserialize_raw_instruction(
+    program_id: &Pubkey,
+    data: &[u8],
+    accounts_indices: &[u8],
+    account_keys: &[Pubkey],
+    is_signer: &[bool],
+    is_writable: &[bool],
+) -> JsonValue {
+    let parsed_data = object! {
+        "discriminator" => hex::encode(&data[..data.len().min(RAW_DISCRIMINATOR_LEN)]),
+    };
+
+    create_standardized_instruction(
+        program_id,
+        "Unknown",
+        "Raw",
+        data,
+        accounts_indices,
+        account_keys,
+        is_signer,
+        is_writable,
+        parsed_data
+    )
+}
@@ -0,0 +1,417 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use json::{object, JsonValue};
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use crate::decode_anomalies::check_parse_residue;
+use crate::utils::{create_standardized_instruction, ValueSanity};
+
+pub const RAYDIUM_CLMM_PROGRAM: Pubkey = pubkey!("CAMMCzo5YL8w4VFF8KVHrK22GGUsp5VTaW7grrKgrWqK");
+
+pub const RAYDIUM_CLMM_CREATE_POOL_ACCOUNTS: &[&str] = &[
+    "poolCreator", "ammConfig", "poolState", "tokenMint0", "tokenMint1", "tokenVault0",
+    "tokenVault1", "observationState", "tickArrayBitmap", "tokenProgram0", "tokenProgram1",
+    "systemProgram", "rent",
+];
+pub const RAYDIUM_CLMM_OPEN_POSITION_ACCOUNTS: &[&str] = &[
+    "payer", "positionNftOwner", "positionNftMint", "positionNftAccount", "metadataAccount",
+    "poolState", "protocolPosition", "tickArrayLower", "tickArrayUpper", "personalPosition",
+    "tokenAccount0", "tokenAccount1", "tokenVault0", "tokenVault1", "rent", "systemProgram",
+    "tokenProgram", "associatedTokenProgram", "metadataProgram",
+];
+pub const RAYDIUM_CLMM_INCREASE_LIQUIDITY_ACCOUNTS: &[&str] = &[
+    "nftOwner", "nftAccount", "poolState", "protocolPosition", "personalPosition", "tickArrayLower",
+    "tickArrayUpper", "tokenAccount0", "tokenAccount1", "tokenVault0", "tokenVault1",
+    "tokenProgram",
+];
+pub const RAYDIUM_CLMM_DECREASE_LIQUIDITY_ACCOUNTS: &[&str] = &[
+    "nftOwner", "nftAccount", "personalPosition", "poolState", "protocolPosition", "tokenVault0",
+    "tokenVault1", "tickArrayLower", "tickArrayUpper", "recipientTokenAccount0",
+    "recipientTokenAccount1", "tokenProgram",
+];
+pub const RAYDIUM_CLMM_OPEN_POSITION_V2_ACCOUNTS: &[&str] = &[
+    "payer", "positionNftOwner", "positionNftMint", "positionNftAccount", "metadataAccount",
+    "poolState", "protocolPosition", "tickArrayLower", "tickArrayUpper", "personalPosition",
+    "tokenAccount0", "tokenAccount1", "tokenVault0", "tokenVault1", "rent", "systemProgram",
+    "tokenProgram", "associatedTokenProgram", "metadataProgram", "tokenProgram2022",
+    "vault0Mint", "vault1Mint",
+];
+pub const RAYDIUM_CLMM_OPEN_POSITION_WITH_TOKEN22_NFT_ACCOUNTS: &[&str] = &[
+    "payer", "positionNftOwner", "positionNftMint", "positionNftAccount", "poolState",
+    "protocolPosition", "tickArrayLower", "tickArrayUpper", "personalPosition", "tokenAccount0",
+    "tokenAccount1", "tokenVault0", "tokenVault1", "rent", "systemProgram", "tokenProgram",
+    "associatedTokenProgram", "tokenProgram2022", "vault0Mint", "vault1Mint",
+];
+pub const RAYDIUM_CLMM_INCREASE_LIQUIDITY_V2_ACCOUNTS: &[&str] = &[
+    "nftOwner", "nftAccount", "poolState", "protocolPosition", "personalPosition", "tickArrayLower",
+    "tickArrayUpper", "tokenAccount0", "tokenAccount1", "tokenVault0", "tokenVault1",
+    "tokenProgram", "tokenProgram2022", "vault0Mint", "vault1Mint",
+];
+pub const RAYDIUM_CLMM_DECREASE_LIQUIDITY_V2_ACCOUNTS: &[&str] = &[
+    "nftOwner", "nftAccount", "personalPosition", "poolState", "protocolPosition", "tokenVault0",
+    "tokenVault1", "tickArrayLower", "tickArrayUpper", "recipientTokenAccount0",
+    "recipientTokenAccount1", "tokenProgram", "tokenProgram2022", "memoProgram", "vault0Mint",
+    "vault1Mint",
+];
+pub const RAYDIUM_CLMM_SWAP_ACCOUNTS: &[&str] = &[
+    "payer", "ammConfig", "poolState", "inputTokenAccount", "outputTokenAccount", "inputVault",
+    "outputVault", "observationState", "tokenProgram", "tickArray",
+];
+pub const RAYDIUM_CLMM_SWAP_V2_ACCOUNTS: &[&str] = &[
+    "payer", "ammConfig", "poolState", "inputTokenAccount", "outputTokenAccount", "inputVault",
+    "outputVault", "observationState", "tokenProgram", "tokenProgram2022", "memoProgram",
+    "inputVaultMint", "outputVaultMint",
+];
+
+pub const RAYDIUM_CLMM_CREATE_POOL_DISCRIMINATOR: [u8; 8] = [233, 146, 209, 142, 207, 104, 64, 188];
+pub const RAYDIUM_CLMM_OPEN_POSITION_DISCRIMINATOR: [u8; 8] = [135, 128, 47, 77, 15, 152, 240, 49];
+pub const RAYDIUM_CLMM_INCREASE_LIQUIDITY_DISCRIMINATOR: [u8; 8] = [46, 156, 243, 118, 13, 205, 251, 178];
+pub const RAYDIUM_CLMM_DECREASE_LIQUIDITY_DISCRIMINATOR: [u8; 8] = [160, 38, 208, 111, 104, 91, 44, 1];
+pub const RAYDIUM_CLMM_OPEN_POSITION_V2_DISCRIMINATOR: [u8; 8] = [77, 184, 74, 214, 112, 86, 241, 199];
+pub const RAYDIUM_CLMM_OPEN_POSITION_WITH_TOKEN22_NFT_DISCRIMINATOR: [u8; 8] = [77, 255, 174, 82, 125, 29, 201, 46];
+pub const RAYDIUM_CLMM_INCREASE_LIQUIDITY_V2_DISCRIMINATOR: [u8; 8] = [133, 29, 89, 223, 69, 238, 176, 10];
+pub const RAYDIUM_CLMM_DECREASE_LIQUIDITY_V2_DISCRIMINATOR: [u8; 8] = [58, 127, 188, 62, 79, 82, 196, 96];
+pub const RAYDIUM_CLMM_SWAP_DISCRIMINATOR: [u8; 8] = [248, 198, 158, 145, 225, 117, 135, 200];
+pub const RAYDIUM_CLMM_SWAP_V2_DISCRIMINATOR: [u8; 8] = [43, 4, 237, 11, 26, 201, 30, 98];
+
+/// Sqrt price bounds (Q64.64), matching MIN_SQRT_PRICE_X64/MAX_SQRT_PRICE_X64 of the program
+pub const RAYDIUM_CLMM_MIN_SQRT_PRICE_X64: u128 = 4295048016;
+pub const RAYDIUM_CLMM_MAX_SQRT_PRICE_X64: u128 = 79226673521066979257578248091;
+
+/// The deprecated OpenPosition, IncreaseLiquidity and DecreaseLiquidity are still accepted by
+/// the program, current clients send the V2 instructions and OpenPositionWithToken22Nft
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RaydiumClmmInstructionType {
+    CreatePool,
+    OpenPosition,
+    OpenPositionV2,
+    OpenPositionWithToken22Nft,
+    IncreaseLiquidity,
+    IncreaseLiquidityV2,
+    DecreaseLiquidity,
+    DecreaseLiquidityV2,
+    Swap,
+    SwapV2,
+}
+
+impl RaydiumClmmInstructionType {
+    pub fn name(&self) -> &'static str {
+        match self {
+            RaydiumClmmInstructionType::CreatePool => "CreatePool",
+            RaydiumClmmInstructionType::OpenPosition => "OpenPosition",
+            RaydiumClmmInstructionType::OpenPositionV2 => "OpenPositionV2",
+            RaydiumClmmInstructionType::OpenPositionWithToken22Nft => "OpenPositionWithToken22Nft",
+            RaydiumClmmInstructionType::IncreaseLiquidity => "IncreaseLiquidity",
+            RaydiumClmmInstructionType::IncreaseLiquidityV2 => "IncreaseLiquidityV2",
+            RaydiumClmmInstructionType::DecreaseLiquidity => "DecreaseLiquidity",
+            RaydiumClmmInstructionType::DecreaseLiquidityV2 => "DecreaseLiquidityV2",
+            RaydiumClmmInstructionType::Swap => "Swap",
+            RaydiumClmmInstructionType::SwapV2 => "SwapV2",
+        }
+    }
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
+pub struct ClmmCreatePoolParams {
+    pub sqrt_price_x64: u128,
+    pub open_time: u64,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
+pub struct ClmmOpenPositionParams {
+    pub tick_lower_index: i32,
+    pub tick_upper_index: i32,
+    pub tick_array_lower_start_index: i32,
+    pub tick_array_upper_start_index: i32,
+    pub liquidity: u128,
+    pub amount_0_max: u64,
+    pub amount_1_max: u64,
+}
+
+/// Trailing args of OpenPositionV2 and OpenPositionWithToken22Nft, after the OpenPosition ones
+#[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
+pub struct ClmmOpenPositionV2Params {
+    pub with_metadata: bool,
+    pub base_flag: Option<bool>,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
+pub struct ClmmIncreaseLiquidityParams {
+    pub liquidity: u128,
+    pub amount_0_max: u64,
+    pub amount_1_max: u64,
+}
+
+/// Trailing arg of IncreaseLiquidityV2, after the IncreaseLiquidity ones
+#[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
+pub struct ClmmIncreaseLiquidityV2Params {
+    pub base_flag: Option<bool>,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
+pub struct ClmmDecreaseLiquidityParams {
+    pub liquidity: u128,
+    pub amount_0_min: u64,
+    pub amount_1_min: u64,
+}
+
+/// Shared by Swap and SwapV2, which only differ by their accounts
+#[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
+pub struct ClmmSwapParams {
+    pub amount: u64,
+    pub other_amount_threshold: u64,
+    pub sqrt_price_limit_x64: u128,
+    pub is_base_input: bool,
+}
+
+pub fn get_raydium_clmm_instruction_type(data: &[u8]) -> Option<RaydiumClmmInstructionType> {
+    match data.get(0..8) {
+        Some(d) if d == RAYDIUM_CLMM_CREATE_POOL_DISCRIMINATOR => Some(RaydiumClmmInstructionType::CreatePool),
+        Some(d) if d == RAYDIUM_CLMM_OPEN_POSITION_DISCRIMINATOR => Some(RaydiumClmmInstructionType::OpenPosition),
+        Some(d) if d == RAYDIUM_CLMM_OPEN_POSITION_V2_DISCRIMINATOR => Some(RaydiumClmmInstructionType::OpenPositionV2),
+        Some(d) if d == RAYDIUM_CLMM_OPEN_POSITION_WITH_TOKEN22_NFT_DISCRIMINATOR => Some(RaydiumClmmInstructionType::OpenPositionWithToken22Nft),
+        Some(d) if d == RAYDIUM_CLMM_INCREASE_LIQUIDITY_DISCRIMINATOR => Some(RaydiumClmmInstructionType::IncreaseLiquidity),
+        Some(d) if d == RAYDIUM_CLMM_INCREASE_LIQUIDITY_V2_DISCRIMINATOR => Some(RaydiumClmmInstructionType::IncreaseLiquidityV2),
+        Some(d) if d == RAYDIUM_CLMM_DECREASE_LIQUIDITY_DISCRIMINATOR => Some(RaydiumClmmInstructionType::DecreaseLiquidity),
+        Some(d) if d == RAYDIUM_CLMM_DECREASE_LIQUIDITY_V2_DISCRIMINATOR => Some(RaydiumClmmInstructionType::DecreaseLiquidityV2),
+        Some(d) if d == RAYDIUM_CLMM_SWAP_DISCRIMINATOR => Some(RaydiumClmmInstructionType::Swap),
+        Some(d) if d == RAYDIUM_CLMM_SWAP_V2_DISCRIMINATOR => Some(RaydiumClmmInstructionType::SwapV2),
+        _ => None,
+    }
+}
+
+/// A zero limit means no limit, anything else must be a valid sqrt price
+fn check_sqrt_price_limit(sanity: &mut ValueSanity, field: &str, sqrt_price_limit: u128) {
+    if sqrt_price_limit != 0 {
+        sanity.check_range_u128(field, sqrt_price_limit, RAYDIUM_CLMM_MIN_SQRT_PRICE_X64, RAYDIUM_CLMM_MAX_SQRT_PRICE_X64);
+    }
+}
+
+/// Accounts: pool creator, amm config, pool state=2, token mint 0=3, token mint 1=4, vaults,
+/// observation state, tick array bitmap, token programs, system program, rent
+pub fn deserialize_raydium_clmm_create_pool_instruction(
+    data: &[u8],
+    accounts_indices: &[u8],
+    account_keys: &[Pubkey],
+    is_signer: &[bool],
+    is_writable: &[bool],
+) -> Result<JsonValue, String> {
+    let mut data_ref = &data[8..];
+
+    let args = match ClmmCreatePoolParams::deserialize(&mut data_ref) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            return Err(format!("CreatePoolParams deserialization failure: {:?}", e));
+        }
+    };
+
+    let mut parsed_data = object! {
+        "sqrt_price_x64" => args.sqrt_price_x64.to_string(),
+        "open_time" => args.open_time.to_string(),
+    };
+
+    let mut sanity = ValueSanity::new();
+    sanity.check_range_u128("sqrt_price_x64", args.sqrt_price_x64, RAYDIUM_CLMM_MIN_SQRT_PRICE_X64, RAYDIUM_CLMM_MAX_SQRT_PRICE_X64);
+    sanity.apply(&mut parsed_data);
+
+    check_parse_residue("RaydiumCLMM CreatePool", data_ref, &mut parsed_data);
+
+    Ok(create_standardized_instruction(
+        &RAYDIUM_CLMM_PROGRAM,
+        "CreatePool",
+        "RaydiumCLMM",
+        data,
+        accounts_indices,
+        account_keys,
+        is_signer,
+        is_writable,
+        parsed_data
+    ))
+}
+
+/// OpenPosition, OpenPositionV2 and OpenPositionWithToken22Nft. Accounts: payer, position nft
+/// owner, position nft mint, position nft account, metadata (not with a Token-2022 nft), pool state,
+/// protocol position, tick arrays, personal position, token accounts, vaults and programs, then the
+/// token-2022 program and vault mints for the V2 instructions
+pub fn deserialize_raydium_clmm_open_position_instruction(
+    instruction_type: RaydiumClmmInstructionType,
+    data: &[u8],
+    accounts_indices: &[u8],
+    account_keys: &[Pubkey],
+    is_signer: &[bool],
+    is_writable: &[bool],
+) -> Result<JsonValue, String> {
+    let mut data_ref = &data[8..];
+
+    let args = match ClmmOpenPositionParams::deserialize(&mut data_ref) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            return Err(format!("OpenPositionParams deserialization failure: {:?}", e));
+        }
+    };
+
+    let mut parsed_data = object! {
+        "tick_lower_index" => args.tick_lower_index,
+        "tick_upper_index" => args.tick_upper_index,
+        "tick_array_lower_start_index" => args.tick_array_lower_start_index,
+        "tick_array_upper_start_index" => args.tick_array_upper_start_index,
+        "liquidity" => args.liquidity.to_string(),
+        "amount_0_max" => args.amount_0_max.to_string(),
+        "amount_1_max" => args.amount_1_max.to_string(),
+    };
+
+    if instruction_type != RaydiumClmmInstructionType::OpenPosition {
+        let v2_args = ClmmOpenPositionV2Params::deserialize(&mut data_ref)
+            .map_err(|e| format!("{}Params deserialization failure: {:?}", instruction_type.name(), e))?;
+        parsed_data["with_metadata"] = v2_args.with_metadata.into();
+        parsed_data["base_flag"] = v2_args.base_flag.into();
+    }
+
+    check_parse_residue(&format!("RaydiumCLMM {}", instruction_type.name()), data_ref, &mut parsed_data);
+
+    Ok(create_standardized_instruction(
+        &RAYDIUM_CLMM_PROGRAM,
+        instruction_type.name(),
+        "RaydiumCLMM",
+        data,
+        accounts_indices,
+        account_keys,
+        is_signer,
+        is_writable,
+        parsed_data
+    ))
+}
+
+/// IncreaseLiquidity and IncreaseLiquidityV2. Accounts: nft owner, nft account, pool state=2,
+/// protocol position, personal position, tick arrays, token accounts, vaults, token program,
+/// then the token-2022 program and vault mints for V2
+pub fn deserialize_raydium_clmm_increase_liquidity_instruction(
+    instruction_type: RaydiumClmmInstructionType,
+    data: &[u8],
+    accounts_indices: &[u8],
+    account_keys: &[Pubkey],
+    is_signer: &[bool],
+    is_writable: &[bool],
+) -> Result<JsonValue, String> {
+    let mut data_ref = &data[8..];
+
+    let args = match ClmmIncreaseLiquidityParams::deserialize(&mut data_ref) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            return Err(format!("IncreaseLiquidityParams deserialization failure: {:?}", e));
+        }
+    };
+
+    let mut parsed_data = object! {
+        "liquidity" => args.liquidity.to_string(),
+        "amount_0_max" => args.amount_0_max.to_string(),
+        "amount_1_max" => args.amount_1_max.to_string(),
+    };
+
+    if instruction_type == RaydiumClmmInstructionType::IncreaseLiquidityV2 {
+        let v2_args = ClmmIncreaseLiquidityV2Params::deserialize(&mut data_ref)
+            .map_err(|e| format!("IncreaseLiquidityV2Params deserialization failure: {:?}", e))?;
+        parsed_data["base_flag"] = v2_args.base_flag.into();
+    }
+
+    check_parse_residue(&format!("RaydiumCLMM {}", instruction_type.name()), data_ref, &mut parsed_data);
+
+    Ok(create_standardized_instruction(
+        &RAYDIUM_CLMM_PROGRAM,
+        instruction_type.name(),
+        "RaydiumCLMM",
+        data,
+        accounts_indices,
+        account_keys,
+        is_signer,
+        is_writable,
+        parsed_data
+    ))
+}
+
+/// DecreaseLiquidity and DecreaseLiquidityV2, which share their args. Accounts: nft owner, nft
+/// account, personal position, pool state=3, protocol position, vaults, tick arrays, recipient
+/// token accounts, token program, then the token-2022 program, memo program and vault mints for V2
+pub fn deserialize_raydium_clmm_decrease_liquidity_instruction(
+    instruction_type: RaydiumClmmInstructionType,
+    data: &[u8],
+    accounts_indices: &[u8],
+    account_keys: &[Pubkey],
+    is_signer: &[bool],
+    is_writable: &[bool],
+) -> Result<JsonValue, String> {
+    let mut data_ref = &data[8..];
+
+    let args = match ClmmDecreaseLiquidityParams::deserialize(&mut data_ref) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            return Err(format!("DecreaseLiquidityParams deserialization failure: {:?}", e));
+        }
+    };
+
+    let mut parsed_data = object! {
+        "liquidity" => args.liquidity.to_string(),
+        "amount_0_min" => args.amount_0_min.to_string(),
+        "amount_1_min" => args.amount_1_min.to_string(),
+    };
+
+    check_parse_residue(&format!("RaydiumCLMM {}", instruction_type.name()), data_ref, &mut parsed_data);
+
+    Ok(create_standardized_instruction(
+        &RAYDIUM_CLMM_PROGRAM,
+        instruction_type.name(),
+        "RaydiumCLMM",
+        data,
+        accounts_indices,
+        account_keys,
+        is_signer,
+        is_writable,
+        parsed_data
+    ))
+}
+
+/// Swap and SwapV2. Accounts: payer, amm config, pool state=2, input token account,
+/// output token account, input vault, output vault, observation state, token program, then
+/// the tick arrays for Swap or the token-2022 program, memo program and vault mints for SwapV2
+pub fn deserialize_raydium_clmm_swap_instruction(
+    instruction_name: &str,
+    data: &[u8],
+    accounts_indices: &[u8],
+    account_keys: &[Pubkey],
+    is_signer: &[bool],
+    is_writable: &[bool],
+) -> Result<JsonValue, String> {
+    let mut data_ref = &data[8..];
+
+    let args = match ClmmSwapParams::deserialize(&mut data_ref) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            return Err(format!("{}Params deserialization failure: {:?}", instruction_name, e));
+        }
+    };
+
+    let mut parsed_data = object! {
+        "amount" => args.amount.to_string(),
+        "other_amount_threshold" => args.other_amount_threshold.to_string(),
+        "sqrt_price_limit_x64" => args.sqrt_price_limit_x64.to_string(),
+        "is_base_input" => args.is_base_input,
+    };
+
+    let mut sanity = ValueSanity::new();
+    sanity.check_nonzero("amount", args.amount as u128);
+    check_sqrt_price_limit(&mut sanity, "sqrt_price_limit_x64", args.sqrt_price_limit_x64);
+    sanity.apply(&mut parsed_data);
+
+    check_parse_residue(&format!("RaydiumCLMM {}", instruction_name), data_ref, &mut parsed_data);
+
+    Ok(create_standardized_instruction(
+        &RAYDIUM_CLMM_PROGRAM,
+        instruction_name,
+        "RaydiumCLMM",
+        data,
+        accounts_indices,
+        account_keys,
+        is_signer,
+        is_writable,
+        parsed_data
+    ))
+}
@@ -2,17 +2,44 @@ use borsh::{BorshDeserialize, BorshSerialize};
 use json::{object, JsonValue};
 use solana_sdk::pubkey;
 use solana_sdk::pubkey::Pubkey;
+use crate::decode_anomalies::check_parse_residue;
 use crate::utils::create_standardized_instruction;
 
 pub const RAYDIUM_CPMM_PROGRAM: Pubkey = pubkey!("CPMMoo8L3F4NbTegBCKVNunggL7H1ZpdTHKxQB5qKP1C");
 
+pub const RAYDIUM_CPMM_INITIALIZE_ACCOUNTS: &[&str] = &[
+    "creator", "ammConfig", "authority", "poolState", "token0Mint", "token1Mint", "lpMint",
+    "creatorToken0", "creatorToken1", "creatorLpToken", "token0Vault", "token1Vault",
+    "createPoolFee", "observationState", "tokenProgram", "token0Program", "token1Program",
+    "associatedTokenProgram", "systemProgram", "rent",
+];
+pub const RAYDIUM_CPMM_SWAP_ACCOUNTS: &[&str] = &[
+    "payer", "authority", "ammConfig", "poolState", "inputTokenAccount", "outputTokenAccount",
+    "inputVault", "outputVault", "inputTokenProgram", "outputTokenProgram", "inputTokenMint",
+    "outputTokenMint", "observationState",
+];
+pub const RAYDIUM_CPMM_DEPOSIT_ACCOUNTS: &[&str] = &[
+    "owner", "authority", "poolState", "ownerLpToken", "token0Account", "token1Account",
+    "token0Vault", "token1Vault", "tokenProgram", "tokenProgram2022", "vault0Mint", "vault1Mint",
+    "lpMint",
+];
+pub const RAYDIUM_CPMM_WITHDRAW_ACCOUNTS: &[&str] = &[
+    "owner", "authority", "poolState", "ownerLpToken", "token0Account", "token1Account",
+    "token0Vault", "token1Vault", "tokenProgram", "tokenProgram2022", "vault0Mint", "vault1Mint",
+    "lpMint", "memoProgram",
+];
+
 pub const SYSTEM_PROGRAM_ID: Pubkey = pubkey!("11111111111111111111111111111111");
 pub const TOKEN_PROGRAM_ID: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
 pub const TOKEN_PROGRAM_2022: Pubkey = pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
 pub const ASSOCIATED_TOKEN_PROGRAM: Pubkey = pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
 pub const RENT_PROGRAM: Pubkey = pubkey!("SysvarRent111111111111111111111111111111111");
 
-const INITIALIZE_DISCRIMINATOR: [u8; 8] = [175, 175, 109, 31, 13, 152, 155, 237];
+pub const INITIALIZE_DISCRIMINATOR: [u8; 8] = [175, 175, 109, 31, 13, 152, 155, 237];
+pub const SWAP_BASE_INPUT_DISCRIMINATOR: [u8; 8] = [143, 190, 90, 218, 196, 30, 51, 222];
+pub const SWAP_BASE_OUTPUT_DISCRIMINATOR: [u8; 8] = [55, 217, 98, 86, 163, 74, 180, 173];
+pub const DEPOSIT_DISCRIMINATOR: [u8; 8] = [242, 35, 198, 137, 82, 225, 242, 182];
+pub const WITHDRAW_DISCRIMINATOR: [u8; 8] = [183, 18, 70, 156, 148, 109, 161, 34];
 
 #[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
 pub struct InitializeParams {
@@ -21,8 +48,38 @@ pub struct InitializeParams {
     pub open_time: u64,
 }
 
+#[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
+pub struct SwapBaseInputParams {
+    pub amount_in: u64,
+    pub minimum_amount_out: u64,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
+pub struct SwapBaseOutputParams {
+    pub max_amount_in: u64,
+    pub amount_out: u64,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
+pub struct CpmmDepositParams {
+    pub lp_token_amount: u64,
+    pub maximum_token_0_amount: u64,
+    pub maximum_token_1_amount: u64,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
+pub struct CpmmWithdrawParams {
+    pub lp_token_amount: u64,
+    pub minimum_token_0_amount: u64,
+    pub minimum_token_1_amount: u64,
+}
+
 pub enum RaydiumCpmmInstructionType {
     Initialize,
+    SwapBaseInput,
+    SwapBaseOutput,
+    Deposit,
+    Withdraw,
 }
 
 pub fn get_raydium_cpmm_instruction_type(data: &[u8]) -> Option<RaydiumCpmmInstructionType> {
@@ -32,10 +89,13 @@ pub fn get_raydium_cpmm_instruction_type(data: &[u8]) -> Option<RaydiumCpmmInstr
     
     let discriminator = &data[0..8];
     
-    if discriminator == INITIALIZE_DISCRIMINATOR {
-        Some(RaydiumCpmmInstructionType::Initialize)
-    } else {
-        None
+    match discriminator {
+        d if d == INITIALIZE_DISCRIMINATOR => Some(RaydiumCpmmInstructionType::Initialize),
+        d if d == SWAP_BASE_INPUT_DISCRIMINATOR => Some(RaydiumCpmmInstructionType::SwapBaseInput),
+        d if d == SWAP_BASE_OUTPUT_DISCRIMINATOR => Some(RaydiumCpmmInstructionType::SwapBaseOutput),
+        d if d == DEPOSIT_DISCRIMINATOR => Some(RaydiumCpmmInstructionType::Deposit),
+        d if d == WITHDRAW_DISCRIMINATOR => Some(RaydiumCpmmInstructionType::Withdraw),
+        _ => None,
     }
 }
 
@@ -62,12 +122,14 @@ pub fn deserialize_raydium_cpmm_initialize_instruction(
         ));
     }
 
-    let parsed_data = object! {
+    let mut parsed_data = object! {
         "init_amount_0" => args.init_amount_0.to_string(),
         "init_amount_1" => args.init_amount_1.to_string(),
         "open_time" => args.open_time.to_string(),
     };
     
+    check_parse_residue("RaydiumCPMM Initialize", data_ref, &mut parsed_data);
+
     Ok(create_standardized_instruction(
         &RAYDIUM_CPMM_PROGRAM,
         "Initialize",
@@ -80,3 +142,155 @@ pub fn deserialize_raydium_cpmm_initialize_instruction(
         parsed_data
     ))
 }
+
+/// Accounts: payer, authority, amm config, pool state=3, input token account, output token account,
+/// input vault, output vault, input/output token programs, input mint=10, output mint=11, observation state
+pub fn deserialize_raydium_cpmm_swap_base_input_instruction(
+    data: &[u8],
+    accounts_indices: &[u8],
+    account_keys: &[Pubkey],
+    is_signer: &[bool],
+    is_writable: &[bool],
+) -> Result<JsonValue, String> {
+    let mut data_ref = &data[8..];
+
+    let args = match SwapBaseInputParams::deserialize(&mut data_ref) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            return Err(format!("SwapBaseInputParams deserialization failure: {:?}", e));
+        }
+    };
+
+    let mut parsed_data = object! {
+        "amount_in" => args.amount_in.to_string(),
+        "minimum_amount_out" => args.minimum_amount_out.to_string(),
+    };
+
+    check_parse_residue("RaydiumCPMM SwapBaseInput", data_ref, &mut parsed_data);
+
+    Ok(create_standardized_instruction(
+        &RAYDIUM_CPMM_PROGRAM,
+        "SwapBaseInput",
+        "RaydiumCPMM",
+        data,
+        accounts_indices,
+        account_keys,
+        is_signer,
+        is_writable,
+        parsed_data
+    ))
+}
+
+/// Same accounts as SwapBaseInput
+pub fn deserialize_raydium_cpmm_swap_base_output_instruction(
+    data: &[u8],
+    accounts_indices: &[u8],
+    account_keys: &[Pubkey],
+    is_signer: &[bool],
+    is_writable: &[bool],
+) -> Result<JsonValue, String> {
+    let mut data_ref = &data[8..];
+
+    let args = match SwapBaseOutputParams::deserialize(&mut data_ref) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            return Err(format!("SwapBaseOutputParams deserialization failure: {:?}", e));
+        }
+    };
+
+    let mut parsed_data = object! {
+        "max_amount_in" => args.max_amount_in.to_string(),
+        "amount_out" => args.amount_out.to_string(),
+    };
+
+    check_parse_residue("RaydiumCPMM SwapBaseOutput", data_ref, &mut parsed_data);
+
+    Ok(create_standardized_instruction(
+        &RAYDIUM_CPMM_PROGRAM,
+        "SwapBaseOutput",
+        "RaydiumCPMM",
+        data,
+        accounts_indices,
+        account_keys,
+        is_signer,
+        is_writable,
+        parsed_data
+    ))
+}
+
+/// Accounts: owner, authority, pool state=2, owner lp token, owner token 0/1 accounts, vault 0/1,
+/// token programs, vault 0 mint=10, vault 1 mint=11, lp mint
+pub fn deserialize_raydium_cpmm_deposit_instruction(
+    data: &[u8],
+    accounts_indices: &[u8],
+    account_keys: &[Pubkey],
+    is_signer: &[bool],
+    is_writable: &[bool],
+) -> Result<JsonValue, String> {
+    let mut data_ref = &data[8..];
+
+    let args = match CpmmDepositParams::deserialize(&mut data_ref) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            return Err(format!("CpmmDepositParams deserialization failure: {:?}", e));
+        }
+    };
+
+    let mut parsed_data = object! {
+        "lp_token_amount" => args.lp_token_amount.to_string(),
+        "maximum_token_0_amount" => args.maximum_token_0_amount.to_string(),
+        "maximum_token_1_amount" => args.maximum_token_1_amount.to_string(),
+    };
+
+    check_parse_residue("RaydiumCPMM Deposit", data_ref, &mut parsed_data);
+
+    Ok(create_standardized_instruction(
+        &RAYDIUM_CPMM_PROGRAM,
+        "Deposit",
+        "RaydiumCPMM",
+        data,
+        accounts_indices,
+        account_keys,
+        is_signer,
+        is_writable,
+        parsed_data
+    ))
+}
+
+/// Same accounts as Deposit followed by the memo program
+pub fn deserialize_raydium_cpmm_withdraw_instruction(
+    data: &[u8],
+    accounts_indices: &[u8],
+    account_keys: &[Pubkey],
+    is_signer: &[bool],
+    is_writable: &[bool],
+) -> Result<JsonValue, String> {
+    let mut data_ref = &data[8..];
+
+    let args = match CpmmWithdrawParams::deserialize(&mut data_ref) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            return Err(format!("CpmmWithdrawParams deserialization failure: {:?}", e));
+        }
+    };
+
+    let mut parsed_data = object! {
+        "lp_token_amount" => args.lp_token_amount.to_string(),
+        "minimum_token_0_amount" => args.minimum_token_0_amount.to_string(),
+        "minimum_token_1_amount" => args.minimum_token_1_amount.to_string(),
+    };
+
+    check_parse_residue("RaydiumCPMM Withdraw", data_ref, &mut parsed_data);
+
+    Ok(create_standardized_instruction(
+        &RAYDIUM_CPMM_PROGRAM,
+        "Withdraw",
+        "RaydiumCPMM",
+        data,
+        accounts_indices,
+        account_keys,
+        is_signer,
+        is_writable,
+        parsed_data
+    ))
+}
@@ -2,9 +2,17 @@ use borsh::{BorshDeserialize, BorshSerialize};
 use json::{object, JsonValue};
 use solana_sdk::pubkey;
 use solana_sdk::pubkey::Pubkey;
+use crate::decode_anomalies::check_parse_residue;
 use crate::utils::create_standardized_instruction;
 
 pub const RAYDIUM_LP_PROGRAM: Pubkey = pubkey!("675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8");
+
+pub const RAYDIUM_INITIALIZE2_ACCOUNTS: &[&str] = &[
+    "tokenProgram", "associatedTokenProgram", "systemProgram", "rent", "amm", "ammAuthority",
+    "ammOpenOrders", "lpMint", "coinMint", "pcMint", "poolCoinTokenAccount", "poolPcTokenAccount",
+    "poolWithdrawQueue", "ammTargetOrders", "poolTempLp", "serumProgram", "serumMarket",
+    "userWallet", "userTokenCoin", "userTokenPc", "userLpTokenAccount",
+];
 pub const SYSTEM_PROGRAM_ID: Pubkey = pubkey!("11111111111111111111111111111111");
 pub const TOKEN_PROGRAM_ID: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
 pub const ASSOCIATED_TOKEN_PROGRAM: Pubkey =
@@ -23,13 +31,31 @@ pub struct Initialize2Params {
     init_coin_amount: u64,
 }
 
+#[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
+pub struct SwapBaseInParams {
+    discriminator: u8,
+    amount_in: u64,
+    minimum_amount_out: u64,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
+pub struct SwapBaseOutParams {
+    discriminator: u8,
+    max_amount_in: u64,
+    amount_out: u64,
+}
+
 pub enum RaydiumInstructionType {
     Initialize2,
+    SwapBaseIn,
+    SwapBaseOut,
 }
 
 pub fn get_raydium_instruction_type(data: &[u8]) -> Option<RaydiumInstructionType> {
     match data.get(0..1) {
         Some(d) if d[0] == 1 => Some(RaydiumInstructionType::Initialize2),
+        Some(d) if d[0] == 9 => Some(RaydiumInstructionType::SwapBaseIn),
+        Some(d) if d[0] == 11 => Some(RaydiumInstructionType::SwapBaseOut),
         _ => None,
     }
 }
@@ -50,7 +76,7 @@ pub fn deserialize_raydium_initialize2_instruction(
         }
     };
     
-    let parsed_data = object! {
+    let mut parsed_data = object! {
         "discriminator" => args.discriminator.to_string(),
         "nonce" => args.nonce.to_string(),
         "open_time" => args.open_time.to_string(),
@@ -58,6 +84,8 @@ pub fn deserialize_raydium_initialize2_instruction(
         "init_coin_amount" => args.init_coin_amount.to_string(),
     };
     
+    check_parse_residue("Raydium Initialize2", data_ref, &mut parsed_data);
+
     Ok(create_standardized_instruction(
         &RAYDIUM_LP_PROGRAM,
         "Initialize2",
@@ -69,4 +97,82 @@ pub fn deserialize_raydium_initialize2_instruction(
         is_writable,
         parsed_data
     ))
-}
\ No newline at end of file
+}
+
+/// Swap with a fixed input amount. Accounts: token program, amm, authority, open orders,
+/// (target orders, omitted by newer clients), coin vault, pc vault, the 8 serum market accounts,
+/// user source, user destination and user owner
+pub fn deserialize_raydium_swap_base_in_instruction(
+    data: &[u8],
+    accounts_indices: &[u8],
+    account_keys: &[Pubkey],
+    is_signer: &[bool],
+    is_writable: &[bool],
+) -> Result<JsonValue, String> {
+    let mut data_ref = &data[0..];
+
+    let args = match SwapBaseInParams::deserialize(&mut data_ref) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            return Err(format!("Failed to deserialize SwapBaseInParams: {:?}", e));
+        }
+    };
+
+    let mut parsed_data = object! {
+        "discriminator" => args.discriminator.to_string(),
+        "amount_in" => args.amount_in.to_string(),
+        "minimum_amount_out" => args.minimum_amount_out.to_string(),
+    };
+
+    check_parse_residue("Raydium SwapBaseIn", data_ref, &mut parsed_data);
+
+    Ok(create_standardized_instruction(
+        &RAYDIUM_LP_PROGRAM,
+        "SwapBaseIn",
+        "Raydium",
+        data,
+        accounts_indices,
+        account_keys,
+        is_signer,
+        is_writable,
+        parsed_data
+    ))
+}
+
+/// Swap with a fixed output amount, same accounts as SwapBaseIn
+pub fn deserialize_raydium_swap_base_out_instruction(
+    data: &[u8],
+    accounts_indices: &[u8],
+    account_keys: &[Pubkey],
+    is_signer: &[bool],
+    is_writable: &[bool],
+) -> Result<JsonValue, String> {
+    let mut data_ref = &data[0..];
+
+    let args = match SwapBaseOutParams::deserialize(&mut data_ref) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            return Err(format!("Failed to deserialize SwapBaseOutParams: {:?}", e));
+        }
+    };
+
+    let mut parsed_data = object! {
+        "discriminator" => args.discriminator.to_string(),
+        "max_amount_in" => args.max_amount_in.to_string(),
+        "amount_out" => args.amount_out.to_string(),
+    };
+
+    check_parse_residue("Raydium SwapBaseOut", data_ref, &mut parsed_data);
+
+    Ok(create_standardized_instruction(
+        &RAYDIUM_LP_PROGRAM,
+        "SwapBaseOut",
+        "Raydium",
+        data,
+        accounts_indices,
+        account_keys,
+        is_signer,
+        is_writable,
+        parsed_data
+    ))
+}
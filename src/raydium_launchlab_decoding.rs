@@ -2,11 +2,30 @@ use borsh::{BorshDeserialize, BorshSerialize};
 use json::{object, JsonValue};
 use solana_sdk::pubkey::Pubkey;
 use solana_sdk::pubkey;
+use crate::decode_anomalies::check_parse_residue;
 use crate::utils::create_standardized_instruction;
 
 pub const RAYDIUM_LAUNCHLAB_INITIALIZE_INSTRUCTION_DISCRIMINATOR: [u8; 8] = [175, 175, 109, 31, 13, 152, 155, 237];
+pub const RAYDIUM_LAUNCHLAB_BUY_EXACT_IN_INSTRUCTION_DISCRIMINATOR: [u8; 8] = [250, 234, 13, 123, 213, 156, 19, 236];
+pub const RAYDIUM_LAUNCHLAB_BUY_EXACT_OUT_INSTRUCTION_DISCRIMINATOR: [u8; 8] = [24, 211, 116, 40, 105, 3, 153, 56];
+pub const RAYDIUM_LAUNCHLAB_SELL_EXACT_IN_INSTRUCTION_DISCRIMINATOR: [u8; 8] = [149, 39, 222, 155, 211, 124, 152, 26];
+pub const RAYDIUM_LAUNCHLAB_SELL_EXACT_OUT_INSTRUCTION_DISCRIMINATOR: [u8; 8] = [95, 200, 71, 34, 8, 9, 11, 166];
+pub const RAYDIUM_LAUNCHLAB_MIGRATE_TO_AMM_INSTRUCTION_DISCRIMINATOR: [u8; 8] = [207, 82, 192, 145, 254, 207, 145, 223];
+pub const RAYDIUM_LAUNCHLAB_MIGRATE_TO_CPSWAP_INSTRUCTION_DISCRIMINATOR: [u8; 8] = [136, 92, 200, 103, 28, 218, 144, 140];
 
 pub const RAYDIUM_LAUNCHLAB_PROGRAM_ID: Pubkey = pubkey!("LanMV9sAd7wArD4vJFi2qDdfnVhFxYSUg6eADduJ3uj");
+
+pub const RAYDIUM_LAUNCHLAB_INITIALIZE_ACCOUNTS: &[&str] = &[
+    "payer", "creator", "globalConfig", "platformConfig", "authority", "poolState", "baseMint",
+    "quoteMint", "baseVault", "quoteVault", "metadataAccount", "baseTokenProgram",
+    "quoteTokenProgram", "metadataProgram", "systemProgram", "rentProgram", "eventAuthority",
+    "program",
+];
+pub const RAYDIUM_LAUNCHLAB_TRADE_ACCOUNTS: &[&str] = &[
+    "payer", "authority", "globalConfig", "platformConfig", "poolState", "userBaseToken",
+    "userQuoteToken", "baseVault", "quoteVault", "baseTokenMint", "quoteTokenMint",
+    "baseTokenProgram", "quoteTokenProgram", "eventAuthority", "program",
+];
 pub const SYSTEM_PROGRAM_ID: Pubkey = pubkey!("11111111111111111111111111111111");
 pub const TOKEN_PROGRAM_ID: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
 pub const METADATA_PROGRAM_ID: Pubkey = pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
@@ -18,6 +37,35 @@ pub const GLOBAL_CONFIG: Pubkey = pubkey!("6s1xP3hpbAfFoNtUNF8mfHsjr2Bd97JxFJRWL
 
 pub enum RaydiumLaunchlabInstructionType {
     Initialize,
+    BuyExactIn,
+    BuyExactOut,
+    SellExactIn,
+    SellExactOut,
+    MigrateToAmm,
+    MigrateToCpswap,
+}
+
+/// BuyExactIn and SellExactIn
+#[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
+pub struct ExactInParams {
+    pub amount_in: u64,
+    pub minimum_amount_out: u64,
+    pub share_fee_rate: u64,
+}
+
+/// BuyExactOut and SellExactOut
+#[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
+pub struct ExactOutParams {
+    pub amount_out: u64,
+    pub maximum_amount_in: u64,
+    pub share_fee_rate: u64,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
+pub struct MigrateToAmmParams {
+    pub base_lot_size: u64,
+    pub quote_lot_size: u64,
+    pub market_vault_signer_nonce: u8,
 }
 
 #[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
@@ -68,13 +116,13 @@ pub struct VestingParams {
 
 pub fn get_raydium_launchlab_instruction_type(data: &[u8]) -> Option<RaydiumLaunchlabInstructionType> {
     match data.get(0..8) {
-        Some(d) => {
-            if d == RAYDIUM_LAUNCHLAB_INITIALIZE_INSTRUCTION_DISCRIMINATOR {
-                Some(RaydiumLaunchlabInstructionType::Initialize)
-            } else {
-                None
-            }
-        },
+        Some(d) if d == RAYDIUM_LAUNCHLAB_INITIALIZE_INSTRUCTION_DISCRIMINATOR => Some(RaydiumLaunchlabInstructionType::Initialize),
+        Some(d) if d == RAYDIUM_LAUNCHLAB_BUY_EXACT_IN_INSTRUCTION_DISCRIMINATOR => Some(RaydiumLaunchlabInstructionType::BuyExactIn),
+        Some(d) if d == RAYDIUM_LAUNCHLAB_BUY_EXACT_OUT_INSTRUCTION_DISCRIMINATOR => Some(RaydiumLaunchlabInstructionType::BuyExactOut),
+        Some(d) if d == RAYDIUM_LAUNCHLAB_SELL_EXACT_IN_INSTRUCTION_DISCRIMINATOR => Some(RaydiumLaunchlabInstructionType::SellExactIn),
+        Some(d) if d == RAYDIUM_LAUNCHLAB_SELL_EXACT_OUT_INSTRUCTION_DISCRIMINATOR => Some(RaydiumLaunchlabInstructionType::SellExactOut),
+        Some(d) if d == RAYDIUM_LAUNCHLAB_MIGRATE_TO_AMM_INSTRUCTION_DISCRIMINATOR => Some(RaydiumLaunchlabInstructionType::MigrateToAmm),
+        Some(d) if d == RAYDIUM_LAUNCHLAB_MIGRATE_TO_CPSWAP_INSTRUCTION_DISCRIMINATOR => Some(RaydiumLaunchlabInstructionType::MigrateToCpswap),
         _ => None,
     }
 }
@@ -168,7 +216,7 @@ pub fn deserialize_raydium_launchlab_initialize_instruction(data: &[u8], account
         },
     };
 
-    let parsed_data = object! {
+    let mut parsed_data = object! {
         "baseMintParam" => object! {
             "decimals" => base_mint_param.decimals,
             "name" => base_mint_param.name,
@@ -183,6 +231,8 @@ pub fn deserialize_raydium_launchlab_initialize_instruction(data: &[u8], account
         },
     };
 
+    check_parse_residue("RaydiumLaunchLab Initialize", remaining_data_ref, &mut parsed_data);
+
     Ok(create_standardized_instruction(
         &RAYDIUM_LAUNCHLAB_PROGRAM_ID,
         "Initialize",
@@ -195,3 +245,100 @@ pub fn deserialize_raydium_launchlab_initialize_instruction(data: &[u8], account
         parsed_data
     ))
 }
+
+/// Trades on the bonding curve. Accounts: payer, authority, global config, platform config,
+/// pool state=4, user base token, user quote token, base vault, quote vault, base mint=9,
+/// quote mint=10, token programs, event authority, program
+pub fn deserialize_raydium_launchlab_trade_instruction(
+    instruction_name: &str,
+    exact_in: bool,
+    data: &[u8],
+    accounts_indices: &[u8],
+    account_keys: &[Pubkey],
+    is_signer: &[bool],
+    is_writable: &[bool],
+) -> Result<JsonValue, String> {
+    let mut remaining_data_ref = &data[8..];
+
+    let mut parsed_data = if exact_in {
+        let params = ExactInParams::deserialize(&mut remaining_data_ref)
+            .map_err(|e| format!("Failed to deserialize {} params: {:?}", instruction_name, e))?;
+        object! {
+            "amountIn" => params.amount_in.to_string(),
+            "minimumAmountOut" => params.minimum_amount_out.to_string(),
+            "shareFeeRate" => params.share_fee_rate,
+        }
+    } else {
+        let params = ExactOutParams::deserialize(&mut remaining_data_ref)
+            .map_err(|e| format!("Failed to deserialize {} params: {:?}", instruction_name, e))?;
+        object! {
+            "amountOut" => params.amount_out.to_string(),
+            "maximumAmountIn" => params.maximum_amount_in.to_string(),
+            "shareFeeRate" => params.share_fee_rate,
+        }
+    };
+
+    check_parse_residue(&format!("RaydiumLaunchLab {}", instruction_name), remaining_data_ref, &mut parsed_data);
+
+    Ok(create_standardized_instruction(
+        &RAYDIUM_LAUNCHLAB_PROGRAM_ID,
+        instruction_name,
+        "RaydiumLaunchLab",
+        data,
+        accounts_indices,
+        account_keys,
+        is_signer,
+        is_writable,
+        parsed_data
+    ))
+}
+
+/// Graduation to a Raydium AMM v4 pool. Accounts: payer, base mint=1, quote mint=2, the
+/// openbook market accounts, amm program, amm pool=13, amm accounts, authority, pool state=23, ...
+pub fn deserialize_raydium_launchlab_migrate_to_amm_instruction(data: &[u8], accounts_indices: &[u8], account_keys: &[Pubkey], is_signer: &[bool], is_writable: &[bool]) -> Result<JsonValue, String> {
+    let mut remaining_data_ref = &data[8..];
+
+    let params = MigrateToAmmParams::deserialize(&mut remaining_data_ref)
+        .map_err(|e| format!("Failed to deserialize MigrateToAmm params: {:?}", e))?;
+
+    let mut parsed_data = object! {
+        "baseLotSize" => params.base_lot_size.to_string(),
+        "quoteLotSize" => params.quote_lot_size.to_string(),
+        "marketVaultSignerNonce" => params.market_vault_signer_nonce,
+    };
+
+    check_parse_residue("RaydiumLaunchLab MigrateToAmm", remaining_data_ref, &mut parsed_data);
+
+    Ok(create_standardized_instruction(
+        &RAYDIUM_LAUNCHLAB_PROGRAM_ID,
+        "MigrateToAmm",
+        "RaydiumLaunchLab",
+        data,
+        accounts_indices,
+        account_keys,
+        is_signer,
+        is_writable,
+        parsed_data
+    ))
+}
+
+/// Graduation to a Raydium CPMM pool, without arguments. Accounts: payer, base mint=1,
+/// quote mint=2, platform config, cpswap program, cpswap pool=5, cpswap accounts, lock accounts,
+/// authority, pool state=17, ...
+pub fn deserialize_raydium_launchlab_migrate_to_cpswap_instruction(data: &[u8], accounts_indices: &[u8], account_keys: &[Pubkey], is_signer: &[bool], is_writable: &[bool]) -> Result<JsonValue, String> {
+    let mut parsed_data = object! {};
+
+    check_parse_residue("RaydiumLaunchLab MigrateToCpswap", &data[8..], &mut parsed_data);
+
+    Ok(create_standardized_instruction(
+        &RAYDIUM_LAUNCHLAB_PROGRAM_ID,
+        "MigrateToCpswap",
+        "RaydiumLaunchLab",
+        data,
+        accounts_indices,
+        account_keys,
+        is_signer,
+        is_writable,
+        parsed_data
+    ))
+}
@@ -0,0 +1,145 @@
+use crate::failover::is_output_active;
+use crate::output_message::{OutputMessage, OutputSender};
+use crate::sinks::{get_sink_manager, RouteTargets};
+use once_cell::sync::Lazy;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::broadcast::error::SendError;
+
+/// Number of published messages kept for subscribers resuming after a disconnect
+static RESUME_RETENTION_MESSAGES: Lazy<usize> = Lazy::new(|| {
+    std::env::var("RESUME_RETENTION_MESSAGES")
+        .unwrap_or_else(|_| "10000".to_string())
+        .parse()
+        .unwrap_or(10000)
+});
+
+/// Age after which published messages leave the retention window, 0 keeps them up to the count limit
+static RESUME_RETENTION_SECONDS: Lazy<u64> = Lazy::new(|| {
+    std::env::var("RESUME_RETENTION_SECONDS")
+        .unwrap_or_else(|_| "120".to_string())
+        .parse()
+        .unwrap_or(120)
+});
+
+struct RetainedOutput {
+    next_sequence: u64,
+    messages: VecDeque<Arc<OutputMessage>>,
+}
+
+// Sequence assignment, retention and broadcast happen under one lock so that the
+// retained order and the live order are the same
+static RETAINED_OUTPUT: Lazy<Mutex<RetainedOutput>> = Lazy::new(|| {
+    Mutex::new(RetainedOutput {
+        next_sequence: 1,
+        messages: VecDeque::with_capacity(*RESUME_RETENTION_MESSAGES),
+    })
+});
+
+/// Where a subscriber left the stream, the slot guards against sequences of another server run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResumePoint {
+    pub slot: u64,
+    pub sequence: u64,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ResumeError {
+    /// The message following the resume point was already evicted
+    OutsideRetention { oldest_sequence: u64 },
+    /// The sequence is retained but for another slot, or was never issued
+    UnknownToken,
+    /// The replay slot is older than the oldest retained message
+    SlotOutsideRetention { oldest_slot: u64 },
+}
+
+/// Assigns the next sequence number and hands the message to the sinks its route selects.
+/// Messages routed to gRPC are retained for resuming and broadcast.
+pub fn publish_output(tx: &OutputSender, mut message: OutputMessage) -> Result<usize, SendError<Arc<OutputMessage>>> {
+    let mut retained = RETAINED_OUTPUT.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    message.sequence = retained.next_sequence;
+    retained.next_sequence += 1;
+
+    let targets = match get_sink_manager() {
+        Some(manager) => manager.route(&message),
+        None => RouteTargets { grpc: true, sinks: Vec::new() },
+    };
+    let message = Arc::new(message);
+
+    let sent = if targets.grpc {
+        let retention = *RESUME_RETENTION_MESSAGES;
+        if retention > 0 {
+            if retained.messages.len() >= retention {
+                retained.messages.pop_front();
+            }
+            evict_expired(&mut retained.messages, message.timestamp);
+            retained.messages.push_back(message.clone());
+        }
+        tx.send(message.clone())
+    } else {
+        Ok(0)
+    };
+    drop(retained);
+
+    // A standby instance serves its own subscribers but leaves the sinks to the active one
+    if is_output_active() {
+        for sink in &targets.sinks {
+            sink.deliver(&message);
+        }
+    }
+    sent
+}
+
+// Timestamps are in microseconds, messages are retained in publication order
+fn evict_expired(messages: &mut VecDeque<Arc<OutputMessage>>, now: u64) {
+    let max_age = *RESUME_RETENTION_SECONDS * 1_000_000;
+    if max_age == 0 {
+        return;
+    }
+    while messages.front().is_some_and(|oldest| oldest.timestamp + max_age < now) {
+        messages.pop_front();
+    }
+}
+
+/// Retained messages published after the resume point, oldest first
+pub fn messages_after(point: ResumePoint) -> Result<Vec<Arc<OutputMessage>>, ResumeError> {
+    let retained = RETAINED_OUTPUT.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    if point.sequence >= retained.next_sequence {
+        return Err(ResumeError::UnknownToken);
+    }
+
+    // Sequences are increasing but not contiguous, messages routed away from gRPC are not retained
+    let oldest_sequence = retained.messages.front().map(|message| message.sequence).unwrap_or(retained.next_sequence);
+    if point.sequence + 1 < oldest_sequence {
+        return Err(ResumeError::OutsideRetention { oldest_sequence });
+    }
+
+    let position = retained.messages.partition_point(|message| message.sequence <= point.sequence);
+    // The resume point itself is still retained, its slot must match
+    if let Some(resume_message) = position.checked_sub(1).and_then(|last| retained.messages.get(last)) {
+        if resume_message.sequence == point.sequence && resume_message.slot != point.slot {
+            return Err(ResumeError::UnknownToken);
+        }
+    }
+
+    Ok(retained.messages.iter().skip(position).cloned().collect())
+}
+
+/// Retained messages of the slot and later ones, oldest first, for clients backfilling a
+/// disconnect by slot rather than by resume token. The oldest retained slot may be partial.
+pub fn messages_from_slot(slot: u64) -> Result<Vec<Arc<OutputMessage>>, ResumeError> {
+    let mut retained = RETAINED_OUTPUT.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    // Nothing may have been published for a while, expire against the clock
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).expect("Time went backwards").as_micros() as u64;
+    evict_expired(&mut retained.messages, now);
+
+    if let Some(oldest) = retained.messages.front() {
+        if slot < oldest.slot {
+            return Err(ResumeError::SlotOutsideRetention { oldest_slot: oldest.slot });
+        }
+    }
+
+    // Slots of the stream are not strictly increasing, late FEC blocks of a slot follow newer slots
+    Ok(retained.messages.iter().filter(|message| message.slot >= slot).cloned().collect())
+}
@@ -0,0 +1,142 @@
+use once_cell::sync::OnceCell;
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::debug;
+
+/// Ships closed sink segments (rotated JSONL files, finished Parquet files) to object storage
+#[derive(Debug, Clone)]
+pub struct SegmentUploadConfig {
+    /// `s3://bucket/prefix` or `gs://bucket/prefix`, S3-compatible stores take AWS_ENDPOINT
+    pub url: String,
+    /// Local segments are removed once uploaded
+    pub delete_uploaded: bool,
+    pub max_attempts: u32,
+    pub retry_delay: Duration,
+}
+
+impl SegmentUploadConfig {
+    /// Build the configuration from env, returns None unless SEGMENT_UPLOAD_URL is set.
+    /// Credentials come from the usual AWS_* and GOOGLE_* variables.
+    pub fn from_env() -> Option<Self> {
+        let url = std::env::var("SEGMENT_UPLOAD_URL").ok().filter(|v| !v.trim().is_empty())?;
+        let delete_uploaded = std::env::var("SEGMENT_UPLOAD_DELETE")
+            .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+            .unwrap_or(true);
+        let max_attempts = std::env::var("SEGMENT_UPLOAD_MAX_ATTEMPTS")
+            .unwrap_or_else(|_| "5".to_string())
+            .parse()
+            .ok()
+            .filter(|attempts| *attempts > 0)
+            .unwrap_or(5);
+        let retry_delay_secs = std::env::var("SEGMENT_UPLOAD_RETRY_DELAY_SECS")
+            .unwrap_or_else(|_| "10".to_string())
+            .parse()
+            .unwrap_or(10);
+
+        Some(Self { url, delete_uploaded, max_attempts, retry_delay: Duration::from_secs(retry_delay_secs) })
+    }
+}
+
+static CLOSED_SEGMENTS: OnceCell<mpsc::UnboundedSender<PathBuf>> = OnceCell::new();
+
+/// Hands a segment its sink will not write anymore to the uploader, a no-op without uploader.
+/// Segments closed before the uploader started stay on disk.
+pub fn announce_closed_segment(path: PathBuf) {
+    match CLOSED_SEGMENTS.get() {
+        Some(segments) => {
+            let _ = segments.send(path);
+        }
+        None => debug!("No segment uploader, {} stays local", path.display()),
+    }
+}
+
+/// Starts the upload worker, must run inside the Tokio runtime and before the sinks
+#[cfg(feature = "upload")]
+pub fn start_segment_uploader(config: SegmentUploadConfig) -> Result<(), String> {
+    use object_store::aws::AmazonS3Builder;
+    use object_store::buffered::BufWriter;
+    use object_store::gcp::GoogleCloudStorageBuilder;
+    use object_store::path::Path as ObjectPath;
+    use object_store::ObjectStore;
+    use std::sync::Arc;
+    use tokio::io::AsyncWriteExt;
+    use tracing::{error, info, warn};
+
+    use crate::operational_events::{publish_operational_event, Severity, SINK_DELIVERY_FAILED_EVENT};
+
+    let parsed = reqwest::Url::parse(&config.url).map_err(|e| format!("Invalid SEGMENT_UPLOAD_URL '{}': {}", config.url, e))?;
+    let store: Arc<dyn ObjectStore> = match parsed.scheme() {
+        "s3" => Arc::new(AmazonS3Builder::from_env().with_url(config.url.as_str()).build().map_err(|e| e.to_string())?),
+        "gs" => Arc::new(GoogleCloudStorageBuilder::from_env().with_url(config.url.as_str()).build().map_err(|e| e.to_string())?),
+        other => return Err(format!("Unsupported SEGMENT_UPLOAD_URL scheme '{}', expected s3 or gs", other)),
+    };
+    let prefix = parsed.path().trim_matches('/').to_string();
+
+    let (segments, mut rx) = mpsc::unbounded_channel::<PathBuf>();
+    CLOSED_SEGMENTS.set(segments).map_err(|_| "Segment uploader already started".to_string())?;
+    info!("Uploading closed sink segments to {}", config.url);
+
+    tokio::spawn(async move {
+        while let Some(segment) = rx.recv().await {
+            // Segments of different sinks are told apart by their directory
+            let key = [
+                prefix.as_str(),
+                segment.parent().and_then(|parent| parent.file_name()).and_then(|name| name.to_str()).unwrap_or_default(),
+                segment.file_name().and_then(|name| name.to_str()).unwrap_or_default(),
+            ]
+            .iter()
+            .filter(|part| !part.is_empty())
+            .copied()
+            .collect::<Vec<_>>()
+            .join("/");
+            let location = ObjectPath::from(key.as_str());
+
+            let mut uploaded = false;
+            for attempt in 1..=config.max_attempts {
+                let result = async {
+                    let mut file = tokio::fs::File::open(&segment).await?;
+                    // Multipart upload, large segments are streamed rather than loaded
+                    let mut writer = BufWriter::new(store.clone(), location.clone());
+                    tokio::io::copy(&mut file, &mut writer).await?;
+                    writer.shutdown().await
+                }
+                .await;
+                match result {
+                    Ok(()) => {
+                        uploaded = true;
+                        break;
+                    }
+                    Err(e) if attempt < config.max_attempts => {
+                        warn!("Upload of {} failed (attempt {} of {}): {}", segment.display(), attempt, config.max_attempts, e);
+                        tokio::time::sleep(config.retry_delay).await;
+                    }
+                    Err(e) => {
+                        error!("Giving up on uploading {}: {}", segment.display(), e);
+                        publish_operational_event(
+                            SINK_DELIVERY_FAILED_EVENT,
+                            Severity::Error,
+                            format!("Segment upload of {} failed: {}", segment.display(), e),
+                            json::object! { "sink" => "segment_uploader", "path" => segment.display().to_string() },
+                        );
+                    }
+                }
+            }
+            if !uploaded {
+                continue;
+            }
+            info!("Uploaded {} to {}", segment.display(), key);
+            if config.delete_uploaded {
+                if let Err(e) = tokio::fs::remove_file(&segment).await {
+                    warn!("Failed to remove uploaded segment {}: {}", segment.display(), e);
+                }
+            }
+        }
+    });
+    Ok(())
+}
+
+#[cfg(not(feature = "upload"))]
+pub fn start_segment_uploader(_config: SegmentUploadConfig) -> Result<(), String> {
+    Err("SEGMENT_UPLOAD_URL needs a build with the upload feature".to_string())
+}
@@ -0,0 +1,146 @@
+use crate::address_lookup_table_cache::{alt_resolve_deadline, is_partial_followup_enabled};
+use crate::batch_output::is_batch_output_enabled;
+use crate::coverage::is_account_min_auto_relax_enabled;
+use crate::entry_verification::is_entry_verification_enabled;
+use crate::blockhash_age::is_blockhash_age_enabled;
+use crate::discriminator_catalog::is_discriminator_catalog_enabled;
+use crate::decoder_registry::REGISTERED_DECODERS;
+use crate::failover::FailoverConfig;
+use crate::shred_tee::ShredTeeConfig;
+use crate::shred_recorder::ShredRecorderConfig;
+use crate::jito_ingest::IngestSources;
+use crate::leader_expiry::LeaderExpiryConfig;
+use crate::operational_events::feed_stall_timeout;
+use crate::canary::CanaryConfig;
+use crate::program_validation::ProgramValidationConfig;
+use crate::sinks::get_sink_manager;
+use crate::post_processing::post_processor_names;
+use crate::latency_breakdown::is_latency_breakdown_enabled;
+use crate::sniper_detection::is_first_buyer_detection_enabled;
+use crate::wallet_clusters::is_wallet_clustering_enabled;
+use crate::decode_anomalies::is_strict_mode;
+use crate::spl_token_decoding::is_token_decoding_enabled;
+use crate::raw_instruction_decoding::is_raw_instructions_enabled;
+use crate::address_lookup_table_decoding::is_lookup_table_decoding_enabled;
+use crate::idl_decoding::idl_program_count;
+use tracing::info;
+
+/// Version of the decoded transaction layout, bumped on breaking output changes
+pub const OUTPUT_SCHEMA_VERSION: u32 = 1;
+
+pub const SERVER_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Request filters understood by StreamTransactions
+pub fn supported_filters() -> Vec<&'static str> {
+    vec!["mints", "event_types", "pools", "kinds", "min_severity", "filter", "programs", "accounts", "instructions", "statuses", "subscription_updates"]
+}
+
+/// Optional features enabled on this instance
+pub fn enabled_features() -> Vec<&'static str> {
+    let mut features = vec![
+        "slot_stats",
+        "discriminator_report",
+        "memo",
+        "priority_fee",
+        "price_ticks",
+        "binary_encoding",
+        "typed_encoding",
+        "slot_status",
+        "raw_entries",
+        "resume_tokens",
+        "slot_replay",
+        "stream_sequence",
+        "coverage_report",
+        "operational_events",
+        "slot_summary",
+    ];
+    if FailoverConfig::from_env().is_some() {
+        features.push("failover");
+    }
+    if is_first_buyer_detection_enabled() {
+        features.push("first_buyer_events");
+    }
+    if is_strict_mode() {
+        features.push("strict_mode");
+    }
+    if is_wallet_clustering_enabled() {
+        features.push("wallet_clusters");
+    }
+    if is_batch_output_enabled() {
+        features.push("batch_account_dictionary");
+    }
+    if alt_resolve_deadline().is_some() {
+        features.push("partial_emission");
+        if is_partial_followup_enabled() {
+            features.push("partial_corrections");
+        }
+    }
+    if get_sink_manager().is_some() {
+        features.push("sink_routing");
+    }
+    if LeaderExpiryConfig::from_env("").is_some() {
+        features.push("leader_aware_expiry");
+    }
+    if ShredTeeConfig::from_env().is_some() {
+        features.push("shred_tee");
+    }
+    if ShredRecorderConfig::from_env().is_some() {
+        features.push("shred_recorder");
+    }
+    if IngestSources::from_env().is_ok_and(|sources| !sources.jito_grpc.is_empty()) {
+        features.push("jito_grpc_ingest");
+    }
+    #[cfg(all(target_os = "linux", feature = "io_uring"))]
+    if crate::uring_receive::ReceiveBackend::from_env() == crate::uring_receive::ReceiveBackend::IoUring {
+        features.push("io_uring_receive");
+    }
+    if feed_stall_timeout().is_some() {
+        features.push("feed_stall_watchdog");
+    }
+    if post_processor_names().contains(&"write_locks") {
+        features.push("write_lock_hotspots");
+    }
+    if ProgramValidationConfig::from_env("").is_some() {
+        features.push("program_validation");
+    }
+    if CanaryConfig::from_env("").is_some() {
+        features.push("canary");
+    }
+    if is_entry_verification_enabled() {
+        features.push("entry_verification");
+    }
+    if is_blockhash_age_enabled() {
+        features.push("blockhash_age");
+    }
+    if is_discriminator_catalog_enabled() {
+        features.push("discriminator_catalog");
+    }
+    if is_token_decoding_enabled() {
+        features.push("token_instructions");
+    }
+    if is_raw_instructions_enabled() {
+        features.push("raw_instructions");
+    }
+    if is_lookup_table_decoding_enabled() {
+        features.push("lookup_table_instructions");
+    }
+    if idl_program_count() > 0 {
+        features.push("idl_decoders");
+    }
+    if is_account_min_auto_relax_enabled() {
+        features.push("account_min_auto_relax");
+    }
+    if is_latency_breakdown_enabled() {
+        features.push("latency_breakdown");
+    }
+    features
+}
+
+pub fn log_startup_banner() {
+    let decoders: Vec<&str> = REGISTERED_DECODERS.iter().map(|decoder| decoder.protocol).collect();
+    info!("════════════════════════════════════════════════════════════════");
+    info!(" Shredstream Decoder v{} (output schema v{})", SERVER_VERSION, OUTPUT_SCHEMA_VERSION);
+    info!(" Decoders: {}", decoders.join(", "));
+    info!(" Features: {}", enabled_features().join(", "));
+    info!("════════════════════════════════════════════════════════════════");
+}
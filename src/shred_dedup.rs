@@ -0,0 +1,132 @@
+use dashmap::{DashMap, DashSet};
+use once_cell::sync::OnceCell;
+use solana_sdk::signature::Signature;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tracing::{info, warn};
+
+use crate::shreds_processing::get_slot_from_shred_data;
+
+/// Slots kept behind the newest slot seen, older duplicates are not caught anymore
+const DEDUP_RETENTION_SLOTS: u64 = 32;
+
+// Common shred header: signature (64), variant (1), slot (8), index (4)
+const SIZE_OF_SIGNATURE: usize = 64;
+const OFFSET_OF_SHRED_VARIANT: usize = 64;
+const OFFSET_OF_SHRED_INDEX: usize = 73;
+
+/// Drops shreds already received from another source, keyed by (slot, index, type) and
+/// signature, so redundant feeds cost a map lookup instead of a shred deserialization. A shred
+/// with a known position but another signature is a conflicting version from the leader: it is
+/// counted and passed on, shred collection keys FEC sets by signature and keeps both versions.
+#[derive(Default)]
+pub struct ShredDeduper {
+    seen: DashMap<(u64, u32, bool), Vec<[u8; SIZE_OF_SIGNATURE]>>,
+    highest_slot: AtomicU64,
+    duplicates: AtomicU64,
+    conflicts: AtomicU64,
+}
+
+impl ShredDeduper {
+    /// Enabled when more than one UDP listener is configured or SHRED_DEDUP_ENABLED is set,
+    /// e.g. when two proxies push to the same port
+    pub fn is_enabled(listeners: usize) -> bool {
+        listeners > 1
+            || std::env::var("SHRED_DEDUP_ENABLED")
+                .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+                .unwrap_or(false)
+    }
+
+    /// True the first time a shred is seen, malformed shreds pass and are rejected later
+    pub fn first_seen(&self, shred_data: &[u8]) -> bool {
+        let Ok(slot) = get_slot_from_shred_data(shred_data) else { return true };
+        let Some(index_bytes) = shred_data.get(OFFSET_OF_SHRED_INDEX..OFFSET_OF_SHRED_INDEX + 4) else { return true };
+        let index = u32::from_le_bytes(index_bytes.try_into().unwrap());
+        let signature: [u8; SIZE_OF_SIGNATURE] = shred_data[..SIZE_OF_SIGNATURE].try_into().unwrap();
+        // Data variants (legacy 0xA5, merkle 0x8_, 0x9_, 0xB_) have the high bit set, code variants do not
+        let is_data = shred_data[OFFSET_OF_SHRED_VARIANT] & 0x80 != 0;
+
+        let mut signatures = self.seen.entry((slot, index, is_data)).or_default();
+        if signatures.contains(&signature) {
+            self.duplicates.fetch_add(1, Ordering::Relaxed);
+            return false;
+        }
+        if !signatures.is_empty() {
+            let conflicts = self.conflicts.fetch_add(1, Ordering::Relaxed) + 1;
+            warn!(
+                "Conflicting shred version for slot {}, index {}, data {} ({} conflicts so far)",
+                slot, index, is_data, conflicts
+            );
+        }
+        signatures.push(signature);
+        drop(signatures);
+
+        let previous_highest = self.highest_slot.fetch_max(slot, Ordering::Relaxed);
+        if slot > previous_highest {
+            let oldest_retained = slot.saturating_sub(DEDUP_RETENTION_SLOTS);
+            self.seen.retain(|&(seen_slot, _, _), _| seen_slot >= oldest_retained);
+        }
+        true
+    }
+
+    /// Duplicates dropped since startup
+    pub fn duplicates(&self) -> u64 {
+        self.duplicates.load(Ordering::Relaxed)
+    }
+
+    /// Conflicting shred versions passed on since startup
+    pub fn conflicts(&self) -> u64 {
+        self.conflicts.load(Ordering::Relaxed)
+    }
+
+    pub fn log_counters(&self) {
+        info!(
+            "Shred dedup: duplicates dropped = {}, conflicting versions = {}, tracked shreds = {}",
+            self.duplicates(),
+            self.conflicts(),
+            self.seen.len()
+        );
+    }
+}
+
+/// Drops transactions already extracted, when the same transactions come deshredded by
+/// a proxy's gRPC API and as shreds, or from several proxies. Shreds cannot be compared
+/// with proxy entries, their transactions can.
+#[derive(Default)]
+struct TransactionDeduper {
+    seen: DashSet<(u64, Signature)>,
+    highest_slot: AtomicU64,
+    duplicates: AtomicU64,
+}
+
+static TRANSACTION_DEDUPER: OnceCell<TransactionDeduper> = OnceCell::new();
+
+/// Turns on transaction dedup, for ingest from more than one source
+pub fn enable_transaction_dedup() {
+    let _ = TRANSACTION_DEDUPER.set(TransactionDeduper::default());
+}
+
+/// True the first time a transaction is seen, always true when transaction dedup is off
+pub fn first_seen_transaction(slot: u64, signature: &Signature) -> bool {
+    let Some(deduper) = TRANSACTION_DEDUPER.get() else { return true };
+    if !deduper.seen.insert((slot, *signature)) {
+        deduper.duplicates.fetch_add(1, Ordering::Relaxed);
+        return false;
+    }
+    let previous_highest = deduper.highest_slot.fetch_max(slot, Ordering::Relaxed);
+    if slot > previous_highest {
+        let oldest_retained = slot.saturating_sub(DEDUP_RETENTION_SLOTS);
+        deduper.seen.retain(|&(seen_slot, _)| seen_slot >= oldest_retained);
+    }
+    true
+}
+
+/// Logs the transaction dedup counters, nothing when transaction dedup is off
+pub fn log_transaction_dedup_counters() {
+    if let Some(deduper) = TRANSACTION_DEDUPER.get() {
+        info!(
+            "Transaction dedup: duplicates dropped = {}, tracked transactions = {}",
+            deduper.duplicates.load(Ordering::Relaxed),
+            deduper.seen.len()
+        );
+    }
+}
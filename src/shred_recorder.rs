@@ -0,0 +1,202 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError, SyncSender, TrySendError};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tracing::{error, info};
+
+use crate::segment_uploader::announce_closed_segment;
+
+/// Extension of the shred logs
+pub const SHRED_LOG_EXTENSION: &str = "ssrl";
+
+/// Every shred log starts with this magic followed by a format version byte
+const SHRED_LOG_MAGIC: &[u8; 4] = b"SSRL";
+const SHRED_LOG_VERSION: u8 = 1;
+
+/// Shreds queued for the writer thread, beyond that they are dropped rather than slowing ingest
+const RECORDER_QUEUE_SIZE: usize = 65536;
+
+/// Configuration for persisting every received shred to rotating log files
+#[derive(Debug, Clone)]
+pub struct ShredRecorderConfig {
+    pub directory: PathBuf,
+    pub rotate_after: Duration,
+}
+
+impl ShredRecorderConfig {
+    /// Build the configuration from env, returns None when SHRED_RECORD_DIR is not set
+    pub fn from_env() -> Option<Self> {
+        let directory = std::env::var("SHRED_RECORD_DIR").ok().filter(|v| !v.trim().is_empty())?;
+        let rotate_seconds = std::env::var("SHRED_RECORD_ROTATE_SECONDS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .filter(|seconds| *seconds > 0)
+            .unwrap_or(3600);
+        Some(Self {
+            directory: PathBuf::from(directory.trim()),
+            rotate_after: Duration::from_secs(rotate_seconds),
+        })
+    }
+}
+
+fn now_micros() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_micros() as u64
+}
+
+/// Writes every shred received, as received, with its receive time in microseconds.
+/// A log is the magic and version, then one `timestamp: u64 LE, length: u16 LE, shred` record per shred.
+pub struct ShredRecorder {
+    queue: SyncSender<(u64, Box<[u8]>)>,
+    recorded: AtomicU64,
+    dropped: AtomicU64,
+}
+
+impl ShredRecorder {
+    /// Starts the writer thread, closed logs are handed to the segment uploader
+    pub fn start(config: ShredRecorderConfig) -> std::io::Result<Self> {
+        std::fs::create_dir_all(&config.directory)?;
+        let (queue, shreds) = mpsc::sync_channel::<(u64, Box<[u8]>)>(RECORDER_QUEUE_SIZE);
+        std::thread::Builder::new()
+            .name("shred-recorder".to_string())
+            .spawn(move || {
+                if let Err(e) = write_shred_logs(&config, shreds) {
+                    error!("Shred recorder stopped: {}", e);
+                }
+            })?;
+        info!("Recording received shreds to {}", config.directory.display());
+        Ok(Self {
+            queue,
+            recorded: AtomicU64::new(0),
+            dropped: AtomicU64::new(0),
+        })
+    }
+
+    pub fn record(&self, shred_data: &[u8]) {
+        match self.queue.try_send((now_micros(), Box::from(shred_data))) {
+            Ok(()) => {
+                self.recorded.fetch_add(1, Ordering::Relaxed);
+            }
+            Err(TrySendError::Full(_) | TrySendError::Disconnected(_)) => {
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// (recorded, dropped) shreds since startup
+    pub fn counters(&self) -> (u64, u64) {
+        (self.recorded.load(Ordering::Relaxed), self.dropped.load(Ordering::Relaxed))
+    }
+}
+
+fn open_shred_log(directory: &Path) -> std::io::Result<(PathBuf, BufWriter<File>)> {
+    let millis = SystemTime::now().duration_since(UNIX_EPOCH).expect("Time went backwards").as_millis();
+    let path = directory.join(format!("shreds.{}.{}", millis, SHRED_LOG_EXTENSION));
+    let mut writer = BufWriter::with_capacity(1 << 20, File::create(&path)?);
+    writer.write_all(SHRED_LOG_MAGIC)?;
+    writer.write_all(&[SHRED_LOG_VERSION])?;
+    Ok((path, writer))
+}
+
+fn close_shred_log(path: PathBuf, mut writer: BufWriter<File>) -> std::io::Result<()> {
+    writer.flush()?;
+    drop(writer);
+    announce_closed_segment(path);
+    Ok(())
+}
+
+fn write_shred_logs(config: &ShredRecorderConfig, shreds: mpsc::Receiver<(u64, Box<[u8]>)>) -> std::io::Result<()> {
+    let (mut path, mut writer) = open_shred_log(&config.directory)?;
+    let mut opened_at = Instant::now();
+    loop {
+        // Flushed whenever the queue runs dry, a crash loses at most the last second of shreds
+        match shreds.recv_timeout(Duration::from_secs(1)) {
+            Ok((timestamp, shred)) => {
+                writer.write_all(&timestamp.to_le_bytes())?;
+                writer.write_all(&(shred.len() as u16).to_le_bytes())?;
+                writer.write_all(&shred)?;
+            }
+            Err(RecvTimeoutError::Timeout) => writer.flush()?,
+            Err(RecvTimeoutError::Disconnected) => return close_shred_log(path, writer),
+        }
+        if opened_at.elapsed() >= config.rotate_after {
+            let (next_path, next_writer) = open_shred_log(&config.directory)?;
+            close_shred_log(std::mem::replace(&mut path, next_path), std::mem::replace(&mut writer, next_writer))?;
+            opened_at = Instant::now();
+        }
+    }
+}
+
+/// Reads back the shreds of one or more logs in order, a directory stands for all its logs by name
+pub struct ShredLogReader {
+    pending: std::vec::IntoIter<PathBuf>,
+    current: Option<(PathBuf, BufReader<File>)>,
+}
+
+impl ShredLogReader {
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let mut logs = Vec::new();
+        if path.is_dir() {
+            let entries = std::fs::read_dir(path).map_err(|e| format!("Failed to list {}: {}", path.display(), e))?;
+            for entry in entries {
+                let log = entry.map_err(|e| format!("Failed to list {}: {}", path.display(), e))?.path();
+                if log.extension().and_then(|ext| ext.to_str()) == Some(SHRED_LOG_EXTENSION) {
+                    logs.push(log);
+                }
+            }
+            // Names carry the opening time, the name order is the recording order
+            logs.sort();
+        } else {
+            logs.push(path.to_path_buf());
+        }
+        if logs.is_empty() {
+            return Err(format!("No .{} shred log in {}", SHRED_LOG_EXTENSION, path.display()));
+        }
+        Ok(Self { pending: logs.into_iter(), current: None })
+    }
+
+    fn open_next_log(&mut self) -> Result<bool, String> {
+        let Some(path) = self.pending.next() else {
+            return Ok(false);
+        };
+        let file = File::open(&path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+        let mut reader = BufReader::new(file);
+        let mut header = [0u8; 5];
+        reader.read_exact(&mut header).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        if &header[..4] != SHRED_LOG_MAGIC || header[4] != SHRED_LOG_VERSION {
+            return Err(format!("{} is not a version {} shred log", path.display(), SHRED_LOG_VERSION));
+        }
+        self.current = Some((path, reader));
+        Ok(true)
+    }
+
+    /// Next shred with its receive time in microseconds, None after the last log.
+    /// A record cut short by a crash ends its log.
+    pub fn next_shred(&mut self) -> Result<Option<(u64, Vec<u8>)>, String> {
+        loop {
+            let Some((path, reader)) = &mut self.current else {
+                if !self.open_next_log()? {
+                    return Ok(None);
+                }
+                continue;
+            };
+            let mut header = [0u8; 10];
+            let record = reader.read_exact(&mut header).and_then(|_| {
+                let mut shred = vec![0u8; u16::from_le_bytes([header[8], header[9]]) as usize];
+                reader.read_exact(&mut shred).map(|_| shred)
+            });
+            match record {
+                Ok(shred) => {
+                    let timestamp = u64::from_le_bytes(header[..8].try_into().expect("8-byte timestamp"));
+                    return Ok(Some((timestamp, shred)));
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => self.current = None,
+                Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+            }
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use std::sync::atomic::{AtomicU64, Ordering};
+use tracing::{debug, info, warn};
+
+use crate::shreds_processing::get_slot_from_shred_data;
+
+/// Which slots are re-broadcast, parity filtering lets two downstream hosts split the load
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlotParity {
+    All,
+    Even,
+    Odd,
+}
+
+impl SlotParity {
+    fn matches(&self, slot: u64) -> bool {
+        match self {
+            SlotParity::All => true,
+            SlotParity::Even => slot % 2 == 0,
+            SlotParity::Odd => slot % 2 == 1,
+        }
+    }
+}
+
+/// Configuration for forwarding the raw shreds received to additional UDP destinations
+#[derive(Debug, Clone)]
+pub struct ShredTeeConfig {
+    pub bind_address: String,
+    pub destinations: Vec<SocketAddr>,
+    pub slot_parity: SlotParity,
+}
+
+impl ShredTeeConfig {
+    /// Build the configuration from env, returns None when no destination is configured
+    pub fn from_env() -> Option<Self> {
+        let configured = std::env::var("SHRED_TEE_DESTINATIONS").ok().filter(|v| !v.trim().is_empty())?;
+        let destinations: Vec<SocketAddr> = configured
+            .split(',')
+            .map(str::trim)
+            .filter(|destination| !destination.is_empty())
+            .filter_map(|destination| match destination.to_socket_addrs().map(|mut addrs| addrs.next()) {
+                Ok(Some(addr)) => Some(addr),
+                _ => {
+                    warn!("Invalid shred tee destination '{}', ignored", destination);
+                    None
+                }
+            })
+            .collect();
+        if destinations.is_empty() {
+            return None;
+        }
+
+        let slot_parity = match std::env::var("SHRED_TEE_SLOT_PARITY").unwrap_or_default().to_ascii_lowercase().as_str() {
+            "even" => SlotParity::Even,
+            "odd" => SlotParity::Odd,
+            _ => SlotParity::All,
+        };
+        let bind_address = std::env::var("SHRED_TEE_BIND_ADDRESS").unwrap_or_else(|_| "0.0.0.0:0".to_string());
+
+        Some(Self {
+            bind_address,
+            destinations,
+            slot_parity,
+        })
+    }
+}
+
+/// Re-broadcasts raw shreds as received, before any decoding, so this binary can double as a fan-out proxy
+pub struct ShredTee {
+    socket: UdpSocket,
+    config: ShredTeeConfig,
+    forwarded: AtomicU64,
+    dropped: AtomicU64,
+}
+
+impl ShredTee {
+    pub fn bind(config: ShredTeeConfig) -> std::io::Result<Self> {
+        let socket = UdpSocket::bind(&config.bind_address)?;
+        // Forwarding must never hold up the receive loop, a full send buffer drops the datagram
+        socket.set_nonblocking(true)?;
+        info!(
+            "Shred tee forwarding {:?} slots to {:?}",
+            config.slot_parity, config.destinations
+        );
+        Ok(Self {
+            socket,
+            config,
+            forwarded: AtomicU64::new(0),
+            dropped: AtomicU64::new(0),
+        })
+    }
+
+    pub fn forward(&self, shred_data: &[u8]) {
+        if self.config.slot_parity != SlotParity::All {
+            match get_slot_from_shred_data(shred_data) {
+                Ok(slot) if self.config.slot_parity.matches(slot) => {}
+                _ => return,
+            }
+        }
+
+        for destination in &self.config.destinations {
+            match self.socket.send_to(shred_data, destination) {
+                Ok(_) => {
+                    self.forwarded.fetch_add(1, Ordering::Relaxed);
+                }
+                Err(e) => {
+                    let dropped = self.dropped.fetch_add(1, Ordering::Relaxed) + 1;
+                    debug!("Shred tee failed to forward to {}: {} ({} dropped so far)", destination, e, dropped);
+                }
+            }
+        }
+    }
+
+    /// (forwarded, dropped) datagrams since startup
+    pub fn counters(&self) -> (u64, u64) {
+        (self.forwarded.load(Ordering::Relaxed), self.dropped.load(Ordering::Relaxed))
+    }
+}
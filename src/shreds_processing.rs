@@ -4,24 +4,62 @@ use crate::pumpamm_decoding::*;
 use crate::raydium_decoding::*;
 use crate::raydium_launchlab_decoding::*;
 use crate::raydium_cpmm_decoding::*;
+use crate::raydium_clmm_decoding::*;
 use crate::meteora_vcurve_decoding::*;
 use crate::boop_decoding::*;
 use crate::meteoradyn_decoding::*;
 use crate::meteora_amm_v2_decoding::*;
+use crate::meteora_dlmm_decoding::*;
 use crate::orca_decoding::*;
+use crate::drift_decoding::*;
+use crate::kamino_lend_decoding::*;
+use crate::memo_decoding::*;
+use crate::compute_budget_decoding::*;
+use crate::spl_token_decoding::*;
+use crate::token_2022_decoding::*;
+use crate::metaplex_metadata_decoding::*;
+use crate::address_lookup_table_decoding::*;
+use crate::raw_instruction_decoding::*;
+use crate::idl_decoding::{deserialize_idl_instruction, find_idl_program};
+use crate::sniper_detection::observe_launch_instruction;
+use crate::wallet_clusters::observe_funding_instruction;
+use crate::post_processing::{run_post_processors, DecodedTransaction};
+use crate::decode_anomalies::*;
+use crate::leader_expiry::{fec_block_expiry, record_fec_outcome};
+use crate::raw_entries::publish_raw_fec_block;
+use crate::slot_status::{record_fec_set_complete, record_shred};
+use crate::fec_recovery::submit_recovery;
+use crate::entry_verification::{is_entry_verification_enabled, verify_entry_chain};
+use crate::blockhash_age::{blockhash_age, is_blockhash_age_enabled, record_tick_hashes};
+use crate::discriminator_catalog::{is_discriminator_catalog_enabled, observe_discriminator};
+use crate::coverage::{accept_account_count, observe_instruction_accounts};
+use crate::decoder_registry::{find_layout_variant, LayoutVariant};
+use crate::operational_events::{publish_operational_event, Severity, DECODER_PANIC_EVENT};
 use crate::utils::*;
 use crate::address_lookup_table_cache::*;
+use crate::latency_breakdown::*;
+use crate::shred_dedup::first_seen_transaction;
+use crate::batch_output::*;
+use crate::mint_index::index_transaction_accounts;
+use crate::output_message::{OutputMessage, OutputSender};
+use crate::encoder::default_output_encoding;
+use crate::resume_buffer::publish_output;
 
 use dashmap::{DashMap, DashSet};
 use json::{object, JsonValue};
+use once_cell::sync::Lazy;
 use rayon::prelude::*;
 use rustc_hash::FxHashMap as HashMap;
+use rustc_hash::FxHashSet as HashSet;
 use solana_entry::entry::Entry;
 use solana_ledger::shred::{ReedSolomonCache, Shred, ShredType, Shredder};
+use solana_sdk::instruction::CompiledInstruction;
 use solana_sdk::message::VersionedMessage;
-use solana_sdk::signature::SIGNATURE_BYTES;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Signature, SIGNATURE_BYTES};
 use solana_sdk::transaction::VersionedTransaction;
 use std::convert::TryInto;
+use std::panic::{self, AssertUnwindSafe};
 use std::sync::Arc;
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use thiserror::Error;
@@ -59,6 +97,11 @@ pub struct CodingShredHeader {
     pub position: u16,
 }
 
+/// Identifies one version of an FEC set: (slot, fec_set_index, shred signature).
+/// Merkle shreds of a set all carry the leader signature over the set's merkle root, so a
+/// resend of the same indices with different content after a duplicate block gets its own key.
+pub type FecBlockKey = (u64, u32, Signature);
+
 #[derive(Debug, Clone)]
 pub struct FecBlock {
     pub num_data_shreds: Option<u16>,
@@ -69,13 +112,14 @@ pub struct FecBlock {
     pub coding_shreds: HashMap<u32, Shred>,
     pub fec_set_index: u32,
     pub slot: u64,
+    pub signature: Signature,
     pub collection_start: Option<Instant>,
     pub last_shred_in_slot: bool,
 }
 
 impl FecBlock {
     // Creating a new FecBlock structure
-    pub fn new(slot: u64, fec_set_index: u32) -> Self {
+    pub fn new(slot: u64, fec_set_index: u32, signature: Signature) -> Self {
         FecBlock {
             num_data_shreds: None,
             num_coding_shreds: None,
@@ -85,13 +129,19 @@ impl FecBlock {
             coding_shreds: HashMap::default(),
             fec_set_index,
             slot,
+            signature,
             collection_start: None,
             last_shred_in_slot: false,
         }
     }
 
+    // Data shreds are missing and have to be recovered from the coding shreds
+    pub fn needs_recovery(&self) -> bool {
+        self.data_shreds.len() < self.num_data_shreds.unwrap_or(1) as usize
+    }
+
     // Function that checks if FecBlock is complete
-    pub fn is_complete(&self, processed_blocks: &Arc<DashSet<(u64, u32)>>) -> bool {
+    pub fn is_complete(&self, processed_blocks: &Arc<DashSet<FecBlockKey>>) -> bool {
         if let (Some(expected_data), Some(expected_coding)) =
             (self.num_data_shreds, self.num_coding_shreds)
         {
@@ -104,7 +154,7 @@ impl FecBlock {
                 (data_count == expected_data as usize) || (total_shreds >= total_expected);
 
             if complete {
-                processed_blocks.insert((self.slot, self.fec_set_index));
+                processed_blocks.insert((self.slot, self.fec_set_index, self.signature));
                 debug!(
                     "FecBlock {} for slot {} has been processed and added to DashSet.",
                     self.fec_set_index, self.slot
@@ -120,9 +170,9 @@ impl FecBlock {
 // Collects data and coding shreds for the FEC block
 pub async fn collect_shred(
     shred_data: &[u8], 
-    fec_blocks: &Arc<DashMap<(u64, u32), FecBlock>>,
-    processed_blocks: &Arc<DashSet<(u64, u32)>>, 
-    broadcast_tx: tokio::sync::broadcast::Sender<(String, u64)>,
+    fec_blocks: &Arc<DashMap<FecBlockKey, FecBlock>>,
+    processed_blocks: &Arc<DashSet<FecBlockKey>>, 
+    broadcast_tx: OutputSender,
 ) -> Result<(), CollectShredsError> {
     debug!("collect_shred: Top of function");
     if shred_data.len() < SIZE_OF_COMMON_SHRED_HEADER {
@@ -150,9 +200,10 @@ pub async fn collect_shred(
 
     let fec_set_index = shred.fec_set_index();
     let shred_slot = shred.slot();
+    let shred_signature = *shred.signature();
 
     // We don't create the Shred object if we don't pass this check
-    if processed_blocks.contains(&(shred_slot, fec_set_index)) {
+    if processed_blocks.contains(&(shred_slot, fec_set_index, shred_signature)) {
         debug!("Skipping FecBlock {} in slot {} as it is already processed.", fec_set_index, shred_slot);
         return Ok(());
     }
@@ -174,6 +225,7 @@ pub async fn collect_shred(
         shred_index, 
         fec_set_index, 
         shred_slot, 
+        shred_signature,
         broadcast_tx,
     )?;
 
@@ -184,18 +236,19 @@ pub async fn collect_shred(
 // Function that adds a shred to the FEC block
 pub fn add_shred( 
     shred: Shred, 
-    fec_blocks: &Arc<DashMap<(u64, u32), FecBlock>>,
-    processed_blocks: &Arc<DashSet<(u64, u32)>>,
+    fec_blocks: &Arc<DashMap<FecBlockKey, FecBlock>>,
+    processed_blocks: &Arc<DashSet<FecBlockKey>>,
     shred_type: ShredType, 
     shred_index: u32, 
     fec_set_index: u32,  
     shred_slot: u64, 
-    broadcast_tx: tokio::sync::broadcast::Sender<(String, u64)>,
+    shred_signature: Signature,
+    broadcast_tx: OutputSender,
 ) -> Result<(), FecBlockError> {
     let start_total = Instant::now(); // For debugging, to be removed in production
 
     let mut should_decode = false;
-    let key = (shred_slot, fec_set_index);
+    let key = (shred_slot, fec_set_index, shred_signature);
     // Get or create FecBlock using slot and fec_set_index   
     
     // if processed_blocks.contains(&key) {
@@ -203,8 +256,8 @@ pub fn add_shred(
     //     return Ok(());
     // }
    
-    let mut fec_block = fec_blocks.entry((shred_slot, fec_set_index))
-        .or_insert_with(|| FecBlock::new(shred_slot, fec_set_index));
+    let mut fec_block = fec_blocks.entry(key)
+        .or_insert_with(|| FecBlock::new(shred_slot, fec_set_index, shred_signature));
 
     if shred_slot == fec_block.slot {
         match shred_type {
@@ -228,13 +281,15 @@ pub fn add_shred(
                 }
                 fec_block.coding_shreds.insert(shred_index, shred); // Adding the Coding shred
                 fec_block.coding_shreds_collected += 1;
+                record_shred(shred_slot, false, false);
             }
             ShredType::Data => {
                 if fec_block.data_shreds.contains_key(&shred_index) {
                     debug!("Data Shred shred_index={} fec_set_index={} from slot={} is already colected", shred_index, fec_block.slot, fec_block.fec_set_index);
                     return Ok(());
                 }
-                if shred.last_in_slot() && shred.data_complete() {
+                let last_in_slot = shred.last_in_slot() && shred.data_complete();
+                if last_in_slot {
                     fec_block.last_shred_in_slot = true;
                     debug!(
                         "Last FecBlock {} detected for slot {} (shred_index: {}).",
@@ -243,6 +298,7 @@ pub fn add_shred(
                 }
                 fec_block.data_shreds.insert(shred_index, shred); // Adding the Data shred
                 fec_block.data_shreds_collected += 1;
+                record_shred(shred_slot, true, last_in_slot);
             }
         };
     } else {
@@ -259,12 +315,15 @@ pub fn add_shred(
     );
 
     // Garbage collector to remove the FecBlocks that are too old and not completed
-    if fec_block.collection_start.map_or(false, |start| start.elapsed() > Duration::from_secs(30)) {
+    if fec_block.collection_start.map_or(false, |start| start.elapsed() > fec_block_expiry(shred_slot)) {
         debug!("FecBlock expired, removing: slot {} fec_set_index {}", shred_slot, fec_set_index);
-        fec_blocks.remove(&(shred_slot, fec_set_index));
+        // The entry guard locks the shard, it must be released before the removal
+        drop(fec_block);
+        fec_blocks.remove(&key);
         increment_slot_counters(shred_slot, 1, 0, 0, 1);
-        // save_slot_statistics_to_file(shred_slot);
-    }        
+        record_fec_outcome(shred_slot, false);
+        return Ok(());
+    }
 
     if fec_block.is_complete(processed_blocks) {
         should_decode = true;
@@ -292,44 +351,31 @@ pub fn add_shred(
                     "FEC Block complete for slot {}, fec_set_index {} in {:?}",
                     fec_block_ref.slot, fec_block_ref.fec_set_index, collection_duration,
                 );
-                
-                let start_processing = Instant::now();
 
-                let decoded_fec_set_index = fec_block_ref.fec_set_index;
-                let decoded_slot = fec_block_ref.slot;
-                let _last_shred_in_slot = fec_block_ref.last_shred_in_slot;
-                
-                match decode_fec_block(&fec_block_ref) {
-                    Ok((reconstructed_payload, slot)) => {
-                        let (_, tx_count) = tokio::task::block_in_place(|| {
-                            tokio::runtime::Handle::current().block_on(async {
-                                extract_transactions_from_payload(
-                                    slot, 
-                                    reconstructed_payload,
-                                    broadcast_tx,
-                                ).await
-                            })
+                // Sets missing data shreds go through the bounded recovery pool so that
+                // a burst of lossy sets cannot starve the decoding of complete ones
+                if fec_block_ref.needs_recovery() {
+                    fec_blocks.remove(&key);
+                    let (slot, fec_set_index) = (fec_block_ref.slot, fec_block_ref.fec_set_index);
+                    let handle = tokio::runtime::Handle::current();
+                    let submitted = submit_recovery(fec_block_ref, move |fec_block, decoded| {
+                        handle.spawn_blocking(move || {
+                            finish_fec_block(&fec_block, decoded, collection_duration, Instant::now(), broadcast_tx);
                         });
-                        let _start_slot_stats_updates = Instant::now();
-                        increment_slot_counters(slot, 1, tx_count as u64, 1, 0);
-                        // if last_shred_in_slot {
-                        //     save_slot_statistics_to_file(slot);
-                        //     info!("Statistics for slot {} has been saved into json file in {:?}", decoded_slot, start_slot_stats_updates.elapsed());
-                        // }
-                        debug!("Finalized decode for FecBlock {} slot = {} in {:?}", 
-                            decoded_fec_set_index, decoded_slot, start_processing.elapsed()
-                        );
-                    }
-                    Err(e) => {
-                        error!("DecodeFecBlockError: slot={}, fec_set_index={}: {:?}", 
-                            decoded_slot, 
-                            decoded_fec_set_index,
-                            e
-                        );
+                    });
+                    if let Err(rejection) = submitted {
+                        warn!("Recovery of slot {}, fec_set_index {} skipped: {}", slot, fec_set_index, rejection);
+                        increment_slot_counters(slot, 1, 0, 0, 1);
+                        record_fec_outcome(slot, false);
                     }
+                    return;
                 }
+
+                let start_processing = Instant::now();
+                let decoded = decode_fec_block(&fec_block_ref);
+                finish_fec_block(&fec_block_ref, decoded, collection_duration, start_processing, broadcast_tx);
                 fec_blocks.remove(&key);
-                debug!("FecBlock {} for slot {} has been processed and removed from DashMap.", decoded_fec_set_index, decoded_slot);
+                debug!("FecBlock {} for slot {} has been processed and removed from DashMap.", fec_block_ref.fec_set_index, fec_block_ref.slot);
             }
         });
     }
@@ -338,10 +384,52 @@ pub fn add_shred(
     Ok(())
 }
 
+// Extracts and broadcasts the transactions of a decoded FecBlock, must run on a blocking thread of the runtime
+fn finish_fec_block(
+    fec_block: &FecBlock,
+    decoded: Result<(Vec<u8>, u64, FecBlockTimings), FecBlockError>,
+    collection_duration: Duration,
+    start_processing: Instant,
+    broadcast_tx: OutputSender,
+) {
+    match decoded {
+        Ok((reconstructed_payload, slot, mut fec_timings)) => {
+            fec_timings.shred_wait = collection_duration;
+            publish_raw_fec_block(slot, fec_block.fec_set_index, &reconstructed_payload);
+            let (_, tx_count) = tokio::task::block_in_place(|| {
+                tokio::runtime::Handle::current().block_on(async {
+                    extract_transactions_from_payload(
+                        slot, 
+                        reconstructed_payload,
+                        broadcast_tx,
+                        fec_timings,
+                    ).await
+                })
+            });
+            increment_slot_counters(slot, 1, tx_count as u64, 1, 0);
+            record_fec_outcome(slot, true);
+            let recovered_data_shreds = fec_block.num_data_shreds
+                .map_or(0, |expected| (expected as usize).saturating_sub(fec_block.data_shreds.len()));
+            record_fec_set_complete(slot, fec_block.fec_set_index, recovered_data_shreds as u64);
+            debug!("Finalized decode for FecBlock {} slot = {} in {:?}", 
+                fec_block.fec_set_index, fec_block.slot, start_processing.elapsed()
+            );
+        }
+        Err(e) => {
+            error!("DecodeFecBlockError: slot={}, fec_set_index={}: {:?}", 
+                fec_block.slot, 
+                fec_block.fec_set_index,
+                e
+            );
+        }
+    }
+}
+
 // Function that decodes the FecBlock without modifying the original FecBlock
 pub fn decode_fec_block(
     fec_block: &FecBlock,
-) -> Result<(Vec<u8>, u64), FecBlockError> {
+) -> Result<(Vec<u8>, u64, FecBlockTimings), FecBlockError> {
+    let mut timings = FecBlockTimings::default();
 
     let mut local_data_shreds: Vec<Shred> = fec_block.data_shreds.values().cloned().collect();
     let expected_data_shreds = fec_block.num_data_shreds.unwrap_or(1) as usize;
@@ -357,6 +445,7 @@ pub fn decode_fec_block(
             .chain(fec_block.coding_shreds.values().cloned())
             .collect();
 
+        let start_recovery = stage_start();
         match Shredder::try_recovery(all_shreds_for_recovery, &ReedSolomonCache::default()) {
             Ok(recovered_shreds) => {
                 let mut count_recovered = 0;
@@ -373,6 +462,7 @@ pub fn decode_fec_block(
                 warn!("Failed to recover data shreds: {:?}", e);
             }
         }
+        timings.rs_recovery = stage_elapsed(start_recovery);
     }
 
     if local_data_shreds.is_empty() {
@@ -381,10 +471,12 @@ pub fn decode_fec_block(
 
     local_data_shreds.sort_by_key(|shred| shred.index());
 
+    let start_deshred = stage_start();
     let payload = reconstruct_full_payload(&local_data_shreds)
         .map_err(|e| FecBlockError::FullPayloadReconstructionError(e))?;
+    timings.deshred = stage_elapsed(start_deshred);
 
-    Ok((payload, fec_block.slot))
+    Ok((payload, fec_block.slot, timings))
 }
 
 fn reconstruct_full_payload(shreds: &[Shred]) -> Result<Vec<u8>, String> {
@@ -423,7 +515,7 @@ fn get_coding_shred_header(shred_data: &[u8]) -> Result<CodingShredHeader, Box<d
     })
 }
 
-fn get_slot_from_shred_data(shred_data: &[u8]) -> Result<u64, &'static str> {
+pub(crate) fn get_slot_from_shred_data(shred_data: &[u8]) -> Result<u64, &'static str> {
     if shred_data.len() < OFFSET_OF_SHRED_SLOT + SIZE_OF_SHRED_SLOT {
         return Err("The shred is too short");
     }
@@ -435,15 +527,29 @@ fn get_slot_from_shred_data(shred_data: &[u8]) -> Result<u64, &'static str> {
 pub async fn extract_transactions_from_payload(
     slot: u64,
     payload: Vec<u8>,
-    broadcast_tx: tokio::sync::broadcast::Sender<(String, u64)>,
+    broadcast_tx: OutputSender,
+    mut fec_timings: FecBlockTimings,
 ) -> (u64, usize) {
+    let breakdown_enabled = is_latency_breakdown_enabled();
+    let start_bincode = stage_start();
     match bincode::deserialize::<Vec<Entry>>(&payload) {
         Ok(entries) => {
+            fec_timings.bincode = stage_elapsed(start_bincode);
+
+            let entries_verified = is_entry_verification_enabled().then(|| verify_entry_chain(slot, &entries));
+            if is_blockhash_age_enabled() {
+                record_tick_hashes(slot, &entries);
+            }
+
             // Pre-resolve all address lookup tables before parallel processing
             let mut transactions_with_resolved_keys = Vec::new();
             
             for entry in &entries {
                 for transaction in &entry.transactions {
+                    // Already extracted from another ingest source
+                    if !transaction.signatures.first().is_none_or(|signature| first_seen_transaction(slot, signature)) {
+                        continue;
+                    }
                     // Process transaction for lookup table extensions first
                     crate::address_lookup_table_cache::process_transaction_for_lookup_table_extensions(transaction);
                     
@@ -459,31 +565,87 @@ pub async fn extract_transactions_from_payload(
                     };
                     
                     // Resolve account keys asynchronously while we have Tokio context
-                    let resolved_account_keys = resolve_transaction_account_keys(
-                        base_account_keys, 
-                        address_table_lookups
-                    ).await;
+                    let start_alt_resolve = stage_start();
+                    let (resolved_account_keys, partial) = match alt_resolve_deadline() {
+                        Some(deadline) => match resolve_transaction_account_keys_with_deadline(
+                            base_account_keys,
+                            address_table_lookups,
+                            deadline,
+                        ).await {
+                            DeadlineResolution::Complete(account_keys) => (account_keys, false),
+                            DeadlineResolution::Partial { account_keys, pending } => {
+                                if is_partial_followup_enabled() {
+                                    tokio::spawn(emit_corrected_transaction(
+                                        slot,
+                                        transaction.clone(),
+                                        pending,
+                                        broadcast_tx.clone(),
+                                    ));
+                                }
+                                (account_keys, true)
+                            }
+                        },
+                        None => (
+                            resolve_transaction_account_keys(base_account_keys, address_table_lookups).await,
+                            false,
+                        ),
+                    };
+                    let alt_resolve = stage_elapsed(start_alt_resolve);
                     
-                    transactions_with_resolved_keys.push((transaction, resolved_account_keys));
+                    transactions_with_resolved_keys.push(ResolvedTransaction {
+                        transaction,
+                        account_keys: resolved_account_keys,
+                        alt_resolve,
+                        partial,
+                        entries_verified,
+                    });
                 }
             }
             
+            if is_batch_output_enabled() {
+                return send_transaction_batch(slot, &transactions_with_resolved_keys, &broadcast_tx);
+            }
+
             // Now do parallel processing with pre-resolved account keys
             let total_txs = transactions_with_resolved_keys
                 .par_iter()
-                .map(|(transaction, resolved_account_keys)| {
+                .map(|resolved| {
                     let start_time = Instant::now();
 
-                    if let Some(json_transaction) = deserialize_versioned_transaction_with_resolved_keys(transaction, slot, resolved_account_keys) {
+                    if let Some(json_transaction) = decode_resolved_transaction(resolved, slot) {
+                        let decode_duration = if breakdown_enabled { start_time.elapsed() } else { Duration::ZERO };
+
                         let timestamp = SystemTime::now()
                             .duration_since(UNIX_EPOCH)
                             .expect("Time went backwards")
                             .as_micros();
 
-                        if let Err(e) = broadcast_tx.send((json_transaction.pretty(2).to_string(), timestamp as u64)) {
+                        let signature = breakdown_enabled
+                            .then(|| json_transaction["signatures"][0].as_str().unwrap_or("unknown").to_string());
+                        let accounts = index_transaction_accounts(&json_transaction);
+                        let message = OutputMessage::new(json_transaction, timestamp as u64, accounts);
+
+                        // Pre-encode the default encoding, the one most subscribers share
+                        let start_serialize = stage_start();
+                        message.encoded(default_output_encoding());
+                        let serialize_duration = stage_elapsed(start_serialize);
+
+                        let start_enqueue = stage_start();
+                        if let Err(e) = publish_output(&broadcast_tx, message) {
                             error!("Failed to send transaction via grpc broadcast channel: {}", e);
                         }
                         debug!("Transaction deserialized & sent to channel in {:?}", start_time.elapsed());
+
+                        if breakdown_enabled {
+                            let breakdown = LatencyBreakdown {
+                                fec: fec_timings,
+                                alt_resolve: resolved.alt_resolve,
+                                decode: decode_duration,
+                                serialize: serialize_duration,
+                                enqueue: stage_elapsed(start_enqueue),
+                            };
+                            breakdown.emit(slot, signature.as_deref().unwrap_or("unknown"));
+                        }
                     }
                     
                     1  // Count each transaction
@@ -499,10 +661,108 @@ pub async fn extract_transactions_from_payload(
     }
 }
 
+// A transaction with its account keys, resolved before the parallel decode
+struct ResolvedTransaction<'a> {
+    transaction: &'a VersionedTransaction,
+    account_keys: Vec<Pubkey>,
+    alt_resolve: Duration,
+    // Lookup table resolution hit the deadline, some keys are placeholders
+    partial: bool,
+    // Outcome of the PoH check of the payload, None when verification is disabled
+    entries_verified: Option<bool>,
+}
+
+fn decode_resolved_transaction(resolved: &ResolvedTransaction, slot: u64) -> Option<JsonValue> {
+    let mut json_transaction = deserialize_versioned_transaction_with_resolved_keys(resolved.transaction, slot, &resolved.account_keys, true)?;
+    if resolved.partial {
+        json_transaction["partial"] = true.into();
+    }
+    if let Some(verified) = resolved.entries_verified {
+        json_transaction["entryHashVerified"] = verified.into();
+    }
+    if is_blockhash_age_enabled() {
+        json_transaction["blockhashAge"] = blockhash_age(slot, resolved.transaction.message.recent_blockhash()).to_json();
+    }
+    Some(json_transaction)
+}
+
+// Waits for the full lookup table resolution of a transaction emitted as partial
+// and sends it again, flagged as a correction, with the complete account keys
+async fn emit_corrected_transaction(
+    slot: u64,
+    transaction: VersionedTransaction,
+    pending: tokio::task::JoinHandle<Vec<Pubkey>>,
+    broadcast_tx: OutputSender,
+) {
+    let account_keys = match pending.await {
+        Ok(account_keys) => account_keys,
+        Err(e) => {
+            error!("Lookup table resolution for corrected transaction failed: {}", e);
+            return;
+        }
+    };
+
+    let Some(mut json_transaction) = deserialize_versioned_transaction_with_resolved_keys(&transaction, slot, &account_keys, false) else {
+        return;
+    };
+    json_transaction["correction"] = true.into();
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_micros();
+
+    let accounts = index_transaction_accounts(&json_transaction);
+    let message = OutputMessage::new(json_transaction, timestamp as u64, accounts);
+
+    if let Err(e) = publish_output(&broadcast_tx, message) {
+        error!("Failed to send corrected transaction via grpc broadcast channel: {}", e);
+    }
+}
+
+// Decodes the whole payload and sends it as one message sharing an account-key dictionary
+fn send_transaction_batch(
+    slot: u64,
+    transactions_with_resolved_keys: &[ResolvedTransaction],
+    broadcast_tx: &OutputSender,
+) -> (u64, usize) {
+    let total_txs = transactions_with_resolved_keys.len();
+    let decoded: Vec<JsonValue> = transactions_with_resolved_keys
+        .par_iter()
+        .filter_map(|resolved| decode_resolved_transaction(resolved, slot))
+        .collect();
+
+    if decoded.is_empty() {
+        return (slot, total_txs);
+    }
+
+    let decoded_count = decoded.len();
+    let mut accounts = HashSet::default();
+    for transaction in &decoded {
+        accounts.extend(index_transaction_accounts(transaction));
+    }
+    let batch = build_transaction_batch(slot, decoded);
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_micros();
+
+    let message = OutputMessage::new(batch, timestamp as u64, accounts);
+
+    if let Err(e) = publish_output(broadcast_tx, message) {
+        error!("Failed to send transaction batch via grpc broadcast channel: {}", e);
+    }
+    debug!("Sent batch of {} decoded transactions for slot {}", decoded_count, slot);
+
+    (slot, total_txs)
+}
+
 // Note: resolve_account_keys_sync function removed - address resolution now happens
 // before parallel processing in extract_transactions_from_payload
 
-fn deserialize_versioned_transaction_with_resolved_keys(transaction: &VersionedTransaction, slot: u64, resolved_account_keys: &[solana_sdk::pubkey::Pubkey]) -> Option<JsonValue> {
+// `observe` feeds the stateful observers (funding, launches, price ticks); it is off when
+// re-decoding a transaction that was already emitted so nothing is counted twice
+fn deserialize_versioned_transaction_with_resolved_keys(transaction: &VersionedTransaction, slot: u64, resolved_account_keys: &[solana_sdk::pubkey::Pubkey], observe: bool) -> Option<JsonValue> {
     let (instructions, _base_account_keys, header, recent_blockhash, _address_table_lookups) =
         match &transaction.message {
         VersionedMessage::Legacy(legacy_msg) => (
@@ -534,375 +794,1154 @@ fn deserialize_versioned_transaction_with_resolved_keys(transaction: &VersionedT
         }
     }
     
-    for i in 0..(header.num_required_signatures - header.num_readonly_signed_accounts) as usize {
+    // Malformed headers must not underflow/overflow and panic the decode task
+    for i in 0..header.num_required_signatures.saturating_sub(header.num_readonly_signed_accounts) as usize {
         if i < num_accounts {
             is_writable[i] = true;
         }
     }
     
-    for i in (header.num_required_signatures + header.num_readonly_unsigned_accounts) as usize..num_accounts {
+    for i in (header.num_required_signatures as usize + header.num_readonly_unsigned_accounts as usize)..num_accounts {
         is_writable[i] = true;
     }
 
-    let mut serialized_instructions = Vec::with_capacity(instructions.len());
-    let mut contains_relevant_instruction = false;
+    // Memos and the compute budget are attached to the output but do not make a transaction relevant on their own
+    let mut memos: Vec<String> = Vec::new();
+    let mut compute_budget = ComputeBudget::default();
+    let mut compute_budget_instructions = 0;
+    let mut candidates: Vec<(&Pubkey, &CompiledInstruction)> = Vec::with_capacity(instructions.len());
 
+    // Observers keep state and run in instruction order, only the decoding itself is parallel
     for instr in instructions.iter() {
         let Some(program_key) = account_keys.get(instr.program_id_index as usize) else { continue };
-        if instr.data.len() < 8 { continue };
-
-        if program_key == &PUMPFUN_PROGRAM_ID {
-            let Some(instr_type) = get_pumpfun_instruction_type(&instr.data) else { continue };
-            contains_relevant_instruction = true;
-                
-            let decoded_result = match instr_type {
-                PumpfunInstructionType::Create => {
-                    if instr.accounts.len() < 14 {
-                        warn!("Pumpfun Create: The instruction does not contain a minimum of 14 accounts needed.");
-                        continue;
-                    }
-                    deserialize_pump_create_instruction(&instr.data, &instr.accounts, account_keys, &is_signer, &is_writable)
-                },
-                PumpfunInstructionType::Buy => {
-                    continue;
-                },
-            };
+        if is_memo_program(program_key) {
+            memos.extend(decode_memo(&instr.data));
+            continue;
+        }
+        if is_compute_budget_program(program_key) {
+            compute_budget.apply(&instr.data);
+            compute_budget_instructions += 1;
+            continue;
+        }
+        if observe {
+            observe_funding_instruction(program_key, instr, account_keys);
+            if is_discriminator_catalog_enabled() {
+                observe_discriminator(program_key, &instr.data);
+            }
+        }
+        // Token and lookup table instructions are selected by one to four bytes and can be shorter
+        // than 8, raw instructions are emitted whatever their length
+        if instr.data.len() < 8 && !is_token_program(program_key) && !is_lookup_table_program(program_key) && !is_raw_program(program_key) { continue };
+
+        if observe {
+            observe_launch_instruction(slot, transaction.signatures.first(), program_key, instr, account_keys);
+            observe_instruction_accounts(program_key, instr);
+        }
+        candidates.push((program_key, instr));
+    }
+
+    // Token and raw instructions are only emitted alongside the instructions of a decoded protocol
+    if candidates.iter().all(|(program_key, _)| is_token_program(program_key) || is_raw_program(program_key)) {
+        return None;
+    }
 
-            match decoded_result {
-                Ok(decoded) => serialized_instructions.push(decoded),
-                Err(err) => error!("Error decoding Pumpfun instruction: {}", err),
+    let decode = |&(program_key, instr): &(&Pubkey, &CompiledInstruction)| {
+        decode_instruction_guarded(slot, program_key, instr, account_keys, &is_signer, &is_writable)
+    };
+    let budget = instruction_decode_budget();
+    let serialized_instructions: Vec<JsonValue> = if budget > 1 && candidates.len() >= instruction_parallel_min() {
+        // At most `budget` tasks, collect keeps the instruction order
+        let decoded: Vec<Option<JsonValue>> = candidates
+            .par_iter()
+            .with_min_len(candidates.len().div_ceil(budget))
+            .map(decode)
+            .collect();
+        decoded.into_iter().flatten().collect()
+    } else {
+        candidates.iter().filter_map(decode).collect()
+    };
+
+    if serialized_instructions.iter().all(|instruction| is_token_instruction(instruction) || is_raw_instruction(instruction)) {
+        return None;
+    }
+
+    let signatures: Vec<String> = transaction
+        .signatures
+        .iter()
+        .map(|signature| bs58::encode(signature).into_string())
+        .collect();
+
+    let mut json_transaction = object! {
+        "signatures" => signatures,
+        "slot" => slot,
+        "message" => object! {
+            "header" => object! {
+                "numRequiredSignatures" => header.num_required_signatures,
+                "numReadonlySignedAccounts" => header.num_readonly_signed_accounts,
+                "numReadonlyUnsignedAccounts" => header.num_readonly_unsigned_accounts,
+            },
+            "recentBlockhash" => bs58::encode(recent_blockhash).into_string(),
+            "instructions" => serialized_instructions,
+        }
+    };
+
+    if !memos.is_empty() {
+        json_transaction["memo"] = memos.join("\n").into();
+    }
+    let non_compute_budget_instructions = instructions.len() - compute_budget_instructions;
+    json_transaction["cu_limit"] = compute_budget.cu_limit(non_compute_budget_instructions).into();
+    json_transaction["priority_fee_lamports"] = compute_budget.priority_fee_lamports(non_compute_budget_instructions).into();
+
+    Some(run_post_processors(DecodedTransaction {
+        slot,
+        json: json_transaction,
+        account_keys,
+        first_emission: observe,
+    }))
+}
+
+/// Parallel decoding tasks per transaction, 1 decodes every transaction serially
+static INSTRUCTION_DECODE_BUDGET: Lazy<usize> = Lazy::new(|| {
+    std::env::var("INSTRUCTION_DECODE_BUDGET")
+        .unwrap_or_else(|_| "4".to_string())
+        .parse()
+        .unwrap_or(4)
+});
+
+/// Instructions to decode before a transaction is split across tasks, below it the overhead dominates
+static INSTRUCTION_PARALLEL_MIN: Lazy<usize> = Lazy::new(|| {
+    std::env::var("INSTRUCTION_PARALLEL_MIN")
+        .unwrap_or_else(|_| "6".to_string())
+        .parse()
+        .unwrap_or(6)
+});
+
+fn instruction_decode_budget() -> usize {
+    *INSTRUCTION_DECODE_BUDGET
+}
+
+fn instruction_parallel_min() -> usize {
+    *INSTRUCTION_PARALLEL_MIN
+}
+
+// Runs the decoder behind a panic boundary so that one malformed instruction
+// cannot take down the blocking task and every other transaction of the FEC set
+fn decode_instruction_guarded(
+    slot: u64,
+    program_key: &Pubkey,
+    instr: &CompiledInstruction,
+    account_keys: &[Pubkey],
+    is_signer: &[bool],
+    is_writable: &[bool],
+) -> Option<JsonValue> {
+    // Slots pinned to a historical layout are decoded with it when the data layout changed
+    let variant = find_layout_variant(program_key, &instr.data, slot);
+    let decode = || match variant {
+        Some(variant) if variant.decode.is_some() => {
+            decode_layout_variant(slot, variant, program_key, instr, account_keys, is_signer, is_writable)
+        }
+        _ => decode_instruction(slot, program_key, instr, account_keys, is_signer, is_writable),
+    };
+    match panic::catch_unwind(AssertUnwindSafe(decode)) {
+        Ok(mut decoded) => {
+            // Instructions of slots pinned to a historical layout say which one was applied
+            if let (Some(decoded), Some(variant)) = (decoded.as_mut(), variant) {
+                decoded["layout"] = variant.layout.into();
             }
-        } else if program_key == &RAYDIUM_LP_PROGRAM {
-            let Some(instr_type) = get_raydium_instruction_type(&instr.data) else { continue };
-            contains_relevant_instruction = true;
-                
-            let decoded_result = match instr_type {
-                RaydiumInstructionType::Initialize2 => {
-                if instr.accounts.len() < 21 {
-                    warn!("Raydium Initialize2: The instruction does not contain a minimum of 21 accounts needed.");
-                    continue;
-                }
-                    deserialize_raydium_initialize2_instruction(&instr.data, &instr.accounts, account_keys, &is_signer, &is_writable)
-                }
-            };
+            decoded
+        }
+        Err(payload) => {
+            let reason = payload
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic payload".to_string());
+            error!(
+                "Decoder panicked on instruction for program {} (data={}): {}",
+                program_key,
+                hex::encode(&instr.data),
+                reason
+            );
+            publish_operational_event(
+                DECODER_PANIC_EVENT,
+                Severity::Error,
+                format!("Decoder panicked on instruction for program {}: {}", program_key, reason),
+                object! { "programId" => program_key.to_string(), "data" => hex::encode(&instr.data) },
+            );
+            None
+        }
+    }
+}
 
-            match decoded_result {
-                Ok(decoded) => serialized_instructions.push(decoded),
-                Err(err) => error!("Error decoding Raydium instruction: {}", err),
-            }
-        } else if program_key == &MOONIT_PROGRAM_ID {
-            let Some(instr_type) = get_moonit_instruction_type(&instr.data) else { continue };
-            contains_relevant_instruction = true;
-                
-            let decoded_result = match instr_type {
-                MoonitInstructionType::TokenMint => {
-                    if instr.accounts.len() < 11 {
-                        warn!("Moonit TokenMint: The instruction does not contain a minimum of 11 accounts needed.");
-                        continue;
-                    }
-                    deserialize_moonit_token_mint_instruction(&instr.data, &instr.accounts, account_keys, &is_signer, &is_writable)
+// Decodes an instruction with the historical layout its slot is pinned to
+fn decode_layout_variant(
+    slot: u64,
+    variant: &LayoutVariant,
+    program_key: &Pubkey,
+    instr: &CompiledInstruction,
+    account_keys: &[Pubkey],
+    is_signer: &[bool],
+    is_writable: &[bool],
+) -> Option<JsonValue> {
+    let decode = variant.decode?;
+    let context = format!("{} {} ({})", program_key, variant.instruction, variant.layout);
+    if let Some(min_accounts) = variant.min_accounts {
+        if !accept_account_count(&context, min_accounts, slot, program_key, instr, account_keys) {
+            return None;
+        }
+    }
+    match decode(&instr.data, &instr.accounts, account_keys, is_signer, is_writable) {
+        Ok(decoded) => Some(decoded),
+        Err(err) => {
+            error!("Error decoding {} instruction: {}", context, err);
+            None
+        }
+    }
+}
+
+// Dispatches a single instruction to the decoder registered for its program
+fn decode_instruction(
+    slot: u64,
+    program_key: &Pubkey,
+    instr: &CompiledInstruction,
+    account_keys: &[Pubkey],
+    is_signer: &[bool],
+    is_writable: &[bool],
+) -> Option<JsonValue> {
+    if program_key == &PUMPFUN_PROGRAM_ID {
+        let Some(instr_type) = get_pumpfun_instruction_type(&instr.data) else {
+            report_unknown_discriminator("Pumpfun", program_key, instr, account_keys);
+            return None;
+        };
+            
+        let decoded_result = match instr_type {
+            PumpfunInstructionType::Create => {
+                if !accept_account_count("Pumpfun Create", 14, slot, program_key, instr, account_keys) {
+                    return None;
                 }
-            };
+                deserialize_pump_create_instruction(&instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            },
+            PumpfunInstructionType::Buy => {
+                return None;
+            },
+        };
 
-            match decoded_result {
-                Ok(decoded) => serialized_instructions.push(decoded),
-                Err(err) => error!("Error decoding Moonit instruction: {}", err),
+        match decoded_result {
+            Ok(decoded) => Some(decoded),
+            Err(err) => {
+                error!("Error decoding Pumpfun instruction: {}", err);
+                None
             }
-        } else if program_key == &RAYDIUM_LAUNCHLAB_PROGRAM_ID {
-            let Some(instr_type) = get_raydium_launchlab_instruction_type(&instr.data) else { continue };
-            contains_relevant_instruction = true;
+        }
+    } else if program_key == &RAYDIUM_LP_PROGRAM {
+        let Some(instr_type) = get_raydium_instruction_type(&instr.data) else {
+            report_unknown_discriminator("Raydium", program_key, instr, account_keys);
+            return None;
+        };
             
-            let decoded_result = match instr_type {
-                RaydiumLaunchlabInstructionType::Initialize => {
-                    if instr.accounts.len() < 18 {
-                        warn!("Raydium Launchlab Initialize: The instruction does not contain a minimum of 18 accounts needed.");
-                        continue;
-                    }
-                    deserialize_raydium_launchlab_initialize_instruction(&instr.data, &instr.accounts, account_keys, &is_signer, &is_writable)
+        let decoded_result = match instr_type {
+            RaydiumInstructionType::Initialize2 => {
+            if !accept_account_count("Raydium Initialize2", 21, slot, program_key, instr, account_keys) {
+                return None;
+            }
+                deserialize_raydium_initialize2_instruction(&instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            }
+            RaydiumInstructionType::SwapBaseIn => {
+                if !accept_account_count("Raydium SwapBaseIn", 17, slot, program_key, instr, account_keys) {
+                    return None;
                 }
-            };
-            match decoded_result {
-                Ok(decoded) => serialized_instructions.push(decoded),
-                Err(err) => {
-                    error!("Error decoding Raydium Launchlab instruction: {}", err);
-                    continue;
-                }
-            }
-        } else if program_key == &BOOP_PROGRAM_ID {
-            let Some(instr_type) = get_boop_instruction_type(&instr.data) else { continue };
-            contains_relevant_instruction = true;
-                
-            let decoded_result = match instr_type {
-                BoopInstructionType::CreateToken => {
-                    if instr.accounts.len() < 8 {
-                        warn!("Boop CreateToken: The instruction does not contain a minimum of 8 accounts needed.");
-                        continue;
-                    }
-                    deserialize_boop_create_token_instruction(&instr.data, &instr.accounts, account_keys, &is_signer, &is_writable)
-                },
-                BoopInstructionType::DeployBondingCurve => {
-                    if instr.accounts.len() < 10 {
-                        warn!("Boop DeployBondingCurve: The instruction does not contain a minimum of 10 accounts needed.");
-                        continue;
-                    }
-                    deserialize_boop_deploy_bonding_curve_instruction(&instr.data, &instr.accounts, account_keys, &is_signer, &is_writable)
+                deserialize_raydium_swap_base_in_instruction(&instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            }
+            RaydiumInstructionType::SwapBaseOut => {
+                if !accept_account_count("Raydium SwapBaseOut", 17, slot, program_key, instr, account_keys) {
+                    return None;
                 }
-            };
+                deserialize_raydium_swap_base_out_instruction(&instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            }
+        };
 
-            match decoded_result {
-                Ok(decoded) => serialized_instructions.push(decoded),
-                Err(err) => {
-                    error!("Error decoding Boop instruction: {}", err);
-                    continue;
-                }
-            }
-        } else if program_key == &PUMPAMM_PROGRAM_ID {
-            let Some(instr_type) = get_pumpamm_instruction_type(&instr.data) else { continue };
-            contains_relevant_instruction = true;
-                
-            let decoded_result = match instr_type {
-                PumpAmmInstructionType::Buy => {
-                    if instr.accounts.len() < 19 {
-                        warn!("PumpAMM Buy: The instruction does not contain a minimum of 16 accounts needed.");
-                        continue;
-                    }
-                    deserialize_pumpamm_buy_instruction(&instr.data, &instr.accounts, account_keys, &is_signer, &is_writable)
-                },
-                PumpAmmInstructionType::Sell => {
-                    if instr.accounts.len() < 19 {
-                        warn!("PumpAMM Sell: The instruction does not contain a minimum of 17 accounts needed.");
-                        continue;
-                    }
-                    deserialize_pumpamm_sell_instruction(&instr.data, &instr.accounts, account_keys, &is_signer, &is_writable)
-                },
-                PumpAmmInstructionType::CreatePool => {
-                    if instr.accounts.len() < 18 {
-                        warn!("PumpAMM CreatePool: The instruction does not contain a minimum of 18 accounts needed.");
-                        continue;
-                    }
-                    deserialize_pumpamm_create_pool_instruction(&instr.data, &instr.accounts, account_keys, &is_signer, &is_writable)
-                },
-                _ => {
-                    continue;
+        match decoded_result {
+            Ok(decoded) => Some(decoded),
+            Err(err) => {
+                error!("Error decoding Raydium instruction: {}", err);
+                None
+            }
+        }
+    } else if program_key == &MOONIT_PROGRAM_ID {
+        let Some(instr_type) = get_moonit_instruction_type(&instr.data) else {
+            report_unknown_discriminator("Moonit", program_key, instr, account_keys);
+            return None;
+        };
+            
+        let decoded_result = match instr_type {
+            MoonitInstructionType::TokenMint => {
+                if !accept_account_count("Moonit TokenMint", 11, slot, program_key, instr, account_keys) {
+                    return None;
                 }
-            };
+                deserialize_moonit_token_mint_instruction(&instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            }
+            MoonitInstructionType::Buy => {
+                if !accept_account_count("Moonit Buy", 11, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_moonit_trade_instruction("Buy", &instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            }
+            MoonitInstructionType::Sell => {
+                if !accept_account_count("Moonit Sell", 11, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_moonit_trade_instruction("Sell", &instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            }
+        };
 
-            match decoded_result {
-                Ok(decoded) => serialized_instructions.push(decoded),
-                Err(err) => {
-                    error!("Error decoding PumpAMM instruction: {}", err);
-                    continue;
+        match decoded_result {
+            Ok(decoded) => Some(decoded),
+            Err(err) => {
+                error!("Error decoding Moonit instruction: {}", err);
+                None
+            }
+        }
+    } else if program_key == &RAYDIUM_LAUNCHLAB_PROGRAM_ID {
+        let Some(instr_type) = get_raydium_launchlab_instruction_type(&instr.data) else {
+            report_unknown_discriminator("RaydiumLaunchLab", program_key, instr, account_keys);
+            return None;
+        };
+        
+        let decoded_result = match instr_type {
+            RaydiumLaunchlabInstructionType::Initialize => {
+                if !accept_account_count("Raydium Launchlab Initialize", 18, slot, program_key, instr, account_keys) {
+                    return None;
                 }
+                deserialize_raydium_launchlab_initialize_instruction(&instr.data, &instr.accounts, account_keys, is_signer, is_writable)
             }
-        } else if program_key == &RAYDIUM_CPMM_PROGRAM {
-            let Some(instr_type) = get_raydium_cpmm_instruction_type(&instr.data) else { continue };
-            contains_relevant_instruction = true;
-            
-            let decoded_result = match instr_type {
-                RaydiumCpmmInstructionType::Initialize => {
-                    if instr.accounts.len() < 20 {
-                        warn!("Raydium CPMM Initialize: The instruction does not contain a minimum of 20 accounts needed.");
-                        continue;
-                    }
-                    deserialize_raydium_cpmm_initialize_instruction(&instr.data, &instr.accounts, account_keys, &is_signer, &is_writable)
+            RaydiumLaunchlabInstructionType::BuyExactIn => {
+                if !accept_account_count("Raydium Launchlab BuyExactIn", 15, slot, program_key, instr, account_keys) {
+                    return None;
                 }
-            };
-            match decoded_result {
-                Ok(decoded) => serialized_instructions.push(decoded),
-                Err(err) => {
-                    error!("Error decoding Raydium CPMM instruction: {}", err);
-                    continue;
+                deserialize_raydium_launchlab_trade_instruction("BuyExactIn", true, &instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            }
+            RaydiumLaunchlabInstructionType::BuyExactOut => {
+                if !accept_account_count("Raydium Launchlab BuyExactOut", 15, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_raydium_launchlab_trade_instruction("BuyExactOut", false, &instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            }
+            RaydiumLaunchlabInstructionType::SellExactIn => {
+                if !accept_account_count("Raydium Launchlab SellExactIn", 15, slot, program_key, instr, account_keys) {
+                    return None;
                 }
+                deserialize_raydium_launchlab_trade_instruction("SellExactIn", true, &instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            }
+            RaydiumLaunchlabInstructionType::SellExactOut => {
+                if !accept_account_count("Raydium Launchlab SellExactOut", 15, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_raydium_launchlab_trade_instruction("SellExactOut", false, &instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            }
+            RaydiumLaunchlabInstructionType::MigrateToAmm => {
+                if !accept_account_count("Raydium Launchlab MigrateToAmm", 24, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_raydium_launchlab_migrate_to_amm_instruction(&instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            }
+            RaydiumLaunchlabInstructionType::MigrateToCpswap => {
+                if !accept_account_count("Raydium Launchlab MigrateToCpswap", 18, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_raydium_launchlab_migrate_to_cpswap_instruction(&instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            }
+        };
+        match decoded_result {
+            Ok(decoded) => Some(decoded),
+            Err(err) => {
+                error!("Error decoding Raydium Launchlab instruction: {}", err);
+                None
             }
-        } else if program_key == &METEORA_VCURVE_PROGRAM_ID {
-            let Some(instr_type) = get_meteora_vcurve_instruction_type(&instr.data) else { continue };
-            contains_relevant_instruction = true;
+        }
+    } else if program_key == &BOOP_PROGRAM_ID {
+        let Some(instr_type) = get_boop_instruction_type(&instr.data) else {
+            report_unknown_discriminator("Boop", program_key, instr, account_keys);
+            return None;
+        };
             
-            let decoded_result = match instr_type {
-                MeteoraVCurveInstructionType::InitializeVirtualPoolWithSplToken => {
-                    if instr.accounts.len() < 16 {
-                        warn!("Meteora VCurve Initialize Virtual Pool With SPL Token: The instruction does not contain a minimum of 16 accounts needed.");
-                        continue;
-                    }
-                    deserialize_meteora_vcurve_initialize_virtual_pool_instruction(&instr.data, &instr.accounts, account_keys, &is_signer, &is_writable)
+        let decoded_result = match instr_type {
+            BoopInstructionType::CreateToken => {
+                if !accept_account_count("Boop CreateToken", 8, slot, program_key, instr, account_keys) {
+                    return None;
                 }
-            };
-            match decoded_result {
-                Ok(decoded) => serialized_instructions.push(decoded),
-                Err(err) => {
-                    error!("Error decoding Meteora VCurve instruction: {}", err);
-                    continue;
+                deserialize_boop_create_token_instruction(&instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            },
+            BoopInstructionType::DeployBondingCurve => {
+                if !accept_account_count("Boop DeployBondingCurve", 10, slot, program_key, instr, account_keys) {
+                    return None;
                 }
+                deserialize_boop_deploy_bonding_curve_instruction(&instr.data, &instr.accounts, account_keys, is_signer, is_writable)
             }
-        } else if program_key == &METEORADYN_PROGRAM_ID {
-            let Some(instr_type) = get_meteoradyn_instruction_type(&instr.data) else { continue };
-            contains_relevant_instruction = true;
+        };
+
+        match decoded_result {
+            Ok(decoded) => Some(decoded),
+            Err(err) => {
+                error!("Error decoding Boop instruction: {}", err);
+                None
+            }
+        }
+    } else if program_key == &PUMPAMM_PROGRAM_ID {
+        let Some(instr_type) = get_pumpamm_instruction_type(&instr.data) else {
+            report_unknown_discriminator("PumpAMM", program_key, instr, account_keys);
+            return None;
+        };
             
-            let decoded_result = match instr_type {
-                MeteoraDynInstructionType::InitializePermissionlessPool => {
-                    if instr.accounts.len() < 24 {
-                        warn!("Meteora DYN InitializePermissionlessPool: The instruction does not contain a minimum of 24 accounts needed.");
-                        continue;
-                    }
-                    deserialize_meteoradyn_initialize_permissionless_pool_instruction(&instr.data, &instr.accounts, account_keys, &is_signer, &is_writable)
-                },
-                _ => {
-                    continue;
+        let decoded_result = match instr_type {
+            PumpAmmInstructionType::Buy => {
+                if !accept_account_count("PumpAMM Buy", 19, slot, program_key, instr, account_keys) {
+                    return None;
                 }
-            };
+                deserialize_pumpamm_buy_instruction(&instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            },
+            PumpAmmInstructionType::Sell => {
+                if !accept_account_count("PumpAMM Sell", 19, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_pumpamm_sell_instruction(&instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            },
+            PumpAmmInstructionType::CreatePool => {
+                if !accept_account_count("PumpAMM CreatePool", 18, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_pumpamm_create_pool_instruction(&instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            },
+            PumpAmmInstructionType::Deposit => {
+                if !accept_account_count("PumpAMM Deposit", 15, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_pumpamm_deposit_instruction(&instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            },
+            PumpAmmInstructionType::Withdraw => {
+                if !accept_account_count("PumpAMM Withdraw", 15, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_pumpamm_withdraw_instruction(&instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            },
+            PumpAmmInstructionType::CollectCoinCreatorFee => {
+                if !accept_account_count("PumpAMM CollectCoinCreatorFee", 8, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_pumpamm_collect_coin_creator_fee_instruction(&instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            },
+            PumpAmmInstructionType::Unknown => {
+                report_unknown_discriminator("PumpAMM", program_key, instr, account_keys);
+                return None;
+            }
+        };
 
-            match decoded_result {
-                Ok(decoded) => serialized_instructions.push(decoded),
-                Err(err) => {
-                    error!("Error decoding Meteora DYN instruction: {}", err);
-                    continue;
+        match decoded_result {
+            Ok(decoded) => Some(decoded),
+            Err(err) => {
+                error!("Error decoding PumpAMM instruction: {}", err);
+                None
+            }
+        }
+    } else if program_key == &RAYDIUM_CPMM_PROGRAM {
+        let Some(instr_type) = get_raydium_cpmm_instruction_type(&instr.data) else {
+            report_unknown_discriminator("RaydiumCPMM", program_key, instr, account_keys);
+            return None;
+        };
+        
+        let decoded_result = match instr_type {
+            RaydiumCpmmInstructionType::Initialize => {
+                if !accept_account_count("Raydium CPMM Initialize", 20, slot, program_key, instr, account_keys) {
+                    return None;
                 }
+                deserialize_raydium_cpmm_initialize_instruction(&instr.data, &instr.accounts, account_keys, is_signer, is_writable)
             }
-        } else if program_key == &METEORA_AMM_V2_PROGRAM_ID {
-            let Some(instr_type) = get_meteora_amm_v2_instruction_type(&instr.data) else { continue };
-            contains_relevant_instruction = true;
-            
-            let decoded_result = match instr_type {
-                MeteoraAmmV2InstructionType::CreatePool1 |
-                MeteoraAmmV2InstructionType::CreatePool2 |
-                MeteoraAmmV2InstructionType::CreatePool3 => {
-                    if instr.accounts.len() < 12 {
-                        warn!("Meteora AMM V2 CreatePool: The instruction does not contain a minimum of 12 accounts needed.");
-                        continue;
-                    }
-                    deserialize_meteora_amm_v2_create_pool_instruction(instr_type, &instr.data, &instr.accounts, account_keys, &is_signer, &is_writable)
-                },
-                MeteoraAmmV2InstructionType::Swap => {
-                    if instr.accounts.len() < 9 {
-                        warn!("Meteora AMM V2 Swap: The instruction does not contain a minimum of 9 accounts needed.");
-                        continue;
-                    }
-                    deserialize_meteora_amm_v2_swap_instruction(&instr.data, &instr.accounts, account_keys, &is_signer, &is_writable)
-                },
-                MeteoraAmmV2InstructionType::AddLiquidity1 |
-                MeteoraAmmV2InstructionType::AddLiquidity2 |
-                MeteoraAmmV2InstructionType::AddLiquidity3 |
-                MeteoraAmmV2InstructionType::AddLiquidity4 => {
-                    if instr.accounts.len() < 13 {
-                        warn!("Meteora AMM V2 AddLiquidity: The instruction does not contain a minimum of 13 accounts needed.");
-                        continue;
-                    }
-                    deserialize_meteora_amm_v2_add_liquidity_instruction(instr_type, &instr.data, &instr.accounts, account_keys, &is_signer, &is_writable)
-                },
-                MeteoraAmmV2InstructionType::RemoveLiquidity1 |
-                MeteoraAmmV2InstructionType::RemoveLiquidity2 => {
-                    if instr.accounts.len() < 7 {
-                        warn!("Meteora AMM V2 RemoveLiquidity: The instruction does not contain a minimum of 7 accounts needed.");
-                        continue;
-                    }
-                    deserialize_meteora_amm_v2_remove_liquidity_instruction(instr_type, &instr.data, &instr.accounts, account_keys, &is_signer, &is_writable)
-                },
-                _ => {
-                    continue;
+            RaydiumCpmmInstructionType::SwapBaseInput => {
+                if !accept_account_count("Raydium CPMM SwapBaseInput", 13, slot, program_key, instr, account_keys) {
+                    return None;
                 }
-            };
+                deserialize_raydium_cpmm_swap_base_input_instruction(&instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            }
+            RaydiumCpmmInstructionType::SwapBaseOutput => {
+                if !accept_account_count("Raydium CPMM SwapBaseOutput", 13, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_raydium_cpmm_swap_base_output_instruction(&instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            }
+            RaydiumCpmmInstructionType::Deposit => {
+                if !accept_account_count("Raydium CPMM Deposit", 13, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_raydium_cpmm_deposit_instruction(&instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            }
+            RaydiumCpmmInstructionType::Withdraw => {
+                if !accept_account_count("Raydium CPMM Withdraw", 14, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_raydium_cpmm_withdraw_instruction(&instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            }
+        };
+        match decoded_result {
+            Ok(decoded) => Some(decoded),
+            Err(err) => {
+                error!("Error decoding Raydium CPMM instruction: {}", err);
+                None
+            }
+        }
+    } else if program_key == &RAYDIUM_CLMM_PROGRAM {
+        let Some(instr_type) = get_raydium_clmm_instruction_type(&instr.data) else {
+            report_unknown_discriminator("RaydiumCLMM", program_key, instr, account_keys);
+            return None;
+        };
 
-            match decoded_result {
-                Ok(decoded) => serialized_instructions.push(decoded),
-                Err(err) => {
-                    error!("Error decoding Meteora AMM V2 instruction: {}", err);
-                    continue;
+        let decoded_result = match instr_type {
+            RaydiumClmmInstructionType::CreatePool => {
+                if !accept_account_count("Raydium CLMM CreatePool", 13, slot, program_key, instr, account_keys) {
+                    return None;
                 }
+                deserialize_raydium_clmm_create_pool_instruction(&instr.data, &instr.accounts, account_keys, is_signer, is_writable)
             }
-        } else if program_key == &ORCA_WHIRLPOOL_PROGRAM_ID {
-            let Some(instr_type) = get_orca_instruction_type(&instr.data) else { continue };
-            contains_relevant_instruction = true;
-            
-            let decoded_result = match instr_type {
-                OrcaInstructionType::Swap => {
-                    if instr.accounts.len() < 11 {
-                        warn!("Orca Swap: The instruction does not contain a minimum of 11 accounts needed.");
-                        continue;
+            RaydiumClmmInstructionType::OpenPosition
+            | RaydiumClmmInstructionType::OpenPositionV2
+            | RaydiumClmmInstructionType::OpenPositionWithToken22Nft => {
+                let required = match instr_type {
+                    RaydiumClmmInstructionType::OpenPositionV2 => 22,
+                    RaydiumClmmInstructionType::OpenPositionWithToken22Nft => 20,
+                    _ => 19,
+                };
+                let context = format!("Raydium CLMM {}", instr_type.name());
+                if !accept_account_count(&context, required, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_raydium_clmm_open_position_instruction(instr_type, &instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            }
+            RaydiumClmmInstructionType::IncreaseLiquidity | RaydiumClmmInstructionType::IncreaseLiquidityV2 => {
+                let required = if instr_type == RaydiumClmmInstructionType::IncreaseLiquidityV2 { 15 } else { 12 };
+                let context = format!("Raydium CLMM {}", instr_type.name());
+                if !accept_account_count(&context, required, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_raydium_clmm_increase_liquidity_instruction(instr_type, &instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            }
+            RaydiumClmmInstructionType::DecreaseLiquidity | RaydiumClmmInstructionType::DecreaseLiquidityV2 => {
+                let required = if instr_type == RaydiumClmmInstructionType::DecreaseLiquidityV2 { 16 } else { 12 };
+                let context = format!("Raydium CLMM {}", instr_type.name());
+                if !accept_account_count(&context, required, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_raydium_clmm_decrease_liquidity_instruction(instr_type, &instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            }
+            RaydiumClmmInstructionType::Swap => {
+                if !accept_account_count("Raydium CLMM Swap", 10, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_raydium_clmm_swap_instruction("Swap", &instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            }
+            RaydiumClmmInstructionType::SwapV2 => {
+                if !accept_account_count("Raydium CLMM SwapV2", 13, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_raydium_clmm_swap_instruction("SwapV2", &instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            }
+        };
+        match decoded_result {
+            Ok(decoded) => Some(decoded),
+            Err(err) => {
+                error!("Error decoding Raydium CLMM instruction: {}", err);
+                None
+            }
+        }
+    } else if program_key == &METEORA_VCURVE_PROGRAM_ID {
+        let Some(instr_type) = get_meteora_vcurve_instruction_type(&instr.data) else {
+            report_unknown_discriminator("MeteoraVCurve", program_key, instr, account_keys);
+            return None;
+        };
+        
+        let decoded_result = match instr_type {
+            MeteoraVCurveInstructionType::InitializeVirtualPoolWithSplToken => {
+                if !accept_account_count("Meteora VCurve Initialize Virtual Pool With SPL Token", 16, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_meteora_vcurve_initialize_virtual_pool_instruction("InitializeVirtualPoolWithSplToken", &instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            }
+            MeteoraVCurveInstructionType::InitializeVirtualPoolWithToken2022 => {
+                if !accept_account_count("Meteora VCurve Initialize Virtual Pool With Token2022", 14, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_meteora_vcurve_initialize_virtual_pool_instruction("InitializeVirtualPoolWithToken2022", &instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            }
+            MeteoraVCurveInstructionType::Swap => {
+                if !accept_account_count("Meteora VCurve Swap", 15, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_meteora_vcurve_swap_instruction(&instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            }
+            MeteoraVCurveInstructionType::MigrationDammV2 => {
+                if !accept_account_count("Meteora VCurve MigrationDammV2", 25, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_meteora_vcurve_migration_instruction("MigrationDammV2", &instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            }
+            MeteoraVCurveInstructionType::MigrateMeteoraDamm => {
+                if !accept_account_count("Meteora VCurve MigrateMeteoraDamm", 29, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_meteora_vcurve_migration_instruction("MigrateMeteoraDamm", &instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            }
+        };
+        match decoded_result {
+            Ok(decoded) => Some(decoded),
+            Err(err) => {
+                error!("Error decoding Meteora VCurve instruction: {}", err);
+                None
+            }
+        }
+    } else if program_key == &METEORADYN_PROGRAM_ID {
+        let Some(instr_type) = get_meteoradyn_instruction_type(&instr.data) else {
+            report_unknown_discriminator("MeteoraDyn", program_key, instr, account_keys);
+            return None;
+        };
+        
+        let decoded_result = match instr_type {
+            MeteoraDynInstructionType::InitializePermissionlessPool => {
+                if !accept_account_count("Meteora DYN InitializePermissionlessPool", 24, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_meteoradyn_initialize_permissionless_pool_instruction(&instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            },
+            _ => {
+                return None;
+            }
+        };
+
+        match decoded_result {
+            Ok(decoded) => Some(decoded),
+            Err(err) => {
+                error!("Error decoding Meteora DYN instruction: {}", err);
+                None
+            }
+        }
+    } else if program_key == &METEORA_AMM_V2_PROGRAM_ID {
+        let Some(instr_type) = get_meteora_amm_v2_instruction_type(&instr.data) else {
+            report_unknown_discriminator("MeteoraAmmV2", program_key, instr, account_keys);
+            return None;
+        };
+        
+        let decoded_result = match instr_type {
+            MeteoraAmmV2InstructionType::CreatePool1 |
+            MeteoraAmmV2InstructionType::CreatePool2 |
+            MeteoraAmmV2InstructionType::CreatePool3 => {
+                if !accept_account_count("Meteora AMM V2 CreatePool", 12, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_meteora_amm_v2_create_pool_instruction(instr_type, &instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            },
+            MeteoraAmmV2InstructionType::Swap => {
+                if !accept_account_count("Meteora AMM V2 Swap", 9, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_meteora_amm_v2_swap_instruction(&instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            },
+            MeteoraAmmV2InstructionType::AddLiquidity => {
+                if !accept_account_count("Meteora AMM V2 AddLiquidity", 13, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_meteora_amm_v2_add_liquidity_instruction(&instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            },
+            MeteoraAmmV2InstructionType::RemoveLiquidity1 |
+            MeteoraAmmV2InstructionType::RemoveLiquidity2 => {
+                if !accept_account_count("Meteora AMM V2 RemoveLiquidity", 7, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_meteora_amm_v2_remove_liquidity_instruction(instr_type, &instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            },
+        };
+
+        match decoded_result {
+            Ok(decoded) => Some(decoded),
+            Err(err) => {
+                error!("Error decoding Meteora AMM V2 instruction: {}", err);
+                None
+            }
+        }
+    } else if program_key == &METEORA_DLMM_PROGRAM_ID {
+        let Some(instr_type) = get_meteora_dlmm_instruction_type(&instr.data) else {
+            report_unknown_discriminator("MeteoraDLMM", program_key, instr, account_keys);
+            return None;
+        };
+
+        let decoded_result = match instr_type {
+            MeteoraDlmmInstructionType::AddLiquidityByStrategy => {
+                if !accept_account_count("Meteora DLMM AddLiquidityByStrategy", 16, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_meteora_dlmm_add_liquidity_by_strategy_instruction("AddLiquidityByStrategy", false, &instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            },
+            MeteoraDlmmInstructionType::AddLiquidityByStrategy2 => {
+                if !accept_account_count("Meteora DLMM AddLiquidityByStrategy2", 14, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_meteora_dlmm_add_liquidity_by_strategy_instruction("AddLiquidityByStrategy2", true, &instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            },
+            MeteoraDlmmInstructionType::AddLiquidityByStrategyOneSide => {
+                if !accept_account_count("Meteora DLMM AddLiquidityByStrategyOneSide", 12, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_meteora_dlmm_add_liquidity_by_strategy_one_side_instruction(&instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            },
+            MeteoraDlmmInstructionType::AddLiquidityOneSide => {
+                if !accept_account_count("Meteora DLMM AddLiquidityOneSide", 12, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_meteora_dlmm_add_liquidity_one_side_instruction(&instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            },
+            MeteoraDlmmInstructionType::Swap |
+            MeteoraDlmmInstructionType::SwapExactOut => {
+                if !accept_account_count("Meteora DLMM Swap", 15, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_meteora_dlmm_swap_instruction(&instr_type, &instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            },
+            MeteoraDlmmInstructionType::Swap2 => {
+                if !accept_account_count("Meteora DLMM Swap2", 16, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_meteora_dlmm_swap_instruction(&instr_type, &instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            },
+            MeteoraDlmmInstructionType::RemoveLiquidity => {
+                if !accept_account_count("Meteora DLMM RemoveLiquidity", 16, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_meteora_dlmm_remove_liquidity_instruction(&instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            },
+        };
+
+        match decoded_result {
+            Ok(decoded) => Some(decoded),
+            Err(err) => {
+                error!("Error decoding Meteora DLMM instruction: {}", err);
+                None
+            }
+        }
+    } else if program_key == &ORCA_WHIRLPOOL_PROGRAM_ID {
+        let Some(instr_type) = get_orca_instruction_type(&instr.data) else {
+            report_unknown_discriminator("Orca", program_key, instr, account_keys);
+            return None;
+        };
+        
+        let decoded_result = match instr_type {
+            OrcaInstructionType::Swap => {
+                if !accept_account_count("Orca Swap", 11, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_orca_swap_instruction(&instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            },
+            OrcaInstructionType::SwapV2 => {
+                if !accept_account_count("Orca SwapV2", 15, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_orca_swap_v2_instruction(&instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            },
+            OrcaInstructionType::IncreaseLiquidity => {
+                if !accept_account_count("Orca IncreaseLiquidity", 11, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_orca_increase_liquidity_instruction(&instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            },
+            OrcaInstructionType::DecreaseLiquidity => {
+                if !accept_account_count("Orca DecreaseLiquidity", 11, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_orca_decrease_liquidity_instruction(&instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            },
+            OrcaInstructionType::InitializePool => {
+                if !accept_account_count("Orca InitializePool", 11, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_orca_initialize_pool_instruction(&instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            },
+            OrcaInstructionType::OpenPosition => {
+                if !accept_account_count("Orca OpenPosition", 10, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_orca_open_position_instruction(&instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            },
+            OrcaInstructionType::ClosePosition => {
+                if !accept_account_count("Orca ClosePosition", 6, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_orca_close_position_instruction(&instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            },
+            OrcaInstructionType::TwoHopSwap => {
+                if !accept_account_count("Orca TwoHopSwap", 20, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_orca_two_hop_swap_instruction(&instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            },
+            OrcaInstructionType::OpenBundledPosition => {
+                if !accept_account_count("Orca OpenBundledPosition", 8, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_orca_open_bundled_position_instruction(&instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            },
+            OrcaInstructionType::CloseBundledPosition => {
+                if !accept_account_count("Orca CloseBundledPosition", 5, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_orca_close_bundled_position_instruction(&instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            },
+            OrcaInstructionType::OpenPositionWithTokenExtensions => {
+                if !accept_account_count("Orca OpenPositionWithTokenExtensions", 10, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_orca_open_position_with_token_extensions_instruction(&instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            },
+            OrcaInstructionType::ClosePositionWithTokenExtensions => {
+                if !accept_account_count("Orca ClosePositionWithTokenExtensions", 6, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_orca_close_position_with_token_extensions_instruction(&instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            },
+            OrcaInstructionType::InitializePoolV2 => {
+                if !accept_account_count("Orca InitializePoolV2", 14, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_orca_initialize_pool_v2_instruction(&instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            },
+            OrcaInstructionType::IncreaseLiquidityV2 => {
+                if !accept_account_count("Orca IncreaseLiquidityV2", 15, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_orca_increase_liquidity_v2_instruction(&instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            },
+            OrcaInstructionType::DecreaseLiquidityV2 => {
+                if !accept_account_count("Orca DecreaseLiquidityV2", 15, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_orca_decrease_liquidity_v2_instruction(&instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            },
+            OrcaInstructionType::TwoHopSwapV2 => {
+                if !accept_account_count("Orca TwoHopSwapV2", 24, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_orca_two_hop_swap_v2_instruction(&instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            },
+            OrcaInstructionType::CollectFees => {
+                if !accept_account_count("Orca CollectFees", 9, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_orca_collect_fees_instruction(&instr_type, &instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            },
+            OrcaInstructionType::CollectFeesV2 => {
+                if !accept_account_count("Orca CollectFeesV2", 13, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_orca_collect_fees_instruction(&instr_type, &instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            },
+            OrcaInstructionType::CollectProtocolFees => {
+                if !accept_account_count("Orca CollectProtocolFees", 8, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_orca_collect_fees_instruction(&instr_type, &instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            },
+            OrcaInstructionType::CollectProtocolFeesV2 => {
+                if !accept_account_count("Orca CollectProtocolFeesV2", 12, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_orca_collect_fees_instruction(&instr_type, &instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            },
+            OrcaInstructionType::CollectReward => {
+                if !accept_account_count("Orca CollectReward", 7, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_orca_collect_reward_instruction(&instr_type, &instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            },
+            OrcaInstructionType::CollectRewardV2 => {
+                if !accept_account_count("Orca CollectRewardV2", 9, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_orca_collect_reward_instruction(&instr_type, &instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            },
+        };
+
+        match decoded_result {
+            Ok(decoded) => Some(decoded),
+            Err(err) => {
+                error!("Error decoding Orca instruction: {}", err);
+                None
+            }
+        }
+    } else if program_key == &DRIFT_PROGRAM_ID {
+        let Some(instr_type) = get_drift_instruction_type(&instr.data) else {
+            report_unknown_discriminator("Drift", program_key, instr, account_keys);
+            return None;
+        };
+
+        let decoded_result = match instr_type {
+            DriftInstructionType::PlacePerpOrder => {
+                if !accept_account_count("Drift PlacePerpOrder", 3, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_drift_order_instruction(&instr_type, &instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            },
+            DriftInstructionType::PlaceAndTakePerpOrder => {
+                if !accept_account_count("Drift PlaceAndTakePerpOrder", 4, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_drift_order_instruction(&instr_type, &instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            },
+            DriftInstructionType::PlaceSpotOrder => {
+                if !accept_account_count("Drift PlaceSpotOrder", 3, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_drift_order_instruction(&instr_type, &instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            },
+            DriftInstructionType::SettlePnl => {
+                if !accept_account_count("Drift SettlePnl", 4, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_drift_settle_instruction(&instr_type, &instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            },
+            DriftInstructionType::SettleMultiplePnls => {
+                if !accept_account_count("Drift SettleMultiplePnls", 4, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_drift_settle_instruction(&instr_type, &instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            },
+            DriftInstructionType::SettleFundingPayment => {
+                if !accept_account_count("Drift SettleFundingPayment", 2, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_drift_settle_instruction(&instr_type, &instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            },
+        };
+
+        match decoded_result {
+            Ok(decoded) => Some(decoded),
+            Err(err) => {
+                error!("Error decoding Drift instruction: {}", err);
+                None
+            }
+        }
+    } else if program_key == &KAMINO_LEND_PROGRAM_ID {
+        let Some(instr_type) = get_kamino_lend_instruction_type(&instr.data) else {
+            report_unknown_discriminator("KaminoLend", program_key, instr, account_keys);
+            return None;
+        };
+
+        let decoded_result = match instr_type {
+            KaminoLendInstructionType::Deposit => {
+                if !accept_account_count("KaminoLend Deposit", 14, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_kamino_lend_liquidity_instruction("Deposit", &instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            },
+            KaminoLendInstructionType::Borrow => {
+                if !accept_account_count("KaminoLend Borrow", 12, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_kamino_lend_liquidity_instruction("Borrow", &instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            },
+            KaminoLendInstructionType::Repay => {
+                if !accept_account_count("KaminoLend Repay", 9, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_kamino_lend_liquidity_instruction("Repay", &instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            },
+            KaminoLendInstructionType::Liquidate => {
+                if !accept_account_count("KaminoLend Liquidate", 20, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_kamino_lend_liquidate_instruction("Liquidate", &instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            },
+            KaminoLendInstructionType::DepositV2 => {
+                if !accept_account_count("KaminoLend DepositV2", 17, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_kamino_lend_liquidity_instruction("DepositV2", &instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            },
+            KaminoLendInstructionType::BorrowV2 => {
+                if !accept_account_count("KaminoLend BorrowV2", 15, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_kamino_lend_liquidity_instruction("BorrowV2", &instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            },
+            KaminoLendInstructionType::RepayV2 => {
+                if !accept_account_count("KaminoLend RepayV2", 12, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_kamino_lend_liquidity_instruction("RepayV2", &instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            },
+            KaminoLendInstructionType::LiquidateV2 => {
+                if !accept_account_count("KaminoLend LiquidateV2", 25, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_kamino_lend_liquidate_instruction("LiquidateV2", &instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            },
+        };
+
+        match decoded_result {
+            Ok(decoded) => Some(decoded),
+            Err(err) => {
+                error!("Error decoding Kamino Lend instruction: {}", err);
+                None
+            }
+        }
+    } else if program_key == &METAPLEX_TOKEN_METADATA_PROGRAM_ID {
+        let Some(instr_type) = get_metaplex_metadata_instruction_type(&instr.data) else {
+            report_unknown_discriminator("MetaplexMetadata", program_key, instr, account_keys);
+            return None;
+        };
+
+        let decoded_result = match instr_type {
+            MetaplexMetadataInstructionType::CreateMetadataAccountV3 => {
+                if !accept_account_count("Metaplex CreateMetadataAccountV3", 6, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_metaplex_create_metadata_account_v3_instruction(&instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            },
+            MetaplexMetadataInstructionType::CreateV1 => {
+                if !accept_account_count("Metaplex CreateV1", 9, slot, program_key, instr, account_keys) {
+                    return None;
+                }
+                deserialize_metaplex_create_v1_instruction(&instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+            },
+        };
+
+        match decoded_result {
+            Ok(decoded) => Some(decoded),
+            Err(err) => {
+                error!("Error decoding Metaplex Token Metadata instruction: {}", err);
+                None
+            }
+        }
+    } else if is_lookup_table_program(program_key) {
+        let Some(instr_type) = get_lookup_table_instruction_type(&instr.data) else {
+            report_unknown_discriminator("AddressLookupTable", program_key, instr, account_keys);
+            return None;
+        };
+
+        let required = match instr_type {
+            LookupTableInstructionType::CreateLookupTable => 4,
+            LookupTableInstructionType::CloseLookupTable => 3,
+            _ => 2,
+        };
+        if !accept_account_count(&format!("AddressLookupTable {}", instr_type.name()), required, slot, program_key, instr, account_keys) {
+            return None;
+        }
+
+        match deserialize_lookup_table_instruction(&instr_type, &instr.data, &instr.accounts, account_keys, is_signer, is_writable) {
+            Ok(decoded) => Some(decoded),
+            Err(err) => {
+                error!("Error decoding Address Lookup Table instruction: {}", err);
+                None
+            }
+        }
+    } else if is_token_program(program_key) && is_token_decoding_enabled() {
+        // Both token programs share the original instructions, Token-2022 adds its extensions.
+        // Account setup, approvals, closes and other extensions are not decoded and not reported.
+        let decoded_result = if let Some(instr_type) = get_spl_token_instruction_type(&instr.data) {
+            match instr_type {
+                SplTokenInstructionType::Transfer => {
+                    if !accept_account_count("Token Transfer", 3, slot, program_key, instr, account_keys) {
+                        return None;
                     }
-                    deserialize_orca_swap_instruction(&instr.data, &instr.accounts, account_keys, &is_signer, &is_writable)
+                    deserialize_spl_token_amount_instruction(program_key, "Transfer", &instr.data, &instr.accounts, account_keys, is_signer, is_writable)
                 },
-                OrcaInstructionType::SwapV2 => {
-                    if instr.accounts.len() < 15 {
-                        warn!("Orca SwapV2: The instruction does not contain a minimum of 15 accounts needed.");
-                        continue;
+                SplTokenInstructionType::MintTo => {
+                    if !accept_account_count("Token MintTo", 3, slot, program_key, instr, account_keys) {
+                        return None;
                     }
-                    deserialize_orca_swap_v2_instruction(&instr.data, &instr.accounts, account_keys, &is_signer, &is_writable)
+                    deserialize_spl_token_amount_instruction(program_key, "MintTo", &instr.data, &instr.accounts, account_keys, is_signer, is_writable)
                 },
-                OrcaInstructionType::IncreaseLiquidity => {
-                    if instr.accounts.len() < 11 {
-                        warn!("Orca IncreaseLiquidity: The instruction does not contain a minimum of 11 accounts needed.");
-                        continue;
+                SplTokenInstructionType::Burn => {
+                    if !accept_account_count("Token Burn", 3, slot, program_key, instr, account_keys) {
+                        return None;
                     }
-                    deserialize_orca_increase_liquidity_instruction(&instr.data, &instr.accounts, account_keys, &is_signer, &is_writable)
+                    deserialize_spl_token_amount_instruction(program_key, "Burn", &instr.data, &instr.accounts, account_keys, is_signer, is_writable)
                 },
-                OrcaInstructionType::DecreaseLiquidity => {
-                    if instr.accounts.len() < 11 {
-                        warn!("Orca DecreaseLiquidity: The instruction does not contain a minimum of 11 accounts needed.");
-                        continue;
+                SplTokenInstructionType::TransferChecked => {
+                    if !accept_account_count("Token TransferChecked", 4, slot, program_key, instr, account_keys) {
+                        return None;
                     }
-                    deserialize_orca_decrease_liquidity_instruction(&instr.data, &instr.accounts, account_keys, &is_signer, &is_writable)
+                    deserialize_spl_token_transfer_checked_instruction(program_key, &instr.data, &instr.accounts, account_keys, is_signer, is_writable)
                 },
-                OrcaInstructionType::InitializePool => {
-                    if instr.accounts.len() < 11 {
-                        warn!("Orca InitializePool: The instruction does not contain a minimum of 11 accounts needed.");
-                        continue;
+                SplTokenInstructionType::InitializeMint => {
+                    if !accept_account_count("Token InitializeMint", 2, slot, program_key, instr, account_keys) {
+                        return None;
                     }
-                    deserialize_orca_initialize_pool_instruction(&instr.data, &instr.accounts, account_keys, &is_signer, &is_writable)
+                    deserialize_spl_token_initialize_mint_instruction(program_key, "InitializeMint", &instr.data, &instr.accounts, account_keys, is_signer, is_writable)
                 },
-                OrcaInstructionType::OpenPosition => {
-                    if instr.accounts.len() < 10 {
-                        warn!("Orca OpenPosition: The instruction does not contain a minimum of 10 accounts needed.");
-                        continue;
+                SplTokenInstructionType::InitializeMint2 => {
+                    if !accept_account_count("Token InitializeMint2", 1, slot, program_key, instr, account_keys) {
+                        return None;
                     }
-                    deserialize_orca_open_position_instruction(&instr.data, &instr.accounts, account_keys, &is_signer, &is_writable)
+                    deserialize_spl_token_initialize_mint_instruction(program_key, "InitializeMint2", &instr.data, &instr.accounts, account_keys, is_signer, is_writable)
                 },
-                OrcaInstructionType::ClosePosition => {
-                    if instr.accounts.len() < 6 {
-                        warn!("Orca ClosePosition: The instruction does not contain a minimum of 6 accounts needed.");
-                        continue;
+            }
+        } else if program_key == &SPL_TOKEN_2022_PROGRAM_ID {
+            let instr_type = get_token_2022_extension_instruction_type(&instr.data)?;
+            match instr_type {
+                Token2022ExtensionInstructionType::InitializeTransferFeeConfig |
+                Token2022ExtensionInstructionType::SetTransferFee => {
+                    if !accept_account_count("Token2022 TransferFeeConfig", 1, slot, program_key, instr, account_keys) {
+                        return None;
                     }
-                    deserialize_orca_close_position_instruction(&instr.data, &instr.accounts, account_keys, &is_signer, &is_writable)
+                    deserialize_token_2022_transfer_fee_config_instruction(&instr_type, &instr.data, &instr.accounts, account_keys, is_signer, is_writable)
                 },
-                OrcaInstructionType::TwoHopSwap => {
-                    if instr.accounts.len() < 20 {
-                        warn!("Orca TwoHopSwap: The instruction does not contain a minimum of 20 accounts needed.");
-                        continue;
+                Token2022ExtensionInstructionType::TransferCheckedWithFee => {
+                    if !accept_account_count("Token2022 TransferCheckedWithFee", 4, slot, program_key, instr, account_keys) {
+                        return None;
                     }
-                    deserialize_orca_two_hop_swap_instruction(&instr.data, &instr.accounts, account_keys, &is_signer, &is_writable)
+                    deserialize_token_2022_transfer_checked_with_fee_instruction(&instr.data, &instr.accounts, account_keys, is_signer, is_writable)
                 },
-            };
+                Token2022ExtensionInstructionType::WithdrawWithheldTokensFromMint |
+                Token2022ExtensionInstructionType::WithdrawWithheldTokensFromAccounts |
+                Token2022ExtensionInstructionType::HarvestWithheldTokensToMint => {
+                    if !accept_account_count("Token2022 WithheldTokens", 1, slot, program_key, instr, account_keys) {
+                        return None;
+                    }
+                    deserialize_token_2022_withheld_tokens_instruction(&instr_type, &instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+                },
+                Token2022ExtensionInstructionType::InitializeTransferHook |
+                Token2022ExtensionInstructionType::UpdateTransferHook |
+                Token2022ExtensionInstructionType::InitializeMetadataPointer => {
+                    if !accept_account_count("Token2022 Pointer", 1, slot, program_key, instr, account_keys) {
+                        return None;
+                    }
+                    deserialize_token_2022_pointer_instruction(&instr_type, &instr.data, &instr.accounts, account_keys, is_signer, is_writable)
+                },
+            }
+        } else {
+            return None;
+        };
 
-            match decoded_result {
-                Ok(decoded) => serialized_instructions.push(decoded),
-                Err(err) => {
-                    error!("Error decoding Orca instruction: {}", err);
-                    continue;
-                }
+        match decoded_result {
+            Ok(decoded) => Some(decoded),
+            Err(err) => {
+                error!("Error decoding token instruction: {}", err);
+                None
             }
         }
-    }
-
-    if !contains_relevant_instruction || serialized_instructions.is_empty() {
-        return None;
-    }
-
-    let signatures: Vec<String> = transaction
-        .signatures
-        .iter()
-        .map(|signature| bs58::encode(signature).into_string())
-        .collect();
+    } else if let Some(program) = find_idl_program(program_key) {
+        let Some(instruction) = program.find_instruction(&instr.data) else {
+            report_unknown_discriminator(&program.protocol, program_key, instr, account_keys);
+            return None;
+        };
+        let context = format!("{} {}", program.protocol, instruction.name);
+        if !accept_account_count(&context, instruction.accounts.len(), slot, program_key, instr, account_keys) {
+            return None;
+        }
 
-    Some(object! {
-        "signatures" => signatures,
-        "slot" => slot,
-        "message" => object! {
-            "header" => object! {
-                "numRequiredSignatures" => header.num_required_signatures,
-                "numReadonlySignedAccounts" => header.num_readonly_signed_accounts,
-                "numReadonlyUnsignedAccounts" => header.num_readonly_unsigned_accounts,
-            },
-            "recentBlockhash" => bs58::encode(recent_blockhash).into_string(),
-            "instructions" => serialized_instructions,
+        match deserialize_idl_instruction(program, instruction, &instr.data, &instr.accounts, account_keys, is_signer, is_writable) {
+            Ok(decoded) => Some(decoded),
+            Err(err) => {
+                error!("Error decoding IDL instruction: {}", err);
+                None
+            }
         }
-    })
-}
\ No newline at end of file
+    } else if is_raw_program(program_key) {
+        Some(deserialize_raw_instruction(program_key, &instr.data, &instr.accounts, account_keys, is_signer, is_writable))
+    } else {
+        None
+    }
+}
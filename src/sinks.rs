@@ -0,0 +1,948 @@
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use json::JsonValue;
+use once_cell::sync::OnceCell;
+use prost::Message;
+use rustc_hash::{FxHashMap as HashMap, FxHashSet as HashSet};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::mpsc;
+use tracing::{debug, error, info, warn};
+
+use crate::archive::{ArchiveError, ArchiveWriter, ARCHIVE_EXTENSION};
+use crate::encoder::{EncodedPayload, OutputEncoding};
+use crate::operational_events::{publish_operational_event, Severity, SINK_DELIVERY_FAILED_EVENT, SINK_DROPPED_EVENT};
+use crate::output_message::OutputMessage;
+use crate::segment_uploader::announce_closed_segment;
+
+/// Name of the built-in sink backed by the gRPC broadcast channel
+pub const GRPC_SINK: &str = "grpc";
+
+/// A destination for output messages other than the gRPC stream
+pub trait Sink: Send + Sync {
+    fn name(&self) -> &str;
+
+    /// Must not block, sinks hand the message over to their own worker
+    fn deliver(&self, message: &Arc<OutputMessage>);
+}
+
+/// Queue in front of a sink worker, full queues drop messages instead of stalling the decoder
+struct QueuedSink {
+    name: String,
+    queue: mpsc::Sender<Arc<OutputMessage>>,
+}
+
+impl Sink for QueuedSink {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn deliver(&self, message: &Arc<OutputMessage>) {
+        if let Err(e) = self.queue.try_send(message.clone()) {
+            warn!("Sink '{}' dropped a message: {}", self.name, e);
+            let reason = match e {
+                mpsc::error::TrySendError::Full(_) => "queue_full",
+                mpsc::error::TrySendError::Closed(_) => "disconnected",
+            };
+            publish_operational_event(
+                SINK_DROPPED_EVENT,
+                Severity::Warning,
+                format!("Sink '{}' dropped a message: {}", self.name, reason),
+                json::object! { "sink" => self.name.clone(), "reason" => reason, "slot" => message.slot },
+            );
+        }
+    }
+}
+
+/// Payload bytes for sinks carrying binary records, typed output is the protobuf TypedPayload
+#[cfg_attr(not(any(feature = "kafka", feature = "nats")), allow(dead_code))]
+fn record_payload(message: &OutputMessage, encoding: OutputEncoding) -> Vec<u8> {
+    match message.encoded(encoding) {
+        EncodedPayload::Text(text) => text.clone().into_bytes(),
+        EncodedPayload::Binary(bytes) => bytes.clone(),
+        EncodedPayload::Typed(typed) => typed.encode_to_vec(),
+    }
+}
+
+fn text_payload(message: &OutputMessage, encoding: OutputEncoding) -> String {
+    match message.encoded(encoding) {
+        EncodedPayload::Text(text) => text.clone(),
+        // Binary and typed are not meaningful for text sinks, compact JSON is used instead
+        EncodedPayload::Binary(_) | EncodedPayload::Typed(_) => message.payload.dump(),
+    }
+}
+
+/// Longest delay between two webhook delivery attempts
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Retries of a webhook delivery, the delay doubles after each failed attempt up to MAX_BACKOFF
+#[derive(Debug, Clone, Copy)]
+struct RetryPolicy {
+    retries: u32,
+    initial_backoff: Duration,
+}
+
+impl RetryPolicy {
+    fn from_config(config: &JsonValue) -> Self {
+        Self {
+            retries: config["retries"].as_u32().unwrap_or(3),
+            initial_backoff: Duration::from_millis(config["backoff_ms"].as_u64().unwrap_or(500)),
+        }
+    }
+}
+
+/// POSTs every message as JSON to a URL. Connection errors, 429 and 5xx responses are retried
+/// with exponential backoff, other responses are final.
+fn spawn_webhook_sink(name: &str, url: String, encoding: OutputEncoding, retry: RetryPolicy, queue_size: usize) -> QueuedSink {
+    let (queue, mut rx) = mpsc::channel::<Arc<OutputMessage>>(queue_size);
+    let sink_name = name.to_string();
+    tokio::spawn(async move {
+        let client = reqwest::Client::new();
+        while let Some(message) = rx.recv().await {
+            let body = text_payload(&message, encoding);
+            let mut backoff = retry.initial_backoff;
+            for attempt in 0..=retry.retries {
+                if attempt > 0 {
+                    tokio::time::sleep(backoff).await;
+                    backoff = backoff.saturating_mul(2).min(MAX_BACKOFF);
+                }
+                let result = client
+                    .post(&url)
+                    .header("content-type", "application/json")
+                    .body(body.clone())
+                    .send()
+                    .await;
+                let retriable = match &result {
+                    Ok(response) => response.status().is_server_error() || response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS,
+                    Err(_) => true,
+                };
+                if retriable && attempt < retry.retries {
+                    debug!("Webhook sink '{}' retries delivery to {} ({} of {})", sink_name, url, attempt + 1, retry.retries);
+                    continue;
+                }
+                match result {
+                    Ok(response) if !response.status().is_success() => {
+                        warn!("Webhook sink '{}' got HTTP {} from {}", sink_name, response.status(), url);
+                        publish_operational_event(
+                            SINK_DELIVERY_FAILED_EVENT,
+                            Severity::Warning,
+                            format!("Webhook sink '{}' got HTTP {}", sink_name, response.status()),
+                            json::object! { "sink" => sink_name.clone(), "status" => response.status().as_u16(), "attempts" => attempt + 1 },
+                        );
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        error!("Webhook sink '{}' failed to post to {}: {}", sink_name, url, e);
+                        publish_operational_event(
+                            SINK_DELIVERY_FAILED_EVENT,
+                            Severity::Error,
+                            format!("Webhook sink '{}' failed to post: {}", sink_name, e),
+                            json::object! { "sink" => sink_name.clone(), "attempts" => attempt + 1 },
+                        );
+                    }
+                }
+                break;
+            }
+        }
+    });
+    QueuedSink { name: name.to_string(), queue }
+}
+
+/// Longest time a line written by a file sink stays in its buffer
+const FILE_SINK_FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// When the file of a file sink is closed and a new one started, unset limits never rotate
+#[derive(Debug, Clone, Copy, Default)]
+struct FileRotation {
+    max_bytes: Option<u64>,
+    max_age: Option<Duration>,
+    gzip: bool,
+}
+
+impl FileRotation {
+    fn from_config(config: &JsonValue) -> Self {
+        Self {
+            max_bytes: config["rotate_bytes"].as_u64().filter(|bytes| *bytes > 0),
+            max_age: config["rotate_seconds"].as_u64().filter(|seconds| *seconds > 0).map(Duration::from_secs),
+            gzip: config["gzip"].as_bool().unwrap_or(false),
+        }
+    }
+
+    fn is_due(&self, written: u64, opened_at: Instant) -> bool {
+        self.max_bytes.is_some_and(|max_bytes| written >= max_bytes)
+            || self.max_age.is_some_and(|max_age| opened_at.elapsed() >= max_age)
+    }
+}
+
+/// Name of a closed segment, `out.ndjson` becomes `out.<unix milliseconds>.ndjson` next to it
+fn segment_path(path: &Path) -> PathBuf {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).expect("Time went backwards").as_millis();
+    let stem = path.file_stem().and_then(|stem| stem.to_str()).unwrap_or("segment");
+    let name = match path.extension().and_then(|extension| extension.to_str()) {
+        Some(extension) => format!("{}.{}.{}", stem, now, extension),
+        None => format!("{}.{}", stem, now),
+    };
+    path.with_file_name(name)
+}
+
+/// Compresses a closed segment to `<segment>.gz` and removes the original
+fn gzip_segment(segment: &Path) -> std::io::Result<PathBuf> {
+    let mut compressed_name = segment.as_os_str().to_owned();
+    compressed_name.push(".gz");
+    let compressed = PathBuf::from(compressed_name);
+    let mut input = std::fs::File::open(segment)?;
+    let mut encoder = GzEncoder::new(std::fs::File::create(&compressed)?, Compression::default());
+    std::io::copy(&mut input, &mut encoder)?;
+    encoder.finish()?;
+    std::fs::remove_file(segment)?;
+    Ok(compressed)
+}
+
+fn open_append(path: &str) -> std::io::Result<(std::io::BufWriter<std::fs::File>, u64)> {
+    let file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+    let written = file.metadata()?.len();
+    Ok((std::io::BufWriter::new(file), written))
+}
+
+/// Appends every message as one NDJSON line, readable by shredstream-reader. With rotation the
+/// file is moved aside once it is large or old enough, closed segments optionally gzipped.
+/// Age is checked when a message arrives, an idle sink keeps its file open. Lines are buffered,
+/// flushed at most FILE_SINK_FLUSH_INTERVAL after being written, on rotation and when the sink stops.
+fn spawn_file_sink(name: &str, path: String, rotation: FileRotation, queue_size: usize) -> std::io::Result<QueuedSink> {
+    let (mut writer, mut written) = open_append(&path)?;
+    let (queue, mut rx) = mpsc::channel::<Arc<OutputMessage>>(queue_size);
+    let sink_name = name.to_string();
+    let runtime = tokio::runtime::Handle::current();
+    tokio::task::spawn_blocking(move || {
+        let report_failure = |e: std::io::Error| {
+            error!("File sink '{}' failed to write to {}: {}", sink_name, path, e);
+            publish_operational_event(
+                SINK_DELIVERY_FAILED_EVENT,
+                Severity::Error,
+                format!("File sink '{}' failed to write: {}", sink_name, e),
+                json::object! { "sink" => sink_name.clone(), "path" => path.clone() },
+            );
+        };
+        let mut opened_at = Instant::now();
+        let mut flushed_at = Instant::now();
+        let mut unflushed = false;
+
+        loop {
+            let message = match runtime.block_on(tokio::time::timeout(FILE_SINK_FLUSH_INTERVAL, rx.recv())) {
+                Ok(Some(message)) => message,
+                Ok(None) => break,
+                // Idle, the lines of the last burst go to disk
+                Err(_) => {
+                    if unflushed {
+                        if let Err(e) = writer.flush() {
+                            report_failure(e);
+                        }
+                        unflushed = false;
+                        flushed_at = Instant::now();
+                    }
+                    continue;
+                }
+            };
+            if written > 0 && rotation.is_due(written, opened_at) {
+                let segment = segment_path(Path::new(&path));
+                let rotated = writer.flush().and_then(|_| std::fs::rename(&path, &segment)).and_then(|_| open_append(&path));
+                match rotated {
+                    Ok((new_writer, new_written)) => {
+                        writer = new_writer;
+                        written = new_written;
+                        opened_at = Instant::now();
+                        unflushed = false;
+                        flushed_at = Instant::now();
+                        debug!("File sink '{}' closed segment {}", sink_name, segment.display());
+                        if rotation.gzip {
+                            let sink_name = sink_name.clone();
+                            // Compression runs aside, the decoder output does not wait for it
+                            std::thread::spawn(move || match gzip_segment(&segment) {
+                                Ok(compressed) => announce_closed_segment(compressed),
+                                Err(e) => error!("File sink '{}' failed to gzip {}: {}", sink_name, segment.display(), e),
+                            });
+                        } else {
+                            announce_closed_segment(segment);
+                        }
+                    }
+                    Err(e) => report_failure(e),
+                }
+            }
+
+            let line = json::object! {
+                "transaction_json" => message.payload.dump(),
+                "timestamp" => message.timestamp,
+            }
+            .dump();
+            match writeln!(writer, "{}", line) {
+                Ok(()) => {
+                    written += line.len() as u64 + 1;
+                    unflushed = true;
+                }
+                Err(e) => report_failure(e),
+            }
+            // A busy sink never idles, it is flushed on the interval instead
+            if unflushed && flushed_at.elapsed() >= FILE_SINK_FLUSH_INTERVAL {
+                if let Err(e) = writer.flush() {
+                    report_failure(e);
+                }
+                unflushed = false;
+                flushed_at = Instant::now();
+            }
+        }
+        if let Err(e) = writer.flush() {
+            report_failure(e);
+        }
+    });
+    Ok(QueuedSink { name: name.to_string(), queue })
+}
+
+/// Writes messages to indexed `.ssda` archives in a directory, one archive per `rotate_slots` slots
+/// named after its first slot. Each archive gets its index when the next one is started.
+fn spawn_archive_sink(name: &str, directory: String, rotate_slots: u64, queue_size: usize) -> std::io::Result<QueuedSink> {
+    std::fs::create_dir_all(&directory)?;
+    let (queue, mut rx) = mpsc::channel::<Arc<OutputMessage>>(queue_size);
+    let sink_name = name.to_string();
+    tokio::task::spawn_blocking(move || {
+        let mut current: Option<(u64, ArchiveWriter)> = None;
+        let report_failure = |e: ArchiveError| {
+            error!("Archive sink '{}' failed to write to {}: {}", sink_name, directory, e);
+            publish_operational_event(
+                SINK_DELIVERY_FAILED_EVENT,
+                Severity::Error,
+                format!("Archive sink '{}' failed to write: {}", sink_name, e),
+                json::object! { "sink" => sink_name.clone(), "path" => directory.clone() },
+            );
+        };
+
+        while let Some(message) = rx.blocking_recv() {
+            let rotate = current
+                .as_ref()
+                .is_some_and(|(first_slot, _)| message.slot >= first_slot.saturating_add(rotate_slots));
+            if rotate {
+                if let Some((_, writer)) = current.take() {
+                    if let Err(e) = writer.finish() {
+                        report_failure(e);
+                    }
+                }
+            }
+            if current.is_none() {
+                let path = Path::new(&directory).join(format!("{}.{}", message.slot, ARCHIVE_EXTENSION));
+                match ArchiveWriter::create(&path) {
+                    Ok(writer) => current = Some((message.slot, writer)),
+                    Err(e) => {
+                        report_failure(e);
+                        continue;
+                    }
+                }
+            }
+            if let Some((_, writer)) = current.as_mut() {
+                if let Err(e) = writer.append(&message) {
+                    report_failure(e);
+                }
+            }
+        }
+        if let Some((_, writer)) = current {
+            if let Err(e) = writer.finish() {
+                report_failure(e);
+            }
+        }
+    });
+    Ok(QueuedSink { name: name.to_string(), queue })
+}
+
+/// Writes the decoded instructions to Parquet files in a directory, one file per `rotate_seconds`
+/// of output time (hourly by default) named after its start and first slot. A file is finished
+/// once a message of the next period arrives, or when the sink stops.
+#[cfg(feature = "parquet")]
+fn spawn_parquet_sink(name: &str, directory: String, rotate_seconds: u64, queue_size: usize) -> std::io::Result<QueuedSink> {
+    use crate::parquet_archive::{ParquetArchiveError, ParquetSegmentWriter, PARQUET_EXTENSION};
+
+    std::fs::create_dir_all(&directory)?;
+    let (queue, mut rx) = mpsc::channel::<Arc<OutputMessage>>(queue_size);
+    let sink_name = name.to_string();
+    let period_micros = rotate_seconds * 1_000_000;
+    tokio::task::spawn_blocking(move || {
+        let mut current: Option<(u64, ParquetSegmentWriter)> = None;
+        let report_failure = |e: ParquetArchiveError| {
+            error!("Parquet sink '{}' failed to write to {}: {}", sink_name, directory, e);
+            publish_operational_event(
+                SINK_DELIVERY_FAILED_EVENT,
+                Severity::Error,
+                format!("Parquet sink '{}' failed to write: {}", sink_name, e),
+                json::object! { "sink" => sink_name.clone(), "path" => directory.clone() },
+            );
+        };
+
+        while let Some(message) = rx.blocking_recv() {
+            let period = message.timestamp / period_micros;
+            if current.as_ref().is_some_and(|(current_period, _)| *current_period != period) {
+                if let Some((_, writer)) = current.take() {
+                    match writer.finish() {
+                        Ok(path) => {
+                            debug!("Parquet sink '{}' finished {}", sink_name, path.display());
+                            announce_closed_segment(path);
+                        }
+                        Err(e) => report_failure(e),
+                    }
+                }
+            }
+            if current.is_none() {
+                let started = chrono::DateTime::from_timestamp_micros((period * period_micros) as i64).unwrap_or_default();
+                let file_name = format!("{}.{}.{}", started.format("%Y%m%dT%H%M%S"), message.slot, PARQUET_EXTENSION);
+                match ParquetSegmentWriter::create(&Path::new(&directory).join(file_name)) {
+                    Ok(writer) => current = Some((period, writer)),
+                    Err(e) => {
+                        report_failure(e);
+                        continue;
+                    }
+                }
+            }
+            if let Some((_, writer)) = current.as_mut() {
+                if let Err(e) = writer.append(&message) {
+                    report_failure(e);
+                }
+            }
+        }
+        if let Some((_, writer)) = current {
+            match writer.finish() {
+                Ok(path) => announce_closed_segment(path),
+                Err(e) => report_failure(e),
+            }
+        }
+    });
+    Ok(QueuedSink { name: name.to_string(), queue })
+}
+
+#[cfg(not(feature = "parquet"))]
+fn spawn_parquet_sink(name: &str, _directory: String, _rotate_seconds: u64, _queue_size: usize) -> std::io::Result<QueuedSink> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        format!("Parquet sink '{}' needs a build with the parquet feature", name),
+    ))
+}
+
+/// Upserts transactions, their instructions, normalized swaps and token launches into Postgres,
+/// creating the tables on start. Messages are written in batches of up to `batch_size`, one
+/// database transaction each, a failed batch is reported and not retried.
+#[cfg(feature = "postgres")]
+fn spawn_postgres_sink(name: &str, url: String, batch_size: usize, queue_size: usize) -> Result<QueuedSink, String> {
+    let (queue, mut rx) = mpsc::channel::<Arc<OutputMessage>>(queue_size);
+    let sink_name = name.to_string();
+    tokio::spawn(async move {
+        let report_failure = |reason: String| {
+            error!("Postgres sink '{}' failed: {}", sink_name, reason);
+            publish_operational_event(
+                SINK_DELIVERY_FAILED_EVENT,
+                Severity::Error,
+                format!("Postgres sink '{}' failed: {}", sink_name, reason),
+                json::object! { "sink" => sink_name.clone() },
+            );
+        };
+        let pool = match crate::postgres_sink::connect(&url, 4).await {
+            Ok(pool) => pool,
+            Err(e) => {
+                report_failure(e);
+                return;
+            }
+        };
+        info!("Postgres sink '{}' connected, schema up to date", sink_name);
+
+        let mut batch = Vec::with_capacity(batch_size);
+        // Whatever queued up while the previous batch was written goes into the next one
+        while rx.recv_many(&mut batch, batch_size).await > 0 {
+            if let Err(e) = crate::postgres_sink::write_messages(&pool, &batch).await {
+                report_failure(format!("{} messages not written: {}", batch.len(), e));
+            }
+            batch.clear();
+        }
+    });
+    Ok(QueuedSink { name: name.to_string(), queue })
+}
+
+#[cfg(not(feature = "postgres"))]
+fn spawn_postgres_sink(name: &str, _url: String, _batch_size: usize, _queue_size: usize) -> Result<QueuedSink, String> {
+    Err(format!("Postgres sink '{}' needs a build with the postgres feature", name))
+}
+
+/// How Kafka records are keyed, which decides the partition and the ordering guarantees
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum KafkaKey {
+    Slot,
+    /// First mint of the decoded instructions other than wrapped SOL, the slot when there is none
+    Mint,
+    None,
+}
+
+impl KafkaKey {
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "slot" => Some(KafkaKey::Slot),
+            "mint" => Some(KafkaKey::Mint),
+            "none" => Some(KafkaKey::None),
+            _ => None,
+        }
+    }
+
+    #[cfg_attr(not(feature = "kafka"), allow(dead_code))]
+    fn of(&self, message: &OutputMessage) -> Option<String> {
+        match self {
+            KafkaKey::Slot => Some(message.slot.to_string()),
+            KafkaKey::Mint => Some(first_mint(&message.payload).unwrap_or_else(|| message.slot.to_string())),
+            KafkaKey::None => None,
+        }
+    }
+}
+
+const WRAPPED_SOL_MINT: &str = "So11111111111111111111111111111111111111112";
+
+/// First account named like a mint (mint, baseMint, token_a_mint, ...) of a transaction or batch payload
+#[cfg_attr(not(feature = "kafka"), allow(dead_code))]
+fn first_mint(payload: &JsonValue) -> Option<String> {
+    let transactions: Vec<&JsonValue> = if payload["transactions"].is_array() {
+        payload["transactions"].members().collect()
+    } else {
+        vec![payload]
+    };
+    transactions
+        .iter()
+        .flat_map(|transaction| transaction["message"]["instructions"].members())
+        .flat_map(|instruction| instruction["accounts"].members())
+        .filter(|account| account["name"].as_str().is_some_and(|name| name.to_ascii_lowercase().ends_with("mint")))
+        // Batch payloads reference accounts through the shared account-key dictionary
+        .filter_map(|account| {
+            account["pubkey"]
+                .as_str()
+                .or_else(|| account["key"].as_usize().and_then(|key| payload["accountKeys"][key].as_str()))
+        })
+        .find(|mint| *mint != WRAPPED_SOL_MINT && *mint != "unknown")
+        .map(str::to_string)
+}
+
+#[cfg(feature = "kafka")]
+fn report_kafka_failure(sink_name: &str, topic: &str, reason: String) {
+    error!("Kafka sink '{}' failed to produce to {}: {}", sink_name, topic, reason);
+    publish_operational_event(
+        SINK_DELIVERY_FAILED_EVENT,
+        Severity::Error,
+        format!("Kafka sink '{}' failed to produce: {}", sink_name, reason),
+        json::object! { "sink" => sink_name, "topic" => topic },
+    );
+}
+
+/// Produces every message to a Kafka topic. librdkafka batches and retries on its own,
+/// records it gives up on are reported as delivery failures.
+#[cfg(feature = "kafka")]
+fn spawn_kafka_sink(
+    name: &str,
+    config: &JsonValue,
+    topic: String,
+    key: KafkaKey,
+    encoding: OutputEncoding,
+    queue_size: usize,
+) -> Result<QueuedSink, String> {
+    use rdkafka::config::ClientConfig;
+    use rdkafka::producer::{FutureProducer, FutureRecord};
+
+    let brokers = config["brokers"].as_str().ok_or_else(|| format!("Kafka sink '{}' has no brokers", name))?;
+    let mut client_config = ClientConfig::new();
+    client_config.set("bootstrap.servers", brokers);
+    // Any librdkafka producer property, e.g. compression.type or security.protocol
+    for (property, value) in config["properties"].entries() {
+        client_config.set(property, value.as_str().map(str::to_string).unwrap_or_else(|| value.dump()));
+    }
+    let producer: FutureProducer = client_config.create().map_err(|e| format!("Kafka sink '{}': {}", name, e))?;
+
+    let (queue, mut rx) = mpsc::channel::<Arc<OutputMessage>>(queue_size);
+    let sink_name = name.to_string();
+    tokio::spawn(async move {
+        while let Some(message) = rx.recv().await {
+            let payload = record_payload(&message, encoding);
+            let record_key = key.of(&message);
+            let mut record = FutureRecord::<String, Vec<u8>>::to(&topic).payload(&payload);
+            if let Some(record_key) = record_key.as_ref() {
+                record = record.key(record_key);
+            }
+            match producer.send_result(record) {
+                // Delivery reports are awaited aside so that records stay in flight together
+                Ok(delivery) => {
+                    let (sink_name, topic) = (sink_name.clone(), topic.clone());
+                    tokio::spawn(async move {
+                        match delivery.await {
+                            Ok(Ok(_)) => {}
+                            Ok(Err((e, _))) => report_kafka_failure(&sink_name, &topic, e.to_string()),
+                            Err(_) => report_kafka_failure(&sink_name, &topic, "producer dropped the record".to_string()),
+                        }
+                    });
+                }
+                Err((e, _)) => report_kafka_failure(&sink_name, &topic, e.to_string()),
+            }
+        }
+    });
+    Ok(QueuedSink { name: name.to_string(), queue })
+}
+
+#[cfg(not(feature = "kafka"))]
+fn spawn_kafka_sink(
+    name: &str,
+    _config: &JsonValue,
+    _topic: String,
+    _key: KafkaKey,
+    _encoding: OutputEncoding,
+    _queue_size: usize,
+) -> Result<QueuedSink, String> {
+    Err(format!("Kafka sink '{}' needs a build with the kafka feature", name))
+}
+
+/// NATS subject token for a protocol or instruction name, subjects are split on dots
+#[cfg_attr(not(feature = "nats"), allow(dead_code))]
+fn subject_token(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' || c == '-' { c.to_ascii_lowercase() } else { '_' })
+        .collect()
+}
+
+/// `<prefix>.tx.<protocol>.<instruction>` of the first decoded instruction other than compute
+/// budget ones, e.g. `shred.tx.pumpfun.create`; batch payloads go to `<prefix>.tx.batch`
+#[cfg_attr(not(feature = "nats"), allow(dead_code))]
+fn transaction_subject(prefix: &str, message: &OutputMessage) -> String {
+    if message.payload["transactions"].is_array() {
+        return format!("{}.tx.batch", prefix);
+    }
+    let mut subject = None;
+    for_each_instruction(&message.payload, |protocol, instruction| {
+        if subject.is_none() && protocol != "ComputeBudget" {
+            subject = Some(format!("{}.tx.{}.{}", prefix, subject_token(protocol), subject_token(instruction)));
+        }
+    });
+    subject.unwrap_or_else(|| format!("{}.tx.unknown", prefix))
+}
+
+#[cfg(feature = "nats")]
+fn report_nats_failure(sink_name: &str, subject: &str, reason: String) {
+    error!("NATS sink '{}' failed to publish to {}: {}", sink_name, subject, reason);
+    publish_operational_event(
+        SINK_DELIVERY_FAILED_EVENT,
+        Severity::Error,
+        format!("NATS sink '{}' failed to publish: {}", sink_name, reason),
+        json::object! { "sink" => sink_name, "subject" => subject },
+    );
+}
+
+#[cfg(feature = "nats")]
+#[derive(Clone)]
+enum NatsPublisher {
+    Core(async_nats::Client),
+    /// Acknowledged publishes into the JetStream streams bound to the subjects, the streams
+    /// are provisioned outside of the decoder
+    JetStream(async_nats::jetstream::Context),
+}
+
+#[cfg(feature = "nats")]
+impl NatsPublisher {
+    async fn publish(&self, sink_name: &str, subject: String, payload: Vec<u8>) {
+        match self {
+            NatsPublisher::Core(client) => {
+                if let Err(e) = client.publish(subject.clone(), payload.into()).await {
+                    report_nats_failure(sink_name, &subject, e.to_string());
+                }
+            }
+            NatsPublisher::JetStream(context) => match context.publish(subject.clone(), payload.into()).await {
+                // Acks are awaited aside so that publishes stay in flight together
+                Ok(ack) => {
+                    let sink_name = sink_name.to_string();
+                    tokio::spawn(async move {
+                        if let Err(e) = ack.await {
+                            report_nats_failure(&sink_name, &subject, e.to_string());
+                        }
+                    });
+                }
+                Err(e) => report_nats_failure(sink_name, &subject, e.to_string()),
+            },
+        }
+    }
+}
+
+/// Publishes every message to a subject named after its protocol and instruction and, with
+/// `events`, the derived events (swaps, launches, ...) to `<prefix>.event.<event_type>`.
+/// Derived events are not subject to the routing table.
+#[cfg(feature = "nats")]
+fn spawn_nats_sink(name: &str, config: &JsonValue, encoding: OutputEncoding, queue_size: usize) -> Result<QueuedSink, String> {
+    let url = config["url"].as_str().ok_or_else(|| format!("NATS sink '{}' has no url", name))?.to_string();
+    let prefix = config["subject_prefix"].as_str().unwrap_or("shred").trim_end_matches('.').to_string();
+    let jetstream = config["jetstream"].as_bool().unwrap_or(false);
+    let events = config["events"].as_bool().unwrap_or(false);
+
+    let (queue, mut rx) = mpsc::channel::<Arc<OutputMessage>>(queue_size);
+    // Subscribed now so that no event published while connecting is missed
+    let event_rx = events.then(crate::derived_events::subscribe_derived_events);
+    let sink_name = name.to_string();
+    tokio::spawn(async move {
+        let client = match async_nats::ConnectOptions::new().retry_on_initial_connect().connect(&url).await {
+            Ok(client) => client,
+            Err(e) => {
+                report_nats_failure(&sink_name, &prefix, format!("cannot connect to {}: {}", url, e));
+                return;
+            }
+        };
+        info!("NATS sink '{}' connected to {}", sink_name, url);
+        let publisher = if jetstream {
+            NatsPublisher::JetStream(async_nats::jetstream::new(client))
+        } else {
+            NatsPublisher::Core(client)
+        };
+
+        if let Some(mut event_rx) = event_rx {
+            let (publisher, sink_name, prefix) = (publisher.clone(), sink_name.clone(), prefix.clone());
+            tokio::spawn(async move {
+                loop {
+                    match event_rx.recv().await {
+                        // Events are published by the active instance of a failover pair
+                        Ok(_) if !crate::failover::is_output_active() => {}
+                        Ok(event) => {
+                            let subject = format!("{}.event.{}", prefix, subject_token(event.event_type));
+                            publisher.publish(&sink_name, subject, event.json.clone().into_bytes()).await;
+                        }
+                        Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                            warn!("NATS sink '{}' skipped {} derived events", sink_name, skipped);
+                        }
+                        Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                    }
+                }
+            });
+        }
+
+        while let Some(message) = rx.recv().await {
+            let subject = transaction_subject(&prefix, &message);
+            publisher.publish(&sink_name, subject, record_payload(&message, encoding)).await;
+        }
+    });
+    Ok(QueuedSink { name: name.to_string(), queue })
+}
+
+#[cfg(not(feature = "nats"))]
+fn spawn_nats_sink(name: &str, _config: &JsonValue, _encoding: OutputEncoding, _queue_size: usize) -> Result<QueuedSink, String> {
+    Err(format!("NATS sink '{}' needs a build with the nats feature", name))
+}
+
+/// One row of the routing table, `*` matches any protocol or instruction. The optional
+/// program, mint and wallet sets further require the message to run one of the programs,
+/// touch one of the mints and be signed by one of the wallets.
+#[derive(Debug, Clone)]
+struct Route {
+    protocol: String,
+    instruction: String,
+    programs: HashSet<String>,
+    mints: HashSet<String>,
+    wallets: HashSet<String>,
+    sinks: Vec<String>,
+}
+
+impl Route {
+    fn matches(&self, protocol: &str, instruction: &str) -> bool {
+        (self.protocol == "*" || self.protocol == protocol) && (self.instruction == "*" || self.instruction == instruction)
+    }
+
+    fn accepts(&self, message: &OutputMessage) -> bool {
+        (self.programs.is_empty() || message.runs_any(&self.programs))
+            && (self.mints.is_empty() || message.touches_any(&self.mints))
+            && (self.wallets.is_empty() || message.signed_by_any(&self.wallets))
+    }
+}
+
+/// Sinks of one message, as decided by the routing table
+pub struct RouteTargets {
+    pub grpc: bool,
+    pub sinks: Vec<Arc<dyn Sink>>,
+}
+
+/// Owns the configured sinks and the routing table deciding which of them get a message
+pub struct SinkManager {
+    sinks: HashMap<String, Arc<dyn Sink>>,
+    routes: Vec<Route>,
+    default_sinks: Vec<String>,
+}
+
+impl SinkManager {
+    /// Builds the manager from a JSON routing file:
+    ///
+    /// ```json
+    /// {
+    ///   "sinks": { "creations": { "type": "webhook", "url": "https://...", "retries": 3, "backoff_ms": 500 },
+    ///              "archive": { "type": "file", "path": "/data/out.ndjson",
+    ///                           "rotate_bytes": 1073741824, "rotate_seconds": 3600, "gzip": true },
+    ///              "history": { "type": "archive", "path": "/data/archives", "rotate_slots": 1000 },
+    ///              "analytics": { "type": "parquet", "path": "/data/parquet", "rotate_seconds": 3600 },
+    ///              "database": { "type": "postgres", "url": "postgres://decoder@db/decoder", "batch_size": 500 },
+    ///              "pipeline": { "type": "kafka", "brokers": "kafka:9092", "topic": "decoded", "key": "mint" },
+    ///              "bus": { "type": "nats", "url": "nats://nats:4222", "subject_prefix": "shred", "jetstream": true, "events": true } },
+    ///   "routes": [ { "protocol": "Pumpfun", "instruction": "Create", "sinks": ["grpc", "creations"] },
+    ///               { "protocol": "*", "instruction": "Swap", "sinks": ["grpc"] },
+    ///               { "instruction": "Buy", "mints": ["<mint>"], "wallets": ["<wallet>"], "sinks": ["creations"] } ],
+    ///   "default": ["grpc", "archive"]
+    /// }
+    /// ```
+    fn from_config(config: &JsonValue) -> Result<Self, String> {
+        let queue_size = config["queue_size"].as_usize().unwrap_or(10_000);
+        let mut sinks: HashMap<String, Arc<dyn Sink>> = HashMap::default();
+
+        for (name, sink_config) in config["sinks"].entries() {
+            if name == GRPC_SINK {
+                return Err(format!("Sink name '{}' is reserved", GRPC_SINK));
+            }
+            let encoding = sink_config["encoding"]
+                .as_str()
+                .and_then(OutputEncoding::parse)
+                .unwrap_or(OutputEncoding::Compact);
+            let sink: Arc<dyn Sink> = match sink_config["type"].as_str() {
+                Some("webhook") => {
+                    let url = sink_config["url"].as_str().ok_or_else(|| format!("Webhook sink '{}' has no url", name))?;
+                    Arc::new(spawn_webhook_sink(name, url.to_string(), encoding, RetryPolicy::from_config(sink_config), queue_size))
+                }
+                Some("file") => {
+                    let path = sink_config["path"].as_str().ok_or_else(|| format!("File sink '{}' has no path", name))?;
+                    let rotation = FileRotation::from_config(sink_config);
+                    Arc::new(
+                        spawn_file_sink(name, path.to_string(), rotation, queue_size)
+                            .map_err(|e| format!("File sink '{}': {}", name, e))?,
+                    )
+                }
+                Some("archive") => {
+                    let path = sink_config["path"].as_str().ok_or_else(|| format!("Archive sink '{}' has no path", name))?;
+                    let rotate_slots = sink_config["rotate_slots"].as_u64().filter(|slots| *slots > 0).unwrap_or(1000);
+                    Arc::new(
+                        spawn_archive_sink(name, path.to_string(), rotate_slots, queue_size)
+                            .map_err(|e| format!("Archive sink '{}': {}", name, e))?,
+                    )
+                }
+                Some("parquet") => {
+                    let path = sink_config["path"].as_str().ok_or_else(|| format!("Parquet sink '{}' has no path", name))?;
+                    let rotate_seconds = sink_config["rotate_seconds"].as_u64().filter(|seconds| *seconds > 0).unwrap_or(3600);
+                    Arc::new(
+                        spawn_parquet_sink(name, path.to_string(), rotate_seconds, queue_size)
+                            .map_err(|e| format!("Parquet sink '{}': {}", name, e))?,
+                    )
+                }
+                Some("postgres") => {
+                    let url = sink_config["url"].as_str().ok_or_else(|| format!("Postgres sink '{}' has no url", name))?;
+                    let batch_size = sink_config["batch_size"].as_usize().filter(|size| *size > 0).unwrap_or(500);
+                    Arc::new(spawn_postgres_sink(name, url.to_string(), batch_size, queue_size)?)
+                }
+                Some("kafka") => {
+                    let topic = sink_config["topic"].as_str().ok_or_else(|| format!("Kafka sink '{}' has no topic", name))?;
+                    let key = sink_config["key"].as_str().unwrap_or("slot");
+                    let key = KafkaKey::parse(key)
+                        .ok_or_else(|| format!("Kafka sink '{}' has key '{}', expected slot, mint or none", name, key))?;
+                    Arc::new(spawn_kafka_sink(name, sink_config, topic.to_string(), key, encoding, queue_size)?)
+                }
+                Some("nats") => Arc::new(spawn_nats_sink(name, sink_config, encoding, queue_size)?),
+                other => return Err(format!("Unsupported type {:?} for sink '{}'", other, name)),
+            };
+            sinks.insert(name.to_string(), sink);
+        }
+
+        let known = |sink: &str| sink == GRPC_SINK || sinks.contains_key(sink);
+        let parse_sink_list = |list: &JsonValue, context: &str| -> Result<Vec<String>, String> {
+            list.members()
+                .map(|sink| {
+                    let sink = sink.as_str().unwrap_or_default();
+                    if known(sink) {
+                        Ok(sink.to_string())
+                    } else {
+                        Err(format!("Unknown sink '{}' in {}", sink, context))
+                    }
+                })
+                .collect()
+        };
+
+        let mut routes = Vec::new();
+        for (position, route) in config["routes"].members().enumerate() {
+            let set = |field: &str| -> HashSet<String> {
+                route[field].members().filter_map(|value| value.as_str()).map(|value| value.trim().to_string()).collect()
+            };
+            routes.push(Route {
+                protocol: route["protocol"].as_str().unwrap_or("*").to_string(),
+                instruction: route["instruction"].as_str().unwrap_or("*").to_string(),
+                programs: set("programs"),
+                mints: set("mints"),
+                wallets: set("wallets"),
+                sinks: parse_sink_list(&route["sinks"], &format!("route {}", position))?,
+            });
+        }
+
+        let default_sinks = if config["default"].is_null() {
+            vec![GRPC_SINK.to_string()]
+        } else {
+            parse_sink_list(&config["default"], "default")?
+        };
+
+        Ok(Self { sinks, routes, default_sinks })
+    }
+
+    /// Union of the sinks of every route matching an instruction of the message,
+    /// the default sinks when no route matches
+    pub fn route(&self, message: &OutputMessage) -> RouteTargets {
+        let mut selected: Vec<&str> = Vec::new();
+        let candidates: Vec<&Route> = self.routes.iter().filter(|route| route.accepts(message)).collect();
+        for_each_instruction(&message.payload, |protocol, instruction| {
+            for route in candidates.iter().filter(|route| route.matches(protocol, instruction)) {
+                for sink in &route.sinks {
+                    if !selected.contains(&sink.as_str()) {
+                        selected.push(sink);
+                    }
+                }
+            }
+        });
+        if selected.is_empty() {
+            selected.extend(self.default_sinks.iter().map(String::as_str));
+        }
+
+        RouteTargets {
+            grpc: selected.contains(&GRPC_SINK),
+            sinks: selected.iter().filter_map(|sink| self.sinks.get(*sink).cloned()).collect(),
+        }
+    }
+}
+
+/// Visits (protocol, instruction name) of every instruction of a transaction or batch payload
+fn for_each_instruction(payload: &JsonValue, mut visit: impl FnMut(&str, &str)) {
+    let mut visit_transaction = |transaction: &JsonValue| {
+        for instruction in transaction["message"]["instructions"].members() {
+            if let (Some(protocol), Some(name)) = (instruction["protocol"].as_str(), instruction["instruction_name"].as_str()) {
+                visit(protocol, name);
+            }
+        }
+    };
+    if payload["transactions"].is_array() {
+        payload["transactions"].members().for_each(&mut visit_transaction);
+    } else {
+        visit_transaction(payload);
+    }
+}
+
+static SINK_MANAGER: OnceCell<SinkManager> = OnceCell::new();
+
+/// Loads the routing table from SINK_ROUTING_FILE, without it every message only goes to gRPC.
+/// Must run inside the Tokio runtime, the sink workers are spawned here.
+pub fn init_sink_manager() -> Result<(), String> {
+    let Some(path) = std::env::var("SINK_ROUTING_FILE").ok().filter(|v| !v.trim().is_empty()) else {
+        debug!("No SINK_ROUTING_FILE configured, output goes to gRPC only");
+        return Ok(());
+    };
+    let content = std::fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let config = json::parse(&content).map_err(|e| format!("Invalid routing file {}: {}", path, e))?;
+    let manager = SinkManager::from_config(&config)?;
+    info!(
+        "Sink routing: {} sinks, {} routes, default {:?}",
+        manager.sinks.len(),
+        manager.routes.len(),
+        manager.default_sinks
+    );
+    SINK_MANAGER.set(manager).map_err(|_| "Sink manager already initialized".to_string())
+}
+
+pub fn get_sink_manager() -> Option<&'static SinkManager> {
+    SINK_MANAGER.get()
+}
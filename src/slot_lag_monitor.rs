@@ -0,0 +1,107 @@
+use json::object;
+use rustc_hash::FxHashSet as HashSet;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use std::time::Duration;
+use tracing::{debug, error, info, warn};
+
+use crate::derived_events::publish_derived_event;
+use crate::operational_events::{publish_operational_event, Severity, RPC_FAILURE_EVENT};
+use crate::utils::get_highest_stats_slot;
+
+pub const SLOT_LAG_EVENT: &str = "slot_lag";
+
+/// Alerting on the gap between the highest slot decoded and the RPC confirmed tip
+#[derive(Debug, Clone)]
+pub struct SlotLagConfig {
+    pub rpc_endpoint: String,
+    pub threshold_slots: u64,
+    pub check_interval: Duration,
+}
+
+impl SlotLagConfig {
+    /// Build the configuration from env, returns None when SLOT_LAG_THRESHOLD is 0
+    pub fn from_env(rpc_endpoint: &str) -> Option<Self> {
+        let threshold_slots = std::env::var("SLOT_LAG_THRESHOLD")
+            .unwrap_or_else(|_| "50".to_string())
+            .parse()
+            .unwrap_or(50);
+        if threshold_slots == 0 {
+            return None;
+        }
+        let check_interval_ms = std::env::var("SLOT_LAG_CHECK_INTERVAL_MS")
+            .unwrap_or_else(|_| "2000".to_string())
+            .parse()
+            .unwrap_or(2000);
+
+        Some(Self {
+            rpc_endpoint: std::env::var("SLOT_LAG_RPC_ENDPOINT").unwrap_or_else(|_| rpc_endpoint.to_string()),
+            threshold_slots,
+            check_interval: Duration::from_millis(check_interval_ms),
+        })
+    }
+}
+
+fn publish_lag_event(status: &'static str, decoder_slot: u64, confirmed_slot: u64, lag: u64, threshold: u64) {
+    let event = object! {
+        "event" => SLOT_LAG_EVENT,
+        "status" => status,
+        "decoderSlot" => decoder_slot,
+        "confirmedSlot" => confirmed_slot,
+        "lagSlots" => lag,
+        "thresholdSlots" => threshold,
+    };
+    publish_derived_event(SLOT_LAG_EVENT, event, HashSet::default());
+}
+
+/// Polls the confirmed tip and alerts when the decoder falls behind by more than the threshold.
+/// Alerts fire on state changes only, so an outage produces one alert and one recovery.
+pub async fn run_slot_lag_monitor(config: SlotLagConfig) {
+    let rpc_client = RpcClient::new_with_commitment(config.rpc_endpoint.clone(), CommitmentConfig::confirmed());
+    let mut ticker = tokio::time::interval(config.check_interval);
+    let mut lagging = false;
+
+    info!(
+        "Slot lag monitor started (threshold={} slots, interval={:?})",
+        config.threshold_slots, config.check_interval
+    );
+
+    loop {
+        ticker.tick().await;
+
+        let confirmed_slot = match rpc_client.get_slot().await {
+            Ok(slot) => slot,
+            Err(e) => {
+                warn!("Slot lag monitor: failed to fetch confirmed slot: {:?}", e);
+                publish_operational_event(
+                    RPC_FAILURE_EVENT,
+                    Severity::Warning,
+                    format!("Slot lag monitor: failed to fetch confirmed slot: {}", e),
+                    object! { "call" => "getSlot", "endpoint" => config.rpc_endpoint.clone() },
+                );
+                continue;
+            }
+        };
+        let decoder_slot = get_highest_stats_slot();
+        if decoder_slot == 0 {
+            debug!("Slot lag monitor: no shred decoded yet");
+            continue;
+        }
+        // Shreds arrive ahead of confirmation, so the decoder is normally in front of the tip
+        let lag = confirmed_slot.saturating_sub(decoder_slot);
+        debug!("Slot lag: decoder={} confirmed={} lag={}", decoder_slot, confirmed_slot, lag);
+
+        if lag > config.threshold_slots && !lagging {
+            lagging = true;
+            error!(
+                "Decoder is {} slots behind the confirmed tip (decoder={}, confirmed={}, threshold={}), check the shred proxy",
+                lag, decoder_slot, confirmed_slot, config.threshold_slots
+            );
+            publish_lag_event("lagging", decoder_slot, confirmed_slot, lag, config.threshold_slots);
+        } else if lag <= config.threshold_slots && lagging {
+            lagging = false;
+            info!("Decoder caught up with the confirmed tip (lag={} slots)", lag);
+            publish_lag_event("recovered", decoder_slot, confirmed_slot, lag, config.threshold_slots);
+        }
+    }
+}
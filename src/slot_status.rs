@@ -0,0 +1,142 @@
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+use std::sync::Arc;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::broadcast;
+
+
+/// Slots kept behind the newest slot seen, statuses of older slots are no longer tracked
+const SLOT_STATUS_RETENTION_SLOTS: u64 = 512;
+
+/// Milestones of a slot as seen from its shreds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlotStatusKind {
+    /// First shred of the slot received
+    FirstShred,
+    /// An FEC set of the slot was decoded, after recovery when data shreds were missing
+    FecSetComplete,
+    /// The last_in_slot data shred was received, the slot has no shreds past it
+    LastShred,
+}
+
+impl SlotStatusKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SlotStatusKind::FirstShred => "first_shred",
+            SlotStatusKind::FecSetComplete => "fec_set_complete",
+            SlotStatusKind::LastShred => "last_shred",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct SlotProgress {
+    first_shred_at: Instant,
+    data_shreds: u64,
+    coding_shreds: u64,
+    fec_sets_complete: u32,
+    fec_sets_recovered: u32,
+    recovered_data_shreds: u64,
+    last_shred_seen: bool,
+}
+
+impl SlotProgress {
+    fn new() -> Self {
+        Self {
+            first_shred_at: Instant::now(),
+            data_shreds: 0,
+            coding_shreds: 0,
+            fec_sets_complete: 0,
+            fec_sets_recovered: 0,
+            recovered_data_shreds: 0,
+            last_shred_seen: false,
+        }
+    }
+}
+
+/// A slot milestone with the slot's counters at that point
+#[derive(Debug, Clone)]
+pub struct SlotStatusEvent {
+    pub slot: u64,
+    pub kind: SlotStatusKind,
+    /// FEC set that completed, for FecSetComplete only
+    pub fec_set_index: Option<u32>,
+    pub data_shreds: u64,
+    pub coding_shreds: u64,
+    pub fec_sets_complete: u32,
+    pub fec_sets_recovered: u32,
+    pub recovered_data_shreds: u64,
+    /// Time since the first shred of the slot
+    pub elapsed_micros: u64,
+    pub timestamp: u64,
+}
+
+static SLOT_PROGRESS: Lazy<DashMap<u64, SlotProgress>> = Lazy::new(DashMap::new);
+
+static SLOT_STATUS_TX: Lazy<broadcast::Sender<Arc<SlotStatusEvent>>> = Lazy::new(|| broadcast::channel(4096).0);
+
+pub fn subscribe_slot_status() -> broadcast::Receiver<Arc<SlotStatusEvent>> {
+    SLOT_STATUS_TX.subscribe()
+}
+
+fn publish(slot: u64, kind: SlotStatusKind, fec_set_index: Option<u32>, progress: &SlotProgress) {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_micros() as u64;
+
+    // Sending only fails when nobody is subscribed
+    let _ = SLOT_STATUS_TX.send(Arc::new(SlotStatusEvent {
+        slot,
+        kind,
+        fec_set_index,
+        data_shreds: progress.data_shreds,
+        coding_shreds: progress.coding_shreds,
+        fec_sets_complete: progress.fec_sets_complete,
+        fec_sets_recovered: progress.fec_sets_recovered,
+        recovered_data_shreds: progress.recovered_data_shreds,
+        elapsed_micros: progress.first_shred_at.elapsed().as_micros() as u64,
+        timestamp,
+    }));
+}
+
+/// Counts a newly collected shred, duplicates must be filtered by the caller
+pub fn record_shred(slot: u64, is_data: bool, last_in_slot: bool) {
+    let mut first_shred = false;
+    let mut progress = SLOT_PROGRESS.entry(slot).or_insert_with(|| {
+        first_shred = true;
+        SlotProgress::new()
+    });
+    if is_data {
+        progress.data_shreds += 1;
+    } else {
+        progress.coding_shreds += 1;
+    }
+    let last_shred = last_in_slot && !progress.last_shred_seen;
+    progress.last_shred_seen |= last_in_slot;
+    let snapshot = *progress;
+    drop(progress);
+
+    if first_shred {
+        publish(slot, SlotStatusKind::FirstShred, None, &snapshot);
+        let oldest_retained = slot.saturating_sub(SLOT_STATUS_RETENTION_SLOTS);
+        SLOT_PROGRESS.retain(|&tracked_slot, _| tracked_slot >= oldest_retained);
+    }
+    if last_shred {
+        publish(slot, SlotStatusKind::LastShred, None, &snapshot);
+    }
+}
+
+/// Counts a decoded FEC set, `recovered_data_shreds` is 0 when no recovery was needed
+pub fn record_fec_set_complete(slot: u64, fec_set_index: u32, recovered_data_shreds: u64) {
+    let Some(mut progress) = SLOT_PROGRESS.get_mut(&slot) else { return };
+    progress.fec_sets_complete += 1;
+    if recovered_data_shreds > 0 {
+        progress.fec_sets_recovered += 1;
+        progress.recovered_data_shreds += recovered_data_shreds;
+    }
+    let snapshot = *progress;
+    drop(progress);
+
+    publish(slot, SlotStatusKind::FecSetComplete, Some(fec_set_index), &snapshot);
+}
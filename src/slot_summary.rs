@@ -0,0 +1,71 @@
+use json::{object, JsonValue};
+use once_cell::sync::Lazy;
+use rustc_hash::FxHashSet as HashSet;
+use std::time::Duration;
+use tracing::{debug, info};
+
+use crate::derived_events::publish_derived_event;
+use crate::utils::{get_highest_stats_slot, get_slot_stats};
+use crate::write_locks::take_write_lock_hotspots;
+
+pub const SLOT_SUMMARY_EVENT: &str = "slot_summary";
+
+/// Slots behind the highest decoded slot before a slot is summarized, late FEC sets
+/// arriving after that are not reflected in its summary
+static SLOT_SUMMARY_DELAY_SLOTS: Lazy<u64> = Lazy::new(|| {
+    std::env::var("SLOT_SUMMARY_DELAY_SLOTS")
+        .unwrap_or_else(|_| "4".to_string())
+        .parse()
+        .unwrap_or(4)
+});
+
+/// Slots summarized in one pass at most, after a long stall older slots are skipped
+const MAX_SLOTS_PER_PASS: u64 = 64;
+
+fn slot_summary(slot: u64) -> Option<(JsonValue, HashSet<String>)> {
+    let stats = get_slot_stats(slot)?;
+    let hotspots = take_write_lock_hotspots(slot);
+    let accounts = hotspots.iter().map(|hotspot| hotspot.account.clone()).collect();
+
+    let summary = object! {
+        "event" => SLOT_SUMMARY_EVENT,
+        "slot" => slot,
+        "fecBlocks" => stats.fec_blocks_count,
+        "fecBlocksComplete" => stats.fec_blocks_complete,
+        "fecBlocksIncomplete" => stats.fec_blocks_incomplete,
+        "transactions" => stats.tx_count,
+        "writeLockHotspots" => hotspots.iter().map(|hotspot| hotspot.to_json()).collect::<Vec<_>>(),
+    };
+    Some((summary, accounts))
+}
+
+/// Publishes one summary per slot on the derived events bus once the decoder moved past it
+pub async fn run_slot_summary_publisher() {
+    let mut ticker = tokio::time::interval(Duration::from_millis(400));
+    let delay = *SLOT_SUMMARY_DELAY_SLOTS;
+    let mut last_summarized = 0u64;
+    info!("Slot summaries published {} slots behind the highest decoded slot", delay);
+
+    loop {
+        ticker.tick().await;
+
+        let highest = get_highest_stats_slot();
+        if highest <= delay {
+            continue;
+        }
+        let summarize_up_to = highest - delay;
+        if summarize_up_to <= last_summarized {
+            continue;
+        }
+        let first = (last_summarized + 1).max(summarize_up_to.saturating_sub(MAX_SLOTS_PER_PASS - 1));
+
+        for slot in first..=summarize_up_to {
+            // Slots without any shred (skipped by their leader) have no stats and no summary
+            if let Some((summary, accounts)) = slot_summary(slot) {
+                debug!("Publishing slot summary for slot {}", slot);
+                publish_derived_event(SLOT_SUMMARY_EVENT, summary, accounts);
+            }
+        }
+        last_summarized = summarize_up_to;
+    }
+}
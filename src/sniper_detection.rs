@@ -0,0 +1,192 @@
+use dashmap::DashMap;
+use json::object;
+use once_cell::sync::Lazy;
+use rustc_hash::FxHashSet as HashSet;
+use solana_sdk::instruction::CompiledInstruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use std::time::{Duration, Instant};
+
+use crate::derived_events::publish_derived_event;
+use crate::pumpfun_decoding::*;
+use crate::raydium_launchlab_decoding::*;
+
+pub const FIRST_BUYER_EVENT: &str = "first_buyer";
+
+/// Number of buys reported per new token, 0 disables the detection
+static SNIPER_FIRST_BUYS: Lazy<u32> = Lazy::new(|| {
+    std::env::var("SNIPER_FIRST_BUYS")
+        .unwrap_or_else(|_| "10".to_string())
+        .parse()
+        .unwrap_or(10)
+});
+
+pub fn is_first_buyer_detection_enabled() -> bool {
+    *SNIPER_FIRST_BUYS > 0
+}
+
+/// How long a creation stays in the correlation cache waiting for its first buys
+static SNIPER_TRACKING_WINDOW: Lazy<Duration> = Lazy::new(|| {
+    let secs = std::env::var("SNIPER_TRACKING_WINDOW_SECS")
+        .unwrap_or_else(|_| "120".to_string())
+        .parse()
+        .unwrap_or(120);
+    Duration::from_secs(secs)
+});
+
+const MAX_TRACKED_CREATIONS: usize = 50_000;
+
+struct TrackedCreation {
+    protocol: &'static str,
+    creator: String,
+    slot: u64,
+    seen_at: Instant,
+    buys_reported: u32,
+}
+
+/// Creation <-> trade correlation cache, keyed by mint for pump.fun and by pool state for LaunchLab
+static RECENT_CREATIONS: Lazy<DashMap<String, TrackedCreation>> = Lazy::new(DashMap::new);
+
+fn account(instr: &CompiledInstruction, account_keys: &[Pubkey], position: usize) -> Option<String> {
+    let index = *instr.accounts.get(position)? as usize;
+    account_keys.get(index).map(|key| key.to_string())
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Option<u64> {
+    Some(u64::from_le_bytes(data.get(offset..offset + 8)?.try_into().ok()?))
+}
+
+fn track_creation(key: String, protocol: &'static str, creator: String, slot: u64) {
+    if RECENT_CREATIONS.len() >= MAX_TRACKED_CREATIONS {
+        let window = *SNIPER_TRACKING_WINDOW;
+        RECENT_CREATIONS.retain(|_, creation| creation.seen_at.elapsed() < window);
+        if RECENT_CREATIONS.len() >= MAX_TRACKED_CREATIONS {
+            return;
+        }
+    }
+
+    RECENT_CREATIONS.entry(key).or_insert_with(|| TrackedCreation {
+        protocol,
+        creator,
+        slot,
+        seen_at: Instant::now(),
+        buys_reported: 0,
+    });
+}
+
+struct ObservedBuy {
+    wallet: String,
+    mint: String,
+    amount_field: &'static str,
+    amount: u64,
+    limit_field: &'static str,
+    limit: u64,
+}
+
+fn report_buy(key: &str, slot: u64, signature: Option<&Signature>, buy: ObservedBuy) {
+    let limit = *SNIPER_FIRST_BUYS;
+    let Some(mut creation) = RECENT_CREATIONS.get_mut(key) else { return };
+
+    let elapsed = creation.seen_at.elapsed();
+    if elapsed >= *SNIPER_TRACKING_WINDOW {
+        drop(creation);
+        RECENT_CREATIONS.remove(key);
+        return;
+    }
+    if creation.buys_reported >= limit {
+        return;
+    }
+    creation.buys_reported += 1;
+
+    let event = object! {
+        "event" => FIRST_BUYER_EVENT,
+        "protocol" => creation.protocol,
+        "mint" => buy.mint.clone(),
+        "buyIndex" => creation.buys_reported,
+        "wallet" => buy.wallet.clone(),
+        "isCreator" => buy.wallet == creation.creator,
+        buy.amount_field => buy.amount.to_string(),
+        buy.limit_field => buy.limit.to_string(),
+        "signature" => signature.map(|signature| signature.to_string()).unwrap_or_default(),
+        "slot" => slot,
+        "creationSlot" => creation.slot,
+        "slotsFromCreation" => slot.saturating_sub(creation.slot),
+        "delayMs" => elapsed.as_millis() as u64,
+    };
+    let finished = creation.buys_reported >= limit;
+    drop(creation);
+
+    if finished {
+        RECENT_CREATIONS.remove(key);
+    }
+
+    let mut accounts = HashSet::default();
+    accounts.insert(buy.mint);
+    accounts.insert(buy.wallet);
+    publish_derived_event(FIRST_BUYER_EVENT, event, accounts);
+}
+
+/// Watches pump.fun and LaunchLab creations and reports the first K buys of each new token.
+/// Runs on every top-level instruction, including the buys the transaction stream does not emit.
+pub fn observe_launch_instruction(
+    slot: u64,
+    signature: Option<&Signature>,
+    program_key: &Pubkey,
+    instr: &CompiledInstruction,
+    account_keys: &[Pubkey],
+) {
+    if !is_first_buyer_detection_enabled() {
+        return;
+    }
+    let Some(discriminator) = instr.data.get(0..8) else { return };
+
+    if program_key == &PUMPFUN_PROGRAM_ID {
+        if discriminator == PUMP_CREATE_INSTRUCTION_DISCRIMINATOR {
+            // Accounts: mint=0, user=7
+            if let (Some(mint), Some(creator)) = (account(instr, account_keys, 0), account(instr, account_keys, 7)) {
+                track_creation(mint, "Pumpfun", creator, slot);
+            }
+        } else if discriminator == PUMP_BUY_INSTRUCTION_DISCRIMINATOR {
+            // Accounts: mint=2, user=6, args: amount, max_sol_cost
+            let (Some(mint), Some(wallet)) = (account(instr, account_keys, 2), account(instr, account_keys, 6)) else { return };
+            let (Some(amount), Some(max_sol_cost)) = (read_u64(&instr.data, 8), read_u64(&instr.data, 16)) else { return };
+            let key = mint.clone();
+            report_buy(&key, slot, signature, ObservedBuy {
+                wallet,
+                mint,
+                amount_field: "tokenAmount",
+                amount,
+                limit_field: "maxSolCost",
+                limit: max_sol_cost,
+            });
+        }
+    } else if program_key == &RAYDIUM_LAUNCHLAB_PROGRAM_ID {
+        if discriminator == RAYDIUM_LAUNCHLAB_INITIALIZE_INSTRUCTION_DISCRIMINATOR {
+            // Accounts: creator=1, poolState=5
+            if let (Some(pool), Some(creator)) = (account(instr, account_keys, 5), account(instr, account_keys, 1)) {
+                track_creation(pool, "RaydiumLaunchLab", creator, slot);
+            }
+        } else if discriminator == RAYDIUM_LAUNCHLAB_BUY_EXACT_IN_INSTRUCTION_DISCRIMINATOR
+            || discriminator == RAYDIUM_LAUNCHLAB_BUY_EXACT_OUT_INSTRUCTION_DISCRIMINATOR
+        {
+            // Accounts: payer=0, poolState=4, baseTokenMint=9
+            let (Some(wallet), Some(pool), Some(mint)) = (
+                account(instr, account_keys, 0),
+                account(instr, account_keys, 4),
+                account(instr, account_keys, 9),
+            ) else {
+                return;
+            };
+            let (Some(first), Some(second)) = (read_u64(&instr.data, 8), read_u64(&instr.data, 16)) else { return };
+            let exact_in = discriminator == RAYDIUM_LAUNCHLAB_BUY_EXACT_IN_INSTRUCTION_DISCRIMINATOR;
+            report_buy(&pool, slot, signature, ObservedBuy {
+                wallet,
+                mint,
+                amount_field: if exact_in { "amountIn" } else { "amountOut" },
+                amount: first,
+                limit_field: if exact_in { "minimumAmountOut" } else { "maximumAmountIn" },
+                limit: second,
+            });
+        }
+    }
+}
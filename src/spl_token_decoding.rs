@@ -0,0 +1,204 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use json::{object, JsonValue};
+use once_cell::sync::Lazy;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use crate::decode_anomalies::check_parse_residue;
+use crate::token_2022_decoding::SPL_TOKEN_2022_PROGRAM_ID;
+use crate::utils::create_standardized_instruction;
+
+pub const SPL_TOKEN_PROGRAM_ID: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+
+pub const SPL_TOKEN_INITIALIZE_MINT_ACCOUNTS: &[&str] = &["mint", "rent"];
+pub const SPL_TOKEN_TRANSFER_ACCOUNTS: &[&str] = &["source", "destination", "authority"];
+pub const SPL_TOKEN_MINT_TO_ACCOUNTS: &[&str] = &["mint", "account", "authority"];
+pub const SPL_TOKEN_BURN_ACCOUNTS: &[&str] = &["account", "mint", "authority"];
+pub const SPL_TOKEN_TRANSFER_CHECKED_ACCOUNTS: &[&str] = &[
+    "source", "mint", "destination", "authority",
+];
+pub const SPL_TOKEN_INITIALIZE_MINT2_ACCOUNTS: &[&str] = &["mint"];
+
+/// The token program is not an Anchor program, instructions are selected by their first byte
+pub const SPL_TOKEN_INITIALIZE_MINT_DISCRIMINATOR: [u8; 1] = [0];
+pub const SPL_TOKEN_TRANSFER_DISCRIMINATOR: [u8; 1] = [3];
+pub const SPL_TOKEN_MINT_TO_DISCRIMINATOR: [u8; 1] = [7];
+pub const SPL_TOKEN_BURN_DISCRIMINATOR: [u8; 1] = [8];
+pub const SPL_TOKEN_TRANSFER_CHECKED_DISCRIMINATOR: [u8; 1] = [12];
+pub const SPL_TOKEN_INITIALIZE_MINT2_DISCRIMINATOR: [u8; 1] = [20];
+
+/// Opt-in flag, token instructions are left out of the output when disabled
+static TOKEN_DECODING_ENABLED: Lazy<bool> = Lazy::new(|| {
+    std::env::var("TOKEN_DECODING_ENABLED")
+        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+});
+
+pub fn is_token_decoding_enabled() -> bool {
+    *TOKEN_DECODING_ENABLED
+}
+
+/// Token movements only complement the protocol instructions of a transaction,
+/// they do not make a transaction relevant on their own
+pub fn is_token_program(program_id: &Pubkey) -> bool {
+    program_id == &SPL_TOKEN_PROGRAM_ID || program_id == &SPL_TOKEN_2022_PROGRAM_ID
+}
+
+pub fn is_token_instruction(instruction: &JsonValue) -> bool {
+    instruction["protocol"] == "SplToken" || instruction["protocol"] == "Token2022"
+}
+
+/// Token-2022 keeps the layouts of the original token program for the instructions both have
+fn token_protocol(program_id: &Pubkey) -> &'static str {
+    if program_id == &SPL_TOKEN_2022_PROGRAM_ID {
+        "Token2022"
+    } else {
+        "SplToken"
+    }
+}
+
+pub enum SplTokenInstructionType {
+    InitializeMint,
+    Transfer,
+    MintTo,
+    Burn,
+    TransferChecked,
+    InitializeMint2,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
+pub struct SplTokenAmountParams {
+    discriminator: u8,
+    amount: u64,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
+pub struct SplTokenTransferCheckedParams {
+    discriminator: u8,
+    amount: u64,
+    decimals: u8,
+}
+
+/// The freeze authority option uses a one byte tag, the same encoding as Borsh
+#[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
+pub struct SplTokenInitializeMintParams {
+    discriminator: u8,
+    decimals: u8,
+    mint_authority: Pubkey,
+    freeze_authority: Option<Pubkey>,
+}
+
+/// Unsupported instructions (account setup, approvals, closes) return None and are not
+/// reported as unknown, the token program is in nearly every transaction
+pub fn get_spl_token_instruction_type(data: &[u8]) -> Option<SplTokenInstructionType> {
+    match data.first() {
+        Some(0) => Some(SplTokenInstructionType::InitializeMint),
+        Some(3) => Some(SplTokenInstructionType::Transfer),
+        Some(7) => Some(SplTokenInstructionType::MintTo),
+        Some(8) => Some(SplTokenInstructionType::Burn),
+        Some(12) => Some(SplTokenInstructionType::TransferChecked),
+        Some(20) => Some(SplTokenInstructionType::InitializeMint2),
+        _ => None,
+    }
+}
+
+/// Transfer (source=0, destination=1, authority=2), MintTo (mint=0, account=1, authority=2)
+/// and Burn (account=0, mint=1, authority=2) only carry an amount
+pub fn deserialize_spl_token_amount_instruction(
+    program_id: &Pubkey,
+    instruction_name: &str,
+    data: &[u8],
+    accounts_indices: &[u8],
+    account_keys: &[Pubkey],
+    is_signer: &[bool],
+    is_writable: &[bool],
+) -> Result<JsonValue, String> {
+    let mut data_ref = data;
+    let params = SplTokenAmountParams::deserialize(&mut data_ref)
+        .map_err(|e| format!("Failed to deserialize {} params: {:?}", instruction_name, e))?;
+
+    let mut parsed_data = object! {
+        "amount" => params.amount.to_string(),
+    };
+
+    check_parse_residue(&format!("{} {}", token_protocol(program_id), instruction_name), data_ref, &mut parsed_data);
+
+    Ok(create_standardized_instruction(
+        program_id,
+        instruction_name,
+        token_protocol(program_id),
+        data,
+        accounts_indices,
+        account_keys,
+        is_signer,
+        is_writable,
+        parsed_data
+    ))
+}
+
+/// Accounts: source=0, mint=1, destination=2, authority=3
+pub fn deserialize_spl_token_transfer_checked_instruction(
+    program_id: &Pubkey,
+    data: &[u8],
+    accounts_indices: &[u8],
+    account_keys: &[Pubkey],
+    is_signer: &[bool],
+    is_writable: &[bool],
+) -> Result<JsonValue, String> {
+    let mut data_ref = data;
+    let params = SplTokenTransferCheckedParams::deserialize(&mut data_ref)
+        .map_err(|e| format!("Failed to deserialize TransferChecked params: {:?}", e))?;
+
+    let mut parsed_data = object! {
+        "amount" => params.amount.to_string(),
+        "decimals" => params.decimals,
+    };
+
+    check_parse_residue(&format!("{} TransferChecked", token_protocol(program_id)), data_ref, &mut parsed_data);
+
+    Ok(create_standardized_instruction(
+        program_id,
+        "TransferChecked",
+        token_protocol(program_id),
+        data,
+        accounts_indices,
+        account_keys,
+        is_signer,
+        is_writable,
+        parsed_data
+    ))
+}
+
+/// InitializeMint (mint=0, rent=1) and InitializeMint2 (mint=0) share the args
+pub fn deserialize_spl_token_initialize_mint_instruction(
+    program_id: &Pubkey,
+    instruction_name: &str,
+    data: &[u8],
+    accounts_indices: &[u8],
+    account_keys: &[Pubkey],
+    is_signer: &[bool],
+    is_writable: &[bool],
+) -> Result<JsonValue, String> {
+    let mut data_ref = data;
+    let params = SplTokenInitializeMintParams::deserialize(&mut data_ref)
+        .map_err(|e| format!("Failed to deserialize {} params: {:?}", instruction_name, e))?;
+
+    let mut parsed_data = object! {
+        "decimals" => params.decimals,
+        "mint_authority" => params.mint_authority.to_string(),
+        "freeze_authority" => params.freeze_authority.map(|authority| authority.to_string()),
+    };
+
+    check_parse_residue(&format!("{} {}", token_protocol(program_id), instruction_name), data_ref, &mut parsed_data);
+
+    Ok(create_standardized_instruction(
+        program_id,
+        instruction_name,
+        token_protocol(program_id),
+        data,
+        accounts_indices,
+        account_keys,
+        is_signer,
+        is_writable,
+        parsed_data
+    ))
+}
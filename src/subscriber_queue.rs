@@ -0,0 +1,268 @@
+use once_cell::sync::Lazy;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::Notify;
+use tracing::warn;
+
+/// What happens to a subscriber whose queue is full
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// The oldest queued message makes room for the new one
+    DropOldest,
+    /// The subscription ends with RESOURCE_EXHAUSTED, the client reconnects (and resumes)
+    Disconnect,
+}
+
+impl OverflowPolicy {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "drop_oldest" | "drop-oldest" => Some(OverflowPolicy::DropOldest),
+            "disconnect" => Some(OverflowPolicy::Disconnect),
+            _ => None,
+        }
+    }
+}
+
+/// Messages buffered per subscriber once filtered, beyond that the overflow policy applies
+static SUBSCRIBER_QUEUE_SIZE: Lazy<usize> = Lazy::new(|| {
+    std::env::var("SUBSCRIBER_QUEUE_SIZE")
+        .unwrap_or_else(|_| "1000".to_string())
+        .parse()
+        .ok()
+        .filter(|size| *size > 0)
+        .unwrap_or(1000)
+});
+
+static SUBSCRIBER_OVERFLOW_POLICY: Lazy<OverflowPolicy> = Lazy::new(|| {
+    let configured = std::env::var("SUBSCRIBER_OVERFLOW_POLICY").unwrap_or_else(|_| "drop_oldest".to_string());
+    OverflowPolicy::parse(&configured).unwrap_or_else(|| {
+        warn!("Unknown SUBSCRIBER_OVERFLOW_POLICY '{}', falling back to drop_oldest", configured);
+        OverflowPolicy::DropOldest
+    })
+});
+
+/// Bounded queue between the fan-out of one subscription and its gRPC stream,
+/// so a slow client only ever loses its own messages
+pub struct SubscriberQueue<T> {
+    items: Mutex<VecDeque<T>>,
+    notify: Notify,
+    capacity: usize,
+    policy: OverflowPolicy,
+    /// Messages lost by this subscriber since it connected
+    dropped: AtomicU64,
+    /// Drops not yet reflected in the stream sequence
+    unreported_drops: AtomicU64,
+    closed: AtomicBool,
+    overflowed: AtomicBool,
+}
+
+impl<T> SubscriberQueue<T> {
+    pub fn new(capacity: usize, policy: OverflowPolicy) -> Arc<Self> {
+        Arc::new(Self {
+            items: Mutex::new(VecDeque::with_capacity(capacity.min(1024))),
+            notify: Notify::new(),
+            capacity,
+            policy,
+            dropped: AtomicU64::new(0),
+            unreported_drops: AtomicU64::new(0),
+            closed: AtomicBool::new(false),
+            overflowed: AtomicBool::new(false),
+        })
+    }
+
+    pub fn from_env() -> Arc<Self> {
+        Self::new(*SUBSCRIBER_QUEUE_SIZE, *SUBSCRIBER_OVERFLOW_POLICY)
+    }
+
+    /// Queues a message, false once the subscription is over and the producer should stop
+    pub fn push(&self, item: T) -> bool {
+        if self.is_closed() {
+            return false;
+        }
+        let mut items = self.items.lock().unwrap();
+        if items.len() >= self.capacity {
+            self.record_dropped(1);
+            match self.policy {
+                OverflowPolicy::DropOldest => {
+                    items.pop_front();
+                }
+                OverflowPolicy::Disconnect => {
+                    drop(items);
+                    self.overflowed.store(true, Ordering::Relaxed);
+                    self.close();
+                    return false;
+                }
+            }
+        }
+        items.push_back(item);
+        drop(items);
+        self.notify.notify_one();
+        true
+    }
+
+    /// Accounts for messages lost before reaching the queue
+    pub fn record_dropped(&self, count: u64) {
+        self.dropped.fetch_add(count, Ordering::Relaxed);
+        self.unreported_drops.fetch_add(count, Ordering::Relaxed);
+    }
+
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Drops since the previous call, for the sequence gap of the next message
+    pub fn take_unreported_drops(&self) -> u64 {
+        self.unreported_drops.swap(0, Ordering::Relaxed)
+    }
+
+    pub fn close(&self) {
+        self.closed.store(true, Ordering::Relaxed);
+        self.notify.notify_one();
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::Relaxed)
+    }
+
+    /// The subscription was closed by the Disconnect policy
+    pub fn overflowed(&self) -> bool {
+        self.overflowed.load(Ordering::Relaxed)
+    }
+
+    /// Next queued message, None once the queue is closed. A queue closed by the
+    /// Disconnect policy yields nothing more, the client is behind anyway.
+    pub async fn pop(&self) -> Option<T> {
+        loop {
+            if self.overflowed() {
+                return None;
+            }
+            let next = self.items.lock().unwrap().pop_front();
+            if let Some(item) = next {
+                return Some(item);
+            }
+            if self.is_closed() {
+                return None;
+            }
+            self.notify.notified().await;
+        }
+    }
+}
+
+/// Closes the queue when the consuming stream goes away, which stops its producer
+pub struct QueueReader<T> {
+    pub queue: Arc<SubscriberQueue<T>>,
+}
+
+impl<T> Drop for QueueReader<T> {
+    fn drop(&mut self) {
+        self.queue.close();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tokio::time::timeout;
+
+    async fn drain(queue: &SubscriberQueue<u32>) -> Vec<u32> {
+        let mut items = Vec::new();
+        while let Some(item) = queue.pop().await {
+            items.push(item);
+        }
+        items
+    }
+
+    #[test]
+    fn policy_names_parse() {
+        assert_eq!(OverflowPolicy::parse("drop_oldest"), Some(OverflowPolicy::DropOldest));
+        assert_eq!(OverflowPolicy::parse(" Drop-Oldest "), Some(OverflowPolicy::DropOldest));
+        assert_eq!(OverflowPolicy::parse("DISCONNECT"), Some(OverflowPolicy::Disconnect));
+        assert_eq!(OverflowPolicy::parse("block"), None);
+    }
+
+    #[tokio::test]
+    async fn full_queue_drops_the_oldest() {
+        let queue = SubscriberQueue::new(3, OverflowPolicy::DropOldest);
+        for item in 1..=5 {
+            assert!(queue.push(item));
+        }
+        assert_eq!(queue.dropped(), 2);
+        assert_eq!(queue.take_unreported_drops(), 2);
+        assert_eq!(queue.take_unreported_drops(), 0);
+
+        assert_eq!(queue.pop().await, Some(3));
+        assert!(queue.push(6));
+        assert_eq!(queue.take_unreported_drops(), 0);
+
+        queue.close();
+        assert!(!queue.push(7));
+        assert!(!queue.overflowed());
+        assert_eq!(drain(&queue).await, vec![4, 5, 6]);
+        assert_eq!(queue.dropped(), 2);
+    }
+
+    #[tokio::test]
+    async fn full_queue_disconnects() {
+        let queue = SubscriberQueue::new(2, OverflowPolicy::Disconnect);
+        assert!(queue.push(1));
+        assert!(queue.push(2));
+        assert!(!queue.push(3));
+
+        assert!(queue.is_closed());
+        assert!(queue.overflowed());
+        assert_eq!(queue.dropped(), 1);
+        assert_eq!(queue.take_unreported_drops(), 1);
+        assert!(!queue.push(4));
+        assert_eq!(queue.pop().await, None);
+    }
+
+    #[tokio::test]
+    async fn drops_before_the_queue_are_reported() {
+        let queue = SubscriberQueue::<u32>::new(4, OverflowPolicy::DropOldest);
+        queue.record_dropped(5);
+        assert!(queue.push(1));
+        assert!(queue.push(2));
+        assert!(queue.push(3));
+        assert!(queue.push(4));
+        assert!(queue.push(5));
+
+        assert_eq!(queue.dropped(), 6);
+        assert_eq!(queue.take_unreported_drops(), 6);
+        assert_eq!(queue.dropped(), 6);
+    }
+
+    #[tokio::test]
+    async fn pop_waits_for_a_push() {
+        let queue = SubscriberQueue::new(4, OverflowPolicy::DropOldest);
+        let waiting = tokio::spawn({
+            let queue = queue.clone();
+            async move { queue.pop().await }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(queue.push(7));
+        assert_eq!(timeout(Duration::from_secs(1), waiting).await.unwrap().unwrap(), Some(7));
+    }
+
+    #[tokio::test]
+    async fn close_wakes_a_waiting_pop() {
+        let queue = SubscriberQueue::<u32>::new(4, OverflowPolicy::DropOldest);
+        let waiting = tokio::spawn({
+            let queue = queue.clone();
+            async move { queue.pop().await }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        queue.close();
+        assert_eq!(timeout(Duration::from_secs(1), waiting).await.unwrap().unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn dropped_reader_closes_the_queue() {
+        let queue = SubscriberQueue::new(4, OverflowPolicy::DropOldest);
+        drop(QueueReader { queue: queue.clone() });
+        assert!(queue.is_closed());
+        assert!(!queue.push(1));
+        assert_eq!(queue.pop().await, None);
+    }
+}
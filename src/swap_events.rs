@@ -0,0 +1,333 @@
+use json::{object, JsonValue};
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+
+use crate::mint_index::pool_mints;
+use crate::utils::associated_token_address;
+
+const WSOL_MINT: &str = "So11111111111111111111111111111111111111112";
+const SPL_TOKEN_PROGRAM: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwapSide {
+    /// Base token bought with quote
+    Buy,
+    /// Base token sold for quote
+    Sell,
+}
+
+impl SwapSide {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SwapSide::Buy => "buy",
+            SwapSide::Sell => "sell",
+        }
+    }
+}
+
+/// A swap normalized across protocols. Amounts are raw token units taken from the
+/// instruction arguments, so the side that is bounded by slippage is a limit
+/// rather than the executed amount.
+#[derive(Debug, Clone)]
+pub struct SwapEvent {
+    pub protocol: String,
+    pub pool: String,
+    pub base_mint: Option<String>,
+    pub quote_mint: Option<String>,
+    pub side: SwapSide,
+    pub base_amount: u64,
+    pub quote_amount: u64,
+}
+
+impl SwapEvent {
+    /// Quote per base in raw units, None when one side is zero (e.g. no slippage bound)
+    pub fn price(&self) -> Option<f64> {
+        if self.base_amount == 0 || self.quote_amount == 0 {
+            return None;
+        }
+        Some(self.quote_amount as f64 / self.base_amount as f64)
+    }
+}
+
+fn account_at(instruction: &JsonValue, position: usize) -> Option<String> {
+    instruction["accounts"][position]["pubkey"]
+        .as_str()
+        .filter(|pubkey| *pubkey != "unknown")
+        .map(str::to_string)
+}
+
+fn parsed_u64(instruction: &JsonValue, field: &str) -> Option<u64> {
+    instruction["parsed_data"][field].as_str()?.parse().ok()
+}
+
+fn normalize_pumpamm(instruction: &JsonValue, side: SwapSide) -> Option<SwapEvent> {
+    // Accounts: pool=0, baseMint=3, quoteMint=4
+    let (base_amount, quote_amount) = match side {
+        SwapSide::Buy => (parsed_u64(instruction, "base_amount_out")?, parsed_u64(instruction, "max_quote_amount_in")?),
+        SwapSide::Sell => (parsed_u64(instruction, "base_amount_in")?, parsed_u64(instruction, "min_quote_amount_out")?),
+    };
+    Some(SwapEvent {
+        protocol: "PumpAMM".to_string(),
+        pool: account_at(instruction, 0)?,
+        base_mint: account_at(instruction, 3),
+        quote_mint: account_at(instruction, 4),
+        side,
+        base_amount,
+        quote_amount,
+    })
+}
+
+fn normalize_orca(instruction: &JsonValue, pool_position: usize) -> Option<SwapEvent> {
+    let amount = parsed_u64(instruction, "amount")?;
+    let threshold = parsed_u64(instruction, "otherAmountThreshold")?;
+    let exact_in = instruction["parsed_data"]["amountSpecifiedIsInput"].as_bool()?;
+    let a_to_b = instruction["parsed_data"]["aToB"].as_bool()?;
+    let pool = account_at(instruction, pool_position)?;
+
+    // Token A is the base: the specified amount is on A when exact-in sells A or exact-out buys A
+    let (base_amount, quote_amount) = if exact_in == a_to_b { (amount, threshold) } else { (threshold, amount) };
+    let mints = pool_mints(&pool);
+
+    Some(SwapEvent {
+        protocol: "Orca".to_string(),
+        pool,
+        base_mint: mints.as_ref().map(|[a, _]| a.clone()),
+        quote_mint: mints.map(|[_, b]| b),
+        side: if a_to_b { SwapSide::Sell } else { SwapSide::Buy },
+        base_amount,
+        quote_amount,
+    })
+}
+
+/// Extracts the normalized swaps of a decoded transaction
+pub fn normalize_swaps(json_transaction: &JsonValue) -> Vec<SwapEvent> {
+    json_transaction["message"]["instructions"]
+        .members()
+        .filter_map(|instruction| {
+            match (instruction["protocol"].as_str()?, instruction["instruction_name"].as_str()?) {
+                ("PumpAMM", "Buy") => normalize_pumpamm(instruction, SwapSide::Buy),
+                ("PumpAMM", "Sell") => normalize_pumpamm(instruction, SwapSide::Sell),
+                // Accounts: whirlpool=2 for Swap, whirlpool=4 for SwapV2
+                ("Orca", "Swap") => normalize_orca(instruction, 2),
+                ("Orca", "SwapV2") => normalize_orca(instruction, 4),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// A swap instruction in the protocol independent shape attached to the instruction JSON.
+/// For exact-out swaps `amount_in` is the maximum spent and `min_amount_out` the exact output.
+/// Mints are None when the direction cannot be told from the accounts.
+#[derive(Debug, Clone)]
+pub struct NormalizedSwap {
+    pub protocol: String,
+    pub pool: String,
+    pub input_mint: Option<String>,
+    pub output_mint: Option<String>,
+    pub amount_in: u64,
+    pub min_amount_out: u64,
+    pub exact_in: bool,
+    pub user: Option<String>,
+}
+
+impl NormalizedSwap {
+    pub fn to_json(&self) -> JsonValue {
+        object! {
+            "protocol" => self.protocol.as_str(),
+            "pool" => self.pool.as_str(),
+            "input_mint" => self.input_mint.clone(),
+            "output_mint" => self.output_mint.clone(),
+            "amount_in" => self.amount_in.to_string(),
+            "min_amount_out" => self.min_amount_out.to_string(),
+            "exact_in" => self.exact_in,
+            "user" => self.user.clone(),
+        }
+    }
+}
+
+/// Amounts of a swap given as a specified amount and a slippage threshold on the other side
+fn amounts_from_threshold(amount: u64, threshold: u64, exact_in: bool) -> (u64, u64) {
+    if exact_in { (amount, threshold) } else { (threshold, amount) }
+}
+
+/// (input, output) mints told by the input token account being the user's associated token
+/// account of one of the two mints. Misses users trading from other token accounts.
+fn mints_by_input_account(
+    instruction: &JsonValue,
+    user_position: usize,
+    input_position: usize,
+    [mint_a, mint_b]: [String; 2],
+    [program_a, program_b]: [&str; 2],
+) -> Option<(String, String)> {
+    let user = Pubkey::from_str(&account_at(instruction, user_position)?).ok()?;
+    let input = Pubkey::from_str(&account_at(instruction, input_position)?).ok()?;
+    let is_user_ata = |mint: &str, program: &str| match (Pubkey::from_str(mint), Pubkey::from_str(program)) {
+        (Ok(mint), Ok(program)) => associated_token_address(&user, &mint, &program) == input,
+        _ => false,
+    };
+    if is_user_ata(&mint_a, program_a) {
+        Some((mint_a, mint_b))
+    } else if is_user_ata(&mint_b, program_b) {
+        Some((mint_b, mint_a))
+    } else {
+        None
+    }
+}
+
+struct SwapAccounts {
+    pool: usize,
+    user: usize,
+}
+
+fn normalized(
+    instruction: &JsonValue,
+    protocol: &str,
+    accounts: SwapAccounts,
+    mints: Option<(String, String)>,
+    (amount_in, min_amount_out): (u64, u64),
+    exact_in: bool,
+) -> Option<NormalizedSwap> {
+    let (input_mint, output_mint) = mints.unzip();
+    Some(NormalizedSwap {
+        protocol: protocol.to_string(),
+        pool: account_at(instruction, accounts.pool)?,
+        input_mint,
+        output_mint,
+        amount_in,
+        min_amount_out,
+        exact_in,
+        user: account_at(instruction, accounts.user),
+    })
+}
+
+fn both_accounts(instruction: &JsonValue, input_position: usize, output_position: usize) -> Option<(String, String)> {
+    Some((account_at(instruction, input_position)?, account_at(instruction, output_position)?))
+}
+
+/// Maps the swap instructions of every protocol to a NormalizedSwap, None for other instructions
+pub fn normalize_swap_instruction(instruction: &JsonValue) -> Option<NormalizedSwap> {
+    let protocol = instruction["protocol"].as_str()?;
+    let name = instruction["instruction_name"].as_str()?;
+    let parsed = |field: &str| parsed_u64(instruction, field);
+
+    match (protocol, name) {
+        // Accounts: mint=2, bonding_curve=3, user=6, paid in SOL
+        ("Pumpfun", "Buy") => normalized(
+            instruction, protocol, SwapAccounts { pool: 3, user: 6 },
+            account_at(instruction, 2).map(|mint| (WSOL_MINT.to_string(), mint)),
+            (parsed("max_sol_cost")?, parsed("amount")?), false,
+        ),
+        // Accounts: pool=0, user=1, base_mint=3, quote_mint=4
+        ("PumpAMM", "Buy") => normalized(
+            instruction, protocol, SwapAccounts { pool: 0, user: 1 },
+            both_accounts(instruction, 4, 3),
+            (parsed("max_quote_amount_in")?, parsed("base_amount_out")?), false,
+        ),
+        ("PumpAMM", "Sell") => normalized(
+            instruction, protocol, SwapAccounts { pool: 0, user: 1 },
+            both_accounts(instruction, 3, 4),
+            (parsed("base_amount_in")?, parsed("min_quote_amount_out")?), true,
+        ),
+        // Accounts: amm=1, then user source, user destination and user owner last. The layout
+        // varies so the mints come from the learned pool, the coin vault side being the SPL program
+        ("Raydium", "SwapBaseIn" | "SwapBaseOut") => {
+            let count = instruction["accounts"].len();
+            let user = count.checked_sub(1)?;
+            let mints = pool_mints(&account_at(instruction, 1)?)
+                .and_then(|mints| mints_by_input_account(instruction, user, count.checked_sub(3)?, mints, [SPL_TOKEN_PROGRAM, SPL_TOKEN_PROGRAM]));
+            let (amounts, exact_in) = if name == "SwapBaseIn" {
+                ((parsed("amount_in")?, parsed("minimum_amount_out")?), true)
+            } else {
+                ((parsed("max_amount_in")?, parsed("amount_out")?), false)
+            };
+            normalized(instruction, protocol, SwapAccounts { pool: 1, user }, mints, amounts, exact_in)
+        },
+        // Accounts: payer=0, pool_state=4, base_mint=9, quote_mint=10
+        ("RaydiumLaunchLab", "BuyExactIn" | "BuyExactOut" | "SellExactIn" | "SellExactOut") => {
+            let mints = if name.starts_with("Buy") { both_accounts(instruction, 10, 9) } else { both_accounts(instruction, 9, 10) };
+            let (amounts, exact_in) = if name.ends_with("ExactIn") {
+                ((parsed("amountIn")?, parsed("minimumAmountOut")?), true)
+            } else {
+                ((parsed("maximumAmountIn")?, parsed("amountOut")?), false)
+            };
+            normalized(instruction, protocol, SwapAccounts { pool: 4, user: 0 }, mints, amounts, exact_in)
+        },
+        // Accounts: payer=0, pool_state=3, input_token_mint=10, output_token_mint=11
+        ("RaydiumCPMM", "SwapBaseInput") => normalized(
+            instruction, protocol, SwapAccounts { pool: 3, user: 0 },
+            both_accounts(instruction, 10, 11),
+            (parsed("amount_in")?, parsed("minimum_amount_out")?), true,
+        ),
+        ("RaydiumCPMM", "SwapBaseOutput") => normalized(
+            instruction, protocol, SwapAccounts { pool: 3, user: 0 },
+            both_accounts(instruction, 10, 11),
+            (parsed("max_amount_in")?, parsed("amount_out")?), false,
+        ),
+        // Accounts: payer=0, pool_state=2, input_token_account=3, token_program=8 and for SwapV2
+        // input_vault_mint=11, output_vault_mint=12
+        ("RaydiumCLMM", "Swap" | "SwapV2") => {
+            let exact_in = instruction["parsed_data"]["is_base_input"].as_bool()?;
+            let mints = if name == "SwapV2" {
+                both_accounts(instruction, 11, 12)
+            } else {
+                pool_mints(&account_at(instruction, 2)?)
+                    .and_then(|mints| mints_by_input_account(instruction, 0, 3, mints, [SPL_TOKEN_PROGRAM, SPL_TOKEN_PROGRAM]))
+            };
+            let amounts = amounts_from_threshold(parsed("amount")?, parsed("other_amount_threshold")?, exact_in);
+            normalized(instruction, protocol, SwapAccounts { pool: 2, user: 0 }, mints, amounts, exact_in)
+        },
+        // Accounts: pool=2, base_mint=7, quote_mint=8, payer=9, the direction comes from the decoder
+        ("MeteoraVCurve", "Swap") => {
+            let mints = match instruction["parsed_data"]["direction"].as_str() {
+                Some("buy") => both_accounts(instruction, 8, 7),
+                Some("sell") => both_accounts(instruction, 7, 8),
+                _ => None,
+            };
+            normalized(
+                instruction, protocol, SwapAccounts { pool: 2, user: 9 }, mints,
+                (parsed("amount_in")?, parsed("minimum_amount_out")?), true,
+            )
+        },
+        // Accounts: pool=1, input_token_account=2, token_a_mint=6, token_b_mint=7, payer=8,
+        // token_a_program=9, token_b_program=10
+        ("MeteoraAmmV2", "Swap") => {
+            let programs = (account_at(instruction, 9)?, account_at(instruction, 10)?);
+            let mints = both_accounts(instruction, 6, 7)
+                .and_then(|(a, b)| mints_by_input_account(instruction, 8, 2, [a, b], [programs.0.as_str(), programs.1.as_str()]));
+            normalized(
+                instruction, protocol, SwapAccounts { pool: 1, user: 8 }, mints,
+                (parsed("amount_in")?, parsed("minimum_amount_out")?), true,
+            )
+        },
+        // Accounts: lb_pair=0, user_token_in=4, token_x_mint=6, token_y_mint=7, user=10,
+        // token_x_program=11, token_y_program=12
+        ("MeteoraDLMM", "Swap" | "Swap2" | "SwapExactOut") => {
+            let programs = (account_at(instruction, 11)?, account_at(instruction, 12)?);
+            let mints = both_accounts(instruction, 6, 7)
+                .and_then(|(x, y)| mints_by_input_account(instruction, 10, 4, [x, y], [programs.0.as_str(), programs.1.as_str()]));
+            let (amounts, exact_in) = if name == "SwapExactOut" {
+                ((parsed("max_in_amount")?, parsed("out_amount")?), false)
+            } else {
+                ((parsed("amount_in")?, parsed("min_amount_out")?), true)
+            };
+            normalized(instruction, protocol, SwapAccounts { pool: 0, user: 10 }, mints, amounts, exact_in)
+        },
+        // Accounts: token_authority=1, whirlpool=2 for Swap; token_authority=3, whirlpool=4,
+        // token_mint_a=5, token_mint_b=6 for SwapV2
+        ("Orca", "Swap" | "SwapV2") => {
+            let accounts = if name == "SwapV2" { SwapAccounts { pool: 4, user: 3 } } else { SwapAccounts { pool: 2, user: 1 } };
+            let exact_in = instruction["parsed_data"]["amountSpecifiedIsInput"].as_bool()?;
+            let a_to_b = instruction["parsed_data"]["aToB"].as_bool()?;
+            let mints = if name == "SwapV2" {
+                both_accounts(instruction, 5, 6)
+            } else {
+                pool_mints(&account_at(instruction, accounts.pool)?).map(|[a, b]| (a, b))
+            };
+            let mints = mints.map(|(a, b)| if a_to_b { (a, b) } else { (b, a) });
+            let amounts = amounts_from_threshold(parsed("amount")?, parsed("otherAmountThreshold")?, exact_in);
+            normalized(instruction, protocol, accounts, mints, amounts, exact_in)
+        },
+        _ => None,
+    }
+}
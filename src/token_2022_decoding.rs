@@ -0,0 +1,255 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use json::{object, JsonValue};
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use crate::decode_anomalies::check_parse_residue;
+use crate::utils::{create_standardized_instruction, ValueSanity};
+
+pub const SPL_TOKEN_2022_PROGRAM_ID: Pubkey = pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+
+pub const TOKEN_2022_MINT_ACCOUNTS: &[&str] = &["mint"];
+pub const TOKEN_2022_MINT_AUTHORITY_ACCOUNTS: &[&str] = &["mint", "authority"];
+pub const TOKEN_2022_WITHDRAW_WITHHELD_ACCOUNTS: &[&str] = &["mint", "destination", "authority"];
+
+/// Extensions are selected by the extension instruction byte followed by a sub instruction byte,
+/// instructions shared with the original token program use the SPL_TOKEN_* discriminators
+pub const TOKEN_2022_INITIALIZE_TRANSFER_FEE_CONFIG_DISCRIMINATOR: [u8; 2] = [26, 0];
+pub const TOKEN_2022_TRANSFER_CHECKED_WITH_FEE_DISCRIMINATOR: [u8; 2] = [26, 1];
+pub const TOKEN_2022_WITHDRAW_WITHHELD_FROM_MINT_DISCRIMINATOR: [u8; 2] = [26, 2];
+pub const TOKEN_2022_WITHDRAW_WITHHELD_FROM_ACCOUNTS_DISCRIMINATOR: [u8; 2] = [26, 3];
+pub const TOKEN_2022_HARVEST_WITHHELD_TO_MINT_DISCRIMINATOR: [u8; 2] = [26, 4];
+pub const TOKEN_2022_SET_TRANSFER_FEE_DISCRIMINATOR: [u8; 2] = [26, 5];
+pub const TOKEN_2022_INITIALIZE_TRANSFER_HOOK_DISCRIMINATOR: [u8; 2] = [36, 0];
+pub const TOKEN_2022_UPDATE_TRANSFER_HOOK_DISCRIMINATOR: [u8; 2] = [36, 1];
+pub const TOKEN_2022_INITIALIZE_METADATA_POINTER_DISCRIMINATOR: [u8; 2] = [39, 0];
+
+/// Upper bound of transfer_fee_basis_points, a 100% fee
+const MAX_FEE_BASIS_POINTS: u16 = 10_000;
+
+pub enum Token2022ExtensionInstructionType {
+    InitializeTransferFeeConfig,
+    TransferCheckedWithFee,
+    WithdrawWithheldTokensFromMint,
+    WithdrawWithheldTokensFromAccounts,
+    HarvestWithheldTokensToMint,
+    SetTransferFee,
+    InitializeTransferHook,
+    UpdateTransferHook,
+    InitializeMetadataPointer,
+}
+
+/// Authorities use the one byte option tag of the token program
+#[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
+pub struct InitializeTransferFeeConfigParams {
+    transfer_fee_config_authority: Option<Pubkey>,
+    withdraw_withheld_authority: Option<Pubkey>,
+    transfer_fee_basis_points: u16,
+    maximum_fee: u64,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
+pub struct TransferCheckedWithFeeParams {
+    amount: u64,
+    decimals: u8,
+    fee: u64,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
+pub struct WithdrawWithheldTokensFromAccountsParams {
+    num_token_accounts: u8,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
+pub struct SetTransferFeeParams {
+    transfer_fee_basis_points: u16,
+    maximum_fee: u64,
+}
+
+/// Pointer extensions store optional pubkeys as 32 bytes, all zero meaning none
+#[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
+pub struct InitializeTransferHookParams {
+    authority: Pubkey,
+    program_id: Pubkey,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
+pub struct UpdateTransferHookParams {
+    program_id: Pubkey,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, Default, Clone)]
+pub struct InitializeMetadataPointerParams {
+    authority: Pubkey,
+    metadata_address: Pubkey,
+}
+
+pub fn get_token_2022_extension_instruction_type(data: &[u8]) -> Option<Token2022ExtensionInstructionType> {
+    match data.get(0..2) {
+        Some(d) if d == TOKEN_2022_INITIALIZE_TRANSFER_FEE_CONFIG_DISCRIMINATOR => Some(Token2022ExtensionInstructionType::InitializeTransferFeeConfig),
+        Some(d) if d == TOKEN_2022_TRANSFER_CHECKED_WITH_FEE_DISCRIMINATOR => Some(Token2022ExtensionInstructionType::TransferCheckedWithFee),
+        Some(d) if d == TOKEN_2022_WITHDRAW_WITHHELD_FROM_MINT_DISCRIMINATOR => Some(Token2022ExtensionInstructionType::WithdrawWithheldTokensFromMint),
+        Some(d) if d == TOKEN_2022_WITHDRAW_WITHHELD_FROM_ACCOUNTS_DISCRIMINATOR => Some(Token2022ExtensionInstructionType::WithdrawWithheldTokensFromAccounts),
+        Some(d) if d == TOKEN_2022_HARVEST_WITHHELD_TO_MINT_DISCRIMINATOR => Some(Token2022ExtensionInstructionType::HarvestWithheldTokensToMint),
+        Some(d) if d == TOKEN_2022_SET_TRANSFER_FEE_DISCRIMINATOR => Some(Token2022ExtensionInstructionType::SetTransferFee),
+        Some(d) if d == TOKEN_2022_INITIALIZE_TRANSFER_HOOK_DISCRIMINATOR => Some(Token2022ExtensionInstructionType::InitializeTransferHook),
+        Some(d) if d == TOKEN_2022_UPDATE_TRANSFER_HOOK_DISCRIMINATOR => Some(Token2022ExtensionInstructionType::UpdateTransferHook),
+        Some(d) if d == TOKEN_2022_INITIALIZE_METADATA_POINTER_DISCRIMINATOR => Some(Token2022ExtensionInstructionType::InitializeMetadataPointer),
+        _ => None,
+    }
+}
+
+fn non_zero(pubkey: Pubkey) -> Option<String> {
+    (pubkey != Pubkey::default()).then(|| pubkey.to_string())
+}
+
+fn token_2022_instruction(
+    instruction_name: &str,
+    data: &[u8],
+    accounts_indices: &[u8],
+    account_keys: &[Pubkey],
+    is_signer: &[bool],
+    is_writable: &[bool],
+    mut parsed_data: JsonValue,
+    residue: &[u8],
+) -> JsonValue {
+    check_parse_residue(&format!("Token2022 {}", instruction_name), residue, &mut parsed_data);
+    create_standardized_instruction(
+        &SPL_TOKEN_2022_PROGRAM_ID,
+        instruction_name,
+        "Token2022",
+        data,
+        accounts_indices,
+        account_keys,
+        is_signer,
+        is_writable,
+        parsed_data
+    )
+}
+
+/// InitializeTransferFeeConfig (mint=0) and SetTransferFee (mint=0, authority=1)
+pub fn deserialize_token_2022_transfer_fee_config_instruction(
+    instruction_type: &Token2022ExtensionInstructionType,
+    data: &[u8],
+    accounts_indices: &[u8],
+    account_keys: &[Pubkey],
+    is_signer: &[bool],
+    is_writable: &[bool],
+) -> Result<JsonValue, String> {
+    let mut data_ref = &data[2..];
+    let (instruction_name, mut parsed_data, basis_points) = match instruction_type {
+        Token2022ExtensionInstructionType::InitializeTransferFeeConfig => {
+            let params = InitializeTransferFeeConfigParams::deserialize(&mut data_ref)
+                .map_err(|e| format!("Failed to deserialize InitializeTransferFeeConfig params: {:?}", e))?;
+            ("InitializeTransferFeeConfig", object! {
+                "transfer_fee_config_authority" => params.transfer_fee_config_authority.map(|authority| authority.to_string()),
+                "withdraw_withheld_authority" => params.withdraw_withheld_authority.map(|authority| authority.to_string()),
+                "transfer_fee_basis_points" => params.transfer_fee_basis_points,
+                "maximum_fee" => params.maximum_fee.to_string(),
+            }, params.transfer_fee_basis_points)
+        }
+        _ => {
+            let params = SetTransferFeeParams::deserialize(&mut data_ref)
+                .map_err(|e| format!("Failed to deserialize SetTransferFee params: {:?}", e))?;
+            ("SetTransferFee", object! {
+                "transfer_fee_basis_points" => params.transfer_fee_basis_points,
+                "maximum_fee" => params.maximum_fee.to_string(),
+            }, params.transfer_fee_basis_points)
+        }
+    };
+
+    let mut sanity = ValueSanity::new();
+    sanity.check_range_u128("transfer_fee_basis_points", basis_points as u128, 0, MAX_FEE_BASIS_POINTS as u128);
+    sanity.apply(&mut parsed_data);
+
+    Ok(token_2022_instruction(instruction_name, data, accounts_indices, account_keys, is_signer, is_writable, parsed_data, data_ref))
+}
+
+/// Accounts: source=0, mint=1, destination=2, authority=3
+pub fn deserialize_token_2022_transfer_checked_with_fee_instruction(
+    data: &[u8],
+    accounts_indices: &[u8],
+    account_keys: &[Pubkey],
+    is_signer: &[bool],
+    is_writable: &[bool],
+) -> Result<JsonValue, String> {
+    let mut data_ref = &data[2..];
+    let params = TransferCheckedWithFeeParams::deserialize(&mut data_ref)
+        .map_err(|e| format!("Failed to deserialize TransferCheckedWithFee params: {:?}", e))?;
+
+    let mut parsed_data = object! {
+        "amount" => params.amount.to_string(),
+        "decimals" => params.decimals,
+        "fee" => params.fee.to_string(),
+    };
+    let mut sanity = ValueSanity::new();
+    sanity.check_range_u128("fee", params.fee as u128, 0, params.amount as u128);
+    sanity.apply(&mut parsed_data);
+
+    Ok(token_2022_instruction("TransferCheckedWithFee", data, accounts_indices, account_keys, is_signer, is_writable, parsed_data, data_ref))
+}
+
+/// WithdrawWithheldTokensFromMint (mint=0, destination=1, authority=2),
+/// WithdrawWithheldTokensFromAccounts (same, then the source accounts) and
+/// HarvestWithheldTokensToMint (mint=0, then the source accounts)
+pub fn deserialize_token_2022_withheld_tokens_instruction(
+    instruction_type: &Token2022ExtensionInstructionType,
+    data: &[u8],
+    accounts_indices: &[u8],
+    account_keys: &[Pubkey],
+    is_signer: &[bool],
+    is_writable: &[bool],
+) -> Result<JsonValue, String> {
+    let mut data_ref = &data[2..];
+    let (instruction_name, parsed_data) = match instruction_type {
+        Token2022ExtensionInstructionType::WithdrawWithheldTokensFromAccounts => {
+            let params = WithdrawWithheldTokensFromAccountsParams::deserialize(&mut data_ref)
+                .map_err(|e| format!("Failed to deserialize WithdrawWithheldTokensFromAccounts params: {:?}", e))?;
+            ("WithdrawWithheldTokensFromAccounts", object! {
+                "num_token_accounts" => params.num_token_accounts,
+            })
+        }
+        Token2022ExtensionInstructionType::WithdrawWithheldTokensFromMint => ("WithdrawWithheldTokensFromMint", object! {}),
+        _ => ("HarvestWithheldTokensToMint", object! {}),
+    };
+
+    Ok(token_2022_instruction(instruction_name, data, accounts_indices, account_keys, is_signer, is_writable, parsed_data, data_ref))
+}
+
+/// InitializeTransferHook / InitializeMetadataPointer (mint=0) and UpdateTransferHook (mint=0, authority=1)
+pub fn deserialize_token_2022_pointer_instruction(
+    instruction_type: &Token2022ExtensionInstructionType,
+    data: &[u8],
+    accounts_indices: &[u8],
+    account_keys: &[Pubkey],
+    is_signer: &[bool],
+    is_writable: &[bool],
+) -> Result<JsonValue, String> {
+    let mut data_ref = &data[2..];
+    let (instruction_name, parsed_data) = match instruction_type {
+        Token2022ExtensionInstructionType::InitializeTransferHook => {
+            let params = InitializeTransferHookParams::deserialize(&mut data_ref)
+                .map_err(|e| format!("Failed to deserialize InitializeTransferHook params: {:?}", e))?;
+            ("InitializeTransferHook", object! {
+                "authority" => non_zero(params.authority),
+                "hook_program_id" => non_zero(params.program_id),
+            })
+        }
+        Token2022ExtensionInstructionType::UpdateTransferHook => {
+            let params = UpdateTransferHookParams::deserialize(&mut data_ref)
+                .map_err(|e| format!("Failed to deserialize UpdateTransferHook params: {:?}", e))?;
+            ("UpdateTransferHook", object! {
+                "hook_program_id" => non_zero(params.program_id),
+            })
+        }
+        _ => {
+            let params = InitializeMetadataPointerParams::deserialize(&mut data_ref)
+                .map_err(|e| format!("Failed to deserialize InitializeMetadataPointer params: {:?}", e))?;
+            ("InitializeMetadataPointer", object! {
+                "authority" => non_zero(params.authority),
+                "metadata_address" => non_zero(params.metadata_address),
+            })
+        }
+    };
+
+    Ok(token_2022_instruction(instruction_name, data, accounts_indices, account_keys, is_signer, is_writable, parsed_data, data_ref))
+}
@@ -0,0 +1,98 @@
+use std::io;
+use tokio::net::UdpSocket;
+use tokio::sync::mpsc;
+
+/// Receive backend of the UDP sockets, from UDP_RECV_BACKEND
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReceiveBackend {
+    /// Tokio sockets, batched through recvmmsg unless UDP_RECV_BATCH is 1
+    Tokio,
+    /// An io_uring ring per socket keeping UDP_RECV_BATCH receives in flight, falls back to
+    /// the tokio socket when the build lacks the io_uring feature or the kernel refuses the ring
+    IoUring,
+}
+
+impl ReceiveBackend {
+    pub fn from_env() -> Self {
+        match std::env::var("UDP_RECV_BACKEND").unwrap_or_default().trim().to_ascii_lowercase().as_str() {
+            "io_uring" | "io-uring" | "uring" => ReceiveBackend::IoUring,
+            _ => ReceiveBackend::Tokio,
+        }
+    }
+}
+
+/// Receives on a dedicated thread through io_uring with `depth` receives in flight, packet
+/// batches come out of the channel as with the recvmmsg receiver. The socket is handed
+/// back with the error when no ring can be set up.
+#[cfg(feature = "io_uring")]
+pub fn spawn_uring_receiver(socket: UdpSocket, depth: usize) -> Result<mpsc::Receiver<Vec<Box<[u8]>>>, (UdpSocket, io::Error)> {
+    use io_uring::{opcode, types, IoUring};
+    use std::os::fd::AsRawFd;
+    use tracing::{error, warn};
+
+    use crate::batch_receive::PACKET_SIZE;
+
+    let mut ring = match IoUring::new(depth.next_power_of_two() as u32) {
+        Ok(ring) => ring,
+        Err(e) => return Err((socket, e)),
+    };
+    // The ring thread waits in the kernel, a nonblocking socket would fail receives with EAGAIN
+    if let Err(e) = socket2::SockRef::from(&socket).set_nonblocking(false) {
+        return Err((socket, e));
+    }
+
+    let (packets_tx, packets_rx) = mpsc::channel::<Vec<Box<[u8]>>>(1024);
+    std::thread::Builder::new()
+        .name("shred-uring-recv".to_string())
+        .spawn(move || {
+            let fd = types::Fd(socket.as_raw_fd());
+            let mut buffers = vec![[0u8; PACKET_SIZE]; depth];
+            // MSG_TRUNC makes the completion report the datagram size, oversized ones are skipped
+            let recv = |buffer: &mut [u8; PACKET_SIZE], index: usize| {
+                opcode::Recv::new(fd, buffer.as_mut_ptr(), PACKET_SIZE as u32)
+                    .flags(libc::MSG_TRUNC)
+                    .build()
+                    .user_data(index as u64)
+            };
+
+            for (index, buffer) in buffers.iter_mut().enumerate() {
+                // SAFETY: the buffers outlive the ring, each is owned by one receive at a time
+                unsafe { ring.submission().push(&recv(buffer, index)).expect("io_uring submission queue full") };
+            }
+            loop {
+                if let Err(e) = ring.submit_and_wait(1) {
+                    if e.kind() == io::ErrorKind::Interrupted {
+                        continue;
+                    }
+                    error!("io_uring receive failed, stopping the receiver: {:?}", e);
+                    break;
+                }
+                let completions: Vec<(usize, i32)> = ring.completion().map(|cqe| (cqe.user_data() as usize, cqe.result())).collect();
+
+                let mut packets = Vec::with_capacity(completions.len());
+                for &(index, result) in &completions {
+                    if result < 0 {
+                        error!("Error receiving shreds: {:?}", io::Error::from_raw_os_error(-result));
+                    } else if result as usize > PACKET_SIZE {
+                        warn!("Received data size {} exceeds buffer length {}", result, PACKET_SIZE);
+                    } else {
+                        packets.push(Box::from(&buffers[index][..result as usize]));
+                    }
+                }
+                for &(index, _) in &completions {
+                    // SAFETY: the receive on this buffer completed and its packet was copied out
+                    unsafe { ring.submission().push(&recv(&mut buffers[index], index)).expect("io_uring submission queue full") };
+                }
+                if !packets.is_empty() && packets_tx.blocking_send(packets).is_err() {
+                    break;
+                }
+            }
+        })
+        .expect("Failed to spawn the io_uring receive thread");
+    Ok(packets_rx)
+}
+
+#[cfg(not(feature = "io_uring"))]
+pub fn spawn_uring_receiver(socket: UdpSocket, _depth: usize) -> Result<mpsc::Receiver<Vec<Box<[u8]>>>, (UdpSocket, io::Error)> {
+    Err((socket, io::Error::new(io::ErrorKind::Unsupported, "UDP_RECV_BACKEND=io_uring needs a build with the io_uring feature")))
+}
@@ -1,42 +1,104 @@
 use dashmap::DashMap;
 use dotenv::var;
 use lazy_static::lazy_static;
-use libc;
-use std::fs::OpenOptions;
-use std::io::{Write, BufWriter};
-use serde_json::Value;
-use std::collections::VecDeque;
-use std::os::unix::io::AsRawFd;
+use socket2::{Domain, Protocol, Socket, Type};
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
 use tokio::net::UdpSocket;
-use tracing::{warn, error, debug};
+use tracing::{debug, warn};
 use json::{object, JsonValue};
+use solana_sdk::pubkey;
 use solana_sdk::pubkey::Pubkey;
+use crate::decoder_registry::find_account_names;
 
 lazy_static! {
-    static ref SLOT_STATS: DashMap<u64, (u64, u64, u64, u64)> = DashMap::new();
+    static ref SLOT_STATS: DashMap<u64, SlotStats> = DashMap::new();
+    static ref SLOT_STATS_RETENTION_SLOTS: u64 = std::env::var("SLOT_STATS_RETENTION_SLOTS")
+        .unwrap_or_else(|_| "500".to_string())
+        .parse()
+        .ok()
+        .filter(|retention| *retention > 0)
+        .unwrap_or(500);
 }
 
-pub async fn create_udp_socket_with_buffer(addr: &str, buffer_size: usize) -> UdpSocket {
-    let socket = std::net::UdpSocket::bind(addr).expect("Failed to bind socket");
+static HIGHEST_STATS_SLOT: AtomicU64 = AtomicU64::new(0);
+
+const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey = pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+
+/// Opens `count` sockets bound to the same address with SO_REUSEPORT, the kernel spreads the
+/// incoming flows across them so that each can be read by its own task. A single socket
+/// without SO_REUSEPORT when `count` is 1 or the platform lacks it.
+pub async fn create_udp_sockets_with_buffer(addr: &str, buffer_size: usize, count: usize) -> Vec<UdpSocket> {
+    if count <= 1 {
+        return vec![bind_udp_socket(addr, buffer_size, false)];
+    }
+    if !cfg!(unix) {
+        warn!("SO_REUSEPORT is not available on this platform, receiving on a single socket");
+        return vec![bind_udp_socket(addr, buffer_size, false)];
+    }
+    (0..count).map(|_| bind_udp_socket(addr, buffer_size, true)).collect()
+}
+
+/// Receive sockets per UDP address, from UDP_RECEIVE_SOCKETS
+pub fn udp_receive_sockets() -> usize {
+    std::env::var("UDP_RECEIVE_SOCKETS")
+        .unwrap_or_else(|_| "1".to_string())
+        .parse()
+        .ok()
+        .filter(|count| *count > 0)
+        .unwrap_or(1)
+}
+
+fn bind_udp_socket(addr: &str, buffer_size: usize, reuse_port: bool) -> UdpSocket {
+    let addr: SocketAddr = addr.parse().expect("Invalid UDP socket address");
+    let socket = Socket::new(Domain::for_address(addr), Type::DGRAM, Some(Protocol::UDP))
+        .expect("Failed to create UDP socket");
+
+    // Set before binding so the buffer is in place when the first shreds arrive
+    socket
+        .set_recv_buffer_size(buffer_size)
+        .expect("Failed to set UDP receive buffer size");
+    warn_if_receive_buffer_capped(&socket, buffer_size);
+
+    #[cfg(unix)]
+    if reuse_port {
+        socket.set_reuse_port(true).expect("Failed to set SO_REUSEPORT");
+    }
+    #[cfg(not(unix))]
+    let _ = reuse_port;
+
+    socket.bind(&addr.into()).expect("Failed to bind socket");
     socket
         .set_nonblocking(true)
         .expect("Failed to set non-blocking");
 
-    let fd = socket.as_raw_fd();
-    unsafe {
-        if libc::setsockopt(
-            fd,
-            libc::SOL_SOCKET,
-            libc::SO_RCVBUF,
-            &buffer_size as *const _ as *const libc::c_void,
-            std::mem::size_of_val(&buffer_size) as libc::socklen_t,
-        ) != 0
-        {
-            panic!("Failed to set UDP receive buffer size");
-        }
+    UdpSocket::from_std(socket.into()).expect("Failed to convert to Tokio UdpSocket")
+}
+
+// Linux silently caps SO_RCVBUF at net.core.rmem_max and reports twice the granted size
+#[cfg(target_os = "linux")]
+fn warn_if_receive_buffer_capped(socket: &Socket, requested: usize) {
+    match socket.recv_buffer_size() {
+        Ok(granted) if granted / 2 < requested => warn!(
+            "UDP receive buffer capped at {} bytes (requested {}), raise net.core.rmem_max",
+            granted / 2,
+            requested
+        ),
+        Ok(_) => {}
+        Err(e) => debug!("Failed to read back the UDP receive buffer size: {}", e),
     }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn warn_if_receive_buffer_capped(_socket: &Socket, _requested: usize) {}
 
-    UdpSocket::from_std(socket).expect("Failed to convert to Tokio UdpSocket")
+/// Per-slot decoding counters, kept for the configured retention window
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SlotStats {
+    pub fec_blocks_count: u64,
+    pub tx_count: u64,
+    pub fec_blocks_complete: u64,
+    pub fec_blocks_incomplete: u64,
 }
 
 pub fn increment_slot_counters(
@@ -46,66 +108,49 @@ pub fn increment_slot_counters(
     fec_blocks_complete: u64, 
     fec_blocks_incomplete: u64,
 ) {
-    let mut entry = SLOT_STATS.entry(slot).or_insert((0, 0, 0, 0)); 
-    entry.value_mut().0 += fec_blocks_count;  
-    entry.value_mut().1 += tx_count;          
-    entry.value_mut().2 += fec_blocks_complete; 
-    entry.value_mut().3 += fec_blocks_incomplete;
+    let retention = *SLOT_STATS_RETENTION_SLOTS;
+    let previous_highest = HIGHEST_STATS_SLOT.fetch_max(slot, Ordering::Relaxed);
+    if slot.saturating_add(retention) <= previous_highest {
+        debug!("Ignoring counters for slot {} outside of the retention window ({} slots)", slot, retention);
+        return;
+    }
+
+    {
+        let mut entry = SLOT_STATS.entry(slot).or_default();
+        entry.fec_blocks_count += fec_blocks_count;
+        entry.tx_count += tx_count;
+        entry.fec_blocks_complete += fec_blocks_complete;
+        entry.fec_blocks_incomplete += fec_blocks_incomplete;
+    }
+
+    // Prune once per new highest slot instead of on every increment
+    if slot > previous_highest {
+        let oldest_retained = slot.saturating_sub(retention - 1);
+        SLOT_STATS.retain(|&stats_slot, _| stats_slot >= oldest_retained);
+    }
     
     debug!("Incrementing counters for slot {}: +{} blocks, +{} txs, +{} complete, +{} incomplete", 
            slot, fec_blocks_count, tx_count, fec_blocks_complete, fec_blocks_incomplete);
 }
 
+pub fn get_slot_stats(slot: u64) -> Option<SlotStats> {
+    SLOT_STATS.get(&slot).map(|entry| *entry)
+}
 
-pub fn save_slot_statistics_to_file(slot: u64) {
-    if let Some((fec_blocks_count, tx_count, fec_blocks_complete, fec_blocks_incomplete)) =
-        SLOT_STATS.get(&slot).map(|entry| *entry)
-    {
-        let file_path = "slot_stats.json";
-
-        let stats_data = std::fs::read_to_string(file_path)
-            .ok()
-            .and_then(|content| serde_json::from_str::<VecDeque<Value>>(&content).ok())
-            .unwrap_or_else(|| VecDeque::with_capacity(100));
+/// Returns the retained statistics for slots in [start_slot, end_slot], ordered by slot
+pub fn get_slot_stats_range(start_slot: u64, end_slot: u64) -> Vec<(u64, SlotStats)> {
+    let mut stats: Vec<(u64, SlotStats)> = SLOT_STATS
+        .iter()
+        .filter(|entry| (start_slot..=end_slot).contains(entry.key()))
+        .map(|entry| (*entry.key(), *entry.value()))
+        .collect();
+    stats.sort_unstable_by_key(|(slot, _)| *slot);
+    stats
+}
 
-        let mut stats_vec = stats_data;
-        
-        if let Some(existing_index) = stats_vec.iter().position(|entry| entry["slot"] == slot) {
-            stats_vec.remove(existing_index);
-        }
-        
-        if stats_vec.len() >= 500 {
-            stats_vec.pop_front();
-        }
-        
-        stats_vec.push_back(serde_json::json!({
-            "slot": slot,
-            "fec_blocks_count": fec_blocks_count,
-            "tx_count": tx_count,
-            "fec_blocks_complete": fec_blocks_complete,
-            "fec_blocks_incomplete": fec_blocks_incomplete
-        }));
-        
-        debug!(
-            "Saved final statistics for slot {}: fec_blocks_count={}, tx_count={}, fec_blocks_complete={}, fec_blocks_incomplete={}",
-            slot, fec_blocks_count, tx_count, fec_blocks_complete, fec_blocks_incomplete
-        );
-
-        match OpenOptions::new().write(true).create(true).truncate(true).open(file_path) {
-            Ok(file) => {
-                let mut writer = BufWriter::new(file);
-                if let Err(e) = serde_json::to_writer_pretty(&mut writer, &stats_vec) {
-                    error!("Failed to save statistics: {}", e);
-                }
-                let _ = writer.flush();
-            }
-            Err(e) => {
-                error!("Failed to open file for writing statistics: {}", e);
-            }
-        }
-    } else {
-        warn!("No statistics found for slot {}. Skipping save.", slot);
-    }
+/// Highest slot for which statistics were recorded, 0 before the first one
+pub fn get_highest_stats_slot() -> u64 {
+    HIGHEST_STATS_SLOT.load(Ordering::Relaxed)
 }
 
 pub fn env(key: &str) -> String {
@@ -130,13 +175,18 @@ pub fn create_standardized_instruction(
     is_writable: &[bool],
     parsed_args: JsonValue
 ) -> JsonValue {
+    // Accounts past the end of the table (remaining accounts) and instructions without a
+    // table get a null name
+    let account_names = find_account_names(program_id, instruction_name);
     let mapped_accounts: Vec<JsonValue> = accounts_indices.iter()
         .enumerate()
         .map(|(i, &idx)| {
             let account_idx = idx as usize;
+            let name = account_names.and_then(|names| names.get(i));
             if account_idx < account_keys.len() {
                 object! {
                     "index": i,
+                    "name": name,
                     "pubkey": bs58::encode(&account_keys[account_idx]).into_string(),
                     "signer": if account_idx < is_signer.len() { is_signer[account_idx] } else { false },
                     "writable": if account_idx < is_writable.len() { is_writable[account_idx] } else { false }
@@ -144,6 +194,7 @@ pub fn create_standardized_instruction(
             } else {
                 object! {
                     "index": i,
+                    "name": name,
                     "pubkey": "unknown",
                     "signer": false,
                     "writable": false
@@ -165,3 +216,65 @@ pub fn create_standardized_instruction(
         "parsed_data": parsed_args
     }
 }
+
+/// Upper bound for a plausible lamport amount, well above the total SOL supply
+pub const MAX_PLAUSIBLE_LAMPORTS: u64 = 1_000_000_000 * 1_000_000_000;
+
+/// Collects plausibility findings for the values parsed by a decoder.
+/// Implausible numbers usually mean the data was parsed with the wrong layout,
+/// e.g. a discriminator shared by two programs routed to the wrong parser.
+#[derive(Debug, Default)]
+pub struct ValueSanity {
+    reasons: Vec<String>,
+}
+
+impl ValueSanity {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Lamport amounts above the total supply are implausible, u64::MAX is accepted as the usual "no limit" sentinel
+    pub fn check_lamports(&mut self, field: &str, value: u64) {
+        if value > MAX_PLAUSIBLE_LAMPORTS && value != u64::MAX {
+            self.reasons.push(format!("{} exceeds plausible lamports: {}", field, value));
+        }
+    }
+
+    /// Basis points above 100% are implausible for slippage and fee rates
+    pub fn check_bps(&mut self, field: &str, value: u64) {
+        if value > 10_000 {
+            self.reasons.push(format!("{} exceeds 10000 basis points: {}", field, value));
+        }
+    }
+
+    pub fn check_nonzero(&mut self, field: &str, value: u128) {
+        if value == 0 {
+            self.reasons.push(format!("{} is zero", field));
+        }
+    }
+
+    pub fn check_range_u128(&mut self, field: &str, value: u128, min: u128, max: u128) {
+        if value < min || value > max {
+            self.reasons.push(format!("{} out of range [{}, {}]: {}", field, min, max, value));
+        }
+    }
+
+    pub fn is_suspect(&self) -> bool {
+        !self.reasons.is_empty()
+    }
+
+    /// Adds the `suspect_values` flag to the parsed data, with the reasons when any check failed
+    pub fn apply(self, parsed_data: &mut JsonValue) {
+        let suspect = self.is_suspect();
+        parsed_data["suspect_values"] = suspect.into();
+        if suspect {
+            debug!("Suspect values in parsed instruction: {}", self.reasons.join("; "));
+            parsed_data["suspect_reasons"] = self.reasons.into();
+        }
+    }
+}
+
+/// Address of the associated token account of `owner` for `mint`, a PDA search so not free
+pub fn associated_token_address(owner: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[owner.as_ref(), token_program.as_ref(), mint.as_ref()], &ASSOCIATED_TOKEN_PROGRAM_ID).0
+}
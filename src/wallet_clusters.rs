@@ -0,0 +1,162 @@
+use dashmap::DashMap;
+use json::{object, JsonValue};
+use once_cell::sync::Lazy;
+use solana_sdk::instruction::CompiledInstruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::system_program;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// Opt-in, funding transfers are only tracked when enabled
+static WALLET_CLUSTERS_ENABLED: Lazy<bool> = Lazy::new(|| {
+    std::env::var("WALLET_CLUSTERS_ENABLED")
+        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+});
+
+/// Transfers below this amount are not considered as funding a wallet
+static MIN_FUNDING_LAMPORTS: Lazy<u64> = Lazy::new(|| {
+    std::env::var("WALLET_CLUSTER_MIN_FUNDING_LAMPORTS")
+        .unwrap_or_else(|_| "10000000".to_string())
+        .parse()
+        .unwrap_or(10_000_000)
+});
+
+/// How long a funding transfer is remembered while waiting for the funded wallet to create a token
+static FUNDING_WINDOW: Lazy<Duration> = Lazy::new(|| {
+    let secs = std::env::var("WALLET_CLUSTER_FUNDING_WINDOW_SECS")
+        .unwrap_or_else(|_| "3600".to_string())
+        .parse()
+        .unwrap_or(3600);
+    Duration::from_secs(secs)
+});
+
+static MAX_TRACKED_FUNDINGS: Lazy<usize> = Lazy::new(|| {
+    std::env::var("WALLET_CLUSTER_MAX_TRACKED")
+        .unwrap_or_else(|_| "1000000".to_string())
+        .parse()
+        .unwrap_or(1_000_000)
+});
+
+const SYSTEM_TRANSFER_INSTRUCTION: u32 = 2;
+
+struct Funding {
+    funder: String,
+    lamports: u64,
+    seen_at: Instant,
+}
+
+/// Funded wallet -> most recent funding transfer
+static FUNDED_BY: Lazy<DashMap<String, Funding>> = Lazy::new(DashMap::new);
+/// Wallet -> cluster id, for creators and their funders
+static WALLET_CLUSTER: Lazy<DashMap<String, u64>> = Lazy::new(DashMap::new);
+static CLUSTER_SIZES: Lazy<DashMap<u64, u32>> = Lazy::new(DashMap::new);
+static NEXT_CLUSTER_ID: AtomicU64 = AtomicU64::new(1);
+
+pub fn is_wallet_clustering_enabled() -> bool {
+    *WALLET_CLUSTERS_ENABLED
+}
+
+fn is_creation(protocol: &str, instruction_name: &str) -> bool {
+    matches!(
+        (protocol, instruction_name),
+        ("Pumpfun", "Create")
+            | ("Moonit", "TokenMint")
+            | ("RaydiumLaunchLab", "Initialize")
+            | ("Boop", "CreateToken")
+            | ("MeteoraVCurve", "InitializeVirtualPoolWithSplToken" | "InitializeVirtualPoolWithToken2022")
+    )
+}
+
+/// Records SystemProgram transfers as potential wallet fundings
+pub fn observe_funding_instruction(program_key: &Pubkey, instr: &CompiledInstruction, account_keys: &[Pubkey]) {
+    if !is_wallet_clustering_enabled() || program_key != &system_program::ID {
+        return;
+    }
+
+    // Transfer: u32 instruction index followed by u64 lamports, accounts: from=0, to=1
+    let Some(kind) = instr.data.get(0..4).and_then(|b| b.try_into().ok()).map(u32::from_le_bytes) else { return };
+    if kind != SYSTEM_TRANSFER_INSTRUCTION {
+        return;
+    }
+    let Some(lamports) = instr.data.get(4..12).and_then(|b| b.try_into().ok()).map(u64::from_le_bytes) else { return };
+    if lamports < *MIN_FUNDING_LAMPORTS {
+        return;
+    }
+    let account = |position: usize| instr.accounts.get(position).and_then(|&index| account_keys.get(index as usize));
+    let (Some(from), Some(to)) = (account(0), account(1)) else { return };
+    if from == to {
+        return;
+    }
+
+    if FUNDED_BY.len() >= *MAX_TRACKED_FUNDINGS {
+        let window = *FUNDING_WINDOW;
+        FUNDED_BY.retain(|_, funding| funding.seen_at.elapsed() < window);
+        if FUNDED_BY.len() >= *MAX_TRACKED_FUNDINGS {
+            return;
+        }
+    }
+
+    FUNDED_BY.insert(to.to_string(), Funding {
+        funder: from.to_string(),
+        lamports,
+        seen_at: Instant::now(),
+    });
+}
+
+fn join_cluster(wallet: &str, cluster_id: u64) {
+    if WALLET_CLUSTER.insert(wallet.to_string(), cluster_id) != Some(cluster_id) {
+        *CLUSTER_SIZES.entry(cluster_id).or_insert(0) += 1;
+    }
+}
+
+/// Tags a transaction creating a token with the funding cluster of its creator (the fee payer).
+/// Creators funded by the same wallet, or by a wallet that created tokens itself, share a cluster id.
+pub fn tag_creation_cluster(json_transaction: &mut JsonValue, creator: &Pubkey) {
+    if !is_wallet_clustering_enabled() {
+        return;
+    }
+
+    let creates_token = json_transaction["message"]["instructions"].members().any(|instruction| {
+        match (instruction["protocol"].as_str(), instruction["instruction_name"].as_str()) {
+            (Some(protocol), Some(name)) => is_creation(protocol, name),
+            _ => false,
+        }
+    });
+    if !creates_token {
+        return;
+    }
+
+    let creator = creator.to_string();
+    let funding = FUNDED_BY
+        .get(&creator)
+        .filter(|funding| funding.seen_at.elapsed() < *FUNDING_WINDOW)
+        .map(|funding| (funding.funder.clone(), funding.lamports));
+
+    let Some((funder, lamports)) = funding else {
+        // Not funded recently, only tag when the creator already belongs to a cluster
+        if let Some(cluster_id) = WALLET_CLUSTER.get(&creator).map(|id| *id) {
+            json_transaction["walletCluster"] = cluster_tag(cluster_id, JsonValue::Null, JsonValue::Null);
+        }
+        return;
+    };
+
+    let cluster_id = WALLET_CLUSTER
+        .get(&funder)
+        .map(|id| *id)
+        .or_else(|| WALLET_CLUSTER.get(&creator).map(|id| *id))
+        .unwrap_or_else(|| NEXT_CLUSTER_ID.fetch_add(1, Ordering::Relaxed));
+    join_cluster(&funder, cluster_id);
+    join_cluster(&creator, cluster_id);
+
+    json_transaction["walletCluster"] = cluster_tag(cluster_id, funder.into(), lamports.to_string().into());
+}
+
+fn cluster_tag(cluster_id: u64, funded_by: JsonValue, funding_lamports: JsonValue) -> JsonValue {
+    object! {
+        "clusterId" => cluster_id,
+        "clusterSize" => CLUSTER_SIZES.get(&cluster_id).map(|size| *size).unwrap_or(1),
+        "fundedBy" => funded_by,
+        "fundingLamports" => funding_lamports,
+    }
+}
@@ -0,0 +1,99 @@
+use dashmap::DashMap;
+use json::{object, JsonValue};
+use once_cell::sync::Lazy;
+use rustc_hash::FxHashMap as HashMap;
+
+use crate::decoder_registry::REGISTERED_DECODERS;
+
+/// Number of hotspots listed per slot
+static WRITE_LOCK_HOTSPOTS_TOP: Lazy<usize> = Lazy::new(|| {
+    std::env::var("WRITE_LOCK_HOTSPOTS_TOP")
+        .unwrap_or_else(|_| "10".to_string())
+        .parse()
+        .unwrap_or(10)
+});
+
+#[derive(Debug, Clone, Default)]
+struct AccountWriteLocks {
+    transactions: u32,
+    protocols: Vec<&'static str>,
+}
+
+/// Slot → writable account → transactions locking it
+static SLOT_WRITE_LOCKS: Lazy<DashMap<u64, HashMap<String, AccountWriteLocks>>> = Lazy::new(DashMap::new);
+
+/// A writable account shared by many transactions of the same slot
+#[derive(Debug, Clone)]
+pub struct WriteLockHotspot {
+    pub account: String,
+    pub transactions: u32,
+    pub protocols: Vec<&'static str>,
+}
+
+impl WriteLockHotspot {
+    pub fn to_json(&self) -> JsonValue {
+        object! {
+            "account" => self.account.clone(),
+            "transactions" => self.transactions,
+            "protocols" => self.protocols.clone(),
+        }
+    }
+}
+
+fn interned_protocol(protocol: &str) -> &'static str {
+    REGISTERED_DECODERS
+        .iter()
+        .map(|decoder| decoder.protocol)
+        .find(|known| *known == protocol)
+        .unwrap_or("unknown")
+}
+
+/// Counts the writable accounts of the decoded instructions of a transaction, once per transaction.
+/// Signers are left out, their locks are the sender's own wallet and never contended by others.
+pub fn record_write_locks(slot: u64, transaction: &JsonValue) {
+    let mut locked: HashMap<&str, &str> = HashMap::default();
+    for instruction in transaction["message"]["instructions"].members() {
+        let protocol = instruction["protocol"].as_str().unwrap_or("unknown");
+        for account in instruction["accounts"].members() {
+            if !account["writable"].as_bool().unwrap_or(false) || account["signer"].as_bool().unwrap_or(false) {
+                continue;
+            }
+            if let Some(pubkey) = account["pubkey"].as_str().filter(|pubkey| *pubkey != "unknown") {
+                locked.entry(pubkey).or_insert(protocol);
+            }
+        }
+    }
+    if locked.is_empty() {
+        return;
+    }
+
+    let mut slot_locks = SLOT_WRITE_LOCKS.entry(slot).or_default();
+    for (pubkey, protocol) in locked {
+        let entry = slot_locks.entry(pubkey.to_string()).or_default();
+        entry.transactions += 1;
+        let protocol = interned_protocol(protocol);
+        if !entry.protocols.contains(&protocol) {
+            entry.protocols.push(protocol);
+        }
+    }
+}
+
+/// Removes the write locks of a slot, and of any older slot left behind, and returns
+/// its most contended accounts. Accounts locked by a single transaction are not hotspots.
+pub fn take_write_lock_hotspots(slot: u64) -> Vec<WriteLockHotspot> {
+    let locks = SLOT_WRITE_LOCKS.remove(&slot).map(|(_, locks)| locks).unwrap_or_default();
+    SLOT_WRITE_LOCKS.retain(|locked_slot, _| *locked_slot > slot);
+
+    let mut hotspots: Vec<WriteLockHotspot> = locks
+        .into_iter()
+        .filter(|(_, locks)| locks.transactions > 1)
+        .map(|(account, locks)| WriteLockHotspot {
+            account,
+            transactions: locks.transactions,
+            protocols: locks.protocols,
+        })
+        .collect();
+    hotspots.sort_unstable_by(|a, b| b.transactions.cmp(&a.transactions).then_with(|| a.account.cmp(&b.account)));
+    hotspots.truncate(*WRITE_LOCK_HOTSPOTS_TOP);
+    hotspots
+}